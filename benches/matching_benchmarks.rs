@@ -0,0 +1,145 @@
+//! Performance benchmarks for fuzzy contact matching.
+//!
+//! These benchmarks compare three ways of matching a name query against
+//! synthetic address books of 10k and 50k contacts:
+//! - The previous hand-rolled substring and Levenshtein scorer
+//! - `ContactMatcher` scoring names with nucleo on the calling thread
+//! - `ContactMatcher` with a warm multithreaded `NameIndex`
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use dex_mcp_server::matching::{ContactMatcher, ContactQuery, NameIndex};
+use dex_mcp_server::models::Contact;
+use std::sync::Arc;
+use std::time::Duration;
+
+const FIRST_NAMES: [&str; 16] = [
+    "Sarah", "John", "Maria", "Wei", "Aisha", "Carlos", "Yuki", "Olga", "David", "Fatima", "Liam",
+    "Priya", "Noah", "Elena", "Kwame", "Sofia",
+];
+
+const LAST_NAMES: [&str; 16] = [
+    "Johnson", "Smith", "García", "Chen", "Khan", "Müller", "Tanaka", "Ivanova", "Brown", "Okafor",
+    "Nguyen", "Patel", "Rossi", "Kowalski", "Silva", "Andersen",
+];
+
+const COMPANIES: [&str; 8] = [
+    "Acme", "Globex", "Initech", "Umbrella", "Hooli", "Stark", "Wayne", "Tyrell",
+];
+
+/// Deterministic contacts with mostly distinct names.
+fn synthetic_contacts(count: usize) -> Vec<Contact> {
+    (0..count)
+        .map(|i| {
+            let first = FIRST_NAMES[i % FIRST_NAMES.len()];
+            let last = LAST_NAMES[(i * 7 / FIRST_NAMES.len()) % LAST_NAMES.len()];
+            let mut contact =
+                Contact::new(format!("contact-{}", i), format!("{} {}{}", first, last, i));
+            contact.company = Some(COMPANIES[i % COMPANIES.len()].to_string());
+            contact
+        })
+        .collect()
+}
+
+/// The pre-nucleo name matcher: substring checks, then Levenshtein.
+fn legacy_matches(contacts: &[Contact], query: &str, min_confidence: u8) -> usize {
+    fn levenshtein(a: &str, b: &str) -> usize {
+        let b: Vec<char> = b.chars().collect();
+        let mut row: Vec<usize> = (0..=b.len()).collect();
+        for (i, ca) in a.chars().enumerate() {
+            let mut diagonal = row[0];
+            row[0] = i + 1;
+            for (j, cb) in b.iter().enumerate() {
+                let above = row[j + 1];
+                row[j + 1] = (above + 1)
+                    .min(row[j] + 1)
+                    .min(diagonal + usize::from(ca != *cb));
+                diagonal = above;
+            }
+        }
+        row[b.len()]
+    }
+
+    fn score(query: &str, target: &str) -> u8 {
+        if query == target {
+            return 95;
+        }
+        let query_len = query.chars().count();
+        let target_len = target.chars().count();
+        if target.contains(query) {
+            return (85.0 * query_len as f64 / target_len as f64 + 10.0) as u8;
+        }
+        if query.contains(target) {
+            return 85;
+        }
+        let max_len = query_len.max(target_len);
+        let distance = levenshtein(query, target) as f64 / max_len as f64;
+        if distance > 0.5 {
+            return 0;
+        }
+        ((1.0 - distance) * 85.0) as u8
+    }
+
+    let query = ContactMatcher::normalize_name(query);
+    contacts
+        .iter()
+        .filter(|contact| {
+            let confidence = score(&query, &ContactMatcher::normalize_name(&contact.name));
+            confidence > 0 && confidence >= min_confidence
+        })
+        .count()
+}
+
+/// Benchmark the legacy scorer against nucleo, sequential and parallel.
+fn bench_name_matching(c: &mut Criterion) {
+    let mut group = c.benchmark_group("name_matching");
+    group.sample_size(20);
+
+    for size in [10_000, 50_000] {
+        let contacts = synthetic_contacts(size);
+        let mut matcher = ContactMatcher::new();
+        let mut index = NameIndex::new(Arc::new(contacts.clone()), None);
+
+        for name in ["sarah johnson", "jon smth", "priya patel 4711"] {
+            let query = ContactQuery {
+                name: Some(name.to_string()),
+                ..Default::default()
+            };
+            let id = format!("{}/{}", size, name);
+
+            group.bench_with_input(
+                BenchmarkId::new("legacy_levenshtein", &id),
+                &name,
+                |b, name| b.iter(|| black_box(legacy_matches(&contacts, name, 30))),
+            );
+            group.bench_with_input(
+                BenchmarkId::new("nucleo_sequential", &id),
+                &query,
+                |b, query| b.iter(|| black_box(matcher.find_matches(query, &contacts, 10, 30))),
+            );
+            group.bench_with_input(
+                BenchmarkId::new("nucleo_parallel", &id),
+                &query,
+                |b, query| {
+                    b.iter(|| black_box(matcher.find_matches_indexed(query, &mut index, 10, 30)))
+                },
+            );
+        }
+    }
+
+    group.bench_function("build_name_index_50k", |b| {
+        let contacts = Arc::new(synthetic_contacts(50_000));
+        b.iter(|| black_box(NameIndex::new(contacts.clone(), None).contacts().len()))
+    });
+
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default()
+        .measurement_time(Duration::from_secs(10))
+        .sample_size(50);
+    targets = bench_name_matching
+}
+
+criterion_main!(benches);
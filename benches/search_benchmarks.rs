@@ -5,14 +5,18 @@
 //! - Cache hit (subsequent searches using cached index)
 //! - Different dataset sizes
 //! - Parallel fetching performance
+//! - Inverted index vs. linear scan on a 10k-contact synthetic corpus
 
-use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 use dex_mcp_server::client::{AsyncDexClient, AsyncDexClientImpl, DexClient};
 use dex_mcp_server::config::Config;
+use dex_mcp_server::models::{Contact, Note, Reminder};
 use dex_mcp_server::repositories::{
     ContactRepository, DexContactRepository, DexNoteRepository, DexReminderRepository,
     NoteRepository, ReminderRepository,
 };
+use dex_mcp_server::search::full_text_index::strip_html;
+use dex_mcp_server::search::FullTextSearchIndex;
 use dex_mcp_server::tools::search::{SearchParams, SearchTools};
 use std::sync::Arc;
 use std::time::Duration;
@@ -54,6 +58,7 @@ fn bench_search_cache_miss(c: &mut Criterion) {
                 query: "john".to_string(),
                 max_results: Some(10),
                 min_confidence: Some(50),
+                ..Default::default()
             };
 
             let _result = search_tools.search_full_text(params).await;
@@ -74,6 +79,7 @@ fn bench_search_cache_hit(c: &mut Criterion) {
             query: "warmup".to_string(),
             max_results: Some(10),
             min_confidence: Some(50),
+            ..Default::default()
         };
         let _result = search_tools.search_full_text(params).await;
     });
@@ -84,6 +90,7 @@ fn bench_search_cache_hit(c: &mut Criterion) {
                 query: "john".to_string(),
                 max_results: Some(10),
                 min_confidence: Some(50),
+                ..Default::default()
             };
 
             let _result = search_tools.search_full_text(params).await;
@@ -104,6 +111,7 @@ fn bench_search_result_limits(c: &mut Criterion) {
             query: "warmup".to_string(),
             max_results: Some(10),
             min_confidence: Some(50),
+            ..Default::default()
         };
         let _result = search_tools.search_full_text(params).await;
     });
@@ -117,6 +125,7 @@ fn bench_search_result_limits(c: &mut Criterion) {
                     query: "john".to_string(),
                     max_results: Some(limit),
                     min_confidence: Some(50),
+                    ..Default::default()
                 };
 
                 let _result = search_tools.search_full_text(params).await;
@@ -140,6 +149,7 @@ fn bench_search_confidence_thresholds(c: &mut Criterion) {
             query: "warmup".to_string(),
             max_results: Some(10),
             min_confidence: Some(50),
+            ..Default::default()
         };
         let _result = search_tools.search_full_text(params).await;
     });
@@ -156,6 +166,7 @@ fn bench_search_confidence_thresholds(c: &mut Criterion) {
                         query: "john".to_string(),
                         max_results: Some(10),
                         min_confidence: Some(confidence),
+                        ..Default::default()
                     };
 
                     let _result = search_tools.search_full_text(params).await;
@@ -167,6 +178,224 @@ fn bench_search_confidence_thresholds(c: &mut Criterion) {
     group.finish();
 }
 
+const FIRST_NAMES: &[&str] = &[
+    "John",
+    "Sarah",
+    "Michael",
+    "Emily",
+    "David",
+    "Olivia",
+    "James",
+    "Sophia",
+    "Robert",
+    "Ava",
+    "William",
+    "Mia",
+    "Daniel",
+    "Isabella",
+    "Joseph",
+    "Charlotte",
+    "Thomas",
+    "Amelia",
+    "Henry",
+    "Harper",
+];
+
+const LAST_NAMES: &[&str] = &[
+    "Smith",
+    "Johnson",
+    "Williams",
+    "Brown",
+    "Jones",
+    "Garcia",
+    "Miller",
+    "Davis",
+    "Rodriguez",
+    "Martinez",
+    "Hernandez",
+    "Lopez",
+    "Wilson",
+    "Anderson",
+    "Taylor",
+    "Thomas",
+    "Moore",
+    "Jackson",
+    "Martin",
+    "Lee",
+];
+
+const COMPANIES: &[&str] = &[
+    "Acme Corp",
+    "Globex",
+    "Initech",
+    "Umbrella",
+    "Stark Industries",
+    "Wayne Enterprises",
+    "Hooli",
+    "Pied Piper",
+    "Vandelay Industries",
+    "Soylent",
+];
+
+const NOTE_WORDS: &[&str] = &[
+    "discussed",
+    "project",
+    "timeline",
+    "coffee",
+    "meeting",
+    "budget",
+    "introduced",
+    "conference",
+    "follow",
+    "proposal",
+    "hiring",
+    "product",
+    "launch",
+    "roadmap",
+    "feedback",
+    "lunch",
+    "partner",
+    "contract",
+    "renewal",
+    "quarterly",
+    "review",
+    "design",
+    "engineering",
+    "marketing",
+    "sales",
+];
+
+/// Build a deterministic synthetic corpus of contacts with notes and reminders.
+fn synthetic_corpus(size: usize) -> Vec<(Contact, Vec<Note>, Vec<Reminder>)> {
+    (0..size)
+        .map(|i| {
+            let first = FIRST_NAMES[i % FIRST_NAMES.len()];
+            let last = LAST_NAMES[(i / FIRST_NAMES.len()) % LAST_NAMES.len()];
+            let mut contact = Contact {
+                id: format!("contact-{}", i),
+                first_name: Some(first.to_string()),
+                last_name: Some(format!("{}{}", last, i / 400)),
+                emails: vec![format!("{}.{}{}@example.com", first, last, i).to_lowercase()],
+                phones: vec![format!("+1 555 {:03} {:04}", i % 1000, i)],
+                company: Some(COMPANIES[i % COMPANIES.len()].to_string()),
+                job_title: Some("Engineer".to_string()),
+                ..Default::default()
+            };
+            contact.populate_computed_fields();
+
+            let sentence = |offset: usize| -> String {
+                (0..12)
+                    .map(|w| NOTE_WORDS[(i * 7 + offset + w * 3) % NOTE_WORDS.len()])
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            };
+            let notes = (0..2)
+                .map(|n| Note {
+                    id: format!("note-{}-{}", i, n),
+                    contact_id: contact.id.clone(),
+                    content: format!("<p>{}</p>", sentence(n)),
+                    ..Default::default()
+                })
+                .collect();
+            let reminders = vec![Reminder {
+                id: format!("reminder-{}", i),
+                contact_id: contact.id.clone(),
+                text: sentence(5),
+                due_date: "2025-01-01".to_string(),
+                ..Default::default()
+            }];
+
+            (contact, notes, reminders)
+        })
+        .collect()
+}
+
+/// The pre-index search: scan every document and fuzzy-match word by word.
+fn linear_scan(documents: &[(String, String)], query: &str) -> usize {
+    fn levenshtein(a: &str, b: &str) -> usize {
+        let b: Vec<char> = b.chars().collect();
+        let mut row: Vec<usize> = (0..=b.len()).collect();
+        for (i, ca) in a.chars().enumerate() {
+            let mut diagonal = row[0];
+            row[0] = i + 1;
+            for (j, cb) in b.iter().enumerate() {
+                let above = row[j + 1];
+                row[j + 1] = (above + 1)
+                    .min(row[j] + 1)
+                    .min(diagonal + usize::from(ca != *cb));
+                diagonal = above;
+            }
+        }
+        row[b.len()]
+    }
+
+    let query = query.to_lowercase();
+    documents
+        .iter()
+        .filter(|(_, content)| {
+            let content = content.to_lowercase();
+            content.contains(&query)
+                || content.split_whitespace().any(|word| {
+                    let max_len = word.len().max(query.len());
+                    word.contains(&query)
+                        || levenshtein(&query, word) as f64 / max_len as f64 <= 0.4
+                })
+        })
+        .count()
+}
+
+/// Benchmark the inverted index against a linear scan on 10k contacts.
+fn bench_index_10k_contacts(c: &mut Criterion) {
+    let corpus = synthetic_corpus(10_000);
+    let contacts: Vec<Contact> = corpus.iter().map(|(c, _, _)| c.clone()).collect();
+
+    let mut index = FullTextSearchIndex::new();
+    for (contact, notes, reminders) in &corpus {
+        index.index_contact(contact, notes, reminders);
+    }
+
+    let documents: Vec<(String, String)> = corpus
+        .iter()
+        .flat_map(|(contact, notes, reminders)| {
+            let mut docs = vec![contact.name.clone()];
+            docs.extend(contact.emails.iter().cloned());
+            docs.extend(contact.phones.iter().cloned());
+            docs.extend(contact.company.iter().cloned());
+            docs.extend(notes.iter().map(|n| strip_html(&n.content)));
+            docs.extend(reminders.iter().map(|r| r.text.clone()));
+            docs.into_iter().map(|d| (contact.id.clone(), d))
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("index_10k_contacts");
+    group.sample_size(20);
+
+    for query in ["sarah", "globex", "roadmap renewal", "jonhson"] {
+        group.bench_with_input(
+            BenchmarkId::new("bm25_inverted_index", query),
+            &query,
+            |b, query| b.iter(|| black_box(index.search(&contacts, query, 10, 30))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("linear_scan_baseline", query),
+            &query,
+            |b, query| b.iter(|| black_box(linear_scan(&documents, query))),
+        );
+    }
+
+    group.bench_function("build_index", |b| {
+        b.iter(|| {
+            let mut index = FullTextSearchIndex::new();
+            for (contact, notes, reminders) in &corpus {
+                index.index_contact(contact, notes, reminders);
+            }
+            black_box(index.document_count())
+        })
+    });
+
+    group.finish();
+}
+
 criterion_group! {
     name = benches;
     config = Criterion::default()
@@ -175,7 +404,8 @@ criterion_group! {
     targets = bench_search_cache_miss,
         bench_search_cache_hit,
         bench_search_result_limits,
-        bench_search_confidence_thresholds
+        bench_search_confidence_thresholds,
+        bench_index_10k_contacts
 }
 
 criterion_main!(benches);
@@ -0,0 +1,430 @@
+//! Append-only JSONL audit log implementation.
+//!
+//! Each mutation is written as a single JSON object per line, so the log can be
+//! tailed, grepped, or loaded by other tools without a custom parser.
+
+use crate::error::{AuditError, AuditResult};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::future::Future;
+use std::io::{BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Tool name recorded for changes made outside of any tool call.
+const UNKNOWN_TOOL: &str = "unknown";
+
+/// Sequence number that keeps change IDs unique within the same microsecond.
+static CHANGE_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+tokio::task_local! {
+    static CURRENT_TOOL: String;
+    static REVERTING_CHANGE: String;
+}
+
+/// Run a future with `tool` recorded as the MCP tool responsible for any
+/// mutations it performs.
+pub async fn with_tool<F: Future>(tool: &str, fut: F) -> F::Output {
+    CURRENT_TOOL.scope(tool.to_string(), fut).await
+}
+
+/// Run a future that reverts `change_id`, so that the mutations it performs
+/// are logged as reverting that change.
+pub async fn with_revert<F: Future>(change_id: &str, fut: F) -> F::Output {
+    REVERTING_CHANGE.scope(change_id.to_string(), fut).await
+}
+
+/// Get the name of the MCP tool currently executing, or `"unknown"` when
+/// called outside of [`with_tool`].
+pub fn current_tool() -> String {
+    CURRENT_TOOL
+        .try_with(|tool| tool.clone())
+        .unwrap_or_else(|_| UNKNOWN_TOOL.to_string())
+}
+
+/// The kind of mutation recorded in an audit entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditOperation {
+    /// A record was created
+    Create,
+    /// An existing record was updated
+    Update,
+    /// A record was deleted
+    Delete,
+}
+
+/// The type of record an audit entry refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditRecordType {
+    /// A contact
+    Contact,
+    /// A note
+    Note,
+    /// A reminder
+    Reminder,
+}
+
+/// A single mutation recorded in the audit log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Unique identifier for this change
+    pub change_id: String,
+
+    /// When the change was made (RFC 3339 timestamp)
+    pub timestamp: String,
+
+    /// MCP tool that made the change
+    pub tool: String,
+
+    /// Kind of mutation
+    pub operation: AuditOperation,
+
+    /// Type of the record that changed
+    pub record_type: AuditRecordType,
+
+    /// ID of the record that changed
+    pub record_id: String,
+
+    /// ID of the contact the record belongs to
+    pub contact_id: String,
+
+    /// Record before the change (absent for creates)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<serde_json::Value>,
+
+    /// Record after the change (absent for deletes)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<serde_json::Value>,
+
+    /// ID of the change this entry reverts, if it was made by an undo
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reverts: Option<String>,
+}
+
+impl AuditEntry {
+    /// Create a new entry attributed to the currently executing tool.
+    pub fn new(
+        operation: AuditOperation,
+        record_type: AuditRecordType,
+        record_id: impl Into<String>,
+        contact_id: impl Into<String>,
+    ) -> Self {
+        let now = Utc::now();
+        let sequence = CHANGE_SEQUENCE.fetch_add(1, Ordering::Relaxed) % 10_000;
+
+        Self {
+            change_id: format!("chg_{}_{:04}", now.format("%Y%m%d%H%M%S%6f"), sequence),
+            timestamp: now.to_rfc3339(),
+            tool: current_tool(),
+            operation,
+            record_type,
+            record_id: record_id.into(),
+            contact_id: contact_id.into(),
+            before: None,
+            after: None,
+            reverts: REVERTING_CHANGE.try_with(|id| id.clone()).ok(),
+        }
+    }
+
+    /// Attach the record state before the change.
+    pub fn with_before(mut self, before: Option<serde_json::Value>) -> Self {
+        self.before = before;
+        self
+    }
+
+    /// Attach the record state after the change.
+    pub fn with_after(mut self, after: Option<serde_json::Value>) -> Self {
+        self.after = after;
+        self
+    }
+}
+
+/// Append-only audit log backed by a JSONL file.
+pub struct AuditLog {
+    path: PathBuf,
+    /// Serializes appends so concurrent writers never interleave lines
+    write_lock: Mutex<()>,
+}
+
+impl AuditLog {
+    /// Create an audit log that appends to the file at `path`.
+    ///
+    /// The file and its parent directories are created on the first write.
+    /// Entries hold full copies of contacts, notes and reminders, so on Unix
+    /// the file is created readable by its owner only.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Get the path of the log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append an entry to the log.
+    pub fn record(&self, entry: &AuditEntry) -> AuditResult<()> {
+        let line = serde_json::to_string(entry)?;
+
+        let _guard = self
+            .write_lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if let Some(parent) = self.path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+
+        let mut options = OpenOptions::new();
+        options.create(true).append(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        let mut file = options.open(&self.path)?;
+        writeln!(file, "{}", line)?;
+
+        Ok(())
+    }
+
+    /// Append an entry, logging instead of propagating failures.
+    ///
+    /// Used by the repository decorators: the mutation has already happened by
+    /// the time it is recorded, so a broken log must not turn it into an error.
+    pub fn append(&self, entry: &AuditEntry) {
+        if let Err(e) = self.record(entry) {
+            tracing::error!(
+                "Failed to write audit log entry {} to {}: {}",
+                entry.change_id,
+                self.path.display(),
+                e
+            );
+        }
+    }
+
+    /// Read entries from the log, oldest first.
+    ///
+    /// # Arguments
+    /// * `contact_id` - Only return changes to this contact's records
+    /// * `since` - Only return changes at or after this time (RFC 3339 or `YYYY-MM-DD`)
+    pub fn query(
+        &self,
+        contact_id: Option<&str>,
+        since: Option<&str>,
+    ) -> AuditResult<Vec<AuditEntry>> {
+        let since = since.map(parse_timestamp).transpose()?;
+
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut entries = Vec::new();
+        for (line_number, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let entry: AuditEntry = match serde_json::from_str(&line) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    tracing::warn!(
+                        "Skipping malformed audit log line {}: {}",
+                        line_number + 1,
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            if let Some(contact_id) = contact_id {
+                if entry.contact_id != contact_id {
+                    continue;
+                }
+            }
+
+            if let Some(since) = since {
+                match DateTime::parse_from_rfc3339(&entry.timestamp) {
+                    Ok(timestamp) if timestamp.with_timezone(&Utc) >= since => {}
+                    _ => continue,
+                }
+            }
+
+            entries.push(entry);
+        }
+
+        Ok(entries)
+    }
+
+    /// Find the entry with the given change ID.
+    pub fn find(&self, change_id: &str) -> AuditResult<Option<AuditEntry>> {
+        Ok(self
+            .query(None, None)?
+            .into_iter()
+            .find(|entry| entry.change_id == change_id))
+    }
+
+    /// Find the entry that reverted the given change, if any.
+    pub fn find_revert_of(&self, change_id: &str) -> AuditResult<Option<AuditEntry>> {
+        Ok(self
+            .query(None, None)?
+            .into_iter()
+            .find(|entry| entry.reverts.as_deref() == Some(change_id)))
+    }
+
+    /// Find the most recent change that is not itself a revert and has not
+    /// already been reverted.
+    pub fn last_undoable(&self) -> AuditResult<Option<AuditEntry>> {
+        let entries = self.query(None, None)?;
+        let reverted: HashSet<&str> = entries
+            .iter()
+            .filter_map(|entry| entry.reverts.as_deref())
+            .collect();
+
+        Ok(entries
+            .iter()
+            .rev()
+            .find(|entry| entry.reverts.is_none() && !reverted.contains(entry.change_id.as_str()))
+            .cloned())
+    }
+}
+
+/// Parse an RFC 3339 timestamp or a plain `YYYY-MM-DD` date (midnight UTC).
+fn parse_timestamp(value: &str) -> AuditResult<DateTime<Utc>> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|datetime| datetime.and_utc())
+        .ok_or_else(|| AuditError::InvalidTimestamp(value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_log(name: &str) -> AuditLog {
+        let path = std::env::temp_dir().join(format!(
+            "dex-audit-{}-{}-{}.jsonl",
+            name,
+            std::process::id(),
+            CHANGE_SEQUENCE.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_file(&path);
+        AuditLog::new(path)
+    }
+
+    #[test]
+    fn test_current_tool_outside_scope() {
+        assert_eq!(current_tool(), "unknown");
+    }
+
+    #[tokio::test]
+    async fn test_with_tool_sets_current_tool() {
+        let tool = with_tool("enrich_contact", async { current_tool() }).await;
+        assert_eq!(tool, "enrich_contact");
+    }
+
+    #[test]
+    fn test_change_ids_are_unique() {
+        let a = AuditEntry::new(AuditOperation::Create, AuditRecordType::Note, "n1", "c1");
+        let b = AuditEntry::new(AuditOperation::Create, AuditRecordType::Note, "n1", "c1");
+        assert_ne!(a.change_id, b.change_id);
+    }
+
+    #[test]
+    fn test_record_and_query_round_trip() {
+        let log = temp_log("round-trip");
+        let entry = AuditEntry::new(AuditOperation::Update, AuditRecordType::Contact, "c1", "c1")
+            .with_before(Some(serde_json::json!({"company": "Old"})))
+            .with_after(Some(serde_json::json!({"company": "New"})));
+
+        log.record(&entry).unwrap();
+        let entries = log.query(None, None).unwrap();
+
+        assert_eq!(entries, vec![entry]);
+        let _ = fs::remove_file(log.path());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_log_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let log = temp_log("private");
+        let entry = AuditEntry::new(AuditOperation::Create, AuditRecordType::Note, "n1", "c1");
+        log.record(&entry).unwrap();
+
+        let mode = fs::metadata(log.path()).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let _ = fs::remove_file(log.path());
+    }
+
+    #[test]
+    fn test_query_filters_by_contact_and_since() {
+        let log = temp_log("filters");
+        let mut old = AuditEntry::new(AuditOperation::Create, AuditRecordType::Note, "n1", "c1");
+        old.timestamp = "2020-01-01T00:00:00+00:00".to_string();
+        let recent = AuditEntry::new(AuditOperation::Create, AuditRecordType::Note, "n2", "c1");
+        let other = AuditEntry::new(AuditOperation::Create, AuditRecordType::Note, "n3", "c2");
+
+        for entry in [&old, &recent, &other] {
+            log.record(entry).unwrap();
+        }
+
+        assert_eq!(log.query(Some("c1"), None).unwrap().len(), 2);
+        assert_eq!(log.query(None, Some("2021-01-01")).unwrap().len(), 2);
+        assert_eq!(
+            log.query(Some("c1"), Some("2021-01-01")).unwrap(),
+            vec![recent]
+        );
+        let _ = fs::remove_file(log.path());
+    }
+
+    #[tokio::test]
+    async fn test_last_undoable_skips_reverted_changes() {
+        let log = temp_log("undoable");
+        let first = AuditEntry::new(AuditOperation::Create, AuditRecordType::Note, "n1", "c1");
+        let second = AuditEntry::new(AuditOperation::Create, AuditRecordType::Note, "n2", "c1");
+        let revert = with_revert(&second.change_id, async {
+            AuditEntry::new(AuditOperation::Delete, AuditRecordType::Note, "n2", "c1")
+        })
+        .await;
+        assert_eq!(revert.reverts.as_deref(), Some(second.change_id.as_str()));
+
+        for entry in [&first, &second, &revert] {
+            log.record(entry).unwrap();
+        }
+
+        assert_eq!(log.last_undoable().unwrap(), Some(first));
+        assert_eq!(log.find_revert_of(&second.change_id).unwrap(), Some(revert));
+        let _ = fs::remove_file(log.path());
+    }
+
+    #[test]
+    fn test_query_missing_file_is_empty() {
+        let log = temp_log("missing");
+        assert!(log.query(None, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_query_rejects_invalid_since() {
+        let log = temp_log("invalid-since");
+        let result = log.query(None, Some("last tuesday"));
+        assert!(matches!(result, Err(AuditError::InvalidTimestamp(_))));
+    }
+}
@@ -0,0 +1,11 @@
+//! Audit trail for mutations made through the MCP server.
+//!
+//! This module provides an append-only JSONL log of every create, update and
+//! delete performed against the Dex repositories, together with the task-local
+//! context that attributes each change to the MCP tool that caused it.
+
+pub mod audit_log;
+
+pub use audit_log::{
+    current_tool, with_revert, with_tool, AuditEntry, AuditLog, AuditOperation, AuditRecordType,
+};
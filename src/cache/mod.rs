@@ -1,7 +1,10 @@
 //! Caching utilities for the Dex MCP Server.
 //!
-//! This module provides a generic time-based cache implementation with TTL support.
+//! This module provides a generic time-based cache implementation with TTL
+//! support, and a single-value cache that refreshes in the background.
 
+pub mod refreshing_cache;
 pub mod timed_cache;
 
+pub use refreshing_cache::RefreshingCache;
 pub use timed_cache::TimedCache;
@@ -0,0 +1,395 @@
+//! Single-value cache that is rebuilt in the background.
+//!
+//! A [`RefreshingCache`] keeps serving its value after it expires while a
+//! replacement is built on a tokio task (stale-while-revalidate), and swaps the
+//! new value in once it is ready. Callers only wait for a build when there is
+//! no value at all. Concurrent callers share a single running build.
+
+use crate::error::{DexApiError, DexApiResult};
+use futures::future::{BoxFuture, FutureExt, Shared};
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// A running build, awaited by every caller that needs its result.
+type SharedBuild<V> = Shared<BoxFuture<'static, Result<V, Arc<DexApiError>>>>;
+
+/// The cached value.
+struct Entry<V> {
+    value: V,
+    /// Identifies this entry, so scheduled refreshes can tell it was replaced
+    id: u64,
+    expires_at: Instant,
+    /// Whether a refresh before expiry has been scheduled
+    refresh_scheduled: bool,
+}
+
+struct State<V> {
+    entry: Option<Entry<V>>,
+    build: Option<SharedBuild<V>>,
+    /// Incremented when the cache is invalidated; builds started before that
+    /// are not stored
+    generation: u64,
+    next_entry_id: u64,
+}
+
+/// A cached value that is refreshed in the background.
+///
+/// Expired values are still served while a refresh runs. The first access to
+/// a value also schedules a refresh `refresh_ahead` before it expires, so a
+/// value in active use is usually replaced before anyone sees it expire.
+/// Cloning is cheap and clones share the value.
+pub struct RefreshingCache<V> {
+    state: Arc<Mutex<State<V>>>,
+    ttl: Duration,
+    refresh_ahead: Duration,
+}
+
+impl<V> Clone for RefreshingCache<V> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            ttl: self.ttl,
+            refresh_ahead: self.refresh_ahead,
+        }
+    }
+}
+
+impl<V> std::fmt::Debug for RefreshingCache<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RefreshingCache")
+            .field("ttl", &self.ttl)
+            .field("refresh_ahead", &self.refresh_ahead)
+            .finish()
+    }
+}
+
+impl<V> RefreshingCache<V>
+where
+    V: Clone + Send + Sync + 'static,
+{
+    /// Create an empty cache whose values live for `ttl`.
+    ///
+    /// Values are refreshed when a tenth of their TTL is left.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                entry: None,
+                build: None,
+                generation: 0,
+                next_entry_id: 0,
+            })),
+            ttl,
+            refresh_ahead: ttl / 10,
+        }
+    }
+
+    /// Refresh values when `refresh_ahead` of their TTL is left.
+    pub fn with_refresh_ahead(mut self, refresh_ahead: Duration) -> Self {
+        self.refresh_ahead = refresh_ahead.min(self.ttl);
+        self
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<V>> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Get the cached value, building it with `build` if there is none.
+    ///
+    /// An expired value is returned as is while `build` refreshes it in the
+    /// background. Returns the value and whether it came from the cache.
+    ///
+    /// # Errors
+    /// Returns the build's error when there is no value to fall back to.
+    pub async fn get_or_build<F, Fut>(&self, build: F) -> DexApiResult<(V, bool)>
+    where
+        F: Fn() -> Fut + Clone + Send + Sync + 'static,
+        Fut: Future<Output = DexApiResult<V>> + Send + 'static,
+    {
+        let pending = {
+            let mut state = self.lock();
+            let now = Instant::now();
+
+            if let Some(entry) = state.entry.as_mut() {
+                let value = entry.value.clone();
+                if now >= entry.expires_at {
+                    tracing::debug!("Serving expired cache entry while it is refreshed");
+                    self.start_build(&mut state, build);
+                } else if !entry.refresh_scheduled {
+                    entry.refresh_scheduled = true;
+                    let delay = entry
+                        .expires_at
+                        .saturating_duration_since(now)
+                        .saturating_sub(self.refresh_ahead);
+                    let entry_id = entry.id;
+                    self.schedule_refresh(delay, entry_id, build);
+                }
+                return Ok((value, true));
+            }
+
+            self.start_build(&mut state, build);
+            state.build.clone().expect("a build is running")
+        };
+
+        pending.await.map(|value| (value, false)).map_err(unshare)
+    }
+
+    /// Start a refresh in the background, unless one is already running.
+    pub fn refresh<F, Fut>(&self, build: F)
+    where
+        F: Fn() -> Fut + Clone + Send + Sync + 'static,
+        Fut: Future<Output = DexApiResult<V>> + Send + 'static,
+    {
+        let mut state = self.lock();
+        self.start_build(&mut state, build);
+    }
+
+    /// Start a build on a tokio task, unless one is already running.
+    ///
+    /// The build runs to completion even if nobody awaits `state.build`.
+    fn start_build<F, Fut>(&self, state: &mut State<V>, build: F)
+    where
+        F: Fn() -> Fut + Clone + Send + Sync + 'static,
+        Fut: Future<Output = DexApiResult<V>> + Send + 'static,
+    {
+        if state.build.is_some() {
+            return;
+        }
+
+        let cache = self.clone();
+        let generation = state.generation;
+        let task = tokio::spawn(async move {
+            let result = build().await;
+
+            let mut state = cache.lock();
+            if state.generation != generation {
+                tracing::debug!("Cache invalidated during build; not storing the result");
+                return result.map_err(Arc::new);
+            }
+            state.build = None;
+            match result {
+                Ok(value) => {
+                    cache.store(&mut state, value.clone(), cache.ttl);
+                    Ok(value)
+                }
+                Err(e) => {
+                    tracing::warn!("Cache build failed: {}", e);
+                    Err(Arc::new(e))
+                }
+            }
+        });
+
+        let pending = async move {
+            task.await.unwrap_or_else(|e| {
+                Err(Arc::new(DexApiError::Other(format!(
+                    "Cache build task failed: {}",
+                    e
+                ))))
+            })
+        }
+        .boxed()
+        .shared();
+        state.build = Some(pending);
+    }
+
+    /// Refresh entry `entry_id` after `delay`, if it is still cached then.
+    fn schedule_refresh<F, Fut>(&self, delay: Duration, entry_id: u64, build: F)
+    where
+        F: Fn() -> Fut + Clone + Send + Sync + 'static,
+        Fut: Future<Output = DexApiResult<V>> + Send + 'static,
+    {
+        let cache = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+
+            let mut state = cache.lock();
+            if state.entry.as_ref().map(|entry| entry.id) == Some(entry_id) {
+                tracing::debug!("Refreshing cache entry before it expires");
+                cache.start_build(&mut state, build);
+            }
+        });
+    }
+
+    fn store(&self, state: &mut State<V>, value: V, lifetime: Duration) {
+        state.next_entry_id += 1;
+        state.entry = Some(Entry {
+            value,
+            id: state.next_entry_id,
+            expires_at: Instant::now() + lifetime,
+            refresh_scheduled: false,
+        });
+    }
+
+    /// Get the cached value, even if expired, without building it.
+    pub fn get(&self) -> Option<V> {
+        self.lock().entry.as_ref().map(|entry| entry.value.clone())
+    }
+
+    /// Store a value that was obtained `age` ago, e.g. loaded from disk.
+    ///
+    /// It expires once `age` plus the time since reaches the TTL.
+    pub fn insert(&self, value: V, age: Duration) {
+        let mut state = self.lock();
+        let lifetime = self.ttl.saturating_sub(age);
+        self.store(&mut state, value, lifetime);
+    }
+
+    /// Keep the cached value, but do not store the result of a build that is
+    /// already running.
+    ///
+    /// Used when the value has been updated in place, so that a build that
+    /// fetched its data before the update does not replace it.
+    pub fn discard_pending(&self) {
+        let mut state = self.lock();
+        state.generation += 1;
+        state.build = None;
+    }
+
+    /// Remove the cached value and discard any running build.
+    pub fn invalidate(&self) {
+        let mut state = self.lock();
+        state.generation += 1;
+        state.build = None;
+        state.entry = None;
+    }
+
+    /// Whether a build is running.
+    pub fn is_building(&self) -> bool {
+        self.lock().build.is_some()
+    }
+
+    /// Get the TTL of cached values.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+}
+
+/// Turn a build error shared between callers back into an owned error.
+fn unshare(error: Arc<DexApiError>) -> DexApiError {
+    Arc::try_unwrap(error).unwrap_or_else(|shared| match &*shared {
+        DexApiError::HttpError(message) => DexApiError::HttpError(message.clone()),
+        DexApiError::ApiError { status, message } => DexApiError::ApiError {
+            status: *status,
+            message: message.clone(),
+        },
+        DexApiError::Timeout => DexApiError::Timeout,
+        DexApiError::NotFound(message) => DexApiError::NotFound(message.clone()),
+        DexApiError::Unauthorized => DexApiError::Unauthorized,
+        DexApiError::RateLimitExceeded => DexApiError::RateLimitExceeded,
+        DexApiError::InvalidRequest(message) => DexApiError::InvalidRequest(message.clone()),
+        other => DexApiError::Other(other.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A build that counts its calls and returns the call number.
+    fn counting_build(
+        calls: &Arc<AtomicUsize>,
+        delay: Duration,
+    ) -> impl Fn() -> BoxFuture<'static, DexApiResult<usize>> + Clone + Send + Sync + 'static {
+        let calls = calls.clone();
+        move || {
+            let calls = calls.clone();
+            async move {
+                tokio::time::sleep(delay).await;
+                Ok(calls.fetch_add(1, Ordering::SeqCst) + 1)
+            }
+            .boxed()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_builds_once_then_serves_cached_value() {
+        let cache = RefreshingCache::new(Duration::from_secs(60));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let build = counting_build(&calls, Duration::ZERO);
+
+        assert_eq!(cache.get_or_build(build.clone()).await.unwrap(), (1, false));
+        assert_eq!(cache.get_or_build(build).await.unwrap(), (1, true));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_callers_share_one_build() {
+        let cache = RefreshingCache::new(Duration::from_secs(60));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let build = counting_build(&calls, Duration::from_millis(50));
+
+        let (a, b) = tokio::join!(
+            cache.get_or_build(build.clone()),
+            cache.get_or_build(build.clone())
+        );
+        assert_eq!(a.unwrap(), (1, false));
+        assert_eq!(b.unwrap(), (1, false));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_expired_value_is_served_while_refreshing() {
+        let cache = RefreshingCache::new(Duration::from_millis(50));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let build = counting_build(&calls, Duration::from_millis(50));
+
+        cache.insert(0, Duration::from_millis(50));
+        assert_eq!(cache.get_or_build(build.clone()).await.unwrap(), (0, true));
+        assert!(cache.is_building());
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!cache.is_building());
+        assert_eq!(cache.get(), Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_refreshes_before_expiry() {
+        let cache = RefreshingCache::new(Duration::from_millis(200))
+            .with_refresh_ahead(Duration::from_millis(150));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let build = counting_build(&calls, Duration::ZERO);
+
+        cache.get_or_build(build.clone()).await.unwrap();
+        // The first access schedules a refresh 50ms in
+        assert_eq!(cache.get_or_build(build).await.unwrap(), (1, true));
+
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(cache.get(), Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_build_invalidated_while_running_is_not_stored() {
+        let cache = RefreshingCache::new(Duration::from_secs(60));
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        cache.insert(0, Duration::from_secs(120));
+        cache.refresh(counting_build(&calls, Duration::from_millis(50)));
+        cache.discard_pending();
+        assert!(!cache.is_building());
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(cache.get(), Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_failed_refresh_keeps_serving_value() {
+        let cache = RefreshingCache::new(Duration::from_secs(60));
+        cache.insert(7, Duration::from_secs(120));
+
+        let failing = || async { Err::<usize, _>(DexApiError::Timeout) };
+        assert_eq!(cache.get_or_build(failing).await.unwrap(), (7, true));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(cache.get(), Some(7));
+
+        cache.invalidate();
+        assert!(matches!(
+            cache.get_or_build(failing).await,
+            Err(DexApiError::Timeout)
+        ));
+    }
+}
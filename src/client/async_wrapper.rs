@@ -38,10 +38,12 @@ pub trait AsyncDexClient: Send + Sync {
     async fn update_contact(&self, id: &str, contact: &Contact) -> DexApiResult<Contact>;
     async fn delete_contact(&self, id: &str) -> DexApiResult<()>;
 
+    async fn get_note(&self, id: &str) -> DexApiResult<Note>;
     async fn create_note(&self, note: &Note) -> DexApiResult<Note>;
     async fn update_note(&self, id: &str, note: &Note) -> DexApiResult<Note>;
     async fn delete_note(&self, id: &str) -> DexApiResult<()>;
 
+    async fn get_reminder(&self, id: &str) -> DexApiResult<Reminder>;
     async fn create_reminder(&self, reminder: &Reminder) -> DexApiResult<Reminder>;
     async fn update_reminder(&self, id: &str, reminder: &Reminder) -> DexApiResult<Reminder>;
     async fn delete_reminder(&self, id: &str) -> DexApiResult<()>;
@@ -151,6 +153,15 @@ impl AsyncDexClient for AsyncDexClientImpl {
             .map_err(|e| DexApiError::HttpError(format!("Task join error: {}", e)))?
     }
 
+    async fn get_note(&self, id: &str) -> DexApiResult<Note> {
+        let client = self.client.clone();
+        let id = id.to_string();
+
+        tokio::task::spawn_blocking(move || client.get_note(&id))
+            .await
+            .map_err(|e| DexApiError::HttpError(format!("Task join error: {}", e)))?
+    }
+
     async fn create_note(&self, note: &Note) -> DexApiResult<Note> {
         let client = self.client.clone();
         let note = note.clone();
@@ -179,6 +190,15 @@ impl AsyncDexClient for AsyncDexClientImpl {
             .map_err(|e| DexApiError::HttpError(format!("Task join error: {}", e)))?
     }
 
+    async fn get_reminder(&self, id: &str) -> DexApiResult<Reminder> {
+        let client = self.client.clone();
+        let id = id.to_string();
+
+        tokio::task::spawn_blocking(move || client.get_reminder(&id))
+            .await
+            .map_err(|e| DexApiError::HttpError(format!("Task join error: {}", e)))?
+    }
+
     async fn create_reminder(&self, reminder: &Reminder) -> DexApiResult<Reminder> {
         let client = self.client.clone();
         let reminder = reminder.clone();
@@ -223,6 +243,7 @@ mod tests {
             max_match_results: 5,
             match_confidence_threshold: 50,
             log_level: "error".to_string(),
+            ..Default::default()
         };
         let client = DexClient::new(&config);
         let async_client = AsyncDexClientImpl::new(client);
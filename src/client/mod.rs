@@ -866,6 +866,7 @@ mod tests {
             max_match_results: 5,
             match_confidence_threshold: 30,
             log_level: "error".to_string(),
+            ..Default::default()
         };
 
         let client = DexClient::new(&config);
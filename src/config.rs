@@ -4,8 +4,12 @@
 //! It avoids polluting stdout (which MCP uses for communication) by manually parsing
 //! the .env file if present.
 
+use crate::domain::PhoneRegion;
 use crate::error::{ConfigError, ConfigResult};
+use crate::matching::MatchWeights;
+use crate::search::Language;
 use std::env;
+use std::path::PathBuf;
 
 /// Configuration for the Dex MCP Server.
 #[derive(Debug, Clone)]
@@ -31,6 +35,41 @@ pub struct Config {
 
     /// Log level (default: "error")
     pub log_level: String,
+
+    /// Path of the append-only JSONL audit log for mutations
+    /// (default: `~/.dex-mcp-server/audit.jsonl`)
+    pub audit_log_path: PathBuf,
+
+    /// Directory for cache files such as the search index snapshot
+    /// (default: `~/.dex-mcp-server/cache`)
+    pub cache_dir: PathBuf,
+
+    /// Maximum age in minutes of a search index snapshot loaded at startup
+    /// (default: 1440, `0` disables snapshots)
+    pub search_index_max_age_minutes: u64,
+
+    /// Language for stemming and stopwords in full-text search
+    /// (default: English, `None` disables both)
+    pub search_language: Option<Language>,
+
+    /// Whether full-text search drops stopwords (default: true)
+    pub search_stopwords: bool,
+
+    /// Path of the user-editable search synonyms file
+    /// (default: `~/.dex-mcp-server/synonyms.txt`)
+    pub search_synonyms_path: PathBuf,
+
+    /// Path of the user-editable nickname file for name matching
+    /// (default: `~/.dex-mcp-server/nicknames.txt`)
+    pub nicknames_path: PathBuf,
+
+    /// Region assumed for phone numbers without a country code
+    /// (default: US)
+    pub default_phone_region: PhoneRegion,
+
+    /// Weights of agreeing and contradicting signals in contact matching
+    /// (default: [`MatchWeights::default`])
+    pub match_weights: MatchWeights,
 }
 
 impl Config {
@@ -46,6 +85,16 @@ impl Config {
     /// - `MAX_MATCH_RESULTS`: Max fuzzy match results (default: 5)
     /// - `MATCH_CONFIDENCE_THRESHOLD`: Min confidence score (default: 30)
     /// - `LOG_LEVEL`: Logging level (default: "error")
+    /// - `DEX_AUDIT_LOG_PATH`: Audit log file (default: `~/.dex-mcp-server/audit.jsonl`)
+    /// - `DEX_CACHE_DIR`: Cache directory (default: `~/.dex-mcp-server/cache`)
+    /// - `DEX_SEARCH_INDEX_MAX_AGE_MINUTES`: Max search index snapshot age (default: 1440)
+    /// - `DEX_SEARCH_LANGUAGE`: Search stemming language, or `none` (default: `english`)
+    /// - `DEX_SEARCH_STOPWORDS`: Drop stopwords in search (default: true)
+    /// - `DEX_SEARCH_SYNONYMS_PATH`: Synonyms file (default: `~/.dex-mcp-server/synonyms.txt`)
+    /// - `DEX_NICKNAMES_PATH`: Nicknames file (default: `~/.dex-mcp-server/nicknames.txt`)
+    /// - `DEX_DEFAULT_PHONE_REGION`: Country code for phone numbers without one (default: US)
+    /// - `DEX_MATCH_WEIGHTS`: Overrides of match signal weights, such as
+    ///   `name.contradict=0.6,company.agree=0.3` (default: none)
     pub fn from_env() -> ConfigResult<Self> {
         // Try to load .env file if it exists (but don't fail if it doesn't)
         // We use dotenvy::dotenv() which doesn't print to stdout
@@ -88,6 +137,27 @@ impl Config {
 
         let log_level = env::var("LOG_LEVEL").unwrap_or_else(|_| "error".to_string());
 
+        let audit_log_path = env::var("DEX_AUDIT_LOG_PATH")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| Self::default_data_dir().join("audit.jsonl"));
+
+        let cache_dir = env::var("DEX_CACHE_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| Self::default_data_dir().join("cache"));
+        let search_index_max_age_minutes =
+            Self::parse_env_u64("DEX_SEARCH_INDEX_MAX_AGE_MINUTES", 1440)?;
+
+        let search_language = Self::parse_env_language("DEX_SEARCH_LANGUAGE")?;
+        let search_stopwords = Self::parse_env_bool("DEX_SEARCH_STOPWORDS", true)?;
+        let search_synonyms_path = env::var("DEX_SEARCH_SYNONYMS_PATH")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| Self::default_data_dir().join("synonyms.txt"));
+        let nicknames_path = env::var("DEX_NICKNAMES_PATH")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| Self::default_data_dir().join("nicknames.txt"));
+        let default_phone_region = Self::parse_env_phone_region("DEX_DEFAULT_PHONE_REGION")?;
+        let match_weights = Self::parse_env_match_weights("DEX_MATCH_WEIGHTS")?;
+
         Ok(Config {
             dex_api_url,
             dex_api_key,
@@ -96,9 +166,34 @@ impl Config {
             max_match_results,
             match_confidence_threshold,
             log_level,
+            audit_log_path,
+            cache_dir,
+            search_index_max_age_minutes,
+            search_language,
+            search_stopwords,
+            search_synonyms_path,
+            nicknames_path,
+            default_phone_region,
+            match_weights,
         })
     }
 
+    /// Path of the search index snapshot, or `None` if snapshots are disabled.
+    pub fn search_index_snapshot_path(&self) -> Option<PathBuf> {
+        (self.search_index_max_age_minutes > 0).then(|| self.cache_dir.join("search_index.json"))
+    }
+
+    /// Directory for files the server keeps between runs.
+    ///
+    /// Uses `~/.dex-mcp-server`, falling back to the system temp directory
+    /// when no home directory is available.
+    pub fn default_data_dir() -> PathBuf {
+        env::var("HOME")
+            .or_else(|_| env::var("USERPROFILE"))
+            .map(|home| PathBuf::from(home).join(".dex-mcp-server"))
+            .unwrap_or_else(|_| env::temp_dir().join("dex-mcp-server"))
+    }
+
     /// Parse an environment variable as u64 with a default value.
     fn parse_env_u64(var_name: &str, default: u64) -> ConfigResult<u64> {
         match env::var(var_name) {
@@ -131,6 +226,70 @@ impl Config {
             Err(_) => Ok(default),
         }
     }
+
+    /// Parse an environment variable as a boolean with a default value.
+    fn parse_env_bool(var_name: &str, default: bool) -> ConfigResult<bool> {
+        match env::var(var_name) {
+            Ok(val) => match val.trim().to_ascii_lowercase().as_str() {
+                "1" | "true" | "yes" | "on" => Ok(true),
+                "0" | "false" | "no" | "off" => Ok(false),
+                _ => Err(ConfigError::InvalidValue {
+                    var: var_name.to_string(),
+                    reason: format!("Must be true or false, got: {}", val),
+                }),
+            },
+            Err(_) => Ok(default),
+        }
+    }
+
+    /// Parse an environment variable as a search language, English by
+    /// default; `none` disables language-specific analysis.
+    fn parse_env_language(var_name: &str) -> ConfigResult<Option<Language>> {
+        match env::var(var_name) {
+            Ok(val) if val.trim().eq_ignore_ascii_case("none") => Ok(None),
+            Ok(val) => {
+                Language::from_name(val.trim())
+                    .map(Some)
+                    .ok_or_else(|| ConfigError::InvalidValue {
+                        var: var_name.to_string(),
+                        reason: format!(
+                            "Must be one of {} or none, got: {}",
+                            Language::ALL
+                                .iter()
+                                .map(Language::name)
+                                .collect::<Vec<_>>()
+                                .join(", "),
+                            val
+                        ),
+                    })
+            }
+            Err(_) => Ok(Some(Language::English)),
+        }
+    }
+
+    /// Parse a phone region (ISO 3166 country code) from an environment
+    /// variable, defaulting to US.
+    fn parse_env_phone_region(var_name: &str) -> ConfigResult<PhoneRegion> {
+        match env::var(var_name) {
+            Ok(val) => val.parse().map_err(|_| ConfigError::InvalidValue {
+                var: var_name.to_string(),
+                reason: format!("Must be a two-letter country code, got: {}", val),
+            }),
+            Err(_) => Ok(PhoneRegion::default()),
+        }
+    }
+
+    /// Parse overrides of the match signal weights from an environment
+    /// variable, defaulting to the default weights.
+    fn parse_env_match_weights(var_name: &str) -> ConfigResult<MatchWeights> {
+        match env::var(var_name) {
+            Ok(val) => val.parse().map_err(|reason| ConfigError::InvalidValue {
+                var: var_name.to_string(),
+                reason,
+            }),
+            Err(_) => Ok(MatchWeights::default()),
+        }
+    }
 }
 
 impl Default for Config {
@@ -143,6 +302,15 @@ impl Default for Config {
             max_match_results: 5,
             match_confidence_threshold: 30,
             log_level: "error".to_string(),
+            audit_log_path: Self::default_data_dir().join("audit.jsonl"),
+            cache_dir: Self::default_data_dir().join("cache"),
+            search_index_max_age_minutes: 1440,
+            search_language: Some(Language::English),
+            search_stopwords: true,
+            search_synonyms_path: Self::default_data_dir().join("synonyms.txt"),
+            nicknames_path: Self::default_data_dir().join("nicknames.txt"),
+            default_phone_region: PhoneRegion::default(),
+            match_weights: MatchWeights::default(),
         }
     }
 }
@@ -186,6 +354,21 @@ mod tests {
         assert_eq!(config.match_confidence_threshold, 30);
     }
 
+    #[test]
+    fn test_search_index_snapshot_path() {
+        let mut config = Config {
+            cache_dir: PathBuf::from("/tmp/dex-cache"),
+            ..Config::default()
+        };
+        assert_eq!(
+            config.search_index_snapshot_path(),
+            Some(PathBuf::from("/tmp/dex-cache/search_index.json"))
+        );
+
+        config.search_index_max_age_minutes = 0;
+        assert_eq!(config.search_index_snapshot_path(), None);
+    }
+
     #[test]
     #[serial]
     fn test_config_from_env_missing_required() {
@@ -306,6 +489,64 @@ mod tests {
         assert_eq!(result.unwrap(), 10);
     }
 
+    #[test]
+    #[serial]
+    fn test_parse_env_search_analysis() {
+        let mut guard = EnvGuard::new();
+        assert_eq!(
+            Config::parse_env_language("TEST_LANGUAGE").unwrap(),
+            Some(Language::English)
+        );
+        guard.set("TEST_LANGUAGE", "de");
+        assert_eq!(
+            Config::parse_env_language("TEST_LANGUAGE").unwrap(),
+            Some(Language::German)
+        );
+        guard.set("TEST_LANGUAGE", "None");
+        assert_eq!(Config::parse_env_language("TEST_LANGUAGE").unwrap(), None);
+        guard.set("TEST_LANGUAGE", "klingon");
+        assert!(Config::parse_env_language("TEST_LANGUAGE").is_err());
+
+        assert!(Config::parse_env_bool("TEST_BOOL", true).unwrap());
+        guard.set("TEST_BOOL", "false");
+        assert!(!Config::parse_env_bool("TEST_BOOL", true).unwrap());
+        guard.set("TEST_BOOL", "maybe");
+        assert!(Config::parse_env_bool("TEST_BOOL", true).is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn test_parse_env_phone_region() {
+        let mut guard = EnvGuard::new();
+        assert_eq!(
+            Config::parse_env_phone_region("TEST_PHONE_REGION").unwrap(),
+            PhoneRegion::default()
+        );
+        guard.set("TEST_PHONE_REGION", "gb");
+        assert_eq!(
+            Config::parse_env_phone_region("TEST_PHONE_REGION").unwrap(),
+            "GB".parse().unwrap()
+        );
+        guard.set("TEST_PHONE_REGION", "Atlantis");
+        assert!(Config::parse_env_phone_region("TEST_PHONE_REGION").is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn test_parse_env_match_weights() {
+        let mut guard = EnvGuard::new();
+        assert_eq!(
+            Config::parse_env_match_weights("TEST_MATCH_WEIGHTS").unwrap(),
+            MatchWeights::default()
+        );
+        guard.set("TEST_MATCH_WEIGHTS", "name.contradict=0.6");
+        let weights = Config::parse_env_match_weights("TEST_MATCH_WEIGHTS").unwrap();
+        assert_eq!(weights.name.contradict, 0.6);
+        assert_eq!(weights.email, MatchWeights::default().email);
+        guard.set("TEST_MATCH_WEIGHTS", "name.contradict=2");
+        assert!(Config::parse_env_match_weights("TEST_MATCH_WEIGHTS").is_err());
+    }
+
     #[test]
     #[serial]
     fn test_parse_env_u64_invalid() {
@@ -4,18 +4,67 @@ use super::errors::ValidationError;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 
+/// How a mail provider delivers the addresses of one of its domains.
+///
+/// All providers listed deliver `user+tag` to `user`.
+struct MailProvider {
+    /// Domain of the addresses, in ASCII form
+    domain: &'static str,
+
+    /// Domain of the mailbox they are delivered to
+    mailbox_domain: &'static str,
+
+    /// Whether dots in the local part are ignored
+    ignores_dots: bool,
+}
+
+impl MailProvider {
+    const fn new(domain: &'static str, mailbox_domain: &'static str, ignores_dots: bool) -> Self {
+        Self {
+            domain,
+            mailbox_domain,
+            ignores_dots,
+        }
+    }
+}
+
+/// Providers whose addresses have other spellings than the plain lowercase
+/// one. Everywhere else, `user+tag` may be a different mailbox from `user`.
+const MAIL_PROVIDERS: &[MailProvider] = &[
+    MailProvider::new("gmail.com", "gmail.com", true),
+    MailProvider::new("googlemail.com", "gmail.com", true),
+    MailProvider::new("outlook.com", "outlook.com", false),
+    MailProvider::new("hotmail.com", "hotmail.com", false),
+    MailProvider::new("live.com", "live.com", false),
+    MailProvider::new("msn.com", "msn.com", false),
+    MailProvider::new("icloud.com", "icloud.com", false),
+    MailProvider::new("me.com", "me.com", false),
+    MailProvider::new("mac.com", "mac.com", false),
+    MailProvider::new("fastmail.com", "fastmail.com", false),
+    MailProvider::new("fastmail.fm", "fastmail.fm", false),
+    MailProvider::new("proton.me", "proton.me", false),
+    MailProvider::new("protonmail.com", "protonmail.com", false),
+    MailProvider::new("pm.me", "pm.me", false),
+    MailProvider::new("zoho.com", "zoho.com", false),
+];
+
 /// A type-safe wrapper for email addresses.
 ///
 /// This ensures that email addresses are validated at construction time.
 /// The validation is basic and checks for the presence of '@' and a domain part.
 ///
+/// The address is kept as written; [`canonical`](Self::canonical) gives the
+/// form to compare addresses by, so that spellings of the same mailbox are
+/// equal.
+///
 /// # Example
 ///
 /// ```
 /// use dex_mcp_server::domain::EmailAddress;
 ///
-/// let email = EmailAddress::new("user@example.com").unwrap();
-/// assert_eq!(email.as_str(), "user@example.com");
+/// let email = EmailAddress::new("John.Doe+crm@googlemail.com").unwrap();
+/// assert_eq!(email.as_str(), "John.Doe+crm@googlemail.com");
+/// assert_eq!(email.canonical(), "johndoe@gmail.com");
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct EmailAddress(String);
@@ -101,6 +150,44 @@ impl EmailAddress {
             .nth(1)
             .expect("email validated to contain '@'")
     }
+
+    /// Get the domain in lowercase ASCII form, with internationalized
+    /// domain names converted to Punycode (`bücher.de` becomes
+    /// `xn--bcher-kva.de`).
+    ///
+    /// Domains that are not valid IDNs are only lowercased.
+    pub fn ascii_domain(&self) -> String {
+        let domain = self.domain().trim_end_matches('.');
+        idna::domain_to_ascii(domain).unwrap_or_else(|_| domain.to_lowercase())
+    }
+
+    /// Get the form of the address to compare by.
+    ///
+    /// The address is lowercased and its domain converted to ASCII. At the
+    /// large webmail providers, other spellings of the same mailbox are
+    /// folded too: Gmail ignores dots and treats `googlemail.com` as
+    /// `gmail.com`, and Gmail, Outlook, iCloud, Fastmail, Proton and Zoho
+    /// deliver `user+tag` to `user`.
+    pub fn canonical(&self) -> String {
+        let domain = self.ascii_domain();
+        let mut local = self.local_part().to_lowercase();
+
+        let Some(provider) = MAIL_PROVIDERS.iter().find(|p| p.domain == domain) else {
+            return format!("{}@{}", local, domain);
+        };
+        if let Some(tag) = local.find('+').filter(|&tag| tag > 0) {
+            local.truncate(tag);
+        }
+        if provider.ignores_dots {
+            local.retain(|c| c != '.');
+        }
+        format!("{}@{}", local, provider.mailbox_domain)
+    }
+
+    /// Whether both addresses reach the same mailbox.
+    pub fn is_same_mailbox(&self, other: &EmailAddress) -> bool {
+        self.canonical() == other.canonical()
+    }
 }
 
 // Serde support - serialize as string
@@ -159,6 +246,29 @@ mod tests {
         assert_eq!(email.domain(), "example.com");
     }
 
+    #[test]
+    fn test_email_canonical() {
+        let canonical = |s| EmailAddress::new(s).unwrap().canonical();
+        assert_eq!(canonical("User@Example.COM"), "user@example.com");
+        assert_eq!(canonical("john.doe+crm@gmail.com"), "johndoe@gmail.com");
+        assert_eq!(canonical("JohnDoe@GoogleMail.com"), "johndoe@gmail.com");
+        assert_eq!(canonical("jane+news@outlook.com"), "jane@outlook.com");
+        assert_eq!(canonical("jane.doe@outlook.com"), "jane.doe@outlook.com");
+        assert_eq!(canonical("+tag@gmail.com"), "+tag@gmail.com");
+
+        // Other servers may deliver tagged addresses elsewhere
+        assert_eq!(canonical("jane+news@example.com"), "jane+news@example.com");
+    }
+
+    #[test]
+    fn test_email_idn_domain() {
+        let email = EmailAddress::new("Max@Bücher.de").unwrap();
+        assert_eq!(email.domain(), "Bücher.de");
+        assert_eq!(email.ascii_domain(), "xn--bcher-kva.de");
+        assert!(email.is_same_mailbox(&EmailAddress::new("max@xn--bcher-kva.de").unwrap()));
+        assert!(!email.is_same_mailbox(&EmailAddress::new("max@bucher.de").unwrap()));
+    }
+
     #[test]
     fn test_email_display() {
         let email = EmailAddress::new("user@example.com").unwrap();
@@ -13,6 +13,9 @@ pub enum ValidationError {
 
     /// The provided phone number is invalid.
     InvalidPhone(String),
+
+    /// The provided phone region is not a known ISO 3166 country code.
+    InvalidPhoneRegion(String),
 }
 
 impl fmt::Display for ValidationError {
@@ -21,6 +24,7 @@ impl fmt::Display for ValidationError {
             Self::EmptyId => write!(f, "ID cannot be empty"),
             Self::InvalidEmail(email) => write!(f, "Invalid email address: {}", email),
             Self::InvalidPhone(phone) => write!(f, "Invalid phone number: {}", phone),
+            Self::InvalidPhoneRegion(region) => write!(f, "Invalid phone region: {}", region),
         }
     }
 }
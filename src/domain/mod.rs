@@ -13,4 +13,4 @@ pub mod phone;
 pub use contact_id::ContactId;
 pub use email::EmailAddress;
 pub use errors::ValidationError;
-pub use phone::PhoneNumber;
+pub use phone::{PhoneNumber, PhoneRegion};
@@ -1,50 +1,158 @@
 //! PhoneNumber value object.
 
 use super::errors::ValidationError;
+use phonenumber::{country, Mode};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
+use std::str::FromStr;
+
+/// Fewest digits a national number may have to be taken as parsed.
+///
+/// libphonenumber reads almost any run of digits as some number ("12345"
+/// becomes "+1 2345"), so shorter results are treated as unparseable.
+const MIN_NATIONAL_DIGITS: usize = 7;
+
+/// The region assumed for phone numbers written without a country code.
+///
+/// A two-letter ISO 3166 country code such as `US` or `GB`, parsed
+/// case-insensitively. Defaults to `US`.
+///
+/// # Example
+///
+/// ```
+/// use dex_mcp_server::domain::PhoneRegion;
+///
+/// let region: PhoneRegion = "gb".parse().unwrap();
+/// assert_eq!(region.to_string(), "GB");
+/// assert!("XX".parse::<PhoneRegion>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PhoneRegion(country::Id);
+
+impl Default for PhoneRegion {
+    fn default() -> Self {
+        Self(country::Id::US)
+    }
+}
+
+impl FromStr for PhoneRegion {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim()
+            .to_ascii_uppercase()
+            .parse()
+            .map(Self)
+            .map_err(|_| ValidationError::InvalidPhoneRegion(s.to_string()))
+    }
+}
+
+impl fmt::Display for PhoneRegion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0.as_ref())
+    }
+}
 
 /// A type-safe wrapper for phone numbers.
 ///
 /// This ensures that phone numbers are validated at construction time.
-/// The validation is basic and checks that the number contains at least
-/// some digits and optional formatting characters.
+/// Numbers are parsed with libphonenumber: a number without a country code
+/// is read in the given [`PhoneRegion`], international prefixes (`+`, `00`,
+/// `011`) and extensions (`x89`, `ext. 89`) are recognized, and the number
+/// is available in E.164, national and international form. The original
+/// text is kept as written.
 ///
 /// # Example
 ///
 /// ```
 /// use dex_mcp_server::domain::PhoneNumber;
 ///
-/// let phone = PhoneNumber::new("+1-555-1234").unwrap();
-/// assert_eq!(phone.as_str(), "+1-555-1234");
+/// let phone = PhoneNumber::new("(415) 555-2671 ext. 89").unwrap();
+/// assert_eq!(phone.as_str(), "(415) 555-2671 ext. 89");
+/// assert_eq!(phone.e164(), Some("+14155552671"));
+/// assert_eq!(phone.international(), Some("+1 415-555-2671"));
+/// assert_eq!(phone.extension(), Some("89"));
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct PhoneNumber(String);
+pub struct PhoneNumber {
+    raw: String,
+    parsed: Option<ParsedPhone>,
+}
+
+/// The formatted forms of a phone number libphonenumber could parse.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ParsedPhone {
+    e164: String,
+    national: String,
+    international: String,
+    extension: Option<String>,
+}
 
 impl PhoneNumber {
-    /// Create a new PhoneNumber, validating the format.
+    /// Create a new PhoneNumber, reading it in the default region (US).
+    ///
+    /// # Errors
+    ///
+    /// Returns `ValidationError::InvalidPhone` if the phone format is invalid.
+    pub fn new(phone: impl Into<String>) -> Result<Self, ValidationError> {
+        Self::parse(phone, PhoneRegion::default())
+    }
+
+    /// Create a new PhoneNumber, reading numbers without a country code in
+    /// `region`.
     ///
     /// # Validation Rules
     ///
     /// - Must contain at least one digit
-    /// - Can contain: digits, spaces, hyphens, parentheses, plus sign, periods
-    /// - Must not be empty
+    /// - Must either parse as a phone number, or contain only digits,
+    ///   spaces, hyphens, parentheses, plus signs and periods
+    ///
+    /// Numbers that are valid but not parseable (too short, say) keep only
+    /// their digits for comparison.
     ///
     /// # Errors
     ///
     /// Returns `ValidationError::InvalidPhone` if the phone format is invalid.
-    pub fn new(phone: impl Into<String>) -> Result<Self, ValidationError> {
-        let phone = phone.into();
+    pub fn parse(phone: impl Into<String>, region: PhoneRegion) -> Result<Self, ValidationError> {
+        let raw = phone.into();
+        let parsed = Self::parse_number(&raw, region);
+
+        if !Self::is_valid(&raw, parsed.is_some()) {
+            return Err(ValidationError::InvalidPhone(raw));
+        }
+
+        Ok(Self { raw, parsed })
+    }
 
-        if !Self::is_valid(&phone) {
-            return Err(ValidationError::InvalidPhone(phone));
+    /// Parse with libphonenumber, if the result is long enough to trust.
+    fn parse_number(raw: &str, region: PhoneRegion) -> Option<ParsedPhone> {
+        // "+44 (0)20 ...": the trunk prefix is only dialled within the country
+        let number = phonenumber::parse(Some(region.0), raw.replace("(0)", "")).ok()?;
+        if number.national().to_string().len() < MIN_NATIONAL_DIGITS {
+            return None;
         }
 
-        Ok(Self(phone))
+        // Formatting modes include the extension; the forms exposed here
+        // carry it separately
+        let extension = number.extension().map(|ext| ext.to_string());
+        let format = |mode| {
+            let formatted = number.format().mode(mode).to_string();
+            match formatted.find(" ext. ") {
+                Some(at) => formatted[..at].to_string(),
+                None => formatted,
+            }
+        };
+
+        Some(ParsedPhone {
+            e164: format(Mode::E164),
+            national: format(Mode::National),
+            international: format(Mode::International),
+            extension,
+        })
     }
 
     /// Validate phone format.
-    fn is_valid(phone: &str) -> bool {
+    fn is_valid(phone: &str, parsed: bool) -> bool {
         if phone.is_empty() {
             return false;
         }
@@ -54,31 +162,72 @@ impl PhoneNumber {
             return false;
         }
 
-        // All characters must be valid phone number characters
-        phone.chars().all(|c| {
-            c.is_ascii_digit()
-                || c == ' '
-                || c == '-'
-                || c == '('
-                || c == ')'
-                || c == '+'
-                || c == '.'
-        })
+        // All characters must be valid phone number characters, unless
+        // libphonenumber understood the rest (extensions, vanity letters)
+        parsed
+            || phone.chars().all(|c| {
+                c.is_ascii_digit()
+                    || c == ' '
+                    || c == '-'
+                    || c == '('
+                    || c == ')'
+                    || c == '+'
+                    || c == '.'
+            })
     }
 
-    /// Get the phone number as a string slice.
+    /// Get the phone number as a string slice, as written.
     pub fn as_str(&self) -> &str {
-        &self.0
+        &self.raw
     }
 
-    /// Convert into the underlying String.
+    /// Convert into the underlying String, as written.
     pub fn into_inner(self) -> String {
-        self.0
+        self.raw
     }
 
     /// Get the phone number with only digits (no formatting).
     pub fn digits_only(&self) -> String {
-        self.0.chars().filter(|c| c.is_ascii_digit()).collect()
+        self.raw.chars().filter(|c| c.is_ascii_digit()).collect()
+    }
+
+    /// The number in E.164 form (`+14155552671`), without the extension.
+    pub fn e164(&self) -> Option<&str> {
+        self.parsed.as_ref().map(|p| p.e164.as_str())
+    }
+
+    /// The number as dialled within its country (`(415) 555-2671`).
+    pub fn national(&self) -> Option<&str> {
+        self.parsed.as_ref().map(|p| p.national.as_str())
+    }
+
+    /// The number as dialled from abroad (`+1 415-555-2671`).
+    pub fn international(&self) -> Option<&str> {
+        self.parsed.as_ref().map(|p| p.international.as_str())
+    }
+
+    /// The extension, if the number has one.
+    pub fn extension(&self) -> Option<&str> {
+        self.parsed.as_ref()?.extension.as_deref()
+    }
+
+    /// The number to compare with other numbers: the E.164 form if the
+    /// number could be parsed, otherwise its digits. Excludes the extension.
+    pub fn comparable(&self) -> String {
+        self.e164()
+            .map_or_else(|| self.digits_only(), str::to_string)
+    }
+
+    /// Whether both numbers reach the same phone.
+    ///
+    /// The numbers must be the same; extensions must be the same too, unless
+    /// one of the numbers has none.
+    pub fn is_same_number(&self, other: &PhoneNumber) -> bool {
+        self.comparable() == other.comparable()
+            && match (self.extension(), other.extension()) {
+                (Some(a), Some(b)) => a == b,
+                _ => true,
+            }
     }
 }
 
@@ -88,7 +237,7 @@ impl Serialize for PhoneNumber {
     where
         S: Serializer,
     {
-        self.0.serialize(serializer)
+        self.raw.serialize(serializer)
     }
 }
 
@@ -106,7 +255,7 @@ impl<'de> Deserialize<'de> for PhoneNumber {
 // Display support
 impl fmt::Display for PhoneNumber {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", self.raw)
     }
 }
 
@@ -129,6 +278,8 @@ mod tests {
         assert!(PhoneNumber::new("555.123.4567").is_ok());
         assert!(PhoneNumber::new("+14155551234").is_ok());
         assert!(PhoneNumber::new("invalid@phone").is_err());
+        assert!(PhoneNumber::new("415-555-2671 x89").is_ok());
+        assert!(PhoneNumber::new("1-800-FLOWERS").is_ok());
     }
 
     #[test]
@@ -137,6 +288,47 @@ mod tests {
         assert_eq!(phone.digits_only(), "15551234567");
     }
 
+    #[test]
+    fn test_phone_formats() {
+        let phone = PhoneNumber::new("555-123-4567").unwrap();
+        assert_eq!(phone.e164(), Some("+15551234567"));
+        assert_eq!(phone.national(), Some("(555) 123-4567"));
+        assert_eq!(phone.international(), Some("+1 555-123-4567"));
+        assert_eq!(phone.extension(), None);
+
+        let phone = PhoneNumber::new("011 44 20 7123 4567").unwrap();
+        assert_eq!(phone.e164(), Some("+442071234567"));
+        assert_eq!(phone.national(), Some("020 7123 4567"));
+        let phone = PhoneNumber::new("+44 (0)20 7123 4567").unwrap();
+        assert_eq!(phone.e164(), Some("+442071234567"));
+
+        let short = PhoneNumber::new("12345").unwrap();
+        assert_eq!(short.e164(), None);
+        assert_eq!(short.comparable(), "12345");
+    }
+
+    #[test]
+    fn test_phone_region() {
+        let region: PhoneRegion = "gb".parse().unwrap();
+        let phone = PhoneNumber::parse("020 7123 4567", region).unwrap();
+        assert_eq!(phone.e164(), Some("+442071234567"));
+        assert_eq!(PhoneRegion::default().to_string(), "US");
+        assert_eq!(
+            "Narnia".parse::<PhoneRegion>(),
+            Err(ValidationError::InvalidPhoneRegion("Narnia".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_phone_same_number() {
+        let phone = |s| PhoneNumber::new(s).unwrap();
+        assert!(phone("+1 (555) 123-4567").is_same_number(&phone("555-123-4567")));
+        assert!(phone("555-123-4567 x89").is_same_number(&phone("555-123-4567")));
+        assert!(phone("555-123-4567 x89").is_same_number(&phone("555.123.4567 ext. 89")));
+        assert!(!phone("555-123-4567 x89").is_same_number(&phone("555-123-4567 x90")));
+        assert!(!phone("555-123-4567").is_same_number(&phone("555-123-4568")));
+    }
+
     #[test]
     fn test_phone_display() {
         let phone = PhoneNumber::new("+1-555-1234").unwrap();
@@ -39,6 +39,23 @@ pub enum DexApiError {
     #[error("Invalid request: {0}")]
     InvalidRequest(String),
 
+    /// The record changed between being read and written
+    #[error(
+        "Conflict updating {id}: expected version {}, found {}{}",
+        .expected.as_deref().unwrap_or("none"),
+        .actual.as_deref().unwrap_or("none"),
+        conflict_fields_suffix(.fields)
+    )]
+    Conflict {
+        id: String,
+        /// Version (`updated_at`) the change was based on
+        expected: Option<String>,
+        /// Version found when writing
+        actual: Option<String>,
+        /// Fields changed by both sides, if a merge was attempted
+        fields: Vec<String>,
+    },
+
     /// Generic API error with context
     #[error("API error: {0}")]
     Other(String),
@@ -99,11 +116,86 @@ pub enum SearchError {
     #[error("Search execution failed: {0}")]
     ExecutionError(String),
 
+    /// Query text could not be parsed
+    #[error("Invalid query at column {column}: {message}")]
+    QueryParse { column: usize, message: String },
+
+    /// Synonyms file could not be read
+    #[error("Failed to read synonyms file {}: {source}", path.display())]
+    Synonyms {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+
     /// Generic search error
     #[error("Search error: {0}")]
     Other(String),
 }
 
+/// Errors that can occur when reading or writing the audit log.
+#[derive(Error, Debug)]
+pub enum AuditError {
+    /// Failed to read or append to the log file
+    #[error("Audit log I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Failed to serialize or parse a log entry
+    #[error("Audit log serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    /// Invalid timestamp in a query filter
+    #[error("Invalid timestamp: {0}")]
+    InvalidTimestamp(String),
+}
+
+/// Errors that can occur when reading or writing the search index snapshot.
+#[derive(Error, Debug)]
+pub enum SnapshotError {
+    /// Failed to read or write the snapshot file
+    #[error("Snapshot I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Failed to serialize or parse the snapshot
+    #[error("Snapshot serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    /// Snapshot was written by an incompatible version of the server
+    #[error("Snapshot schema version {found} is not supported (expected {expected})")]
+    SchemaVersion { found: u32, expected: u32 },
+
+    /// Snapshot is older than the configured maximum age
+    #[error("Snapshot is {age_secs}s old (maximum {max_age_secs}s)")]
+    Expired { age_secs: u64, max_age_secs: u64 },
+}
+
+fn conflict_fields_suffix(fields: &[String]) -> String {
+    if fields.is_empty() {
+        String::new()
+    } else {
+        format!(" (both changed: {})", fields.join(", "))
+    }
+}
+
+impl From<AuditError> for DexApiError {
+    fn from(e: AuditError) -> Self {
+        match e {
+            AuditError::InvalidTimestamp(_) => DexApiError::InvalidRequest(e.to_string()),
+            _ => DexApiError::Other(e.to_string()),
+        }
+    }
+}
+
+impl From<SearchError> for DexApiError {
+    fn from(e: SearchError) -> Self {
+        match e {
+            SearchError::InvalidParameters(_) | SearchError::QueryParse { .. } => {
+                DexApiError::InvalidRequest(e.to_string())
+            }
+            _ => DexApiError::Other(e.to_string()),
+        }
+    }
+}
+
 /// Convenience type alias for Results with DexApiError
 pub type DexApiResult<T> = Result<T, DexApiError>;
 
@@ -116,6 +208,12 @@ pub type MatchingResult<T> = Result<T, MatchingError>;
 /// Convenience type alias for Results with SearchError
 pub type SearchResult<T> = Result<T, SearchError>;
 
+/// Convenience type alias for Results with AuditError
+pub type AuditResult<T> = Result<T, AuditError>;
+
+/// Convenience type alias for Results with SnapshotError
+pub type SnapshotResult<T> = Result<T, SnapshotError>;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,6 +234,9 @@ mod tests {
 
         let err = SearchError::IndexNotReady;
         assert_eq!(err.to_string(), "Search index not ready");
+
+        let err = AuditError::InvalidTimestamp("yesterday".to_string());
+        assert_eq!(err.to_string(), "Invalid timestamp: yesterday");
     }
 
     #[test]
@@ -147,4 +248,19 @@ mod tests {
         assert!(err.to_string().contains("404"));
         assert!(err.to_string().contains("Not found"));
     }
+
+    #[test]
+    fn test_conflict_display() {
+        let err = DexApiError::Conflict {
+            id: "c1".to_string(),
+            expected: Some("2024-01-01T00:00:00Z".to_string()),
+            actual: Some("2024-01-02T00:00:00Z".to_string()),
+            fields: vec!["company".to_string()],
+        };
+        assert_eq!(
+            err.to_string(),
+            "Conflict updating c1: expected version 2024-01-01T00:00:00Z, \
+             found 2024-01-02T00:00:00Z (both changed: company)"
+        );
+    }
 }
@@ -6,6 +6,7 @@
 //!
 //! # Architecture
 //!
+//! - **audit**: Append-only log of every mutation made through the server
 //! - **models**: Data structures for contacts, notes, and reminders
 //! - **error**: Custom error types for precise error handling
 //! - **config**: Configuration management from environment variables
@@ -14,8 +15,10 @@
 //! - **matching**: Fuzzy matching and search utilities (to be implemented)
 //! - **cache**: Caching implementations (to be implemented)
 //! - **server**: MCP protocol server (to be implemented)
+//! - **text**: Unicode normalization and tokenization for matching and search
 
 // Re-export commonly used types
+pub mod audit;
 pub mod cache;
 pub mod client;
 pub mod config;
@@ -29,8 +32,10 @@ pub mod repositories;
 pub mod search;
 pub mod server;
 pub mod services;
+pub mod text;
 pub mod tools;
 
+pub use audit::{AuditEntry, AuditLog};
 pub use cache::TimedCache;
 pub use client::DexClient;
 pub use config::Config;
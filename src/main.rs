@@ -5,12 +5,17 @@
 
 use anyhow::Result;
 use dex_mcp_server::client::{AsyncDexClient, AsyncDexClientImpl};
+use dex_mcp_server::matching::NicknameMap;
 use dex_mcp_server::repositories::{
-    ContactRepository, DexContactRepository, DexNoteRepository, DexReminderRepository,
-    NoteRepository, ReminderRepository,
+    AuditedContactRepository, AuditedNoteRepository, AuditedReminderRepository, ContactRepository,
+    DexContactRepository, DexNoteRepository, DexReminderRepository, NoteRepository,
+    ReminderRepository,
 };
-use dex_mcp_server::{Config, DexClient, DexMcpServer};
+use dex_mcp_server::search::{Analyzer, IndexSnapshotStore, SynonymMap};
+use dex_mcp_server::tools::{DiscoveryConfig, SearchCacheConfig};
+use dex_mcp_server::{AuditLog, Config, DexClient, DexMcpServer};
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{error, info};
 use tracing_subscriber::EnvFilter;
 
@@ -52,17 +57,91 @@ async fn main() -> Result<()> {
     let reminder_repo =
         Arc::new(DexReminderRepository::new(client.clone())) as Arc<dyn ReminderRepository>;
 
+    // Record every mutation in the audit log
+    let audit_log = Arc::new(AuditLog::new(config.audit_log_path.clone()));
+    info!("Audit log: {}", audit_log.path().display());
+
+    let contact_repo = Arc::new(AuditedContactRepository::new(
+        contact_repo,
+        audit_log.clone(),
+    )) as Arc<dyn ContactRepository>;
+    let note_repo = Arc::new(AuditedNoteRepository::new(note_repo, audit_log.clone()))
+        as Arc<dyn NoteRepository>;
+    let reminder_repo = Arc::new(AuditedReminderRepository::new(
+        reminder_repo,
+        audit_log.clone(),
+    )) as Arc<dyn ReminderRepository>;
+
     // Cache TTL configuration
     let cache_ttl_secs = config.cache_ttl_minutes * 60; // Convert minutes to seconds
 
+    // Keep the search index on disk for warm starts
+    let search_snapshot = config.search_index_snapshot_path().map(|path| {
+        info!("Search index snapshot: {}", path.display());
+        IndexSnapshotStore::new(
+            path,
+            Duration::from_secs(config.search_index_max_age_minutes * 60),
+        )
+    });
+
+    // Text analysis for full-text search: stemming, stopwords and synonyms
+    let mut analyzer = match config.search_language {
+        Some(language) => Analyzer::for_language(language),
+        None => Analyzer::new(),
+    };
+    if !config.search_stopwords {
+        analyzer = analyzer.without_stopwords();
+    }
+    match SynonymMap::load(&config.search_synonyms_path) {
+        Ok(synonyms) => {
+            info!(
+                "Loaded {} search synonyms from {}",
+                synonyms.len(),
+                config.search_synonyms_path.display()
+            );
+            analyzer = analyzer.with_synonyms(synonyms);
+        }
+        Err(e) => error!("Search synonyms not loaded: {}", e),
+    }
+
+    // Nicknames for name matching: the built-in table plus the user's file
+    let nicknames = match NicknameMap::load(&config.nicknames_path) {
+        Ok(nicknames) => {
+            info!(
+                "Loaded {} nicknames, including {}",
+                nicknames.len(),
+                config.nicknames_path.display()
+            );
+            Arc::new(nicknames)
+        }
+        Err(e) => {
+            error!(
+                "Nicknames not loaded from {}: {}",
+                config.nicknames_path.display(),
+                e
+            );
+            NicknameMap::builtin()
+        }
+    };
+
     // Create the MCP server (tools are constructed internally)
     let server = DexMcpServer::new(
         contact_repo,
         note_repo,
         reminder_repo,
+        audit_log,
         client,
-        cache_ttl_secs, // discovery cache TTL
-        cache_ttl_secs, // search cache TTL
+        DiscoveryConfig {
+            cache_ttl_secs,
+            nicknames,
+            phone_region: config.default_phone_region,
+            match_weights: config.match_weights,
+        },
+        SearchCacheConfig {
+            ttl_secs: cache_ttl_secs,
+            snapshot: search_snapshot,
+            analyzer: Arc::new(analyzer),
+        },
     );
 
     info!("Dex MCP Server initialized");
@@ -0,0 +1,429 @@
+//! Address-book-wide duplicate detection.
+//!
+//! Comparing every pair of contacts is quadratic, so contacts are first
+//! grouped into blocks by key: a normalized email address, the E.164 form of
+//! a phone number, the set of name words, or a single name word. Only contacts
+//! sharing a block are scored with [`ContactMatcher`], and pairs scoring at
+//! least the minimum confidence are joined into clusters.
+
+use super::fuzzy_matcher::{ContactMatcher, ContactQuery, MatchType};
+use crate::domain::PhoneRegion;
+use crate::models::Contact;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Default minimum confidence for two contacts to count as duplicates.
+pub const DEFAULT_DUPLICATE_CONFIDENCE: u8 = 80;
+
+/// Default maximum number of clusters returned.
+pub const DEFAULT_MAX_CLUSTERS: usize = 50;
+
+/// Blocks with more contacts than this are skipped.
+///
+/// Keys this common, such as a popular first name or a shared office
+/// number, say little about whether two contacts are the same person.
+pub const MAX_BLOCK_SIZE: usize = 100;
+
+/// Phone numbers with fewer digits are not used as blocking keys.
+const MIN_PHONE_DIGITS: usize = 7;
+
+/// Name words shorter than this only block as part of the whole name.
+const MIN_NAME_WORD_LENGTH: usize = 3;
+
+/// One reason two contacts were matched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateEvidence {
+    /// How the contacts matched
+    pub match_type: MatchType,
+
+    /// Confidence of this piece of evidence (0-100)
+    pub confidence: u8,
+
+    /// The email, phone, profile URL or names that matched
+    pub value: String,
+}
+
+/// Two contacts likely to be the same person.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateMatch {
+    /// ID of one contact
+    pub contact_id: String,
+
+    /// ID of the other contact
+    pub other_contact_id: String,
+
+    /// Confidence of the strongest evidence (0-100)
+    pub confidence: u8,
+
+    /// Every reason the contacts matched, strongest first
+    pub evidence: Vec<DuplicateEvidence>,
+}
+
+/// A group of contacts likely to be the same person.
+#[derive(Debug, Clone)]
+pub struct DuplicateCluster {
+    /// The contacts, by name
+    pub contacts: Vec<Contact>,
+
+    /// Confidence of the weakest match holding the cluster together
+    pub confidence: u8,
+
+    /// Every matching pair within the cluster, strongest first
+    pub matches: Vec<DuplicateMatch>,
+}
+
+/// Result of scanning an address book for duplicates.
+#[derive(Debug, Clone)]
+pub struct DuplicateReport {
+    /// Clusters, most confident and then largest first
+    pub clusters: Vec<DuplicateCluster>,
+
+    /// Number of clusters found, before limiting
+    pub total_clusters: usize,
+
+    /// Number of contacts scanned
+    pub contacts_scanned: usize,
+
+    /// Number of contact pairs scored
+    pub pairs_compared: usize,
+}
+
+/// Find clusters of duplicate contacts.
+///
+/// # Arguments
+/// * `matcher` - Matcher used to score candidate pairs
+/// * `contacts` - The address book
+/// * `min_confidence` - Minimum confidence for a pair to be duplicates
+/// * `max_clusters` - Maximum number of clusters to return
+pub fn find_duplicates(
+    matcher: &mut ContactMatcher,
+    contacts: &[Contact],
+    min_confidence: u8,
+    max_clusters: usize,
+) -> DuplicateReport {
+    let pairs = candidate_pairs(contacts, matcher.phone_region());
+    let pairs_compared = pairs.len();
+
+    let mut matches: Vec<(usize, usize, DuplicateMatch)> = pairs
+        .into_iter()
+        .filter_map(|(a, b)| {
+            let found = compare(matcher, &contacts[a], &contacts[b])?;
+            (found.confidence >= min_confidence).then_some((a, b, found))
+        })
+        .collect();
+    matches.sort_by(|x, y| {
+        y.2.confidence
+            .cmp(&x.2.confidence)
+            .then_with(|| x.2.contact_id.cmp(&y.2.contact_id))
+            .then_with(|| x.2.other_contact_id.cmp(&y.2.other_contact_id))
+    });
+
+    // Join the strongest matches first, so the match that completes a
+    // cluster is its weakest link
+    let mut sets = DisjointSets::new(contacts.len());
+    let mut confidences: HashMap<usize, u8> = HashMap::new();
+    for (a, b, found) in &matches {
+        let (root_a, root_b) = (sets.find(*a), sets.find(*b));
+        if let Some(root) = sets.union(root_a, root_b) {
+            confidences.remove(&root_a.max(root_b));
+            confidences.insert(root, found.confidence);
+        }
+    }
+
+    let mut members: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    let mut cluster_matches: HashMap<usize, Vec<DuplicateMatch>> = HashMap::new();
+    for (a, _, found) in matches {
+        let root = sets.find(a);
+        cluster_matches.entry(root).or_default().push(found);
+    }
+    for position in 0..contacts.len() {
+        let root = sets.find(position);
+        if cluster_matches.contains_key(&root) {
+            members.entry(root).or_default().push(position);
+        }
+    }
+
+    let mut clusters: Vec<DuplicateCluster> = members
+        .into_iter()
+        .map(|(root, positions)| {
+            let mut cluster: Vec<Contact> =
+                positions.iter().map(|&p| contacts[p].clone()).collect();
+            cluster.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+            DuplicateCluster {
+                contacts: cluster,
+                confidence: confidences.get(&root).copied().unwrap_or(0),
+                matches: cluster_matches.remove(&root).unwrap_or_default(),
+            }
+        })
+        .collect();
+    clusters.sort_by(|a, b| {
+        b.confidence
+            .cmp(&a.confidence)
+            .then(b.contacts.len().cmp(&a.contacts.len()))
+            .then_with(|| a.contacts[0].name.cmp(&b.contacts[0].name))
+            .then_with(|| a.contacts[0].id.cmp(&b.contacts[0].id))
+    });
+
+    let total_clusters = clusters.len();
+    clusters.truncate(max_clusters);
+    DuplicateReport {
+        clusters,
+        total_clusters,
+        contacts_scanned: contacts.len(),
+        pairs_compared,
+    }
+}
+
+/// Distinct pairs of contact positions sharing a blocking key.
+///
+/// The contact with the smaller ID comes first, so results do not depend on
+/// the order of the address book.
+fn candidate_pairs(contacts: &[Contact], region: PhoneRegion) -> Vec<(usize, usize)> {
+    let mut blocks: HashMap<String, Vec<usize>> = HashMap::new();
+    for (position, contact) in contacts.iter().enumerate() {
+        for key in blocking_keys(contact, region) {
+            blocks.entry(key).or_default().push(position);
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let mut pairs = Vec::new();
+    for block in blocks
+        .values()
+        .filter(|block| block.len() <= MAX_BLOCK_SIZE)
+    {
+        for (i, &a) in block.iter().enumerate() {
+            for &b in &block[i + 1..] {
+                let pair = if contacts[a].id <= contacts[b].id {
+                    (a, b)
+                } else {
+                    (b, a)
+                };
+                if seen.insert(pair) {
+                    pairs.push(pair);
+                }
+            }
+        }
+    }
+    pairs.sort_unstable();
+    pairs
+}
+
+/// Blocking keys of a contact, each prefixed with its kind.
+///
+/// Phone numbers are keyed by their E.164 form, reading them in `region`
+/// if they have no country code.
+fn blocking_keys(contact: &Contact, region: PhoneRegion) -> HashSet<String> {
+    let mut keys = HashSet::new();
+    for email in emails(contact) {
+        keys.insert(format!("email:{}", ContactMatcher::normalize_email(email)));
+    }
+    for phone in phones(contact) {
+        keys.insert(format!(
+            "phone:{}",
+            ContactMatcher::normalize_phone(phone, region)
+        ));
+    }
+
+    let name = ContactMatcher::normalize_name(&contact.name);
+    let mut words: Vec<&str> = name.split_whitespace().collect();
+    words.sort_unstable();
+    words.dedup();
+    if !words.is_empty() {
+        keys.insert(format!("name:{}", words.join(" ")));
+    }
+    for word in words {
+        if word.chars().count() >= MIN_NAME_WORD_LENGTH {
+            keys.insert(format!("word:{}", word));
+        }
+    }
+    keys
+}
+
+/// Non-empty email addresses of a contact.
+fn emails(contact: &Contact) -> impl Iterator<Item = &String> {
+    contact
+        .email
+        .iter()
+        .chain(&contact.emails)
+        .filter(|email| !ContactMatcher::normalize_email(email).is_empty())
+}
+
+/// Phone numbers of a contact with enough digits to compare.
+fn phones(contact: &Contact) -> impl Iterator<Item = &String> {
+    contact
+        .phone
+        .iter()
+        .chain(&contact.phones)
+        .filter(|phone| phone.chars().filter(char::is_ascii_digit).count() >= MIN_PHONE_DIGITS)
+}
+
+/// Score a pair of contacts, if anything about them matches.
+///
+/// Each email, phone and social profile of `a` is looked up in `b`, and each
+/// contact's name (with its company) is matched against the other's.
+fn compare(matcher: &mut ContactMatcher, a: &Contact, b: &Contact) -> Option<DuplicateMatch> {
+    let mut lookup = |query: ContactQuery, target: &Contact, value: &str| {
+        let found = matcher
+            .find_matches(&query, std::slice::from_ref(target), 1, 1)
+            .pop()?;
+        Some(DuplicateEvidence {
+            match_type: found.match_type,
+            confidence: found.confidence,
+            value: value.to_string(),
+        })
+    };
+
+    let mut evidence = Vec::new();
+    for email in emails(a) {
+        let query = ContactQuery {
+            email: Some(email.clone()),
+            ..Default::default()
+        };
+        evidence.extend(lookup(query, b, email));
+    }
+    for phone in phones(a) {
+        let query = ContactQuery {
+            phone: Some(phone.clone()),
+            ..Default::default()
+        };
+        evidence.extend(lookup(query, b, phone));
+    }
+    for profile in &a.social_profiles {
+        let query = ContactQuery {
+            social_url: Some(profile.url.clone()),
+            ..Default::default()
+        };
+        evidence.extend(lookup(query, b, &profile.url));
+    }
+
+    // Name matching is asymmetric, so keep the better direction
+    let names = format!("{} / {}", a.name, b.name);
+    let name_match = [(a, b), (b, a)]
+        .into_iter()
+        .filter(|(query, _)| !query.name.trim().is_empty())
+        .filter_map(|(query, target)| {
+            let query = ContactQuery {
+                name: Some(query.name.clone()),
+                company: query.company.clone(),
+                ..Default::default()
+            };
+            lookup(query, target, &names)
+        })
+        .max_by_key(|found| found.confidence);
+    evidence.extend(name_match);
+
+    evidence.sort_by_key(|found| std::cmp::Reverse(found.confidence));
+    let confidence = evidence.first()?.confidence;
+    Some(DuplicateMatch {
+        contact_id: a.id.clone(),
+        other_contact_id: b.id.clone(),
+        confidence,
+        evidence,
+    })
+}
+
+/// Union-find over contact positions.
+struct DisjointSets {
+    parents: Vec<usize>,
+}
+
+impl DisjointSets {
+    fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+        }
+    }
+
+    fn find(&mut self, mut position: usize) -> usize {
+        while self.parents[position] != position {
+            self.parents[position] = self.parents[self.parents[position]];
+            position = self.parents[position];
+        }
+        position
+    }
+
+    /// Join the sets of `a` and `b`, returning the new root if they differ.
+    fn union(&mut self, a: usize, b: usize) -> Option<usize> {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return None;
+        }
+        let (root, child) = (a.min(b), a.max(b));
+        self.parents[child] = root;
+        Some(root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contact(id: &str, name: &str, email: Option<&str>, phone: Option<&str>) -> Contact {
+        let mut contact = Contact::new(id.to_string(), name.to_string());
+        contact.email = email.map(str::to_string);
+        contact.phone = phone.map(str::to_string);
+        contact
+    }
+
+    #[test]
+    fn test_blocking_skips_unrelated_pairs() {
+        let contacts = vec![
+            contact("1", "John Smith", Some("john@acme.com"), None),
+            contact("2", "Jon Smith", None, None),
+            contact("3", "Alice Wong", None, Some("+1 (555) 010-2000")),
+            contact("4", "A. Wong", None, Some("555-010-2000")),
+            contact("5", "Bob Marley", None, None),
+        ];
+
+        assert_eq!(
+            candidate_pairs(&contacts, PhoneRegion::default()),
+            vec![(0, 1), (2, 3)]
+        );
+    }
+
+    #[test]
+    fn test_clusters_join_transitive_matches() {
+        let contacts = vec![
+            contact("1", "John Smith", Some("john@acme.com"), None),
+            contact(
+                "2",
+                "J. Smith",
+                Some("JOHN@acme.com "),
+                Some("555 123 4567"),
+            ),
+            contact("3", "Johnny Smith", None, Some("(555) 123-4567")),
+            contact("4", "Mary Jones", None, None),
+        ];
+
+        let report = find_duplicates(&mut ContactMatcher::new(), &contacts, 80, 10);
+        assert_eq!(report.total_clusters, 1);
+        let cluster = &report.clusters[0];
+        let ids: Vec<&str> = cluster.contacts.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["2", "1", "3"]);
+        assert_eq!(cluster.confidence, 100);
+
+        let email_match = cluster
+            .matches
+            .iter()
+            .find(|m| m.contact_id == "1" && m.other_contact_id == "2")
+            .unwrap();
+        assert_eq!(email_match.evidence[0].match_type, MatchType::ExactEmail);
+        assert_eq!(email_match.evidence[0].value, "john@acme.com");
+    }
+
+    #[test]
+    fn test_cluster_confidence_is_weakest_joining_match() {
+        let contacts = vec![
+            contact("1", "Katherine Johnson", Some("kj@nasa.gov"), None),
+            contact("2", "Katherine Johnsen", Some("kj@nasa.gov"), None),
+            contact("3", "Katherine Johnson", None, None),
+        ];
+
+        let report = find_duplicates(&mut ContactMatcher::new(), &contacts, 80, 10);
+        assert_eq!(report.clusters.len(), 1);
+        assert_eq!(report.clusters[0].contacts.len(), 3);
+        // 1-2 share an email; 1-3 have the same name; 2-3 only a similar one
+        assert_eq!(report.clusters[0].confidence, 95);
+        assert_eq!(report.clusters[0].matches.len(), 3);
+    }
+}
@@ -0,0 +1,338 @@
+//! Combining the signals of a contact query into one confidence.
+//!
+//! Each signal of a query (email, phone, social URL, name, company) either
+//! agrees with a contact, contradicts it (the contact has a value, and it
+//! does not match) or is missing (the contact has no value to compare). The
+//! strongest agreeing email, phone, social or name signal sets the starting
+//! confidence. Every other agreeing signal then closes part of the gap to
+//! the highest confidence, and every contradicting signal takes away part
+//! of what is left. How large these parts are is set by [`MatchWeights`].
+//!
+//! Both steps are multiplicative, so the result does not depend on the order
+//! signals are applied in.
+
+use super::explanation::MatchSignal;
+use std::str::FromStr;
+
+/// Highest confidence of a match that no exact email, phone or social
+/// match supports; 100 is reserved for those.
+pub(crate) const MAX_NAME_CONFIDENCE: u8 = 95;
+
+/// What one signal of a query says about a contact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Evidence {
+    /// The contact's value matches the query's
+    Agrees,
+
+    /// The contact has a value for the signal, and it does not match
+    Contradicts,
+
+    /// The contact has no value to compare
+    Missing,
+}
+
+impl Evidence {
+    /// The evidence's name, as used in tool responses.
+    pub fn name(self) -> &'static str {
+        match self {
+            Evidence::Agrees => "agrees",
+            Evidence::Contradicts => "contradicts",
+            Evidence::Missing => "missing",
+        }
+    }
+}
+
+/// How strongly one signal moves a match's confidence.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SignalWeight {
+    /// Share (0-1) of the gap to the highest confidence that an agreeing
+    /// signal closes, scaled by the signal's score
+    pub agree: f64,
+
+    /// Share (0-1) of the confidence that a contradicting signal takes away
+    pub contradict: f64,
+}
+
+impl SignalWeight {
+    const fn new(agree: f64, contradict: f64) -> Self {
+        Self { agree, contradict }
+    }
+}
+
+/// Weights of the signals of a contact query.
+///
+/// The defaults let a second matching email, phone or social URL all but
+/// settle a match, while a name that does not match at all halves it. A
+/// company only nudges the confidence either way, since people change jobs.
+///
+/// Weights can be overridden from text such as
+/// `"name.contradict=0.6, company.agree=0.3"`:
+///
+/// ```
+/// use dex_mcp_server::matching::{MatchSignal, MatchWeights};
+///
+/// let weights: MatchWeights = "name.contradict=0.6, company.agree=0.3".parse().unwrap();
+/// assert_eq!(weights.get(MatchSignal::Name).contradict, 0.6);
+/// assert_eq!(weights.get(MatchSignal::Company).agree, 0.3);
+/// assert_eq!(weights.get(MatchSignal::Email), MatchWeights::default().get(MatchSignal::Email));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MatchWeights {
+    /// Email address
+    pub email: SignalWeight,
+
+    /// Phone number
+    pub phone: SignalWeight,
+
+    /// Social profile URL
+    pub social: SignalWeight,
+
+    /// Name
+    pub name: SignalWeight,
+
+    /// Company
+    pub company: SignalWeight,
+}
+
+impl Default for MatchWeights {
+    fn default() -> Self {
+        Self {
+            email: SignalWeight::new(0.9, 0.15),
+            phone: SignalWeight::new(0.9, 0.15),
+            social: SignalWeight::new(0.9, 0.15),
+            name: SignalWeight::new(0.5, 0.5),
+            company: SignalWeight::new(0.5, 0.1),
+        }
+    }
+}
+
+impl MatchWeights {
+    /// The weight of `signal`.
+    pub fn get(&self, signal: MatchSignal) -> SignalWeight {
+        match signal {
+            MatchSignal::Email => self.email,
+            MatchSignal::Phone => self.phone,
+            MatchSignal::Social => self.social,
+            MatchSignal::Name => self.name,
+            MatchSignal::Company => self.company,
+        }
+    }
+
+    fn get_mut(&mut self, signal: MatchSignal) -> &mut SignalWeight {
+        match signal {
+            MatchSignal::Email => &mut self.email,
+            MatchSignal::Phone => &mut self.phone,
+            MatchSignal::Social => &mut self.social,
+            MatchSignal::Name => &mut self.name,
+            MatchSignal::Company => &mut self.company,
+        }
+    }
+
+    /// Combine the evidence about a contact into a confidence (0-100).
+    ///
+    /// Returns the confidence and the signal it started from, or `None` if
+    /// no email, phone, social or name signal agrees. `record` is called
+    /// for every signal with evidence, in [`MatchSignal::ALL`] order per
+    /// step, with the weight applied and the points the signal added (or,
+    /// if negative, took away); the points add up to the confidence.
+    pub(crate) fn combine(
+        &self,
+        evidence: &SignalEvidence,
+        mut record: impl FnMut(MatchSignal, f64, i8),
+    ) -> Option<(u8, MatchSignal)> {
+        // The strongest agreeing signal, the first of equals
+        let (primary, score) = MatchSignal::ALL
+            .into_iter()
+            .filter(|&signal| signal != MatchSignal::Company)
+            .filter_map(|signal| match evidence.get(signal) {
+                Some((Evidence::Agrees, score)) if score > 0 => Some((signal, score)),
+                _ => None,
+            })
+            .fold(
+                None,
+                |best: Option<(MatchSignal, u8)>, (signal, score)| match best {
+                    Some((_, best_score)) if best_score >= score => best,
+                    _ => Some((signal, score)),
+                },
+            )?;
+        let cap = if primary == MatchSignal::Name {
+            f64::from(MAX_NAME_CONFIDENCE)
+        } else {
+            100.0
+        };
+
+        let mut confidence = f64::from(score);
+        record(primary, 1.0, score as i8);
+        // Move the confidence from `before` to `after`, recording the points
+        let mut apply = |signal, weight, before: f64, after: f64| {
+            record(signal, weight, (after.round() - before.round()) as i8);
+            after
+        };
+
+        for signal in MatchSignal::ALL {
+            if signal == primary {
+                continue;
+            }
+            match evidence.get(signal) {
+                Some((Evidence::Agrees, score)) => {
+                    let weight = self.get(signal).agree;
+                    let closed = weight * f64::from(score) / 100.0 * (cap - confidence);
+                    confidence = apply(signal, weight, confidence, confidence + closed);
+                }
+                Some((Evidence::Missing, _)) => {
+                    confidence = apply(signal, 0.0, confidence, confidence);
+                }
+                _ => {}
+            }
+        }
+        for signal in MatchSignal::ALL {
+            if let Some((Evidence::Contradicts, _)) = evidence.get(signal) {
+                let weight = self.get(signal).contradict;
+                confidence = apply(signal, weight, confidence, confidence * (1.0 - weight));
+            }
+        }
+
+        Some((confidence.round() as u8, primary))
+    }
+}
+
+impl FromStr for MatchWeights {
+    type Err = String;
+
+    /// Parse comma-separated `signal.agree=weight` and
+    /// `signal.contradict=weight` overrides of the default weights.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut weights = Self::default();
+        for item in s.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+            let (key, value) = item
+                .split_once('=')
+                .ok_or_else(|| format!("Expected signal.agree=weight, got: {}", item))?;
+            let (signal, kind) = key.trim().split_once('.').ok_or_else(|| {
+                format!("Expected signal.agree or signal.contradict, got: {}", key)
+            })?;
+            let signal = MatchSignal::ALL
+                .into_iter()
+                .find(|s| s.name() == signal)
+                .ok_or_else(|| {
+                    format!(
+                        "Unknown signal {}, expected one of email, phone, social, name, company",
+                        signal
+                    )
+                })?;
+            let value: f64 = value
+                .trim()
+                .parse()
+                .ok()
+                .filter(|value| (0.0..=1.0).contains(value))
+                .ok_or_else(|| format!("Weight must be between 0 and 1, got: {}", value))?;
+
+            let weight = weights.get_mut(signal);
+            match kind {
+                "agree" => weight.agree = value,
+                "contradict" => weight.contradict = value,
+                _ => return Err(format!("Expected agree or contradict, got: {}", kind)),
+            }
+        }
+        Ok(weights)
+    }
+}
+
+/// The evidence of each signal of a query about one contact, with the
+/// signal's score (0-100).
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct SignalEvidence([Option<(Evidence, u8)>; MatchSignal::ALL.len()]);
+
+impl SignalEvidence {
+    pub(crate) fn get(&self, signal: MatchSignal) -> Option<(Evidence, u8)> {
+        self.0[signal as usize]
+    }
+
+    pub(crate) fn set(&mut self, signal: MatchSignal, evidence: Evidence, score: u8) {
+        self.0[signal as usize] = Some((evidence, score));
+    }
+
+    /// Whether an email, phone, social or name signal agrees.
+    pub(crate) fn any_agrees(&self) -> bool {
+        MatchSignal::ALL
+            .into_iter()
+            .filter(|&signal| signal != MatchSignal::Company)
+            .any(|signal| matches!(self.get(signal), Some((Evidence::Agrees, _))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn combine(evidence: &[(MatchSignal, Evidence, u8)]) -> Option<(u8, MatchSignal)> {
+        let mut signals = SignalEvidence::default();
+        for &(signal, kind, score) in evidence {
+            signals.set(signal, kind, score);
+        }
+        let mut total = 0i16;
+        let combined = MatchWeights::default().combine(&signals, |_, _, added| {
+            total += i16::from(added);
+        });
+        if let Some((confidence, _)) = combined {
+            assert_eq!(total, i16::from(confidence), "{:?}", evidence);
+        }
+        combined
+    }
+
+    #[test]
+    fn test_single_signals_keep_their_score() {
+        use Evidence::*;
+        use MatchSignal::*;
+        assert_eq!(combine(&[(Email, Agrees, 100)]), Some((100, Email)));
+        assert_eq!(combine(&[(Name, Agrees, 72)]), Some((72, Name)));
+        assert_eq!(
+            combine(&[(Name, Agrees, 72), (Email, Missing, 0)]),
+            Some((72, Name))
+        );
+        assert_eq!(combine(&[(Name, Contradicts, 0)]), None);
+        assert_eq!(combine(&[(Company, Agrees, 100)]), None);
+    }
+
+    #[test]
+    fn test_agreeing_and_contradicting_signals() {
+        use Evidence::*;
+        use MatchSignal::*;
+        // Agreement closes part of the gap to the cap
+        assert_eq!(
+            combine(&[(Name, Agrees, 60), (Company, Agrees, 100)]),
+            Some((78, Name))
+        );
+        assert_eq!(
+            combine(&[(Email, Agrees, 100), (Name, Agrees, 60)]),
+            Some((100, Email))
+        );
+        // Contradiction takes away part of the confidence
+        assert_eq!(
+            combine(&[(Email, Agrees, 100), (Name, Contradicts, 0)]),
+            Some((50, Email))
+        );
+        assert_eq!(
+            combine(&[(Name, Agrees, 80), (Phone, Contradicts, 0)]),
+            Some((68, Name))
+        );
+        // The strongest agreeing signal leads, whatever the order
+        assert_eq!(
+            combine(&[(Name, Agrees, 95), (Phone, Agrees, 100)]),
+            Some((100, Phone))
+        );
+    }
+
+    #[test]
+    fn test_parse_weights() {
+        let weights: MatchWeights = " email.agree=0.5 ,phone.contradict=0 ".parse().unwrap();
+        assert_eq!(weights.email.agree, 0.5);
+        assert_eq!(weights.phone.contradict, 0.0);
+        assert_eq!(weights.name, MatchWeights::default().name);
+        assert_eq!("".parse::<MatchWeights>(), Ok(MatchWeights::default()));
+
+        assert!("email=0.5".parse::<MatchWeights>().is_err());
+        assert!("fax.agree=0.5".parse::<MatchWeights>().is_err());
+        assert!("name.agree=1.5".parse::<MatchWeights>().is_err());
+        assert!("name.boost=0.5".parse::<MatchWeights>().is_err());
+    }
+}
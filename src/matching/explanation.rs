@@ -0,0 +1,120 @@
+//! Explanations of match confidences.
+//!
+//! A [`MatchExplanation`] breaks a [`MatchResult`](super::MatchResult)'s
+//! confidence down into the signals the query was scored on, so a caller can
+//! tell why "Jon Smyth" matched at 72: how similar the name was and by which
+//! method, whether the company agreed or contradicted, how much each signal
+//! added or took away (see [`super::evidence`]), and what the query looked
+//! like once normalized.
+
+use super::evidence::Evidence;
+
+/// A part of a contact query that contacts are scored on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MatchSignal {
+    /// Email address, compared in canonical form
+    Email,
+
+    /// Phone number, compared in E.164 form
+    Phone,
+
+    /// Social profile URL
+    Social,
+
+    /// Name similarity
+    Name,
+
+    /// Company similarity, supporting or weakening another signal
+    Company,
+}
+
+impl MatchSignal {
+    /// Every signal, in the order they are tried.
+    pub const ALL: [MatchSignal; 5] = [
+        MatchSignal::Email,
+        MatchSignal::Phone,
+        MatchSignal::Social,
+        MatchSignal::Name,
+        MatchSignal::Company,
+    ];
+
+    /// The signal's name, as used in tool responses.
+    pub fn name(self) -> &'static str {
+        match self {
+            MatchSignal::Email => "email",
+            MatchSignal::Phone => "phone",
+            MatchSignal::Social => "social",
+            MatchSignal::Name => "name",
+            MatchSignal::Company => "company",
+        }
+    }
+}
+
+/// How one signal scored a contact.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalScore {
+    /// The signal
+    pub signal: MatchSignal,
+
+    /// Whether the contact agrees with the signal, contradicts it, or has
+    /// no value for it
+    pub evidence: Evidence,
+
+    /// Score of the signal on its own (0-100, 0 if it did not match)
+    pub raw_score: u8,
+
+    /// Weight applied to the signal: 1.0 for the signal the confidence
+    /// starts from, its agree or contradict weight for the others, and 0 if
+    /// it was not applied
+    pub weight: f64,
+
+    /// Points the signal added to the confidence, or took away if negative
+    pub contribution: i8,
+
+    /// How the raw score was found: `exact`, `nucleo`, `edit_distance`, or
+    /// the kind of name variant
+    pub method: Option<&'static str>,
+
+    /// The contact's value the query matched
+    pub matched_value: Option<String>,
+}
+
+/// The values of a query as they are compared with contacts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NormalizedQuery {
+    /// Folded name
+    pub name: Option<String>,
+
+    /// Canonical email address
+    pub email: Option<String>,
+
+    /// E.164 phone number (or its digits, if it could not be parsed)
+    pub phone: Option<String>,
+
+    /// Social URL without protocol, `www.` or trailing slash
+    pub social_url: Option<String>,
+
+    /// Folded company
+    pub company: Option<String>,
+}
+
+/// Why a contact matched a query with its confidence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchExplanation {
+    /// The query as compared with the contact
+    pub query: NormalizedQuery,
+
+    /// Every signal of the query, in the order they are tried
+    pub signals: Vec<SignalScore>,
+}
+
+impl MatchExplanation {
+    /// The confidence the signals add up to.
+    pub fn confidence(&self) -> u8 {
+        self.signals
+            .iter()
+            .map(|signal| i16::from(signal.contribution))
+            .sum::<i16>()
+            .clamp(0, 100) as u8
+    }
+}
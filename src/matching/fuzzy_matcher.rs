@@ -2,11 +2,38 @@
 //!
 //! This module provides intelligent contact matching with:
 //! - Exact matching on email, phone, and social URLs
-//! - Fuzzy name matching using the nucleo library
-//! - Confidence scoring (0-100 scale)
-//! - Company-based confidence boosting
-
+//! - Fuzzy name matching using the nucleo library, with an edit-distance
+//!   fallback for typos
+//! - Name variants: nicknames, initials, middle names, hyphenated surnames
+//!   and surname-first order (see [`super::name_variants`])
+//! - Confidence scoring (0-100 scale), combining every signal of the query:
+//!   agreeing signals raise the confidence and contradicting ones lower it
+//!   (see [`super::evidence`])
+//! - Optional per-signal explanations of the confidence (see
+//!   [`super::explanation`])
+//!
+//! Nucleo scores grow with the length of the query, so they are normalized
+//! by the score of the query matched against itself before being turned
+//! into a confidence.
+
+use super::evidence::{Evidence, MatchWeights, SignalEvidence, MAX_NAME_CONFIDENCE};
+use super::explanation::{MatchExplanation, MatchSignal, NormalizedQuery, SignalScore};
+use super::name_index::NameIndex;
+use super::name_variants::{
+    NameVariantKind, NameVariantQuery, NicknameMap, MAX_VARIANT_CONFIDENCE,
+};
+use crate::domain::{EmailAddress, PhoneNumber, PhoneRegion};
 use crate::models::Contact;
+use crate::search::inverted_index::levenshtein_distance;
+use nucleo_matcher::pattern::{CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Config, Matcher, Utf32Str};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Phone numbers whose comparison keys a matcher remembers before starting
+/// over.
+const PHONE_KEY_CACHE_CAPACITY: usize = 100_000;
 
 /// A match result containing a contact and its confidence score.
 #[derive(Debug, Clone)]
@@ -19,6 +46,9 @@ pub struct MatchResult {
 
     /// Type of match that produced this result
     pub match_type: MatchType,
+
+    /// How the confidence came about, if the query asked for it
+    pub explanation: Option<MatchExplanation>,
 }
 
 /// The type of match that was found.
@@ -35,19 +65,314 @@ pub enum MatchType {
 
     /// Fuzzy name match
     FuzzyName,
+
+    /// Name match through a nickname, initial or other variant
+    NameVariant(NameVariantKind),
+}
+
+/// Nucleo scores below this fraction of a perfect match are ignored.
+const MIN_NUCLEO_RATIO: f64 = 0.5;
+
+/// Highest confidence of a match found by edit distance alone.
+const TYPO_MAX_CONFIDENCE: u8 = 85;
+
+/// A company must match with more than this confidence to agree with a
+/// contact.
+const MIN_COMPANY_CONFIDENCE: u8 = 50;
+
+/// A name or company query prepared for nucleo matching.
+pub(crate) struct NamePattern {
+    /// Folded query text
+    text: String,
+
+    /// Nucleo pattern with one fuzzy atom per word
+    pattern: Pattern,
+
+    /// Score of the query matched against itself
+    perfect_score: u32,
+}
+
+impl NamePattern {
+    /// Fold `query` and parse it into a nucleo pattern.
+    ///
+    /// Characters nucleo treats as operators at word edges (`!`, `^`, `'`,
+    /// `$` and `\`) are dropped, so every word is a plain fuzzy atom.
+    pub(crate) fn new(query: &str, matcher: &mut Matcher) -> Self {
+        let text = ContactMatcher::normalize_name(query);
+        let pattern_text = Self::pattern_text(&text);
+        let pattern = Pattern::parse(&pattern_text, CaseMatching::Ignore, Normalization::Smart);
+        let mut buf = Vec::new();
+        let perfect_score = pattern
+            .score(Utf32Str::new(&text, &mut buf), matcher)
+            .unwrap_or(0);
+        Self {
+            text,
+            pattern,
+            perfect_score,
+        }
+    }
+
+    /// `text` with nucleo's special characters removed from word edges.
+    pub(crate) fn pattern_text(text: &str) -> String {
+        text.split(|c: char| c.is_whitespace() || c == '\\')
+            .map(|word| {
+                word.trim_start_matches(['!', '^', '\''])
+                    .trim_end_matches('$')
+            })
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Lowest nucleo score that counts towards a match's confidence.
+    pub(crate) fn min_nucleo_score(&self) -> u32 {
+        (self.perfect_score as f64 * MIN_NUCLEO_RATIO).ceil() as u32
+    }
+
+    /// Nucleo score of a folded target, if every word of the query matches.
+    pub(crate) fn score(&self, target: &str, matcher: &mut Matcher) -> Option<u32> {
+        let mut buf = Vec::new();
+        self.pattern.score(Utf32Str::new(target, &mut buf), matcher)
+    }
+
+    /// Confidence (0-95) that a folded target matches the query, with the
+    /// method that produced it: `exact`, `nucleo` or `edit_distance`.
+    ///
+    /// `nucleo_score` is the target's nucleo score, if it matched. It is
+    /// divided by the query's perfect score and weighted by how much of the
+    /// target the query covers, so "john" matches "John" better than "John
+    /// Doe". Targets nucleo rejects, such as misspellings, are scored by edit
+    /// distance instead, up to 85. The higher of the two wins.
+    fn scored(&self, target: &str, nucleo_score: Option<u32>) -> (u8, &'static str) {
+        if self.text.is_empty() || target.is_empty() {
+            return (0, "nucleo");
+        }
+        if self.text == target {
+            return (MAX_NAME_CONFIDENCE, "exact"); // Reserve 100 for exact email/phone/social matches
+        }
+
+        let nucleo = nucleo_score
+            .filter(|_| self.perfect_score > 0)
+            .map(|score| (score as f64 / self.perfect_score as f64).min(1.0))
+            .filter(|&ratio| ratio >= MIN_NUCLEO_RATIO)
+            .map(|ratio| {
+                let letters = |s: &str| s.chars().filter(|c| !c.is_whitespace()).count();
+                let coverage = (letters(&self.text) as f64 / letters(target) as f64).min(1.0);
+                (94.0 * ratio * (0.7 + 0.3 * coverage)).round() as u8
+            })
+            .unwrap_or(0);
+
+        if nucleo >= TYPO_MAX_CONFIDENCE {
+            return (nucleo, "nucleo");
+        }
+        let typo = Self::typo_confidence(&self.text, target);
+        if typo > nucleo {
+            (typo, "edit_distance")
+        } else {
+            (nucleo, "nucleo")
+        }
+    }
+
+    /// Confidence (0-85) from the edit distance of two folded strings.
+    fn typo_confidence(query: &str, target: &str) -> u8 {
+        let max_len = query.chars().count().max(target.chars().count());
+        let min_len = query.chars().count().min(target.chars().count());
+        // Length difference is a lower bound on the edit distance
+        if (max_len - min_len) as f64 / max_len as f64 > 0.5 {
+            return 0;
+        }
+
+        let distance = levenshtein_distance(query, target) as f64 / max_len as f64;
+        if distance > 0.5 {
+            // Too many differences
+            return 0;
+        }
+        ((1.0 - distance) * TYPO_MAX_CONFIDENCE as f64) as u8 // Scale to 0-85 range
+    }
 }
 
 /// Contact matcher with fuzzy and exact matching capabilities.
-pub struct ContactMatcher;
+pub struct ContactMatcher {
+    /// Nucleo scratch space, reused across matches
+    matcher: Matcher,
+
+    /// Nicknames used for name variant matching
+    nicknames: Arc<NicknameMap>,
+
+    /// Region assumed for phone numbers without a country code
+    phone_region: PhoneRegion,
+
+    /// Comparison keys of phone numbers seen so far, since parsing a phone
+    /// number costs far more than comparing it
+    phone_keys: HashMap<String, PhoneKey>,
+
+    /// How much agreeing and contradicting signals move a confidence
+    weights: MatchWeights,
+}
+
+/// A phone number reduced for comparison.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PhoneKey {
+    /// E.164 form, or the digits of a number that could not be parsed
+    number: String,
+    extension: Option<String>,
+}
+
+impl PhoneKey {
+    fn new(phone: &str, region: PhoneRegion) -> Self {
+        match PhoneNumber::parse(phone, region) {
+            Ok(phone) => Self {
+                number: phone.comparable(),
+                extension: phone.extension().map(str::to_string),
+            },
+            Err(_) => Self {
+                number: phone.chars().filter(|c| c.is_ascii_digit()).collect(),
+                extension: None,
+            },
+        }
+    }
+
+    /// Whether both keys reach the same phone: the numbers are the same, and
+    /// so are the extensions unless one of them has none.
+    fn matches(&self, other: &PhoneKey) -> bool {
+        !self.number.is_empty()
+            && self.number == other.number
+            && match (&self.extension, &other.extension) {
+                (Some(a), Some(b)) => a == b,
+                _ => true,
+            }
+    }
+}
+
+/// A query normalized and parsed once for matching many contacts.
+struct PreparedQuery {
+    /// Canonical email address
+    email: Option<String>,
+    phone: Option<PhoneKey>,
+    /// Normalized social URL
+    social_url: Option<String>,
+    name: Option<(NamePattern, NameVariantQuery)>,
+    company: Option<NamePattern>,
+}
+
+/// What each signal of a query says about one contact.
+struct ContactEvidence<'a> {
+    signals: SignalEvidence,
+
+    /// Match type of the name signal, if it agrees
+    name_match: MatchType,
+
+    /// How each signal's score was found, by signal
+    methods: [Option<&'static str>; MatchSignal::ALL.len()],
+
+    /// The contact's value each signal matched, by signal
+    matched: [Option<&'a str>; MatchSignal::ALL.len()],
+}
+
+impl<'a> ContactEvidence<'a> {
+    fn new() -> Self {
+        Self {
+            signals: SignalEvidence::default(),
+            name_match: MatchType::FuzzyName,
+            methods: [None; MatchSignal::ALL.len()],
+            matched: [None; MatchSignal::ALL.len()],
+        }
+    }
+
+    fn set(
+        &mut self,
+        signal: MatchSignal,
+        evidence: Evidence,
+        score: u8,
+        method: Option<&'static str>,
+        matched: Option<&'a str>,
+    ) {
+        self.signals.set(signal, evidence, score);
+        self.methods[signal as usize] = method;
+        self.matched[signal as usize] = matched;
+    }
+
+    /// Record an exact signal, scored 100 if it agrees.
+    fn set_exact(&mut self, signal: MatchSignal, evidence: Evidence, matched: Option<&'a String>) {
+        let score = if evidence == Evidence::Agrees { 100 } else { 0 };
+        let method = matched.map(|_| "exact");
+        self.set(signal, evidence, score, method, matched.map(String::as_str));
+    }
+
+    /// The match type of a match whose confidence starts from `primary`.
+    fn match_type(&self, primary: MatchSignal) -> MatchType {
+        match primary {
+            MatchSignal::Email => MatchType::ExactEmail,
+            MatchSignal::Phone => MatchType::ExactPhone,
+            MatchSignal::Social => MatchType::ExactSocial,
+            MatchSignal::Name | MatchSignal::Company => self.name_match.clone(),
+        }
+    }
+}
+
+/// Evidence of an exact signal: it agrees if one of the non-blank `values`
+/// matches, with that value, and contradicts if none does.
+fn exact_evidence<'a>(
+    values: impl Iterator<Item = &'a String>,
+    mut matches: impl FnMut(&str) -> bool,
+) -> (Evidence, Option<&'a String>) {
+    let mut evidence = Evidence::Missing;
+    for value in values.filter(|value| !value.trim().is_empty()) {
+        if matches(value) {
+            return (Evidence::Agrees, Some(value));
+        }
+        evidence = Evidence::Contradicts;
+    }
+    (evidence, None)
+}
 
 impl ContactMatcher {
-    /// Create a new ContactMatcher.
+    /// Create a new ContactMatcher with the built-in nickname table.
     pub fn new() -> Self {
-        Self
+        Self {
+            matcher: Matcher::new(Config::DEFAULT),
+            nicknames: NicknameMap::builtin(),
+            phone_region: PhoneRegion::default(),
+            phone_keys: HashMap::new(),
+            weights: MatchWeights::default(),
+        }
+    }
+
+    /// Use `nicknames` instead of the built-in nickname table.
+    pub fn with_nicknames(mut self, nicknames: Arc<NicknameMap>) -> Self {
+        self.nicknames = nicknames;
+        self
+    }
+
+    /// Read phone numbers without a country code in `region` instead of
+    /// the US.
+    pub fn with_phone_region(mut self, region: PhoneRegion) -> Self {
+        self.phone_region = region;
+        self.phone_keys.clear();
+        self
+    }
+
+    /// The region assumed for phone numbers without a country code.
+    pub fn phone_region(&self) -> PhoneRegion {
+        self.phone_region
+    }
+
+    /// Combine signals with `weights` instead of the default weights.
+    pub fn with_weights(mut self, weights: MatchWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    /// The weights signals are combined with.
+    pub fn weights(&self) -> &MatchWeights {
+        &self.weights
     }
 
     /// Find matching contacts from a list based on the search query.
     ///
+    /// Names are scored on the calling thread; see
+    /// [`find_matches_indexed`](Self::find_matches_indexed) for large lists.
+    ///
     /// # Arguments
     /// * `query` - Search parameters (name, email, phone, company, etc.)
     /// * `contacts` - List of contacts to search through
@@ -63,272 +388,376 @@ impl ContactMatcher {
         max_results: usize,
         min_confidence: u8,
     ) -> Vec<MatchResult> {
-        let mut results: Vec<MatchResult> = Vec::new();
-
-        for contact in contacts {
-            // Try exact matches first (highest priority)
-            if let Some(email) = &query.email {
-                if let Some(confidence) = self.match_email(email, contact) {
-                    results.push(MatchResult {
-                        contact: contact.clone(),
-                        confidence,
-                        match_type: MatchType::ExactEmail,
-                    });
-                    continue;
-                }
-            }
+        self.collect_matches(query, contacts, None, None, max_results, min_confidence)
+    }
 
-            if let Some(phone) = &query.phone {
-                if let Some(confidence) = self.match_phone(phone, contact) {
-                    results.push(MatchResult {
-                        contact: contact.clone(),
-                        confidence,
-                        match_type: MatchType::ExactPhone,
-                    });
-                    continue;
-                }
-            }
+    /// Find matching contacts among the contacts of a warm [`NameIndex`].
+    ///
+    /// Names are scored by the index's worker threads, which pays off for
+    /// large contact lists. Results are the same as those of
+    /// [`find_matches`](Self::find_matches) on the index's contacts.
+    pub fn find_matches_indexed(
+        &mut self,
+        query: &ContactQuery,
+        index: &mut NameIndex,
+        max_results: usize,
+        min_confidence: u8,
+    ) -> Vec<MatchResult> {
+        let name_scores = query.name.as_deref().map(|name| {
+            let pattern = NamePattern::new(name, &mut self.matcher);
+            index.scores(name, pattern.min_nucleo_score())
+        });
+        let contacts = index.contacts().clone();
+        self.collect_matches(
+            query,
+            &contacts,
+            Some(index.names()),
+            name_scores.as_deref(),
+            max_results,
+            min_confidence,
+        )
+    }
 
-            if let Some(social_url) = &query.social_url {
-                if let Some(confidence) = self.match_social_url(social_url, contact) {
-                    results.push(MatchResult {
-                        contact: contact.clone(),
-                        confidence,
-                        match_type: MatchType::ExactSocial,
+    /// Match every contact against the query.
+    ///
+    /// `names` and `name_scores` hold the normalized names and nucleo name
+    /// scores of the contacts by position; without them they are computed
+    /// here.
+    fn collect_matches(
+        &mut self,
+        query: &ContactQuery,
+        contacts: &[Contact],
+        names: Option<&[String]>,
+        name_scores: Option<&[Option<u32>]>,
+        max_results: usize,
+        min_confidence: u8,
+    ) -> Vec<MatchResult> {
+        // Matches by contact position; contacts are cloned once the best are known
+        let mut results: Vec<(usize, u8, MatchType)> = Vec::new();
+        let prepared = self.prepare(query);
+
+        for (position, contact) in contacts.iter().enumerate() {
+            let target =
+                prepared
+                    .name
+                    .as_ref()
+                    .map(|_| match names.and_then(|names| names.get(position)) {
+                        Some(name) => Cow::Borrowed(name.as_str()),
+                        None => Cow::Owned(Self::normalize_name(&contact.name)),
                     });
-                    continue;
-                }
+            let name =
+                target
+                    .as_deref()
+                    .zip(prepared.name.as_ref())
+                    .map(|(target, (pattern, _))| match name_scores {
+                        Some(scores) => (target, scores.get(position).copied().flatten()),
+                        None => (target, pattern.score(target, &mut self.matcher)),
+                    });
+
+            let mut evidence = self.identify(&prepared, contact, name);
+            // A company alone never makes a match, so skip comparing it
+            if !evidence.signals.any_agrees() {
+                continue;
             }
+            self.compare_company(&prepared, contact, &mut evidence);
 
-            // Try fuzzy name matching
-            if let Some(name) = &query.name {
-                if let Some(mut confidence) = self.fuzzy_match_name(name, &contact.name) {
-                    // Boost confidence if company also matches
-                    if let (Some(query_company), Some(contact_company)) =
-                        (&query.company, &contact.company)
-                    {
-                        if self
-                            .fuzzy_match_company(query_company, contact_company)
-                            .is_some()
-                        {
-                            confidence = (confidence + 15).min(95); // Boost but don't exceed exact match range
-                        }
-                    }
-
-                    if confidence >= min_confidence {
-                        results.push(MatchResult {
-                            contact: contact.clone(),
-                            confidence,
-                            match_type: MatchType::FuzzyName,
-                        });
-                    }
+            if let Some((confidence, primary)) =
+                self.weights.combine(&evidence.signals, |_, _, _| {})
+            {
+                if confidence > 0 && confidence >= min_confidence {
+                    results.push((position, confidence, evidence.match_type(primary)));
                 }
             }
         }
 
-        // Sort by confidence (highest first), then by name
+        // Sort by confidence (highest first), then by name and id
         results.sort_by(|a, b| {
-            b.confidence
-                .cmp(&a.confidence)
-                .then_with(|| a.contact.name.cmp(&b.contact.name))
+            b.1.cmp(&a.1)
+                .then_with(|| contacts[a.0].name.cmp(&contacts[b.0].name))
+                .then_with(|| contacts[a.0].id.cmp(&contacts[b.0].id))
         });
 
         // Limit results
         results.truncate(max_results);
 
         results
+            .into_iter()
+            .map(|(position, confidence, match_type)| MatchResult {
+                contact: contacts[position].clone(),
+                confidence,
+                match_type,
+                explanation: query
+                    .explain
+                    .then(|| self.explain(query, &contacts[position])),
+            })
+            .collect()
     }
 
-    /// Match email addresses (exact match with normalization).
-    ///
-    /// Returns confidence score (100) if matched, None otherwise.
-    fn match_email(&self, query_email: &str, contact: &Contact) -> Option<u8> {
-        let normalized_query = Self::normalize_email(query_email);
-
-        // Check primary email
-        if let Some(ref email) = contact.email {
-            if Self::normalize_email(email) == normalized_query {
-                return Some(100);
-            }
+    /// Normalize and parse the query once for matching many contacts.
+    fn prepare(&mut self, query: &ContactQuery) -> PreparedQuery {
+        PreparedQuery {
+            email: query.email.as_deref().map(Self::normalize_email),
+            phone: query.phone.as_deref().map(|phone| self.phone_key(phone)),
+            social_url: query.social_url.as_deref().map(Self::normalize_url),
+            name: query.name.as_deref().map(|name| {
+                (
+                    NamePattern::new(name, &mut self.matcher),
+                    NameVariantQuery::new(name, self.nicknames.clone()),
+                )
+            }),
+            company: query
+                .company
+                .as_deref()
+                .map(|company| NamePattern::new(company, &mut self.matcher)),
         }
-
-        // Check additional emails
-        for email in &contact.emails {
-            if Self::normalize_email(email) == normalized_query {
-                return Some(100);
-            }
-        }
-
-        None
     }
 
-    /// Match phone numbers (exact match with normalization).
+    /// Compare the email, phone, social URL and name of the query with a
+    /// contact.
     ///
-    /// Returns confidence score (100) if matched, None otherwise.
-    fn match_phone(&self, query_phone: &str, contact: &Contact) -> Option<u8> {
-        let normalized_query = Self::normalize_phone(query_phone);
-
-        // Check primary phone
-        if let Some(ref phone) = contact.phone {
-            if Self::normalize_phone(phone) == normalized_query {
-                return Some(100);
-            }
+    /// `name` is the contact's folded name with its nucleo score, given if
+    /// the query has a name.
+    fn identify<'a>(
+        &mut self,
+        query: &PreparedQuery,
+        contact: &'a Contact,
+        name: Option<(&str, Option<u32>)>,
+    ) -> ContactEvidence<'a> {
+        let mut evidence = ContactEvidence::new();
+
+        if let Some(email) = &query.email {
+            let (kind, matched) = Self::email_evidence(email, contact);
+            evidence.set_exact(MatchSignal::Email, kind, matched);
         }
-
-        // Check additional phones
-        for phone in &contact.phones {
-            if Self::normalize_phone(phone) == normalized_query {
-                return Some(100);
-            }
+        if let Some(phone) = &query.phone {
+            let (kind, matched) = self.phone_evidence(phone, contact);
+            evidence.set_exact(MatchSignal::Phone, kind, matched);
+        }
+        if let Some(social_url) = &query.social_url {
+            let (kind, matched) = Self::social_evidence(social_url, contact);
+            evidence.set_exact(MatchSignal::Social, kind, matched);
         }
 
-        None
-    }
-
-    /// Match social media URLs (exact match with normalization).
-    ///
-    /// Returns confidence score (100) if matched, None otherwise.
-    fn match_social_url(&self, query_url: &str, contact: &Contact) -> Option<u8> {
-        let normalized_query = Self::normalize_url(query_url);
-
-        for profile in &contact.social_profiles {
-            if Self::normalize_url(&profile.url) == normalized_query {
-                return Some(100);
+        if let (Some((pattern, variant_query)), Some((target, nucleo_score))) = (&query.name, name)
+        {
+            if target.is_empty() {
+                evidence.set(MatchSignal::Name, Evidence::Missing, 0, None, None);
+            } else {
+                let (confidence, match_type, method) =
+                    Self::score_name(pattern, Some(variant_query), target, nucleo_score);
+                if confidence > 0 {
+                    let matched = Some(contact.name.as_str());
+                    evidence.set(
+                        MatchSignal::Name,
+                        Evidence::Agrees,
+                        confidence,
+                        Some(method),
+                        matched,
+                    );
+                    evidence.name_match = match_type;
+                } else {
+                    evidence.set(MatchSignal::Name, Evidence::Contradicts, 0, None, None);
+                }
             }
         }
 
-        None
+        evidence
     }
 
-    /// Fuzzy match names using a simple but effective algorithm.
+    /// Compare the company of the query with a contact's.
     ///
-    /// Returns confidence score (0-100) if matched, None otherwise.
-    fn fuzzy_match_name(&self, query: &str, contact_name: &str) -> Option<u8> {
-        // Normalize and prepare strings
-        let query_normalized = Self::normalize_name(query);
-        let name_normalized = Self::normalize_name(contact_name);
-
-        // Calculate fuzzy match score
-        let score = Self::calculate_fuzzy_score(&query_normalized, &name_normalized);
+    /// Companies must match with more than 50 confidence to agree.
+    fn compare_company<'a>(
+        &mut self,
+        query: &PreparedQuery,
+        contact: &'a Contact,
+        evidence: &mut ContactEvidence<'a>,
+    ) {
+        let Some(pattern) = &query.company else {
+            return;
+        };
+        let company = contact
+            .company
+            .as_deref()
+            .filter(|company| !company.trim().is_empty());
+        let Some(company) = company else {
+            evidence.set(MatchSignal::Company, Evidence::Missing, 0, None, None);
+            return;
+        };
 
-        if score > 0 {
-            Some(score)
+        let target = Self::normalize_name(company);
+        let nucleo_score = pattern.score(&target, &mut self.matcher);
+        let (confidence, method) = pattern.scored(&target, nucleo_score);
+        let method = (confidence > 0).then_some(method);
+        if confidence > MIN_COMPANY_CONFIDENCE {
+            evidence.set(
+                MatchSignal::Company,
+                Evidence::Agrees,
+                confidence,
+                method,
+                Some(company),
+            );
         } else {
-            None
+            evidence.set(
+                MatchSignal::Company,
+                Evidence::Contradicts,
+                confidence,
+                method,
+                None,
+            );
         }
     }
 
-    /// Fuzzy match company names.
-    fn fuzzy_match_company(&self, query: &str, company: &str) -> Option<u8> {
-        let query_normalized = Self::normalize_name(query);
-        let company_normalized = Self::normalize_name(company);
-
-        let score = Self::calculate_fuzzy_score(&query_normalized, &company_normalized);
+    /// Explain how `query` scores `contact`.
+    ///
+    /// Every signal of the query is scored, with whether the contact agrees
+    /// with it, contradicts it or has no value for it, and the points it
+    /// adds to or takes from the confidence (see [`super::evidence`]). The
+    /// contributions add up to the confidence
+    /// [`find_matches`](Self::find_matches) gives the contact, or to 0 if
+    /// no email, phone, social or name signal agrees.
+    pub fn explain(&mut self, query: &ContactQuery, contact: &Contact) -> MatchExplanation {
+        let prepared = self.prepare(query);
+        let normalized = NormalizedQuery {
+            name: prepared
+                .name
+                .as_ref()
+                .map(|(pattern, _)| pattern.text.clone()),
+            email: prepared.email.clone(),
+            phone: prepared.phone.as_ref().map(|key| key.number.clone()),
+            social_url: prepared.social_url.clone(),
+            company: prepared
+                .company
+                .as_ref()
+                .map(|pattern| pattern.text.clone()),
+        };
 
-        if score > 50 {
-            // Higher threshold for company matching
-            Some(score)
-        } else {
-            None
+        let target = Self::normalize_name(&contact.name);
+        let name = prepared
+            .name
+            .as_ref()
+            .map(|(pattern, _)| (target.as_str(), pattern.score(&target, &mut self.matcher)));
+        let mut evidence = self.identify(&prepared, contact, name);
+        self.compare_company(&prepared, contact, &mut evidence);
+
+        let mut applied = [None; MatchSignal::ALL.len()];
+        self.weights
+            .combine(&evidence.signals, |signal, weight, points| {
+                applied[signal as usize] = Some((weight, points));
+            });
+
+        let signals = MatchSignal::ALL
+            .into_iter()
+            .filter_map(|signal| {
+                let (kind, raw_score) = evidence.signals.get(signal)?;
+                let (weight, contribution) = applied[signal as usize].unwrap_or((0.0, 0));
+                Some(SignalScore {
+                    signal,
+                    evidence: kind,
+                    raw_score,
+                    weight,
+                    contribution,
+                    method: evidence.methods[signal as usize],
+                    matched_value: evidence.matched[signal as usize].map(str::to_string),
+                })
+            })
+            .collect();
+
+        MatchExplanation {
+            query: normalized,
+            signals,
         }
     }
 
-    /// Calculate fuzzy match score using Levenshtein distance and substring matching.
+    /// Confidence of a name match against a folded target, with its match
+    /// type and the method that found it.
     ///
-    /// Returns a confidence score from 0-95 (95 max to reserve 100 for exact matches).
-    fn calculate_fuzzy_score(query: &str, target: &str) -> u8 {
-        if query.is_empty() || target.is_empty() {
-            return 0;
+    /// Name variants are only tried if nucleo or edit distance scored below
+    /// what a variant can reach.
+    fn score_name(
+        pattern: &NamePattern,
+        variant_query: Option<&NameVariantQuery>,
+        target: &str,
+        nucleo_score: Option<u32>,
+    ) -> (u8, MatchType, &'static str) {
+        let (confidence, method) = pattern.scored(target, nucleo_score);
+        match variant_query
+            .filter(|_| confidence < MAX_VARIANT_CONFIDENCE)
+            .and_then(|variant_query| variant_query.matches(target))
+        {
+            Some((kind, variant_confidence)) if variant_confidence > confidence => (
+                variant_confidence,
+                MatchType::NameVariant(kind),
+                kind.name(),
+            ),
+            _ => (confidence, MatchType::FuzzyName, method),
         }
-
-        // Exact match
-        if query == target {
-            return 95; // Reserve 100 for exact email/phone/social matches
-        }
-
-        // Contains match (substring)
-        if target.contains(query) {
-            let ratio = query.len() as f64 / target.len() as f64;
-            return (85.0 * ratio + 10.0) as u8; // 85-95 range for contains matches
-        }
-
-        if query.contains(target) {
-            return 85;
-        }
-
-        // Levenshtein distance-based fuzzy matching
-        let distance = Self::levenshtein_distance(query, target);
-        let max_len = query.len().max(target.len());
-
-        if distance as f64 / max_len as f64 > 0.5 {
-            // Too many differences
-            return 0;
-        }
-
-        // Calculate similarity percentage
-        let similarity = 1.0 - (distance as f64 / max_len as f64);
-        (similarity * 85.0) as u8 // Scale to 0-85 range
     }
 
-    /// Calculate Levenshtein distance between two strings.
-    fn levenshtein_distance(s1: &str, s2: &str) -> usize {
-        let len1 = s1.chars().count();
-        let len2 = s2.chars().count();
-
-        if len1 == 0 {
-            return len2;
-        }
-        if len2 == 0 {
-            return len1;
-        }
+    /// Whether one of the contact's email addresses (primary first) is the
+    /// canonical `email`, with the one that is.
+    fn email_evidence<'a>(email: &str, contact: &'a Contact) -> (Evidence, Option<&'a String>) {
+        exact_evidence(contact.email.iter().chain(&contact.emails), |candidate| {
+            Self::normalize_email(candidate) == email
+        })
+    }
 
-        let mut matrix: Vec<Vec<usize>> = vec![vec![0; len2 + 1]; len1 + 1];
+    /// Whether one of the contact's phone numbers (primary first) reaches
+    /// the same phone as `key`, with the one that does.
+    ///
+    /// Numbers match if they are the same in E.164 form, with the same
+    /// extension or an extension on only one side.
+    fn phone_evidence<'a>(
+        &mut self,
+        key: &PhoneKey,
+        contact: &'a Contact,
+    ) -> (Evidence, Option<&'a String>) {
+        exact_evidence(contact.phone.iter().chain(&contact.phones), |candidate| {
+            key.matches(&self.phone_key(candidate))
+        })
+    }
 
-        // Initialize first column
-        for (i, row) in matrix.iter_mut().enumerate() {
-            row[0] = i;
+    /// The comparison key of a phone number, parsed once per matcher.
+    fn phone_key(&mut self, phone: &str) -> PhoneKey {
+        if let Some(key) = self.phone_keys.get(phone) {
+            return key.clone();
         }
-        // Initialize first row
-        for (j, cell) in matrix[0].iter_mut().enumerate() {
-            *cell = j;
-        }
-
-        let s1_chars: Vec<char> = s1.chars().collect();
-        let s2_chars: Vec<char> = s2.chars().collect();
-
-        for (i, c1) in s1_chars.iter().enumerate() {
-            for (j, c2) in s2_chars.iter().enumerate() {
-                let cost = if c1 == c2 { 0 } else { 1 };
-                matrix[i + 1][j + 1] = (matrix[i][j + 1] + 1)
-                    .min(matrix[i + 1][j] + 1)
-                    .min(matrix[i][j] + cost);
-            }
+        if self.phone_keys.len() >= PHONE_KEY_CACHE_CAPACITY {
+            self.phone_keys.clear();
         }
+        let key = PhoneKey::new(phone, self.phone_region);
+        self.phone_keys.insert(phone.to_string(), key.clone());
+        key
+    }
 
-        matrix[len1][len2]
+    /// Whether the URL of one of the contact's social profiles is the
+    /// normalized `url`, with the one that is.
+    fn social_evidence<'a>(url: &str, contact: &'a Contact) -> (Evidence, Option<&'a String>) {
+        exact_evidence(
+            contact.social_profiles.iter().map(|profile| &profile.url),
+            |candidate| Self::normalize_url(candidate) == url,
+        )
     }
 
     // ==================== Normalization Functions ====================
 
     /// Normalize an email address for comparison.
     ///
-    /// Converts to lowercase and trims whitespace.
+    /// Trims whitespace and returns the canonical form of the address (see
+    /// [`EmailAddress::canonical`]), or the lowercased text if it is not a
+    /// valid address.
     pub fn normalize_email(email: &str) -> String {
-        email.trim().to_lowercase()
+        let email = email.trim();
+        EmailAddress::new(email).map_or_else(|_| email.to_lowercase(), |email| email.canonical())
     }
 
     /// Normalize a phone number for comparison.
     ///
-    /// Extracts only digits and takes the last 10 digits (US format).
-    pub fn normalize_phone(phone: &str) -> String {
-        let digits: String = phone.chars().filter(|c| c.is_ascii_digit()).collect();
-
-        // Take last 10 digits (handles country codes)
-        if digits.len() > 10 {
-            digits[digits.len() - 10..].to_string()
-        } else {
-            digits
-        }
+    /// Returns the E.164 form of the number, reading it in `region` if it
+    /// has no country code, or only its digits if it cannot be parsed. The
+    /// extension is dropped.
+    pub fn normalize_phone(phone: &str, region: PhoneRegion) -> String {
+        PhoneKey::new(phone, region).number
     }
 
     /// Normalize a URL for comparison.
@@ -346,13 +775,10 @@ impl ContactMatcher {
 
     /// Normalize a name for fuzzy matching.
     ///
-    /// Converts to lowercase and collapses whitespace.
+    /// Folds case, diacritics and Unicode compatibility forms the same way as
+    /// full-text search (see [`crate::text::fold`]), and collapses whitespace.
     pub fn normalize_name(name: &str) -> String {
-        name.trim()
-            .to_lowercase()
-            .split_whitespace()
-            .collect::<Vec<_>>()
-            .join(" ")
+        crate::text::normalize(name)
     }
 }
 
@@ -379,6 +805,9 @@ pub struct ContactQuery {
 
     /// Social media URL to search for
     pub social_url: Option<String>,
+
+    /// Whether to explain the confidence of each result
+    pub explain: bool,
 }
 
 #[cfg(test)]
@@ -403,22 +832,36 @@ mod tests {
             ContactMatcher::normalize_email("user@domain.com"),
             "user@domain.com"
         );
+        assert_eq!(
+            ContactMatcher::normalize_email("John.Doe+crm@GoogleMail.com"),
+            "johndoe@gmail.com"
+        );
+        assert_eq!(
+            ContactMatcher::normalize_email(" Not An Email "),
+            "not an email"
+        );
     }
 
     #[test]
     fn test_normalize_phone() {
+        let us = PhoneRegion::default();
+        assert_eq!(
+            ContactMatcher::normalize_phone("+1 (555) 123-4567", us),
+            "+15551234567"
+        );
         assert_eq!(
-            ContactMatcher::normalize_phone("+1 (555) 123-4567"),
-            "5551234567"
+            ContactMatcher::normalize_phone("555-123-4567 x89", us),
+            "+15551234567"
         );
         assert_eq!(
-            ContactMatcher::normalize_phone("555-123-4567"),
-            "5551234567"
+            ContactMatcher::normalize_phone("+44 20 7123 4567", us),
+            "+442071234567"
         );
         assert_eq!(
-            ContactMatcher::normalize_phone("+44 20 7123 4567"),
-            "2071234567"
-        ); // Last 10 digits
+            ContactMatcher::normalize_phone("020 7123 4567", "GB".parse().unwrap()),
+            "+442071234567"
+        );
+        assert_eq!(ContactMatcher::normalize_phone("12345", us), "12345"); // Too short to parse
     }
 
     #[test]
@@ -437,49 +880,80 @@ mod tests {
     fn test_normalize_name() {
         assert_eq!(ContactMatcher::normalize_name("  John   Doe  "), "john doe");
         assert_eq!(ContactMatcher::normalize_name("JANE SMITH"), "jane smith");
+        assert_eq!(
+            ContactMatcher::normalize_name("José  Müller"),
+            "jose muller"
+        );
     }
 
     #[test]
     fn test_exact_email_match() {
-        let matcher = ContactMatcher::new();
         let contact = create_test_contact("John Doe", Some("john@example.com"), None);
+        let evidence = |email: &str, contact: &Contact| {
+            ContactMatcher::email_evidence(&ContactMatcher::normalize_email(email), contact).0
+        };
 
-        assert_eq!(matcher.match_email("john@example.com", &contact), Some(100));
-        assert_eq!(matcher.match_email("JOHN@EXAMPLE.COM", &contact), Some(100)); // Case insensitive
-        assert_eq!(matcher.match_email("jane@example.com", &contact), None);
+        assert_eq!(evidence("john@example.com", &contact), Evidence::Agrees);
+        assert_eq!(evidence("JOHN@EXAMPLE.COM", &contact), Evidence::Agrees); // Case insensitive
+        assert_eq!(
+            evidence("jane@example.com", &contact),
+            Evidence::Contradicts
+        );
+
+        let contact = create_test_contact("John Doe", Some("johndoe@googlemail.com"), None);
+        assert_eq!(
+            evidence("john.doe+crm@gmail.com", &contact),
+            Evidence::Agrees
+        );
+
+        let contact = create_test_contact("John Doe", None, None);
+        assert_eq!(evidence("john@example.com", &contact), Evidence::Missing);
     }
 
     #[test]
     fn test_exact_phone_match() {
-        let matcher = ContactMatcher::new();
+        let mut matcher = ContactMatcher::new();
+        let mut evidence = |phone: &str, contact: &Contact| {
+            let key = matcher.phone_key(phone);
+            matcher.phone_evidence(&key, contact).0
+        };
         let contact = create_test_contact("John Doe", None, Some("+1 (555) 123-4567"));
 
-        assert_eq!(matcher.match_phone("555-123-4567", &contact), Some(100));
-        assert_eq!(matcher.match_phone("+1 555 123 4567", &contact), Some(100)); // Normalized
-        assert_eq!(matcher.match_phone("555-999-8888", &contact), None);
+        assert_eq!(evidence("555-123-4567", &contact), Evidence::Agrees);
+        assert_eq!(evidence("+1 555 123 4567", &contact), Evidence::Agrees); // Normalized
+        assert_eq!(evidence("011 1 555 123 4567", &contact), Evidence::Agrees); // Dialling prefix
+        assert_eq!(evidence("555-123-4567 ext. 2", &contact), Evidence::Agrees);
+        assert_eq!(evidence("555-999-8888", &contact), Evidence::Contradicts);
+
+        let contact = create_test_contact("Jane Doe", None, Some("555-123-4567 x1"));
+        assert_eq!(evidence("555-123-4567 x2", &contact), Evidence::Contradicts);
     }
 
     #[test]
     fn test_fuzzy_name_match() {
-        let matcher = ContactMatcher::new();
+        let mut matcher = ContactMatcher::new();
+        let contact = create_test_contact("John Doe", None, None);
+        let mut name_score = |name: &str| {
+            let query = ContactQuery {
+                name: Some(name.to_string()),
+                ..Default::default()
+            };
+            let explanation = matcher.explain(&query, &contact);
+            assert_eq!(explanation.signals[0].signal, MatchSignal::Name);
+            explanation.signals[0].raw_score
+        };
 
         // Exact match should score very high
-        let score = matcher.fuzzy_match_name("john doe", "John Doe");
-        assert!(score.is_some());
-        assert!(score.unwrap() >= 85);
+        assert!(name_score("john doe") >= 85);
 
         // Partial match
-        let score = matcher.fuzzy_match_name("john", "John Doe");
-        assert!(score.is_some());
-        assert!(score.unwrap() >= 50);
+        assert!(name_score("john") >= 50);
 
         // Typo tolerance
-        let score = matcher.fuzzy_match_name("johnn doe", "John Doe");
-        assert!(score.is_some());
+        assert!(name_score("johnn doe") > 0);
 
         // No match
-        let _score = matcher.fuzzy_match_name("alice", "John Doe");
-        // May or may not match depending on nucleo's algorithm
+        assert!(name_score("alice") < 50);
     }
 
     #[test]
@@ -551,6 +1025,27 @@ mod tests {
         assert_eq!(results[0].confidence, 100);
     }
 
+    #[test]
+    fn test_contradicting_name_lowers_email_match() {
+        let mut matcher = ContactMatcher::new();
+        let contacts = vec![create_test_contact(
+            "John Doe",
+            Some("john@example.com"),
+            None,
+        )];
+
+        let query = ContactQuery {
+            name: Some("Alice Walker".to_string()),
+            email: Some("john@example.com".to_string()),
+            ..Default::default()
+        };
+
+        let results = matcher.find_matches(&query, &contacts, 5, 0);
+        assert_eq!(results[0].confidence, 50);
+        assert_eq!(results[0].match_type, MatchType::ExactEmail);
+        assert!(matcher.find_matches(&query, &contacts, 5, 60).is_empty());
+    }
+
     #[test]
     fn test_confidence_threshold() {
         let mut matcher = ContactMatcher::new();
@@ -1,8 +1,27 @@
 //! Fuzzy matching utilities for contact discovery.
 //!
 //! This module provides fuzzy and exact matching for contacts based on
-//! names, emails, phone numbers, and social media URLs.
+//! names, emails, phone numbers, and social media URLs. Names are scored
+//! with nucleo, on the calling thread for small contact lists and through a
+//! warm multithreaded [`NameIndex`] for large ones, and compared word by
+//! word for nicknames, initials and other variants. [`find_duplicates`]
+//! scans a whole address book for contacts that are the same person.
+//! Signals that agree with a contact raise its confidence and signals that
+//! contradict it lower it, by configurable [`MatchWeights`], and a
+//! [`MatchExplanation`] breaks a match's confidence down by signal.
 
+pub mod duplicates;
+pub mod evidence;
+pub mod explanation;
 pub mod fuzzy_matcher;
+pub mod name_index;
+pub mod name_variants;
 
+pub use duplicates::{
+    find_duplicates, DuplicateCluster, DuplicateEvidence, DuplicateMatch, DuplicateReport,
+};
+pub use evidence::{Evidence, MatchWeights, SignalWeight};
+pub use explanation::{MatchExplanation, MatchSignal, NormalizedQuery, SignalScore};
 pub use fuzzy_matcher::{ContactMatcher, ContactQuery, MatchResult, MatchType};
+pub use name_index::{NameIndex, PARALLEL_MATCH_THRESHOLD};
+pub use name_variants::{NameVariantKind, NicknameMap};
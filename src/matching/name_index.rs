@@ -0,0 +1,140 @@
+//! Multithreaded name scoring for large contact lists.
+//!
+//! A [`NameIndex`] keeps a `nucleo::Nucleo` instance loaded with the names of
+//! a contact list, so each lookup only has to reparse the pattern and let
+//! nucleo's worker threads filter every name in parallel. Nucleo's snapshot
+//! does not expose match scores, so only the names it kept are rescored on
+//! the calling thread.
+
+use super::fuzzy_matcher::{ContactMatcher, NamePattern};
+use crate::models::Contact;
+use nucleo::pattern::{CaseMatching, Normalization};
+use nucleo::{Config, Matcher, Nucleo};
+use std::sync::Arc;
+
+/// Contact lists at least this long are matched through a [`NameIndex`].
+pub const PARALLEL_MATCH_THRESHOLD: usize = 2000;
+
+/// Milliseconds to wait for nucleo's workers per tick.
+const TICK_TIMEOUT_MS: u64 = 10;
+
+/// Contact names loaded into a warm nucleo matcher.
+pub struct NameIndex {
+    /// The indexed contacts
+    contacts: Arc<Vec<Contact>>,
+
+    /// Normalized contact names, by position
+    names: Vec<String>,
+
+    /// Nucleo instance whose items are positions into `contacts`
+    nucleo: Nucleo<u32>,
+
+    /// Scratch space for rescoring matched names
+    matcher: Matcher,
+}
+
+impl NameIndex {
+    /// Load the names of `contacts`.
+    ///
+    /// `threads` defaults to the number of available cores.
+    pub fn new(contacts: Arc<Vec<Contact>>, threads: Option<usize>) -> Self {
+        let nucleo = Nucleo::new(Config::DEFAULT, Arc::new(|| {}), threads, 1);
+        let names: Vec<String> = contacts
+            .iter()
+            .map(|contact| ContactMatcher::normalize_name(&contact.name))
+            .collect();
+        let injector = nucleo.injector();
+        for (position, name) in names.iter().enumerate() {
+            injector.push(position as u32, |_, columns| {
+                columns[0] = name.as_str().into()
+            });
+        }
+        Self {
+            contacts,
+            names,
+            nucleo,
+            matcher: Matcher::new(Config::DEFAULT),
+        }
+    }
+
+    /// The indexed contacts.
+    pub fn contacts(&self) -> &Arc<Vec<Contact>> {
+        &self.contacts
+    }
+
+    /// Normalized names of the indexed contacts, by position.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Whether the index was built from this very contact list.
+    pub fn is_for(&self, contacts: &Arc<Vec<Contact>>) -> bool {
+        Arc::ptr_eq(&self.contacts, contacts)
+    }
+
+    /// Nucleo score of every contact name against `query`, by position.
+    ///
+    /// Names that do not match every word of the query, or score below
+    /// `min_score`, score `None`. Blocks until nucleo's workers are done, so
+    /// call it from a blocking thread rather than an async task.
+    pub fn scores(&mut self, query: &str, min_score: u32) -> Vec<Option<u32>> {
+        let mut scores = vec![None; self.contacts.len()];
+        let pattern = NamePattern::pattern_text(&ContactMatcher::normalize_name(query));
+        if pattern.is_empty() {
+            return scores;
+        }
+
+        self.nucleo.pattern.reparse(
+            0,
+            &pattern,
+            CaseMatching::Ignore,
+            Normalization::Smart,
+            false,
+        );
+        while self.nucleo.tick(TICK_TIMEOUT_MS).running {}
+
+        let snapshot = self.nucleo.snapshot();
+        // Matched items come best first, so rescoring can stop early
+        for item in snapshot.matched_items(..) {
+            let score = snapshot
+                .pattern()
+                .score(item.matcher_columns, &mut self.matcher);
+            if score.is_none_or(|score| score < min_score) {
+                break;
+            }
+            scores[*item.data as usize] = score;
+        }
+        scores
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scores_match_sequential_matcher() {
+        let contacts: Vec<Contact> = ["John Doe", "Jane Doe", "Johnny Appleseed", "Zoë Müller"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| Contact::new(i.to_string(), name.to_string()))
+            .collect();
+        let mut index = NameIndex::new(Arc::new(contacts.clone()), Some(2));
+
+        let scores = index.scores("john", 0);
+        assert!(scores[0].is_some());
+        assert!(scores[1].is_none());
+        assert!(scores[2].is_some());
+
+        // Names scoring below the minimum are left out
+        let best = scores[0].max(scores[2]).unwrap();
+        assert_eq!(index.scores("john", best + 1), vec![None; 4]);
+
+        let mut matcher = Matcher::new(Config::DEFAULT);
+        let pattern = NamePattern::new("zoe", &mut matcher);
+        let expected = pattern.score(&ContactMatcher::normalize_name("Zoë Müller"), &mut matcher);
+        assert_eq!(index.scores("zoe", 0)[3], expected);
+
+        assert!(index.scores("!^$", 0).iter().all(Option::is_none));
+    }
+}
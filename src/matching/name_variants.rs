@@ -0,0 +1,544 @@
+//! Name variants for fuzzy name matching.
+//!
+//! Character-level matching scores "Bob Smith" poorly against "Robert
+//! Smith", and cannot tell that "J. Smith", "Smith, John" and "John Paul
+//! Smith" may all be "John Smith". This module compares names word by word
+//! instead: given names may be nicknames of each other (from a built-in
+//! table, extensible with a user file) or initials, middle names may be left
+//! out, hyphenated surnames may be shortened to one part, and the surname
+//! may come first. Each kind of variant caps the confidence of the match.
+
+use crate::text::normalize;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, LazyLock};
+
+/// Built-in groups of given names and their nicknames, one group per line.
+const BUILTIN_NICKNAMES: &str = "
+abigail, abby, abbie, gail
+alexander, alex, al, sasha, xander, sandy
+alexandra, alex, lexi, sasha, sandra, sandy
+albert, al, bert, bertie
+alan, al
+alfred, al, alf, fred, freddie
+andrew, andy, drew
+angela, angie
+anthony, tony
+barbara, barb, barbie, babs
+benjamin, ben, benny, benji
+bradley, brad
+catherine, cathy, cat, kate, katie, kathy
+charles, charlie, chuck, chas, chaz
+christina, chris, tina, christy
+christine, chris, tina, christy
+christopher, chris, kit, topher
+daniel, dan, danny
+david, dave, davey
+deborah, debbie, deb
+donald, don, donny
+dorothy, dot, dottie
+edward, ed, eddie, ted, teddy, ned
+elizabeth, liz, lizzie, beth, betty, betsy, eliza, libby, lisa, liza
+eleanor, ellie, nora, nell
+emily, em, emmy
+frances, fran, frannie
+francis, frank, fran
+frederick, fred, freddie, fritz
+gabriel, gabe
+gabrielle, gabby, gabi
+gerald, gerry, jerry
+gregory, greg
+harold, harry, hal
+henry, harry, hank, hal
+jacob, jake, jakey
+james, jim, jimmy, jamie, jem
+jennifer, jen, jenny
+jessica, jess, jessie
+john, jack, johnny, jon
+jonathan, jon, jonny, nathan
+joseph, joe, joey
+joshua, josh
+katherine, kathy, kate, katie, kat, kay, kit
+kenneth, ken, kenny
+lawrence, larry
+leonard, leo, len, lenny
+louis, lou
+madeline, maddie, maddy
+margaret, maggie, meg, peggy, marge, margie, greta, daisy
+matthew, matt
+michael, mike, mikey, mick, mickey
+nathaniel, nate, nathan, nat
+nicholas, nick, nicky, nico
+patricia, pat, patty, trish, tricia
+patrick, pat, paddy
+peter, pete
+philip, phil
+rebecca, becky, becca
+richard, rick, ricky, dick, rich, richie
+robert, bob, bobby, rob, robbie, bert
+ronald, ron, ronnie
+samantha, sam, sammy
+samuel, sam, sammy
+stephen, steve, stevie
+steven, steve, stevie
+susan, sue, susie, suzy
+theodore, ted, teddy, theo
+thomas, tom, tommy
+timothy, tim, timmy
+victoria, vicky, tori
+walter, walt, wally
+william, bill, billy, will, willy, liam
+zachary, zach, zack
+";
+
+/// The built-in table, parsed once.
+static BUILTIN: LazyLock<Arc<NicknameMap>> =
+    LazyLock::new(|| Arc::new(NicknameMap::parse(BUILTIN_NICKNAMES)));
+
+/// Groups of given names that may stand for each other.
+///
+/// A name can belong to several groups ("al" is short for Albert, Alan and
+/// Alexander); two names are variants if they share a group.
+#[derive(Debug, Clone, Default)]
+pub struct NicknameMap {
+    /// Folded name, mapped to the groups it belongs to
+    groups: HashMap<String, Vec<usize>>,
+
+    /// Names of each group
+    members: Vec<Vec<String>>,
+}
+
+impl NicknameMap {
+    /// Create an empty nickname map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The built-in table of common English given names and nicknames.
+    pub fn builtin() -> Arc<Self> {
+        BUILTIN.clone()
+    }
+
+    /// Parse nickname groups, one per line, with comma-separated names:
+    ///
+    /// ```text
+    /// # comment
+    /// robert, bob, bobby, rob
+    /// margaret, maggie, peggy
+    /// ```
+    ///
+    /// Blank lines, comments and lines with a single name are ignored.
+    pub fn parse(text: &str) -> Self {
+        let mut map = Self::new();
+        map.extend_from(text);
+        map
+    }
+
+    /// Load the built-in table plus the groups of a file in the format of
+    /// [`parse`](Self::parse).
+    ///
+    /// A missing file yields just the built-in table.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let mut map = (*Self::builtin()).clone();
+        match std::fs::read_to_string(path) {
+            Ok(text) => map.extend_from(&text),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        Ok(map)
+    }
+
+    /// Add the groups of `text`, in the format of [`parse`](Self::parse).
+    fn extend_from(&mut self, text: &str) {
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut group: Vec<String> = Vec::new();
+            for name in line.split(',').map(normalize) {
+                if !name.is_empty() && !group.contains(&name) {
+                    group.push(name);
+                }
+            }
+            if group.len() < 2 {
+                continue;
+            }
+            let id = self.members.len();
+            for name in &group {
+                self.groups.entry(name.clone()).or_default().push(id);
+            }
+            self.members.push(group);
+        }
+    }
+
+    /// Number of names with a nickname.
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Whether the map has no nicknames.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Whether two different folded names share a group.
+    pub fn are_variants(&self, a: &str, b: &str) -> bool {
+        if a == b {
+            return false;
+        }
+        match (self.groups.get(a), self.groups.get(b)) {
+            (Some(a), Some(b)) => a.iter().any(|group| b.contains(group)),
+            _ => false,
+        }
+    }
+
+    /// Every name sharing a group with a folded name.
+    fn variants_of<'a>(&'a self, name: &str) -> impl Iterator<Item = &'a str> {
+        self.groups
+            .get(name)
+            .into_iter()
+            .flatten()
+            .flat_map(|&group| self.members[group].iter().map(String::as_str))
+    }
+}
+
+/// The kind of name variant that matched, from most to least certain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NameVariantKind {
+    /// Surname first ("Smith, John" or "Smith John")
+    Reordered,
+
+    /// A middle name on one side only ("John Paul Smith")
+    MiddleName,
+
+    /// One part of a hyphenated surname ("Smith" for "Smith-Jones")
+    HyphenatedSurname,
+
+    /// A nickname of the given name ("Bob" for "Robert")
+    Nickname,
+
+    /// An initial for a name ("J. Smith")
+    Initial,
+}
+
+/// Highest confidence of any name variant match.
+pub(crate) const MAX_VARIANT_CONFIDENCE: u8 = 92;
+
+impl NameVariantKind {
+    /// The kind's name, as used in match explanations.
+    pub fn name(self) -> &'static str {
+        match self {
+            NameVariantKind::Reordered => "reordered",
+            NameVariantKind::MiddleName => "middle_name",
+            NameVariantKind::HyphenatedSurname => "hyphenated_surname",
+            NameVariantKind::Nickname => "nickname",
+            NameVariantKind::Initial => "initial",
+        }
+    }
+
+    /// Highest confidence of a match involving this kind of variant.
+    pub fn max_confidence(self) -> u8 {
+        match self {
+            NameVariantKind::Reordered => MAX_VARIANT_CONFIDENCE,
+            NameVariantKind::MiddleName => 90,
+            NameVariantKind::HyphenatedSurname => 88,
+            NameVariantKind::Nickname => 88,
+            NameVariantKind::Initial => 80,
+        }
+    }
+}
+
+/// A name split into given names and surname parts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PersonName {
+    /// First and middle names, folded, without trailing periods
+    given: Vec<String>,
+
+    /// Hyphen-separated parts of the surname (empty for a single word)
+    surname: Vec<String>,
+
+    /// Whether the surname was written first, before a comma
+    comma_reversed: bool,
+}
+
+impl PersonName {
+    /// Split a folded name; "last, first middle" is put back in order.
+    fn parse(name: &str) -> Self {
+        let (mut words, comma_reversed) = match name.split_once(',') {
+            Some((last, rest)) if !last.trim().is_empty() && !rest.trim().is_empty() => {
+                let mut words = name_words(rest);
+                words.extend(name_words(last));
+                (words, true)
+            }
+            _ => (name_words(name), false),
+        };
+
+        let surname = if words.len() > 1 {
+            let last = words.pop().unwrap_or_default();
+            last.split('-')
+                .filter(|part| !part.is_empty())
+                .map(str::to_string)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Self {
+            given: words,
+            surname,
+            comma_reversed,
+        }
+    }
+
+    /// The same name with the first and last words swapped.
+    fn swapped(&self) -> Option<Self> {
+        let [first, middle @ ..] = self.given.as_slice() else {
+            return None;
+        };
+        if self.surname.is_empty() || self.comma_reversed {
+            return None;
+        }
+        let mut given = vec![self.surname.join("-")];
+        given.extend(middle.iter().cloned());
+        Some(Self {
+            given,
+            surname: first.split('-').map(str::to_string).collect(),
+            comma_reversed: false,
+        })
+    }
+}
+
+/// Words of a folded name, split on whitespace, periods and commas.
+fn name_words(text: &str) -> Vec<String> {
+    text.split(|c: char| c.is_whitespace() || c == '.' || c == ',')
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// A name query prepared for variant matching.
+pub(crate) struct NameVariantQuery {
+    /// The query name split into parts
+    name: PersonName,
+
+    /// Surname parts, one of which a target must contain (unless its
+    /// surname could be the query's first name or an initial)
+    surname_keys: Vec<String>,
+
+    /// For queries without a full surname, the first name and its
+    /// nicknames, one of which a target must contain
+    given_keys: Vec<String>,
+
+    /// Nicknames used to compare given names
+    nicknames: Arc<NicknameMap>,
+}
+
+impl NameVariantQuery {
+    /// Prepare `query` for matching.
+    pub(crate) fn new(query: &str, nicknames: Arc<NicknameMap>) -> Self {
+        let name = PersonName::parse(&normalize(query));
+        let mut surname_keys = Vec::new();
+        let mut given_keys = Vec::new();
+        if name.surname.iter().all(|part| part.chars().count() > 1) {
+            surname_keys.extend(name.surname.iter().cloned());
+        }
+        if surname_keys.is_empty() {
+            if let Some(first) = name.given.first() {
+                given_keys.push(first.clone());
+                given_keys.extend(nicknames.variants_of(first).map(str::to_string));
+            }
+        }
+        Self {
+            name,
+            surname_keys,
+            given_keys,
+            nicknames,
+        }
+    }
+
+    /// Kind and confidence of the variant of the query a folded name is, if
+    /// any.
+    ///
+    /// Names that are spelled the same, word for word, are left to fuzzy
+    /// matching and return `None`.
+    pub(crate) fn matches(&self, target: &str) -> Option<(NameVariantKind, u8)> {
+        if !self.may_match(target) {
+            return None;
+        }
+
+        let target = PersonName::parse(target);
+        let mut kinds = Vec::new();
+        if self.name.comma_reversed || target.comma_reversed {
+            kinds.push(NameVariantKind::Reordered);
+        }
+        if let Some(found) = self.compare(&self.name, &target) {
+            kinds.extend(found);
+        } else {
+            let swapped = self.name.swapped()?;
+            kinds.extend(self.compare(&swapped, &target)?);
+            kinds.push(NameVariantKind::Reordered);
+        }
+
+        let weakest = *kinds.iter().max()?;
+        // Each further difference costs a little more confidence
+        let penalty = 2 * (kinds.len() as u8 - 1);
+        Some((weakest, weakest.max_confidence().saturating_sub(penalty)))
+    }
+
+    /// Cheap check, before parsing, that a folded name shares the words a
+    /// variant match needs.
+    fn may_match(&self, target: &str) -> bool {
+        let mut words = target
+            .split(|c: char| c.is_whitespace() || c == '-' || c == '.' || c == ',')
+            .filter(|word| !word.is_empty());
+        if self.surname_keys.is_empty() {
+            return words.any(|word| self.given_keys.iter().any(|key| key == word));
+        }
+
+        let mut last = "";
+        for word in words {
+            if self.surname_keys.iter().any(|key| key == word) {
+                return true;
+            }
+            last = word;
+        }
+        // Surname first, or the target's surname is an initial
+        last.chars().count() == 1 || self.name.given.first().is_some_and(|first| first == last)
+    }
+
+    /// Variants needed to turn `query` into `target`, or `None` if they are
+    /// different names.
+    fn compare(&self, query: &PersonName, target: &PersonName) -> Option<Vec<NameVariantKind>> {
+        let mut kinds = Vec::new();
+
+        // A lone word is a given name; compare it with the target's
+        let (q_first, t_first) = (query.given.first()?, target.given.first()?);
+        kinds.extend(self.compare_given(q_first, t_first)?);
+
+        if !query.surname.is_empty() {
+            if target.surname.is_empty() {
+                return None;
+            }
+            kinds.extend(compare_surname(&query.surname, &target.surname)?);
+        }
+
+        let (q_middle, t_middle) = (&query.given[1..], &target.given[1..]);
+        if !query.surname.is_empty() && q_middle.len() != t_middle.len() {
+            kinds.push(NameVariantKind::MiddleName);
+        }
+        // Middle names given on both sides must agree
+        for (q, t) in q_middle.iter().zip(t_middle) {
+            if q != t {
+                if !is_initial_of(q, t) && !is_initial_of(t, q) {
+                    return None;
+                }
+                kinds.push(NameVariantKind::Initial);
+            }
+        }
+
+        Some(kinds)
+    }
+
+    /// How one given name stands for another, if it can.
+    fn compare_given(&self, query: &str, target: &str) -> Option<Option<NameVariantKind>> {
+        if query == target {
+            Some(None)
+        } else if is_initial_of(query, target) || is_initial_of(target, query) {
+            Some(Some(NameVariantKind::Initial))
+        } else if self.nicknames.are_variants(query, target) {
+            Some(Some(NameVariantKind::Nickname))
+        } else {
+            None
+        }
+    }
+}
+
+/// How one surname stands for another, if it can.
+fn compare_surname(query: &[String], target: &[String]) -> Option<Option<NameVariantKind>> {
+    if query == target {
+        return Some(None);
+    }
+    match (query, target) {
+        ([q], [t]) if is_initial_of(q, t) || is_initial_of(t, q) => {
+            Some(Some(NameVariantKind::Initial))
+        }
+        ([q], parts) | (parts, [q]) if parts.contains(q) => {
+            Some(Some(NameVariantKind::HyphenatedSurname))
+        }
+        _ => None,
+    }
+}
+
+/// Whether `initial` is a single letter that starts `name`.
+fn is_initial_of(initial: &str, name: &str) -> bool {
+    let mut chars = initial.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => name.chars().count() > 1 && name.starts_with(c),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variant(query: &str, target: &str) -> Option<(NameVariantKind, u8)> {
+        NameVariantQuery::new(query, NicknameMap::builtin()).matches(&normalize(target))
+    }
+
+    #[test]
+    fn test_nickname_map() {
+        let map = NicknameMap::builtin();
+        assert!(map.are_variants("bob", "robert"));
+        assert!(map.are_variants("liz", "elizabeth"));
+        assert!(map.are_variants("al", "alan"));
+        assert!(!map.are_variants("bob", "william"));
+        assert!(!map.are_variants("bob", "bob"));
+
+        let custom = NicknameMap::parse("# comment\nIñigo, Iñi\nsolo\n");
+        assert!(custom.are_variants("inigo", "ini"));
+        assert_eq!(custom.len(), 2);
+    }
+
+    #[test]
+    fn test_person_name_parse() {
+        let name = PersonName::parse(&normalize("Smith-Jones, Mary Ann"));
+        assert_eq!(name.given, vec!["mary", "ann"]);
+        assert_eq!(name.surname, vec!["smith", "jones"]);
+        assert!(name.comma_reversed);
+
+        let name = PersonName::parse(&normalize("J. R. R. Tolkien"));
+        assert_eq!(name.given, vec!["j", "r", "r"]);
+        assert_eq!(name.surname, vec!["tolkien"]);
+    }
+
+    #[test]
+    fn test_variant_kinds() {
+        use NameVariantKind::*;
+
+        assert_eq!(variant("Bob Smith", "Robert Smith"), Some((Nickname, 88)));
+        assert_eq!(variant("Liz", "Elizabeth Taylor"), Some((Nickname, 88)));
+        assert_eq!(variant("J. Smith", "John Smith"), Some((Initial, 80)));
+        assert_eq!(variant("Smith, John", "John Smith"), Some((Reordered, 92)));
+        assert_eq!(variant("Smith John", "John Smith"), Some((Reordered, 92)));
+        assert_eq!(
+            variant("John Smith", "John Paul Smith"),
+            Some((MiddleName, 90))
+        );
+        assert_eq!(
+            variant("Mary Smith", "Mary Smith-Jones"),
+            Some((HyphenatedSurname, 88))
+        );
+        // Several variants at once: the weakest counts, minus a little per extra
+        assert_eq!(
+            variant("Bob Jones", "Robert James Smith-Jones"),
+            Some((Nickname, 84))
+        );
+
+        assert_eq!(variant("John Smith", "John Smith"), None);
+        assert_eq!(variant("Bob Smith", "Bill Smith"), None);
+        assert_eq!(variant("John A. Smith", "John B. Smith"), None);
+        assert_eq!(variant("John Smith", "John Jones"), None);
+    }
+}
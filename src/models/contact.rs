@@ -30,13 +30,30 @@ pub struct PhoneEntry {
     pub phone: String,
 }
 
+/// An email or phone entry as it may appear in JSON.
+///
+/// The API sends `{email: ...}` / `{phone_number: ...}` objects, while a
+/// serialized `Contact` (e.g. an audit log snapshot) holds plain strings.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ListEntry<T> {
+    Plain(String),
+    Entry(T),
+}
+
 /// Custom deserializer for emails that converts from API format to Vec<String>
 fn deserialize_emails<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let entries: Vec<EmailEntry> = Vec::deserialize(deserializer)?;
-    Ok(entries.into_iter().map(|e| e.email).collect())
+    let entries: Vec<ListEntry<EmailEntry>> = Vec::deserialize(deserializer)?;
+    Ok(entries
+        .into_iter()
+        .map(|e| match e {
+            ListEntry::Plain(email) => email,
+            ListEntry::Entry(entry) => entry.email,
+        })
+        .collect())
 }
 
 /// Custom deserializer for phones that converts from API format to Vec<String>
@@ -44,8 +61,14 @@ fn deserialize_phones<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let entries: Vec<PhoneEntry> = Vec::deserialize(deserializer)?;
-    Ok(entries.into_iter().map(|e| e.phone).collect())
+    let entries: Vec<ListEntry<PhoneEntry>> = Vec::deserialize(deserializer)?;
+    Ok(entries
+        .into_iter()
+        .map(|e| match e {
+            ListEntry::Plain(phone) => phone,
+            ListEntry::Entry(entry) => entry.phone,
+        })
+        .collect())
 }
 
 /// A contact in the Dex Personal CRM system.
@@ -539,6 +562,20 @@ mod tests {
         assert_eq!(contact.email, Some("john@example.com".to_string()));
     }
 
+    #[test]
+    fn test_contact_serialization_round_trip() {
+        let mut contact = Contact::new("123".to_string(), String::new());
+        contact.first_name = Some("John".to_string());
+        contact.emails = vec!["john@example.com".to_string()];
+        contact.phones = vec!["+1 555 123 4567".to_string()];
+
+        let json = serde_json::to_string(&contact).unwrap();
+        let restored: Contact = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.emails, contact.emails);
+        assert_eq!(restored.phones, contact.phones);
+    }
+
     #[test]
     fn test_social_profile() {
         let profile = SocialProfile::new(
@@ -0,0 +1,105 @@
+use crate::audit::{AuditEntry, AuditLog, AuditOperation, AuditRecordType};
+use crate::error::DexApiResult;
+use crate::models::Contact;
+use crate::repositories::traits::ContactRepository;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Contact repository decorator that records every mutation in the audit log.
+///
+/// Reads are passed straight through. Updates and deletes fetch the current
+/// record first so the log entry carries a before-image.
+pub struct AuditedContactRepository {
+    inner: Arc<dyn ContactRepository>,
+    audit_log: Arc<AuditLog>,
+}
+
+impl AuditedContactRepository {
+    /// Wrap a contact repository with audit logging.
+    pub fn new(inner: Arc<dyn ContactRepository>, audit_log: Arc<AuditLog>) -> Self {
+        Self { inner, audit_log }
+    }
+
+    /// Fetch the current state of a contact for the audit log.
+    async fn before_image(&self, id: &str) -> Option<Contact> {
+        match self.inner.get(id).await {
+            Ok(contact) => Some(contact),
+            Err(e) => {
+                tracing::warn!("Could not capture before-image of contact {}: {}", id, e);
+                None
+            }
+        }
+    }
+}
+
+/// Serialize a contact for storage in the audit log.
+fn snapshot(contact: &Contact) -> Option<serde_json::Value> {
+    serde_json::to_value(contact).ok()
+}
+
+#[async_trait]
+impl ContactRepository for AuditedContactRepository {
+    async fn get(&self, id: &str) -> DexApiResult<Contact> {
+        self.inner.get(id).await
+    }
+
+    async fn list(&self, limit: usize, offset: usize) -> DexApiResult<Vec<Contact>> {
+        self.inner.list(limit, offset).await
+    }
+
+    async fn search_by_email(
+        &self,
+        email: &str,
+        limit: usize,
+        offset: usize,
+    ) -> DexApiResult<Vec<Contact>> {
+        self.inner.search_by_email(email, limit, offset).await
+    }
+
+    async fn search_by_name(
+        &self,
+        query: &str,
+        limit: usize,
+        offset: usize,
+    ) -> DexApiResult<Vec<Contact>> {
+        self.inner.search_by_name(query, limit, offset).await
+    }
+
+    async fn create(&self, contact: &Contact) -> DexApiResult<Contact> {
+        let created = self.inner.create(contact).await?;
+
+        let entry = AuditEntry::new(
+            AuditOperation::Create,
+            AuditRecordType::Contact,
+            &created.id,
+            &created.id,
+        )
+        .with_after(snapshot(&created));
+        self.audit_log.append(&entry);
+
+        Ok(created)
+    }
+
+    async fn update(&self, id: &str, contact: &Contact) -> DexApiResult<Contact> {
+        let before = self.before_image(id).await;
+        let updated = self.inner.update(id, contact).await?;
+
+        let entry = AuditEntry::new(AuditOperation::Update, AuditRecordType::Contact, id, id)
+            .with_before(before.as_ref().and_then(snapshot))
+            .with_after(snapshot(&updated));
+        self.audit_log.append(&entry);
+
+        Ok(updated)
+    }
+
+    async fn delete(&self, id: &str) -> DexApiResult<()> {
+        let before = self.before_image(id).await;
+        self.inner.delete(id).await?;
+
+        let entry = AuditEntry::new(AuditOperation::Delete, AuditRecordType::Contact, id, id)
+            .with_before(before.as_ref().and_then(snapshot));
+        self.audit_log.append(&entry);
+
+        Ok(())
+    }
+}
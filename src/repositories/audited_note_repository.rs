@@ -0,0 +1,124 @@
+use crate::audit::{AuditEntry, AuditLog, AuditOperation, AuditRecordType};
+use crate::error::DexApiResult;
+use crate::models::Note;
+use crate::repositories::traits::NoteRepository;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Note repository decorator that records every mutation in the audit log.
+///
+/// Reads are passed straight through. Updates and deletes fetch the current
+/// note first so the log entry carries a before-image.
+pub struct AuditedNoteRepository {
+    inner: Arc<dyn NoteRepository>,
+    audit_log: Arc<AuditLog>,
+}
+
+impl AuditedNoteRepository {
+    /// Wrap a note repository with audit logging.
+    pub fn new(inner: Arc<dyn NoteRepository>, audit_log: Arc<AuditLog>) -> Self {
+        Self { inner, audit_log }
+    }
+
+    /// Fetch the current state of a note for the audit log.
+    async fn before_image(&self, id: &str) -> Option<Note> {
+        match self.inner.get(id).await {
+            Ok(note) => Some(note),
+            Err(e) => {
+                tracing::warn!("Could not capture before-image of note {}: {}", id, e);
+                None
+            }
+        }
+    }
+}
+
+/// Serialize a note for storage in the audit log.
+///
+/// The API serialization omits the ID and timestamp, so they are added back
+/// to keep the snapshot complete.
+fn snapshot(note: &Note) -> Option<serde_json::Value> {
+    let mut value = serde_json::to_value(note).ok()?;
+    if let Some(object) = value.as_object_mut() {
+        object.insert("id".to_string(), note.id.clone().into());
+        object.insert("event_time".to_string(), note.created_at.clone().into());
+    }
+    Some(value)
+}
+
+#[async_trait]
+impl NoteRepository for AuditedNoteRepository {
+    async fn get_for_contact(
+        &self,
+        contact_id: &str,
+        limit: usize,
+        offset: usize,
+    ) -> DexApiResult<Vec<Note>> {
+        self.inner.get_for_contact(contact_id, limit, offset).await
+    }
+
+    async fn get(&self, id: &str) -> DexApiResult<Note> {
+        self.inner.get(id).await
+    }
+
+    async fn create(&self, note: &Note) -> DexApiResult<Note> {
+        let created = self.inner.create(note).await?;
+
+        let contact_id = if created.contact_id.is_empty() {
+            &note.contact_id
+        } else {
+            &created.contact_id
+        };
+        let entry = AuditEntry::new(
+            AuditOperation::Create,
+            AuditRecordType::Note,
+            &created.id,
+            contact_id,
+        )
+        .with_after(snapshot(&created));
+        self.audit_log.append(&entry);
+
+        Ok(created)
+    }
+
+    async fn update(&self, id: &str, note: &Note) -> DexApiResult<Note> {
+        let before = self.before_image(id).await;
+        let updated = self.inner.update(id, note).await?;
+
+        let contact_id = before
+            .as_ref()
+            .map(|b| b.contact_id.as_str())
+            .filter(|c| !c.is_empty())
+            .unwrap_or(&note.contact_id);
+        let entry = AuditEntry::new(
+            AuditOperation::Update,
+            AuditRecordType::Note,
+            id,
+            contact_id,
+        )
+        .with_before(before.as_ref().and_then(snapshot))
+        .with_after(snapshot(&updated));
+        self.audit_log.append(&entry);
+
+        Ok(updated)
+    }
+
+    async fn delete(&self, id: &str) -> DexApiResult<()> {
+        let before = self.before_image(id).await;
+        self.inner.delete(id).await?;
+
+        let contact_id = before
+            .as_ref()
+            .map(|b| b.contact_id.clone())
+            .unwrap_or_default();
+        let entry = AuditEntry::new(
+            AuditOperation::Delete,
+            AuditRecordType::Note,
+            id,
+            contact_id,
+        )
+        .with_before(before.as_ref().and_then(snapshot));
+        self.audit_log.append(&entry);
+
+        Ok(())
+    }
+}
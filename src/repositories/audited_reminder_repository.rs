@@ -0,0 +1,123 @@
+use crate::audit::{AuditEntry, AuditLog, AuditOperation, AuditRecordType};
+use crate::error::DexApiResult;
+use crate::models::Reminder;
+use crate::repositories::traits::ReminderRepository;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Reminder repository decorator that records every mutation in the audit log.
+///
+/// Reads are passed straight through. Updates and deletes fetch the current
+/// reminder first so the log entry carries a before-image.
+pub struct AuditedReminderRepository {
+    inner: Arc<dyn ReminderRepository>,
+    audit_log: Arc<AuditLog>,
+}
+
+impl AuditedReminderRepository {
+    /// Wrap a reminder repository with audit logging.
+    pub fn new(inner: Arc<dyn ReminderRepository>, audit_log: Arc<AuditLog>) -> Self {
+        Self { inner, audit_log }
+    }
+
+    /// Fetch the current state of a reminder for the audit log.
+    async fn before_image(&self, id: &str) -> Option<Reminder> {
+        match self.inner.get(id).await {
+            Ok(reminder) => Some(reminder),
+            Err(e) => {
+                tracing::warn!("Could not capture before-image of reminder {}: {}", id, e);
+                None
+            }
+        }
+    }
+}
+
+/// Serialize a reminder for storage in the audit log.
+///
+/// The API serialization omits the ID, so it is added back to keep the
+/// snapshot complete.
+fn snapshot(reminder: &Reminder) -> Option<serde_json::Value> {
+    let mut value = serde_json::to_value(reminder).ok()?;
+    if let Some(object) = value.as_object_mut() {
+        object.insert("id".to_string(), reminder.id.clone().into());
+    }
+    Some(value)
+}
+
+#[async_trait]
+impl ReminderRepository for AuditedReminderRepository {
+    async fn get_for_contact(
+        &self,
+        contact_id: &str,
+        limit: usize,
+        offset: usize,
+    ) -> DexApiResult<Vec<Reminder>> {
+        self.inner.get_for_contact(contact_id, limit, offset).await
+    }
+
+    async fn get(&self, id: &str) -> DexApiResult<Reminder> {
+        self.inner.get(id).await
+    }
+
+    async fn create(&self, reminder: &Reminder) -> DexApiResult<Reminder> {
+        let created = self.inner.create(reminder).await?;
+
+        let contact_id = if created.contact_id.is_empty() {
+            &reminder.contact_id
+        } else {
+            &created.contact_id
+        };
+        let entry = AuditEntry::new(
+            AuditOperation::Create,
+            AuditRecordType::Reminder,
+            &created.id,
+            contact_id,
+        )
+        .with_after(snapshot(&created));
+        self.audit_log.append(&entry);
+
+        Ok(created)
+    }
+
+    async fn update(&self, id: &str, reminder: &Reminder) -> DexApiResult<Reminder> {
+        let before = self.before_image(id).await;
+        let updated = self.inner.update(id, reminder).await?;
+
+        let contact_id = before
+            .as_ref()
+            .map(|b| b.contact_id.as_str())
+            .filter(|c| !c.is_empty())
+            .unwrap_or(&reminder.contact_id);
+        let entry = AuditEntry::new(
+            AuditOperation::Update,
+            AuditRecordType::Reminder,
+            id,
+            contact_id,
+        )
+        .with_before(before.as_ref().and_then(snapshot))
+        .with_after(snapshot(&updated));
+        self.audit_log.append(&entry);
+
+        Ok(updated)
+    }
+
+    async fn delete(&self, id: &str) -> DexApiResult<()> {
+        let before = self.before_image(id).await;
+        self.inner.delete(id).await?;
+
+        let contact_id = before
+            .as_ref()
+            .map(|b| b.contact_id.clone())
+            .unwrap_or_default();
+        let entry = AuditEntry::new(
+            AuditOperation::Delete,
+            AuditRecordType::Reminder,
+            id,
+            contact_id,
+        )
+        .with_before(before.as_ref().and_then(snapshot));
+        self.audit_log.append(&entry);
+
+        Ok(())
+    }
+}
@@ -34,6 +34,10 @@ impl NoteRepository for DexNoteRepository {
             .await
     }
 
+    async fn get(&self, id: &str) -> DexApiResult<Note> {
+        self.client.get_note(id).await
+    }
+
     async fn create(&self, note: &Note) -> DexApiResult<Note> {
         self.client.create_note(note).await
     }
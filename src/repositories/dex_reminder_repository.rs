@@ -34,6 +34,10 @@ impl ReminderRepository for DexReminderRepository {
             .await
     }
 
+    async fn get(&self, id: &str) -> DexApiResult<Reminder> {
+        self.client.get_reminder(id).await
+    }
+
     async fn create(&self, reminder: &Reminder) -> DexApiResult<Reminder> {
         self.client.create_reminder(reminder).await
     }
@@ -1,8 +1,14 @@
+mod audited_contact_repository;
+mod audited_note_repository;
+mod audited_reminder_repository;
 mod dex_contact_repository;
 mod dex_note_repository;
 mod dex_reminder_repository;
 mod traits;
 
+pub use audited_contact_repository::AuditedContactRepository;
+pub use audited_note_repository::AuditedNoteRepository;
+pub use audited_reminder_repository::AuditedReminderRepository;
 pub use dex_contact_repository::DexContactRepository;
 pub use dex_note_repository::DexNoteRepository;
 pub use dex_reminder_repository::DexReminderRepository;
@@ -51,6 +51,9 @@ pub trait NoteRepository: Send + Sync {
         offset: usize,
     ) -> DexApiResult<Vec<Note>>;
 
+    /// Retrieve a single note by ID.
+    async fn get(&self, id: &str) -> DexApiResult<Note>;
+
     /// Create a new note.
     async fn create(&self, note: &Note) -> DexApiResult<Note>;
 
@@ -72,6 +75,9 @@ pub trait ReminderRepository: Send + Sync {
         offset: usize,
     ) -> DexApiResult<Vec<Reminder>>;
 
+    /// Retrieve a single reminder by ID.
+    async fn get(&self, id: &str) -> DexApiResult<Reminder>;
+
     /// Create a new reminder.
     async fn create(&self, reminder: &Reminder) -> DexApiResult<Reminder>;
 
@@ -0,0 +1,632 @@
+//! Text analysis for full-text search.
+//!
+//! An [`Analyzer`] turns text into index terms: the shared tokenizer folds
+//! case and diacritics, stopwords are dropped and the remaining terms are
+//! stemmed with a Snowball stemmer for the configured language. Synonym
+//! phrases in documents also yield their group's canonical term, and in
+//! queries are replaced by it. Documents and queries go through the same
+//! analyzer, so "investing" finds "invested", "VC" finds "venture capital",
+//! and "capital" still finds it too.
+
+use crate::error::{SearchError, SearchResult};
+use crate::text::{fold, token_spans, tokenize};
+use rust_stemmers::{Algorithm, Stemmer};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+
+/// Languages with stemming and stopword support.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English,
+    German,
+    French,
+    Spanish,
+    Italian,
+    Portuguese,
+    Dutch,
+    Swedish,
+    Russian,
+}
+
+impl Language {
+    /// All supported languages.
+    pub const ALL: [Language; 9] = [
+        Self::English,
+        Self::German,
+        Self::French,
+        Self::Spanish,
+        Self::Italian,
+        Self::Portuguese,
+        Self::Dutch,
+        Self::Swedish,
+        Self::Russian,
+    ];
+
+    /// Lowercase English name of the language.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::English => "english",
+            Self::German => "german",
+            Self::French => "french",
+            Self::Spanish => "spanish",
+            Self::Italian => "italian",
+            Self::Portuguese => "portuguese",
+            Self::Dutch => "dutch",
+            Self::Swedish => "swedish",
+            Self::Russian => "russian",
+        }
+    }
+
+    /// Look up a language by name or ISO 639-1 code, case-insensitively.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        let code = match name.as_str() {
+            "en" => "english",
+            "de" => "german",
+            "fr" => "french",
+            "es" => "spanish",
+            "it" => "italian",
+            "pt" => "portuguese",
+            "nl" => "dutch",
+            "sv" => "swedish",
+            "ru" => "russian",
+            other => other,
+        };
+        Self::ALL
+            .into_iter()
+            .find(|language| language.name() == code)
+    }
+
+    fn algorithm(&self) -> Algorithm {
+        match self {
+            Self::English => Algorithm::English,
+            Self::German => Algorithm::German,
+            Self::French => Algorithm::French,
+            Self::Spanish => Algorithm::Spanish,
+            Self::Italian => Algorithm::Italian,
+            Self::Portuguese => Algorithm::Portuguese,
+            Self::Dutch => Algorithm::Dutch,
+            Self::Swedish => Algorithm::Swedish,
+            Self::Russian => Algorithm::Russian,
+        }
+    }
+
+    /// Common function words that carry no meaning for search.
+    ///
+    /// Kept short on purpose: words that double as names (such as "will")
+    /// are left out, since names are searched with the same analyzer.
+    fn stopwords(&self) -> &'static [&'static str] {
+        match self {
+            Self::English => &[
+                "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "if", "in", "into",
+                "is", "it", "of", "on", "or", "such", "that", "the", "their", "then", "there",
+                "these", "they", "this", "to", "was", "with",
+            ],
+            Self::German => &[
+                "der", "die", "das", "den", "dem", "des", "ein", "eine", "einen", "einem", "einer",
+                "und", "oder", "aber", "in", "im", "an", "am", "auf", "mit", "von", "zu", "zum",
+                "zur", "für", "ist", "sind", "war", "dass", "es", "sie", "er",
+            ],
+            Self::French => &[
+                "le", "la", "les", "l", "un", "une", "des", "du", "de", "d", "et", "ou", "mais",
+                "dans", "en", "au", "aux", "avec", "pour", "par", "sur", "est", "sont", "était",
+                "que", "qui", "ce", "cette", "il", "elle", "ils", "elles",
+            ],
+            Self::Spanish => &[
+                "el", "la", "los", "las", "un", "una", "unos", "unas", "y", "o", "pero", "de",
+                "del", "en", "con", "por", "para", "a", "al", "es", "son", "era", "que", "se",
+                "su", "sus", "lo", "este", "esta",
+            ],
+            Self::Italian => &[
+                "il", "lo", "la", "i", "gli", "le", "un", "uno", "una", "e", "o", "ma", "di",
+                "del", "della", "in", "con", "per", "su", "da", "a", "al", "è", "sono", "che",
+                "questo", "questa",
+            ],
+            Self::Portuguese => &[
+                "o", "a", "os", "as", "um", "uma", "e", "ou", "mas", "de", "do", "da", "dos",
+                "das", "em", "no", "na", "com", "por", "para", "é", "são", "era", "que", "se",
+                "seu", "sua", "este", "esta",
+            ],
+            Self::Dutch => &[
+                "de", "het", "een", "en", "of", "maar", "in", "op", "aan", "met", "van", "voor",
+                "naar", "is", "zijn", "was", "dat", "die", "dit", "deze", "er", "te", "om",
+            ],
+            Self::Swedish => &[
+                "en", "ett", "och", "eller", "men", "i", "på", "av", "med", "för", "till", "om",
+                "är", "var", "som", "det", "den", "de", "att", "har", "inte",
+            ],
+            Self::Russian => &[
+                "и", "в", "во", "не", "что", "он", "на", "я", "с", "со", "как", "а", "то", "все",
+                "она", "так", "его", "но", "да", "ты", "к", "у", "же", "вы", "за", "бы", "по",
+                "от", "из", "о", "это",
+            ],
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Groups of equivalent words and phrases.
+///
+/// Every entry of a group is replaced by the group's first entry, its
+/// canonical form, at index and query time.
+#[derive(Debug, Clone, Default)]
+pub struct SynonymMap {
+    /// Folded token sequence of each entry, mapped to its canonical term
+    entries: HashMap<Vec<String>, String>,
+
+    /// Longest entry, in tokens
+    max_tokens: usize,
+}
+
+impl SynonymMap {
+    /// Create an empty synonym map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse synonym groups, one per line, with comma-separated entries:
+    ///
+    /// ```text
+    /// # comment
+    /// vc, venture capital, venture capitalist
+    /// cto, chief technology officer
+    /// ```
+    ///
+    /// Blank lines, comments and lines with a single entry are ignored.
+    pub fn parse(text: &str) -> Self {
+        let mut map = Self::new();
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let group: Vec<Vec<String>> = line
+                .split(',')
+                .map(tokenize)
+                .filter(|tokens| !tokens.is_empty())
+                .collect();
+            map.add_group(&group);
+        }
+        map
+    }
+
+    /// Load synonym groups from a file in the format of [`parse`](Self::parse).
+    ///
+    /// A missing file yields an empty map.
+    pub fn load(path: &Path) -> SearchResult<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Ok(Self::parse(&text)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::new()),
+            Err(e) => Err(SearchError::Synonyms {
+                path: path.to_path_buf(),
+                source: e,
+            }),
+        }
+    }
+
+    /// Add a group of equivalent token sequences; the first is canonical.
+    fn add_group(&mut self, group: &[Vec<String>]) {
+        let [canonical, ..] = group else {
+            return;
+        };
+        if group.len() < 2 {
+            return;
+        }
+        let canonical = canonical.join(" ");
+        for entry in group {
+            self.max_tokens = self.max_tokens.max(entry.len());
+            self.entries.insert(entry.clone(), canonical.clone());
+        }
+    }
+
+    /// Number of words and phrases with a synonym.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the map has no synonyms.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Longest entry starting at the first of `tokens`: (tokens used, canonical term).
+    fn longest_match<'a, T: AsRef<str>>(&'a self, tokens: &[T]) -> Option<(usize, &'a str)> {
+        let longest = self.max_tokens.min(tokens.len());
+        (1..=longest).rev().find_map(|len| {
+            let key: Vec<String> = tokens[..len]
+                .iter()
+                .map(|t| t.as_ref().to_string())
+                .collect();
+            self.entries
+                .get(&key)
+                .map(|canonical| (len, canonical.as_str()))
+        })
+    }
+}
+
+/// An index term and the part of the text it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnalyzedToken {
+    /// Byte range in the analyzed text
+    pub span: Range<usize>,
+
+    /// Index term
+    pub term: String,
+
+    /// Range of the text's words the term covers; a synonym phrase's
+    /// canonical term covers the same words as the terms of the phrase
+    pub words: Range<usize>,
+}
+
+/// Byte ranges of every run of `tokens` whose terms are `needle`, in order.
+///
+/// Each term must follow the words of the previous one, skipping words that
+/// yield no term (dropped stopwords), so a phrase may run through either a
+/// synonym's canonical term or the terms of its words.
+pub fn find_sequences(tokens: &[AnalyzedToken], needle: &[String]) -> Vec<Range<usize>> {
+    let Some((first, rest)) = needle.split_first() else {
+        return Vec::new();
+    };
+    // Tokens are ordered by their first word
+    let next = |after: usize| {
+        let start = tokens.partition_point(|t| t.words.start < after);
+        let word = tokens.get(start).map(|t| t.words.start);
+        tokens[start..]
+            .iter()
+            .take_while(move |t| Some(t.words.start) == word)
+    };
+
+    let mut found = Vec::new();
+    for token in tokens.iter().filter(|t| t.term == *first) {
+        let mut ends = vec![token];
+        for term in rest {
+            ends = ends
+                .into_iter()
+                .flat_map(|end| next(end.words.end).filter(|t| t.term == *term))
+                .collect();
+        }
+        found.extend(ends.iter().map(|end| token.span.start..end.span.end));
+    }
+    found.sort_by_key(|range| (range.start, range.end));
+    found.dedup();
+    found
+}
+
+/// Turns text into index terms.
+pub struct Analyzer {
+    language: Option<Language>,
+    stemmer: Option<Stemmer>,
+    stopwords: HashSet<String>,
+    synonyms: SynonymMap,
+}
+
+impl Analyzer {
+    /// Create an analyzer that only tokenizes and folds text.
+    pub fn new() -> Self {
+        Self {
+            language: None,
+            stemmer: None,
+            stopwords: HashSet::new(),
+            synonyms: SynonymMap::new(),
+        }
+    }
+
+    /// Create an analyzer that removes stopwords and stems for a language.
+    pub fn for_language(language: Language) -> Self {
+        Self {
+            language: Some(language),
+            stemmer: Some(Stemmer::create(language.algorithm())),
+            stopwords: language.stopwords().iter().map(|word| fold(word)).collect(),
+            synonyms: SynonymMap::new(),
+        }
+    }
+
+    /// Keep stopwords in the index and in queries.
+    pub fn without_stopwords(mut self) -> Self {
+        self.stopwords.clear();
+        self
+    }
+
+    /// Add the canonical term of synonyms to documents, and replace synonyms
+    /// in queries by it.
+    pub fn with_synonyms(mut self, synonyms: SynonymMap) -> Self {
+        self.synonyms = synonyms;
+        self
+    }
+
+    /// Language used for stemming and stopwords, if any.
+    pub fn language(&self) -> Option<Language> {
+        self.language
+    }
+
+    /// Analyze document text into index terms.
+    pub fn analyze(&self, text: &str) -> Vec<String> {
+        self.analyze_spans(text)
+            .into_iter()
+            .map(|token| token.term)
+            .collect()
+    }
+
+    /// Analyze document text into index terms with the byte ranges they
+    /// came from.
+    ///
+    /// A synonym phrase yields its words' terms and, before them, its
+    /// canonical term spanning all of its words.
+    pub fn analyze_spans(&self, text: &str) -> Vec<AnalyzedToken> {
+        self.document_tokens(text, false)
+    }
+
+    /// Analyze already tokenized query terms.
+    ///
+    /// A synonym phrase is replaced by its canonical term, which documents
+    /// with any synonym of the group contain.
+    pub fn analyze_terms(&self, terms: &[String]) -> Vec<String> {
+        self.analyze_tokens(terms, false, false)
+            .into_iter()
+            .map(|(_, term)| term)
+            .collect()
+    }
+
+    /// Analyze document text for phrase matching: like
+    /// [`analyze`](Self::analyze), but stopwords are kept so that they still
+    /// count in a phrase.
+    pub fn analyze_phrase(&self, text: &str) -> Vec<String> {
+        self.analyze_phrase_spans(text)
+            .into_iter()
+            .map(|token| token.term)
+            .collect()
+    }
+
+    /// Like [`analyze_spans`](Self::analyze_spans), keeping stopwords; see
+    /// [`find_sequences`] to look for a phrase in the result.
+    pub fn analyze_phrase_spans(&self, text: &str) -> Vec<AnalyzedToken> {
+        self.document_tokens(text, true)
+    }
+
+    /// Analyze already tokenized query phrase terms, keeping stopwords.
+    pub fn analyze_phrase_terms(&self, terms: &[String]) -> Vec<String> {
+        self.analyze_tokens(terms, true, false)
+            .into_iter()
+            .map(|(_, term)| term)
+            .collect()
+    }
+
+    /// Analyze document text, keeping the terms of synonym phrases.
+    fn document_tokens(&self, text: &str, keep_stopwords: bool) -> Vec<AnalyzedToken> {
+        let (spans, tokens): (Vec<_>, Vec<_>) = token_spans(text)
+            .into_iter()
+            .map(|span| {
+                let token = fold(&text[span.clone()]);
+                (span, token)
+            })
+            .filter(|(_, token)| !token.is_empty())
+            .unzip();
+
+        self.analyze_tokens(&tokens, keep_stopwords, true)
+            .into_iter()
+            .map(|(words, term)| AnalyzedToken {
+                span: spans[words.start].start..spans[words.end - 1].end,
+                term,
+                words,
+            })
+            .collect()
+    }
+
+    /// Apply synonyms, stopwords and stemming to folded tokens, returning
+    /// each term with the range of tokens it covers.
+    ///
+    /// Synonym phrases become their canonical term. With `keep_phrase_terms`
+    /// their words are analyzed as usual as well, following the canonical
+    /// term, so that documents can still be found by those words.
+    fn analyze_tokens(
+        &self,
+        tokens: &[String],
+        keep_stopwords: bool,
+        keep_phrase_terms: bool,
+    ) -> Vec<(Range<usize>, String)> {
+        let mut terms: Vec<(Range<usize>, String)> = Vec::with_capacity(tokens.len());
+        let mut i = 0;
+        while i < tokens.len() {
+            if let Some((len, canonical)) = self.synonyms.longest_match(&tokens[i..]) {
+                terms.push((i..i + len, canonical.to_string()));
+                if !keep_phrase_terms {
+                    i += len;
+                    continue;
+                }
+            }
+
+            let token = &tokens[i];
+            if keep_stopwords || !self.stopwords.contains(token) {
+                let term = match &self.stemmer {
+                    Some(stemmer) => stemmer.stem(token).into_owned(),
+                    None => token.clone(),
+                };
+                // A one-word synonym may be its own canonical term
+                if terms.last() != Some(&(i..i + 1, term.clone())) {
+                    terms.push((i..i + 1, term));
+                }
+            }
+            i += 1;
+        }
+        terms
+    }
+}
+
+impl Default for Analyzer {
+    /// English stemming and stopwords, without synonyms.
+    fn default() -> Self {
+        Self::for_language(Language::English)
+    }
+}
+
+impl fmt::Debug for Analyzer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Analyzer")
+            .field("language", &self.language)
+            .field("stemming", &self.stemmer.is_some())
+            .field("stopwords", &self.stopwords.len())
+            .field("synonyms", &self.synonyms.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms(analyzer: &Analyzer, query: &str) -> Vec<String> {
+        analyzer.analyze_terms(&tokenize(query))
+    }
+
+    #[test]
+    fn test_plain_analyzer_only_tokenizes() {
+        let analyzer = Analyzer::new();
+        assert_eq!(
+            analyzer.analyze("The Investors of Acme"),
+            vec!["the", "investors", "of", "acme"]
+        );
+    }
+
+    #[test]
+    fn test_english_stems_and_drops_stopwords() {
+        let analyzer = Analyzer::default();
+        let stem = |word: &str| analyzer.analyze(word);
+
+        assert_eq!(stem("investing"), stem("invested"));
+        assert_eq!(stem("investing"), stem("investment"));
+        assert_eq!(stem("investors"), stem("investor"));
+        assert_eq!(
+            analyzer.analyze("the investors of acme"),
+            vec!["investor", "acm"]
+        );
+        // Names that are also common words are kept
+        assert_eq!(analyzer.analyze("Will Smith"), vec!["will", "smith"]);
+    }
+
+    #[test]
+    fn test_without_stopwords_keeps_them() {
+        let analyzer = Analyzer::for_language(Language::English).without_stopwords();
+        assert_eq!(analyzer.analyze("the deck"), vec!["the", "deck"]);
+    }
+
+    #[test]
+    fn test_phrases_keep_stopwords() {
+        let analyzer = Analyzer::default();
+        assert_eq!(analyzer.analyze("Series A funding"), vec!["seri", "fund"]);
+        assert_eq!(
+            analyzer.analyze_phrase("Series A funding"),
+            vec!["seri", "a", "fund"]
+        );
+    }
+
+    #[test]
+    fn test_other_languages() {
+        let german = Analyzer::for_language(Language::German);
+        assert_eq!(
+            german.analyze("die Investitionen"),
+            german.analyze("Investition")
+        );
+
+        let french = Analyzer::for_language(Language::French);
+        assert_eq!(
+            french.analyze("les investisseurs"),
+            french.analyze("investisseur")
+        );
+    }
+
+    #[test]
+    fn test_language_names() {
+        for language in Language::ALL {
+            assert_eq!(Language::from_name(language.name()), Some(language));
+        }
+        assert_eq!(Language::from_name("DE"), Some(Language::German));
+        assert_eq!(Language::from_name("klingon"), None);
+    }
+
+    #[test]
+    fn test_synonyms_map_to_canonical_term() {
+        let synonyms = SynonymMap::parse(
+            "# investors\nvc, venture capital, venture capitalist\n\nsolo\ncto, chief technology officer\n",
+        );
+        assert_eq!(synonyms.len(), 5);
+        let analyzer = Analyzer::default().with_synonyms(synonyms);
+
+        assert_eq!(terms(&analyzer, "VC"), vec!["vc"]);
+        assert_eq!(terms(&analyzer, "venture capital fund"), vec!["vc", "fund"]);
+        // Words of a phrase on their own are analyzed as usual
+        assert_eq!(terms(&analyzer, "capital"), vec!["capit"]);
+
+        // Documents keep the words of the phrase besides the canonical term
+        assert_eq!(analyzer.analyze("VC"), vec!["vc"]);
+        assert_eq!(
+            analyzer.analyze("Met a venture capitalist"),
+            vec!["met", "vc", "ventur", "capitalist"]
+        );
+        assert_eq!(
+            analyzer.analyze("Venture Capital fund"),
+            vec!["vc", "ventur", "capit", "fund"]
+        );
+    }
+
+    #[test]
+    fn test_analyze_spans_cover_synonym_phrases() {
+        let analyzer = Analyzer::default().with_synonyms(SynonymMap::parse("vc, venture capital"));
+        let text = "Ask the Venture Capital folks";
+        let spans: Vec<(&str, String)> = analyzer
+            .analyze_spans(text)
+            .into_iter()
+            .map(|token| (&text[token.span], token.term))
+            .collect();
+
+        assert_eq!(
+            spans,
+            vec![
+                ("Ask", "ask".to_string()),
+                ("Venture Capital", "vc".to_string()),
+                ("Venture", "ventur".to_string()),
+                ("Capital", "capit".to_string()),
+                ("folks", "folk".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_sequences_through_synonyms() {
+        let analyzer = Analyzer::default().with_synonyms(SynonymMap::parse("vc, venture capital"));
+        let text = "Head of venture capital funds";
+        let tokens = analyzer.analyze_phrase_spans(text);
+        let find = |phrase: &str| {
+            find_sequences(&tokens, &analyzer.analyze_phrase_terms(&tokenize(phrase)))
+                .into_iter()
+                .map(|range| &text[range])
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(find("vc funds"), vec!["venture capital funds"]);
+        assert_eq!(find("capital funds"), vec!["capital funds"]);
+        assert_eq!(
+            find("head of venture capital"),
+            vec!["Head of venture capital"]
+        );
+        assert!(find("head venture").is_empty());
+
+        // Without stopwords, a phrase skips over them
+        let tokens = analyzer.analyze_spans(text);
+        let needle = analyzer.analyze_terms(&tokenize("head vc"));
+        assert_eq!(find_sequences(&tokens, &needle), vec![0..23]);
+    }
+
+    #[test]
+    fn test_load_missing_synonym_file_is_empty() {
+        let path = std::env::temp_dir().join("dex-synonyms-test-missing.txt");
+        assert!(SynonymMap::load(&path).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,297 @@
+//! Facet counts and filters for search results.
+//!
+//! Facets summarize the contacts matching a search by tag, company, email
+//! domain and the fields the query matched in, so a long result list can be
+//! narrowed down. A [`FacetFilter`] keeps only the contacts having one of the
+//! selected values of every facet it restricts.
+
+use super::full_text_index::SearchableField;
+use crate::models::Contact;
+use crate::text::fold;
+use std::collections::HashMap;
+
+/// Maximum number of values reported per facet.
+pub const MAX_FACET_VALUES: usize = 20;
+
+/// Restricts search results to contacts with selected facet values.
+///
+/// Values of one facet are alternatives; facets are combined with AND. Tags
+/// and companies are compared ignoring case and accents, email domains
+/// ignoring case.
+#[derive(Debug, Clone, Default)]
+pub struct FacetFilter {
+    /// Tags, any of which a contact must have
+    pub tags: Vec<String>,
+
+    /// Companies, one of which a contact must work at
+    pub companies: Vec<String>,
+
+    /// Email domains, one of which a contact must have an address at
+    pub email_domains: Vec<String>,
+
+    /// Fields, in any of which the query must have matched
+    ///
+    /// Unlike [`SearchFilter::include_types`](super::SearchFilter::include_types),
+    /// this does not restrict what a query matches, only which of the
+    /// matching contacts are kept.
+    pub fields: Vec<SearchableField>,
+}
+
+impl FacetFilter {
+    /// Whether the filter keeps every contact.
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+            && self.companies.is_empty()
+            && self.email_domains.is_empty()
+            && self.fields.is_empty()
+    }
+
+    /// Whether a contact that matched in `fields` passes the filter.
+    pub fn matches(&self, contact: &Contact, fields: &[SearchableField]) -> bool {
+        let any_of = |selected: &[String], values: Vec<String>, normalize: fn(&str) -> String| {
+            selected.is_empty()
+                || selected
+                    .iter()
+                    .any(|s| values.contains(&normalize(s.trim_start_matches('@'))))
+        };
+
+        any_of(&self.tags, tag_keys(contact), fold)
+            && any_of(
+                &self.companies,
+                company_key(contact).into_iter().collect(),
+                fold,
+            )
+            && any_of(&self.email_domains, email_domains(contact), |d| {
+                d.to_lowercase()
+            })
+            && (self.fields.is_empty() || fields.iter().any(|f| self.fields.contains(f)))
+    }
+}
+
+/// Number of matching contacts with a facet value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FacetCount {
+    /// Facet value, as first seen in the results
+    pub value: String,
+
+    /// Number of matching contacts with this value
+    pub count: usize,
+}
+
+/// Facet counts over the contacts matching a search.
+///
+/// Each facet lists its most frequent values first, up to
+/// [`MAX_FACET_VALUES`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchFacets {
+    /// Contacts per tag
+    pub tags: Vec<FacetCount>,
+
+    /// Contacts per company
+    pub companies: Vec<FacetCount>,
+
+    /// Contacts per email domain
+    pub email_domains: Vec<FacetCount>,
+
+    /// Contacts per field the query matched in, by field name (e.g. "note")
+    pub fields: Vec<FacetCount>,
+}
+
+/// Counts of one facet's values, keyed by normalized value.
+#[derive(Default)]
+struct Counter {
+    counts: HashMap<String, FacetCount>,
+}
+
+impl Counter {
+    /// Count one contact for each of `values`, given as (key, display) pairs.
+    fn add(&mut self, values: impl IntoIterator<Item = (String, String)>) {
+        for (key, value) in values {
+            self.counts
+                .entry(key)
+                .or_insert(FacetCount { value, count: 0 })
+                .count += 1;
+        }
+    }
+
+    /// The most frequent values, ties broken alphabetically.
+    fn into_top(self) -> Vec<FacetCount> {
+        let mut counts: Vec<FacetCount> = self.counts.into_values().collect();
+        counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+        counts.truncate(MAX_FACET_VALUES);
+        counts
+    }
+}
+
+/// Accumulates [`SearchFacets`] one matching contact at a time.
+#[derive(Default)]
+pub struct FacetCounter {
+    tags: Counter,
+    companies: Counter,
+    email_domains: Counter,
+    fields: Counter,
+}
+
+impl FacetCounter {
+    /// Create an empty counter.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a contact that matched in `fields`.
+    ///
+    /// Values are counted once per contact, however often they occur on it.
+    /// Display values are taken from the first contact counted with them.
+    pub fn add(&mut self, contact: &Contact, fields: &[SearchableField]) {
+        let mut tags: Vec<(String, String)> = contact
+            .tags
+            .iter()
+            .map(|tag| tag.trim())
+            .filter(|tag| !tag.is_empty())
+            .map(|tag| (fold(tag), tag.to_string()))
+            .collect();
+        dedup_keys(&mut tags);
+        self.tags.add(tags);
+
+        if let Some(key) = company_key(contact) {
+            let company = contact.company.as_deref().unwrap_or_default().trim();
+            self.companies.add([(key, company.to_string())]);
+        }
+
+        self.email_domains
+            .add(email_domains(contact).into_iter().map(|d| (d.clone(), d)));
+
+        let mut fields: Vec<(String, String)> = fields
+            .iter()
+            .map(|field| {
+                let name = field.display_name().replace(' ', "_");
+                (name.clone(), name)
+            })
+            .collect();
+        dedup_keys(&mut fields);
+        self.fields.add(fields);
+    }
+
+    /// The counted facets.
+    pub fn finish(self) -> SearchFacets {
+        SearchFacets {
+            tags: self.tags.into_top(),
+            companies: self.companies.into_top(),
+            email_domains: self.email_domains.into_top(),
+            fields: self.fields.into_top(),
+        }
+    }
+}
+
+/// Drop pairs whose key occurred earlier, keeping the first display value.
+fn dedup_keys(values: &mut Vec<(String, String)>) {
+    let mut seen = Vec::with_capacity(values.len());
+    values.retain(|(key, _)| {
+        let new = !seen.contains(key);
+        if new {
+            seen.push(key.clone());
+        }
+        new
+    });
+}
+
+/// Folded tags of a contact.
+fn tag_keys(contact: &Contact) -> Vec<String> {
+    contact.tags.iter().map(|tag| fold(tag.trim())).collect()
+}
+
+/// Folded company of a contact, if it has one.
+fn company_key(contact: &Contact) -> Option<String> {
+    contact
+        .company
+        .as_deref()
+        .map(str::trim)
+        .filter(|company| !company.is_empty())
+        .map(fold)
+}
+
+/// Distinct lowercase domains of a contact's email addresses.
+fn email_domains(contact: &Contact) -> Vec<String> {
+    let mut domains: Vec<String> = Vec::new();
+    for email in contact.email.iter().chain(&contact.emails) {
+        let Some((_, domain)) = email.trim().rsplit_once('@') else {
+            continue;
+        };
+        let domain = domain.to_lowercase();
+        if !domain.is_empty() && !domains.contains(&domain) {
+            domains.push(domain);
+        }
+    }
+    domains
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contact(tags: &[&str], company: Option<&str>, emails: &[&str]) -> Contact {
+        let mut contact = Contact::new("1".to_string(), "Test".to_string());
+        contact.tags = tags.iter().map(|t| t.to_string()).collect();
+        contact.company = company.map(str::to_string);
+        contact.emails = emails.iter().map(|e| e.to_string()).collect();
+        contact
+    }
+
+    #[test]
+    fn test_counts_values_once_per_contact() {
+        let mut counter = FacetCounter::new();
+        counter.add(
+            &contact(
+                &["Investor", "investor", "VC"],
+                Some("Acme"),
+                &["a@acme.com", "b@ACME.com"],
+            ),
+            &[SearchableField::Note, SearchableField::Note],
+        );
+        counter.add(
+            &contact(&["Investor"], Some(" acme "), &["c@gmail.com"]),
+            &[SearchableField::JobTitle],
+        );
+        let facets = counter.finish();
+
+        let pairs = |counts: &[FacetCount]| -> Vec<(String, usize)> {
+            counts.iter().map(|c| (c.value.clone(), c.count)).collect()
+        };
+        assert_eq!(
+            pairs(&facets.tags),
+            vec![("Investor".to_string(), 2), ("VC".to_string(), 1)]
+        );
+        assert_eq!(pairs(&facets.companies), vec![("Acme".to_string(), 2)]);
+        assert_eq!(
+            pairs(&facets.email_domains),
+            vec![("acme.com".to_string(), 1), ("gmail.com".to_string(), 1)]
+        );
+        assert_eq!(
+            pairs(&facets.fields),
+            vec![("job_title".to_string(), 1), ("note".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn test_filter_combines_facets_with_and() {
+        let acme = contact(&["Investor"], Some("Acme"), &["a@acme.com"]);
+        let fields = [SearchableField::Note];
+
+        assert!(FacetFilter::default().matches(&acme, &fields));
+
+        let filter = FacetFilter {
+            tags: vec!["investor".to_string(), "founder".to_string()],
+            companies: vec!["ACME".to_string()],
+            email_domains: vec!["@Acme.com".to_string()],
+            fields: vec![SearchableField::Note],
+        };
+        assert!(filter.matches(&acme, &fields));
+        assert!(!filter.matches(&acme, &[SearchableField::Name]));
+
+        let other_company = FacetFilter {
+            companies: vec!["Globex".to_string()],
+            ..filter.clone()
+        };
+        assert!(!other_company.matches(&acme, &fields));
+    }
+}
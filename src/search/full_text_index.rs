@@ -1,22 +1,25 @@
 //! Full-text search index implementation.
 //!
 //! This module provides fuzzy full-text search across contacts, notes, and reminders,
-//! with snippet generation and match context extraction.
-
+//! with snippet generation and match context extraction. Documents are looked
+//! up through an [`InvertedIndex`] and ranked with BM25.
+
+use super::analyzer::{find_sequences, Analyzer};
+use super::facets::{FacetCounter, FacetFilter, SearchFacets};
+use super::highlight::{build_snippets, find_hits, Snippet, SnippetOptions, TextSpan};
+use super::inverted_index::{tokenize, DocumentHit, InvertedIndex};
+use super::query::{QueryNode, SearchQuery};
+use super::similarity::{SimilarContact, TermVectors};
+use super::suggest::{SpellingDictionary, SpellingSuggestion};
 use crate::models::{Contact, ContactRef, Note, Reminder};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 
-/// Maximum snippet length in characters
-const MAX_SNIPPET_LENGTH: usize = 150;
-
-/// Context characters to show before and after match
-const CONTEXT_CHARS: usize = 50;
-
 /// A searchable document extracted from contact data.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SearchableDocument {
     /// Contact ID this document belongs to
     pub contact_id: String,
@@ -32,10 +35,14 @@ pub struct SearchableDocument {
 
     /// Original item ID (for notes/reminders)
     pub item_id: Option<String>,
+
+    /// Creation timestamp (for notes/reminders, ISO 8601)
+    pub date: Option<String>,
 }
 
 /// Type of searchable field.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SearchableField {
     /// Contact name
     Name,
@@ -47,6 +54,22 @@ pub enum SearchableField {
     Company,
     /// Contact job title
     JobTitle,
+    /// Contact tag
+    Tag,
+    /// Contact description
+    Description,
+    /// Contact location or address
+    Location,
+    /// Contact education
+    Education,
+    /// Contact website
+    Website,
+    /// Contact social media profile or handle
+    SocialProfile,
+    /// Contact birthday
+    Birthday,
+    /// Custom notes field on the contact itself
+    ContactNotes,
     /// Note content
     Note,
     /// Reminder content
@@ -54,6 +77,25 @@ pub enum SearchableField {
 }
 
 impl SearchableField {
+    /// All field types, in display order.
+    pub const ALL: [SearchableField; 15] = [
+        Self::Name,
+        Self::Email,
+        Self::Phone,
+        Self::Company,
+        Self::JobTitle,
+        Self::Tag,
+        Self::Description,
+        Self::Location,
+        Self::Education,
+        Self::Website,
+        Self::SocialProfile,
+        Self::Birthday,
+        Self::ContactNotes,
+        Self::Note,
+        Self::Reminder,
+    ];
+
     /// Get display name for the field type.
     pub fn display_name(&self) -> &'static str {
         match self {
@@ -62,10 +104,120 @@ impl SearchableField {
             Self::Phone => "phone",
             Self::Company => "company",
             Self::JobTitle => "job title",
+            Self::Tag => "tag",
+            Self::Description => "description",
+            Self::Location => "location",
+            Self::Education => "education",
+            Self::Website => "website",
+            Self::SocialProfile => "social profile",
+            Self::Birthday => "birthday",
+            Self::ContactNotes => "contact notes",
             Self::Note => "note",
             Self::Reminder => "reminder",
         }
     }
+
+    /// BM25 boost for matches in this field.
+    ///
+    /// Identifying fields rank above free text: name > email/phone > company,
+    /// tags, website and social profiles > job title, location and education >
+    /// descriptions, birthdays and notes/reminders.
+    pub fn boost(&self) -> f64 {
+        match self {
+            Self::Name => 3.0,
+            Self::Email | Self::Phone => 2.5,
+            Self::Company | Self::Tag | Self::Website | Self::SocialProfile => 2.0,
+            Self::JobTitle | Self::Location | Self::Education => 1.5,
+            Self::Description
+            | Self::Birthday
+            | Self::ContactNotes
+            | Self::Note
+            | Self::Reminder => 1.0,
+        }
+    }
+
+    /// Look up a field by name, as used in `field:value` query prefixes.
+    ///
+    /// Case-insensitive, accepts plurals ("notes"), `job_title`/`title` for
+    /// the job title and `social` for social profiles. `notes` means note
+    /// records; the contact's own notes field is `contact_notes`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "name" => Some(Self::Name),
+            "email" | "emails" => Some(Self::Email),
+            "phone" | "phones" => Some(Self::Phone),
+            "company" => Some(Self::Company),
+            "title" | "job_title" | "jobtitle" => Some(Self::JobTitle),
+            "tag" | "tags" => Some(Self::Tag),
+            "description" => Some(Self::Description),
+            "location" => Some(Self::Location),
+            "education" => Some(Self::Education),
+            "website" | "websites" => Some(Self::Website),
+            "social" | "social_profile" | "social_profiles" => Some(Self::SocialProfile),
+            "birthday" => Some(Self::Birthday),
+            "contact_notes" | "contact_note" => Some(Self::ContactNotes),
+            "note" | "notes" => Some(Self::Note),
+            "reminder" | "reminders" => Some(Self::Reminder),
+            _ => None,
+        }
+    }
+
+    /// Comma-separated field names accepted by [`from_name`](Self::from_name),
+    /// for error messages.
+    pub fn name_list() -> String {
+        Self::ALL
+            .iter()
+            .map(|field| field.display_name().replace(' ', "_"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Restricts which documents a search may match.
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilter {
+    /// Field kinds to match (all kinds if `None`)
+    pub include_types: Option<Vec<SearchableField>>,
+
+    /// Earliest creation date of matching notes and reminders (ISO 8601, inclusive)
+    pub date_from: Option<String>,
+
+    /// Latest creation date of matching notes and reminders (ISO 8601, inclusive)
+    pub date_to: Option<String>,
+
+    /// Facet values the matching contacts must have
+    pub facets: FacetFilter,
+}
+
+impl SearchFilter {
+    /// Whether a document may match under this filter.
+    ///
+    /// Date bounds only apply to dated documents (notes and reminders);
+    /// contact fields always pass them. A date-only `date_to` includes the
+    /// whole day.
+    pub fn matches(&self, doc: &SearchableDocument) -> bool {
+        if let Some(ref types) = self.include_types {
+            if !types.contains(&doc.field_type) {
+                return false;
+            }
+        }
+
+        let Some(ref date) = doc.date else {
+            return true;
+        };
+        if let Some(ref from) = self.date_from {
+            if date.as_str() < from.as_str() {
+                return false;
+            }
+        }
+        if let Some(ref to) = self.date_to {
+            let prefix = date.get(..to.len()).unwrap_or(date);
+            if prefix > to.as_str() {
+                return false;
+            }
+        }
+        true
+    }
 }
 
 /// A match found in a document with context.
@@ -74,9 +226,16 @@ pub struct MatchContext {
     /// Field where the match was found
     pub field_type: SearchableField,
 
-    /// Snippet showing the match with context
+    /// Best snippet showing the match with context (the text of the first
+    /// of `snippets`)
     pub snippet: String,
 
+    /// Snippets around the hits, best first
+    pub snippets: Vec<Snippet>,
+
+    /// Every hit in the field's indexed text (HTML stripped), in order
+    pub hits: Vec<TextSpan>,
+
     /// Confidence score (0-100)
     pub confidence: u8,
 
@@ -97,18 +256,176 @@ pub struct SearchResult {
     pub confidence: u8,
 }
 
+/// Ranked search results with facet counts over every matching contact.
+#[derive(Debug, Clone, Default)]
+pub struct FacetedResults {
+    /// Best matching contacts, up to the requested number
+    pub results: Vec<SearchResult>,
+
+    /// Facet counts over all matching contacts, not just the returned ones
+    pub facets: SearchFacets,
+
+    /// Number of matching contacts before the result limit
+    pub total_matches: usize,
+}
+
+/// A document matching a query, with the terms and phrases to highlight.
+struct DocMatch {
+    doc_id: usize,
+    confidence: u8,
+    needles: Vec<Vec<String>>,
+}
+
+/// The matching documents of one contact and their summed BM25 score.
+#[derive(Default)]
+struct ContactMatch {
+    score: f64,
+    docs: Vec<DocMatch>,
+}
+
+impl ContactMatch {
+    /// Add another match of the same contact, keeping each document once.
+    fn merge(&mut self, other: ContactMatch) {
+        self.score += other.score;
+        for doc in other.docs {
+            match self.docs.iter_mut().find(|d| d.doc_id == doc.doc_id) {
+                Some(existing) => {
+                    existing.confidence = existing.confidence.max(doc.confidence);
+                    existing.needles.extend(doc.needles);
+                }
+                None => self.docs.push(doc),
+            }
+        }
+    }
+}
+
+/// Matching contacts keyed by contact ID.
+type ContactMatches<'a> = HashMap<&'a str, ContactMatch>;
+
+/// Contacts present in both sets, with their matches combined.
+fn intersect<'a>(mut left: ContactMatches<'a>, right: ContactMatches<'a>) -> ContactMatches<'a> {
+    left.retain(|id, _| right.contains_key(id));
+    for (id, contact_match) in right {
+        if let Some(existing) = left.get_mut(id) {
+            existing.merge(contact_match);
+        }
+    }
+    left
+}
+
 /// Full-text search index for contacts and related data.
+///
+/// The index can be updated in place as contacts, notes and reminders
+/// change. Removed documents leave an empty slot behind, so document IDs stay
+/// stable; rebuilding the index reclaims them.
 pub struct FullTextSearchIndex {
-    /// All searchable documents
-    documents: Vec<SearchableDocument>,
+    /// All searchable documents, indexed by their inverted index `DocId`;
+    /// `None` once removed
+    documents: Vec<Option<SearchableDocument>>,
+
+    /// Term postings over `documents`
+    inverted: InvertedIndex,
+
+    /// Live document IDs of each contact
+    contact_documents: HashMap<String, Vec<usize>>,
+
+    /// Document ID of each indexed note or reminder, keyed by field and item ID
+    item_documents: HashMap<(SearchableField, String), usize>,
+
+    /// Words of the live documents, for spelling suggestions
+    spelling: SpellingDictionary,
+
+    /// Turns document contents and queries into terms
+    analyzer: Arc<Analyzer>,
 }
 
 impl FullTextSearchIndex {
-    /// Create a new empty search index.
+    /// Create a new empty search index with the default (English) analyzer.
     pub fn new() -> Self {
+        Self::with_analyzer(Arc::new(Analyzer::default()))
+    }
+
+    /// Create a new empty search index that analyzes text with `analyzer`.
+    pub fn with_analyzer(analyzer: Arc<Analyzer>) -> Self {
         Self {
             documents: Vec::new(),
+            inverted: InvertedIndex::new(),
+            contact_documents: HashMap::new(),
+            item_documents: HashMap::new(),
+            spelling: SpellingDictionary::new(),
+            analyzer,
+        }
+    }
+
+    /// Create an index from previously extracted documents.
+    ///
+    /// Used to restore an index from [`documents`](Self::documents), e.g. when
+    /// loading a snapshot; postings are rebuilt from the document contents
+    /// with `analyzer`.
+    pub fn from_documents(
+        analyzer: Arc<Analyzer>,
+        documents: impl IntoIterator<Item = SearchableDocument>,
+    ) -> Self {
+        let mut index = Self::with_analyzer(analyzer);
+        for doc in documents {
+            index.add_document(doc);
+        }
+        index
+    }
+
+    /// The analyzer used for documents and queries.
+    pub fn analyzer(&self) -> &Arc<Analyzer> {
+        &self.analyzer
+    }
+
+    /// All live documents, in indexing order.
+    pub fn documents(&self) -> impl Iterator<Item = &SearchableDocument> {
+        self.documents.iter().flatten()
+    }
+
+    /// Add a document to both the document store and the inverted index.
+    fn add_document(&mut self, doc: SearchableDocument) {
+        let terms = self.analyzer.analyze(&doc.content);
+        let id = self.inverted.add_terms(doc.field_type, &terms);
+        debug_assert_eq!(id, self.documents.len());
+        self.spelling.add_text(&doc.content);
+
+        self.contact_documents
+            .entry(doc.contact_id.clone())
+            .or_default()
+            .push(id);
+        if let Some(ref item_id) = doc.item_id {
+            self.item_documents
+                .insert((doc.field_type, item_id.clone()), id);
+        }
+        self.documents.push(Some(doc));
+    }
+
+    /// Remove a document from the document store and the inverted index.
+    fn remove_document(&mut self, id: usize) {
+        let Some(doc) = self.documents.get_mut(id).and_then(Option::take) else {
+            return;
+        };
+        self.inverted
+            .remove_terms(id, self.analyzer.analyze(&doc.content));
+        self.spelling.remove_text(&doc.content);
+
+        if let Some(ids) = self.contact_documents.get_mut(&doc.contact_id) {
+            ids.retain(|&other| other != id);
+            if ids.is_empty() {
+                self.contact_documents.remove(&doc.contact_id);
+            }
         }
+        if let Some(item_id) = doc.item_id {
+            self.item_documents.remove(&(doc.field_type, item_id));
+        }
+    }
+
+    /// A live document. Postings and lookups only refer to live documents.
+    fn document(&self, id: usize) -> &SearchableDocument {
+        self.documents[id]
+            .as_ref()
+            .expect("index refers to a removed document")
     }
 
     /// Index a contact and all its related data.
@@ -116,115 +433,223 @@ impl FullTextSearchIndex {
     /// This extracts searchable documents from the contact's fields,
     /// notes, and reminders.
     pub fn index_contact(&mut self, contact: &Contact, notes: &[Note], reminders: &[Reminder]) {
-        let contact_id = &contact.id;
-        let contact_name = &contact.name;
+        self.index_contact_fields(contact);
+
+        // Index notes
+        for note in notes {
+            self.index_note(&contact.id, &contact.name, note);
+        }
+
+        // Index reminders
+        for reminder in reminders {
+            self.index_reminder(&contact.id, &contact.name, reminder);
+        }
+    }
+
+    /// Add or replace a contact's own fields, keeping its notes and reminders.
+    pub fn upsert_contact(&mut self, contact: &Contact) {
+        let field_docs: Vec<usize> = self
+            .contact_documents
+            .get(&contact.id)
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|&id| self.document(id).item_id.is_none())
+            .collect();
+        for id in field_docs {
+            self.remove_document(id);
+        }
+
+        // Notes and reminders carry the contact name for display
+        for &id in self
+            .contact_documents
+            .get(&contact.id)
+            .into_iter()
+            .flatten()
+        {
+            if let Some(doc) = self.documents[id].as_mut() {
+                doc.contact_name = contact.name.clone();
+            }
+        }
 
-        // Index contact name
-        self.documents.push(SearchableDocument {
-            contact_id: contact_id.clone(),
-            contact_name: contact_name.clone(),
+        self.index_contact_fields(contact);
+    }
+
+    /// Remove a contact with its notes and reminders.
+    pub fn remove_contact(&mut self, contact_id: &str) {
+        for id in self
+            .contact_documents
+            .remove(contact_id)
+            .unwrap_or_default()
+        {
+            self.remove_document(id);
+        }
+    }
+
+    /// Add or replace a note.
+    pub fn upsert_note(&mut self, note: &Note) {
+        self.remove_note(&note.id);
+        let contact_name = self.contact_name(&note.contact_id);
+        self.index_note(&note.contact_id, &contact_name, note);
+    }
+
+    /// Remove a note.
+    pub fn remove_note(&mut self, note_id: &str) {
+        self.remove_item(SearchableField::Note, note_id);
+    }
+
+    /// Add or replace a reminder.
+    pub fn upsert_reminder(&mut self, reminder: &Reminder) {
+        self.remove_reminder(&reminder.id);
+        let contact_name = self.contact_name(&reminder.contact_id);
+        self.index_reminder(&reminder.contact_id, &contact_name, reminder);
+    }
+
+    /// Remove a reminder.
+    pub fn remove_reminder(&mut self, reminder_id: &str) {
+        self.remove_item(SearchableField::Reminder, reminder_id);
+    }
+
+    fn remove_item(&mut self, field: SearchableField, item_id: &str) {
+        if let Some(id) = self.item_documents.get(&(field, item_id.to_string())) {
+            self.remove_document(*id);
+        }
+    }
+
+    /// Name of an indexed contact, or an empty string if it is not indexed.
+    fn contact_name(&self, contact_id: &str) -> String {
+        self.contact_documents
+            .get(contact_id)
+            .and_then(|ids| ids.first())
+            .map(|&id| self.document(id).contact_name.clone())
+            .unwrap_or_default()
+    }
+
+    /// Index the contact's own fields.
+    fn index_contact_fields(&mut self, contact: &Contact) {
+        // The name is always indexed, so every contact has a document
+        self.add_document(SearchableDocument {
+            contact_id: contact.id.clone(),
+            contact_name: contact.name.clone(),
             field_type: SearchableField::Name,
             content: contact.name.clone(),
             item_id: None,
+            date: None,
         });
 
-        // Index primary email
-        if let Some(ref email) = contact.email {
-            self.documents.push(SearchableDocument {
-                contact_id: contact_id.clone(),
-                contact_name: contact_name.clone(),
-                field_type: SearchableField::Email,
-                content: email.clone(),
-                item_id: None,
-            });
+        // Primary and additional emails and phones
+        for email in contact.email.iter().chain(&contact.emails) {
+            self.add_contact_field(contact, SearchableField::Email, email);
+        }
+        for phone in contact.phone.iter().chain(&contact.phones) {
+            self.add_contact_field(contact, SearchableField::Phone, phone);
         }
 
-        // Index additional emails
-        for email in &contact.emails {
-            self.documents.push(SearchableDocument {
-                contact_id: contact_id.clone(),
-                contact_name: contact_name.clone(),
-                field_type: SearchableField::Email,
-                content: email.clone(),
-                item_id: None,
-            });
+        let optional_fields = [
+            (SearchableField::Company, &contact.company),
+            (SearchableField::JobTitle, &contact.title),
+            (SearchableField::Location, &contact.location),
+            (SearchableField::Education, &contact.education),
+            (SearchableField::Website, &contact.website),
+            (SearchableField::Birthday, &contact.birthday),
+        ];
+        for (field, value) in optional_fields {
+            if let Some(value) = value {
+                self.add_contact_field(contact, field, value);
+            }
         }
 
-        // Index primary phone
-        if let Some(ref phone) = contact.phone {
-            self.documents.push(SearchableDocument {
-                contact_id: contact_id.clone(),
-                contact_name: contact_name.clone(),
-                field_type: SearchableField::Phone,
-                content: phone.clone(),
-                item_id: None,
-            });
+        for tag in &contact.tags {
+            self.add_contact_field(contact, SearchableField::Tag, tag);
         }
 
-        // Index additional phones
-        for phone in &contact.phones {
-            self.documents.push(SearchableDocument {
-                contact_id: contact_id.clone(),
-                contact_name: contact_name.clone(),
-                field_type: SearchableField::Phone,
-                content: phone.clone(),
-                item_id: None,
-            });
+        // Free-text fields may contain HTML from the Dex editor
+        for (field, value) in [
+            (SearchableField::Description, &contact.description),
+            (SearchableField::ContactNotes, &contact.notes),
+        ] {
+            if let Some(value) = value {
+                self.add_contact_field(contact, field, &strip_html(value));
+            }
         }
 
-        // Index company
-        if let Some(ref company) = contact.company {
-            self.documents.push(SearchableDocument {
-                contact_id: contact_id.clone(),
-                contact_name: contact_name.clone(),
-                field_type: SearchableField::Company,
-                content: company.clone(),
-                item_id: None,
-            });
+        // Social profiles, and the handles Dex keeps in dedicated fields
+        for profile in &contact.social_profiles {
+            let text = [
+                Some(profile.profile_type.as_str()),
+                profile.username.as_deref(),
+                Some(profile.url.as_str()),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+            self.add_contact_field(contact, SearchableField::SocialProfile, &text);
+        }
+        let handles = [
+            &contact.linkedin,
+            &contact.twitter,
+            &contact.facebook,
+            &contact.instagram,
+            &contact.telegram,
+        ];
+        for handle in handles.into_iter().flatten() {
+            self.add_contact_field(contact, SearchableField::SocialProfile, handle);
         }
+    }
 
-        // Index job title
-        if let Some(ref title) = contact.title {
-            self.documents.push(SearchableDocument {
-                contact_id: contact_id.clone(),
-                contact_name: contact_name.clone(),
-                field_type: SearchableField::JobTitle,
-                content: title.clone(),
-                item_id: None,
-            });
+    /// Index one contact field value, unless it has no text.
+    fn add_contact_field(&mut self, contact: &Contact, field: SearchableField, content: &str) {
+        if content.trim().is_empty() {
+            return;
         }
+        self.add_document(SearchableDocument {
+            contact_id: contact.id.clone(),
+            contact_name: contact.name.clone(),
+            field_type: field,
+            content: content.to_string(),
+            item_id: None,
+            date: None,
+        });
+    }
 
-        // Index notes
-        for note in notes {
-            let plain_text = strip_html(&note.content);
-            if !plain_text.trim().is_empty() {
-                self.documents.push(SearchableDocument {
-                    contact_id: contact_id.clone(),
-                    contact_name: contact_name.clone(),
-                    field_type: SearchableField::Note,
-                    content: plain_text,
-                    item_id: Some(note.id.clone()),
-                });
-            }
+    /// Index a note, unless it has no text.
+    fn index_note(&mut self, contact_id: &str, contact_name: &str, note: &Note) {
+        let plain_text = strip_html(&note.content);
+        if !plain_text.trim().is_empty() {
+            self.add_document(SearchableDocument {
+                contact_id: contact_id.to_string(),
+                contact_name: contact_name.to_string(),
+                field_type: SearchableField::Note,
+                content: plain_text,
+                item_id: Some(note.id.clone()),
+                date: Some(note.created_at.clone()),
+            });
         }
+    }
 
-        // Index reminders
-        for reminder in reminders {
-            if !reminder.text.trim().is_empty() {
-                self.documents.push(SearchableDocument {
-                    contact_id: contact_id.clone(),
-                    contact_name: contact_name.clone(),
-                    field_type: SearchableField::Reminder,
-                    content: reminder.text.clone(),
-                    item_id: Some(reminder.id.clone()),
-                });
-            }
+    /// Index a reminder, unless it has no text.
+    fn index_reminder(&mut self, contact_id: &str, contact_name: &str, reminder: &Reminder) {
+        if !reminder.text.trim().is_empty() {
+            self.add_document(SearchableDocument {
+                contact_id: contact_id.to_string(),
+                contact_name: contact_name.to_string(),
+                field_type: SearchableField::Reminder,
+                content: reminder.text.clone(),
+                item_id: Some(reminder.id.clone()),
+                date: Some(reminder.created_at.clone()),
+            });
         }
     }
 
-    /// Search the index for a query string.
+    /// Search the index for a plain keyword query.
     ///
-    /// Returns results grouped by contact, sorted by confidence.
+    /// Candidate documents come from the inverted index; a document matches
+    /// when at least half of the query terms match it exactly, by prefix or
+    /// fuzzily. Results are grouped by contact and ranked by the sum of their
+    /// documents' BM25 scores, with field boosts favouring names over emails,
+    /// companies and notes. Query syntax is not interpreted; see
+    /// [`search_query`](Self::search_query).
     ///
     /// # Arguments
     /// * `query` - The search query
@@ -237,187 +662,424 @@ impl FullTextSearchIndex {
         max_results: usize,
         min_confidence: u8,
     ) -> Vec<SearchResult> {
-        let query_lower = query.to_lowercase();
-        let mut matches_by_contact: HashMap<String, Vec<MatchContext>> = HashMap::new();
-
-        // Search all documents
-        for doc in &self.documents {
-            if let Some(match_ctx) = self.find_match(doc, &query_lower) {
-                if match_ctx.confidence >= min_confidence {
-                    matches_by_contact
-                        .entry(doc.contact_id.clone())
-                        .or_default()
-                        .push(match_ctx);
-                }
-            }
-        }
+        let matches = self.search_terms(&tokenize(query), &SearchFilter::default(), min_confidence);
+        self.rank(
+            contacts,
+            matches,
+            &FacetFilter::default(),
+            max_results,
+            min_confidence,
+            &SnippetOptions::default(),
+        )
+        .results
+    }
 
-        // Build search results
-        let mut results: Vec<SearchResult> = Vec::new();
+    /// Search the index for a parsed query.
+    ///
+    /// Plain keyword queries behave exactly like [`search`](Self::search).
+    /// Otherwise every term or phrase of the query is matched within its
+    /// field, and the matching contacts are combined with AND, OR and NOT.
+    /// Contacts are ranked by the summed BM25 scores of their matching
+    /// documents. Only documents passing `filter` can match, including for
+    /// negated terms. Match contexts are cut and highlighted per `snippets`.
+    pub fn search_query(
+        &self,
+        contacts: &[Contact],
+        query: &SearchQuery,
+        filter: &SearchFilter,
+        max_results: usize,
+        min_confidence: u8,
+        snippets: &SnippetOptions,
+    ) -> Vec<SearchResult> {
+        self.search_faceted(
+            contacts,
+            query,
+            filter,
+            max_results,
+            min_confidence,
+            snippets,
+        )
+        .results
+    }
 
-        for (contact_id, matches) in matches_by_contact {
-            // Find the contact
-            if let Some(contact) = contacts.iter().find(|c| c.id == contact_id) {
-                // Calculate overall confidence (boost for multiple matches)
-                let max_confidence = matches.iter().map(|m| m.confidence).max().unwrap_or(0);
-                let match_count_boost = (matches.len().saturating_sub(1) * 5).min(15) as u8;
-                let overall_confidence = (max_confidence + match_count_boost).min(100);
+    /// Search the index for a parsed query and count facets of the matches.
+    ///
+    /// Matches like [`search_query`](Self::search_query), keeping only the
+    /// contacts that pass `filter.facets`. Facet counts and `total_matches`
+    /// cover every matching contact, while only the best `max_results` are
+    /// returned.
+    pub fn search_faceted(
+        &self,
+        contacts: &[Contact],
+        query: &SearchQuery,
+        filter: &SearchFilter,
+        max_results: usize,
+        min_confidence: u8,
+        snippets: &SnippetOptions,
+    ) -> FacetedResults {
+        let matches = match query.plain_terms() {
+            Some(terms) => self.search_terms(&terms, filter, min_confidence),
+            None => self
+                .evaluate(query.root(), filter, min_confidence)
+                .unwrap_or_default(),
+        };
+        self.rank(
+            contacts,
+            matches,
+            &filter.facets,
+            max_results,
+            min_confidence,
+            snippets,
+        )
+    }
 
-                if overall_confidence >= min_confidence {
-                    results.push(SearchResult {
-                        contact: Arc::new(contact.clone()),
-                        matches,
-                        confidence: overall_confidence,
-                    });
-                }
-            }
+    /// Keyword search requiring at least half of the terms to match.
+    fn search_terms(
+        &self,
+        query_terms: &[String],
+        filter: &SearchFilter,
+        min_confidence: u8,
+    ) -> ContactMatches<'_> {
+        let mut matches = ContactMatches::new();
+        let query_terms = self.analyzer.analyze_terms(query_terms);
+        if query_terms.is_empty() {
+            return matches;
         }
+        let required_terms = query_terms.len().div_ceil(2);
 
-        // Sort by confidence (highest first)
-        results.sort_by(|a, b| b.confidence.cmp(&a.confidence));
+        // Group matching documents by contact
+        for (doc_id, hit) in self.inverted.score(&query_terms) {
+            let doc = self.document(doc_id);
+            if hit.matched_terms < required_terms || !filter.matches(doc) {
+                continue;
+            }
 
-        // Limit results
-        results.truncate(max_results);
+            let confidence = self.match_confidence(&hit, query_terms.len(), doc_id);
+            if confidence < min_confidence {
+                continue;
+            }
 
-        results
+            let entry = matches.entry(doc.contact_id.as_str()).or_default();
+            entry.score += hit.score;
+            entry.docs.push(DocMatch {
+                doc_id,
+                confidence,
+                needles: hit.terms.into_iter().map(|term| vec![term]).collect(),
+            });
+        }
+        matches
     }
 
-    /// Find a match in a document and generate context.
-    fn find_match(&self, doc: &SearchableDocument, query_lower: &str) -> Option<MatchContext> {
-        let content_lower = doc.content.to_lowercase();
-
-        // Calculate match confidence
-        let confidence = self.calculate_match_confidence(query_lower, &content_lower)?;
-
-        // Generate snippet
-        let snippet = self.generate_snippet(&doc.content, &content_lower, query_lower);
-
-        Some(MatchContext {
-            field_type: doc.field_type.clone(),
-            snippet,
-            confidence,
-            item_id: doc.item_id.clone(),
-        })
+    /// Find the contacts matching a query node.
+    ///
+    /// Returns `None` for nodes that place no constraint on the results,
+    /// such as a term consisting of a stopword; `AND` and `OR` ignore them.
+    fn evaluate(
+        &self,
+        node: &QueryNode,
+        filter: &SearchFilter,
+        min_confidence: u8,
+    ) -> Option<ContactMatches<'_>> {
+        match node {
+            QueryNode::Term { field, term } => self.evaluate_terms(
+                *field,
+                std::slice::from_ref(term),
+                false,
+                filter,
+                min_confidence,
+            ),
+            QueryNode::Phrase { field, terms } => {
+                self.evaluate_terms(*field, terms, true, filter, min_confidence)
+            }
+            QueryNode::Not(inner) => {
+                let excluded = self.evaluate(inner, filter, min_confidence)?;
+                let mut matches = self.every_contact();
+                matches.retain(|id, _| !excluded.contains_key(id));
+                Some(matches)
+            }
+            QueryNode::And(children) => {
+                // Intersect the positive children, then drop excluded contacts,
+                // so negations never have to enumerate every contact.
+                let mut matches: Option<ContactMatches<'_>> = None;
+                for child in children {
+                    if matches!(child, QueryNode::Not(_)) {
+                        continue;
+                    }
+                    let Some(child_matches) = self.evaluate(child, filter, min_confidence) else {
+                        continue;
+                    };
+                    matches = Some(match matches {
+                        None => child_matches,
+                        Some(acc) => intersect(acc, child_matches),
+                    });
+                }
+                let mut constrained = matches.is_some();
+                let mut matches = matches.unwrap_or_else(|| self.every_contact());
+
+                for child in children {
+                    if let QueryNode::Not(inner) = child {
+                        if matches.is_empty() {
+                            break;
+                        }
+                        if let Some(excluded) = self.evaluate(inner, filter, min_confidence) {
+                            constrained = true;
+                            matches.retain(|id, _| !excluded.contains_key(id));
+                        }
+                    }
+                }
+                constrained.then_some(matches)
+            }
+            QueryNode::Or(children) => {
+                let mut matches: Option<ContactMatches<'_>> = None;
+                for child in children {
+                    if let Some(child_matches) = self.evaluate(child, filter, min_confidence) {
+                        let acc = matches.get_or_insert_with(ContactMatches::new);
+                        for (id, contact_match) in child_matches {
+                            acc.entry(id).or_default().merge(contact_match);
+                        }
+                    }
+                }
+                matches
+            }
+        }
     }
 
-    /// Calculate match confidence for a query against content.
+    /// Match a term, or a phrase of consecutive terms, within an optional field.
     ///
-    /// Returns None if no match, Some(confidence) if matched.
-    fn calculate_match_confidence(&self, query: &str, content: &str) -> Option<u8> {
-        if query.is_empty() || content.is_empty() {
+    /// Documents are looked up by the analyzed terms; a phrase must also
+    /// appear word for word. Returns `None` if no terms are left after
+    /// analysis.
+    fn evaluate_terms(
+        &self,
+        field: Option<SearchableField>,
+        terms: &[String],
+        phrase: bool,
+        filter: &SearchFilter,
+        min_confidence: u8,
+    ) -> Option<ContactMatches<'_>> {
+        let analyzed = self.analyzer.analyze_terms(terms);
+        if analyzed.is_empty() {
             return None;
         }
 
-        // Exact substring match (highest confidence)
-        if content.contains(query) {
-            let ratio = query.len() as f64 / content.len() as f64;
-            let confidence = (85.0 * ratio + 10.0).min(95.0) as u8;
-            return Some(confidence);
-        }
+        let phrase_terms = if phrase {
+            self.analyzer.analyze_phrase_terms(terms)
+        } else {
+            Vec::new()
+        };
 
-        // Fuzzy word matching - split query and content into words
-        let query_words: Vec<&str> = query.split_whitespace().collect();
-        let content_words: Vec<&str> = content.split_whitespace().collect();
+        let mut matches = ContactMatches::new();
+        for (doc_id, hit) in self.inverted.score(&analyzed) {
+            let doc = self.document(doc_id);
+            if hit.matched_terms < analyzed.len()
+                || field.is_some_and(|f| f != doc.field_type)
+                || !filter.matches(doc)
+            {
+                continue;
+            }
+            if phrase
+                && find_sequences(
+                    &self.analyzer.analyze_phrase_spans(&doc.content),
+                    &phrase_terms,
+                )
+                .is_empty()
+            {
+                continue;
+            }
 
-        if query_words.is_empty() || content_words.is_empty() {
-            return None;
+            let confidence = self.match_confidence(&hit, analyzed.len(), doc_id);
+            if confidence < min_confidence {
+                continue;
+            }
+
+            let entry = matches.entry(doc.contact_id.as_str()).or_default();
+            entry.score += hit.score;
+            entry.docs.push(DocMatch {
+                doc_id,
+                confidence,
+                needles: if phrase {
+                    vec![analyzed.clone()]
+                } else {
+                    hit.terms.into_iter().map(|term| vec![term]).collect()
+                },
+            });
         }
+        Some(matches)
+    }
 
-        // Check if all query words have fuzzy matches in content
-        let mut total_score = 0;
-        let mut matches = 0;
+    /// Every indexed contact, without matching documents.
+    fn every_contact(&self) -> ContactMatches<'_> {
+        self.contact_documents
+            .keys()
+            .map(|id| (id.as_str(), ContactMatch::default()))
+            .collect()
+    }
 
-        for query_word in &query_words {
-            let mut best_word_score = 0;
+    /// Resolve, rank and truncate matching contacts, then build their snippets.
+    fn rank(
+        &self,
+        contacts: &[Contact],
+        matches: ContactMatches<'_>,
+        facet_filter: &FacetFilter,
+        max_results: usize,
+        min_confidence: u8,
+        snippets: &SnippetOptions,
+    ) -> FacetedResults {
+        // Resolve contacts in one pass
+        let contacts_by_id: HashMap<&str, &Contact> = contacts
+            .iter()
+            .filter(|c| matches.contains_key(c.id.as_str()))
+            .map(|c| (c.id.as_str(), c))
+            .collect();
 
-            for content_word in &content_words {
-                if content_word.contains(query_word) {
-                    // Substring match within word
-                    best_word_score = 85;
-                    break;
-                }
+        // Rank contacts before building snippets, so only returned results pay for them
+        let mut ranked: Vec<(u8, &Contact, ContactMatch)> = Vec::new();
+        let matched_fields = |contact_match: &ContactMatch| -> Vec<SearchableField> {
+            contact_match
+                .docs
+                .iter()
+                .map(|d| self.document(d.doc_id).field_type)
+                .collect()
+        };
 
-                // Fuzzy match using Levenshtein distance
-                let distance = levenshtein_distance(query_word, content_word);
-                let max_len = query_word.len().max(content_word.len());
+        for (contact_id, contact_match) in matches {
+            // Find the contact
+            if let Some(contact) = contacts_by_id.get(contact_id) {
+                // Calculate overall confidence (boost for multiple matches)
+                let docs = &contact_match.docs;
+                let max_confidence = docs.iter().map(|d| d.confidence).max().unwrap_or(0);
+                let match_count_boost = (docs.len().saturating_sub(1) * 5).min(15) as u8;
+                let overall_confidence = (max_confidence + match_count_boost).min(100);
 
-                if max_len > 0 && distance as f64 / max_len as f64 <= 0.4 {
-                    // Allow 40% difference for fuzzy match
-                    let similarity = 1.0 - (distance as f64 / max_len as f64);
-                    let score = (similarity * 75.0) as u8;
-                    best_word_score = best_word_score.max(score);
+                if overall_confidence >= min_confidence
+                    && facet_filter.matches(contact, &matched_fields(&contact_match))
+                {
+                    ranked.push((overall_confidence, contact, contact_match));
                 }
             }
-
-            if best_word_score > 0 {
-                total_score += best_word_score as usize;
-                matches += 1;
-            }
         }
 
-        // Require at least 50% of query words to match
-        if matches >= query_words.len().div_ceil(2) {
-            let avg_score = (total_score / query_words.len()).min(90) as u8;
-            Some(avg_score)
-        } else {
-            None
+        // Sort by relevance (highest BM25 score first), then confidence
+        ranked.sort_by(|a, b| {
+            b.2.score
+                .total_cmp(&a.2.score)
+                .then(b.0.cmp(&a.0))
+                .then_with(|| a.1.id.cmp(&b.1.id))
+        });
+
+        // Count facets over every match, in rank order so that display
+        // values come from the best matches
+        let mut facet_counter = FacetCounter::new();
+        for (_, contact, contact_match) in &ranked {
+            facet_counter.add(contact, &matched_fields(contact_match));
         }
-    }
+        let total_matches = ranked.len();
 
-    /// Generate a snippet with context around the match.
-    fn generate_snippet(&self, original: &str, content_lower: &str, query: &str) -> String {
-        // Find the position of the query in the content
-        let pos = if let Some(idx) = content_lower.find(query) {
-            idx
-        } else {
-            // If exact match not found, find the first word from query
-            let first_word = query.split_whitespace().next().unwrap_or(query);
-            content_lower
-                .split_whitespace()
-                .enumerate()
-                .find(|(_, word)| word.contains(first_word))
-                .map(|(i, _)| {
-                    content_lower
-                        .split_whitespace()
-                        .take(i)
-                        .map(|w| w.len() + 1)
-                        .sum::<usize>()
-                })
-                .unwrap_or(0)
-        };
+        // Limit results
+        ranked.truncate(max_results);
+
+        let results = ranked
+            .into_iter()
+            .map(|(confidence, contact, mut contact_match)| {
+                contact_match
+                    .docs
+                    .sort_by_key(|d| (std::cmp::Reverse(d.confidence), d.doc_id));
+                SearchResult {
+                    contact: Arc::new(contact.clone()),
+                    matches: contact_match
+                        .docs
+                        .iter()
+                        .map(|d| {
+                            self.match_context(
+                                self.document(d.doc_id),
+                                &d.needles,
+                                d.confidence,
+                                snippets,
+                            )
+                        })
+                        .collect(),
+                    confidence,
+                }
+            })
+            .collect();
 
-        // Calculate snippet boundaries
-        let start = pos.saturating_sub(CONTEXT_CHARS);
-        let end = (pos + query.len() + CONTEXT_CHARS).min(original.len());
+        FacetedResults {
+            results,
+            facets: facet_counter.finish(),
+            total_matches,
+        }
+    }
 
-        // Extract snippet
-        let mut snippet = original[start..end].to_string();
+    /// Confidence (0-100) that a document matches the query.
+    ///
+    /// Combines how well the query terms matched (exact > prefix > fuzzy)
+    /// with how much of the field they cover, so a query equal to a short
+    /// field scores higher than the same term buried in a long note.
+    fn match_confidence(&self, hit: &DocumentHit, query_terms: usize, doc_id: usize) -> u8 {
+        let quality = hit.match_quality / query_terms as f64;
+        let length = self.inverted.document_length(doc_id).max(1) as f64;
+        let coverage = (hit.matched_tokens as f64 / length).min(1.0);
+
+        (95.0 * quality * (0.6 + 0.4 * coverage)).round() as u8
+    }
 
-        // Add ellipsis if truncated
-        if start > 0 {
-            snippet = format!("...{}", snippet);
-        }
-        if end < original.len() {
-            snippet = format!("{}...", snippet);
+    /// Build the match context for a matching document.
+    fn match_context(
+        &self,
+        doc: &SearchableDocument,
+        needles: &[Vec<String>],
+        confidence: u8,
+        options: &SnippetOptions,
+    ) -> MatchContext {
+        let hits = find_hits(&doc.content, needles, &self.analyzer);
+        let snippets = build_snippets(&doc.content, &hits, options);
+
+        MatchContext {
+            field_type: doc.field_type,
+            snippet: snippets
+                .first()
+                .map(|snippet| snippet.text.clone())
+                .unwrap_or_default(),
+            snippets,
+            hits,
+            confidence,
+            item_id: doc.item_id.clone(),
         }
+    }
 
-        // Truncate if too long
-        if snippet.len() > MAX_SNIPPET_LENGTH {
-            snippet.truncate(MAX_SNIPPET_LENGTH - 3);
-            snippet.push_str("...");
+    /// Find the `k` contacts most similar to `contact_id`.
+    ///
+    /// Contacts are compared by the TF-IDF vectors of their describing
+    /// fields, notes and reminders; see [`TermVectors`]. Returns `None` if the
+    /// contact is not indexed.
+    pub fn similar_contacts(&self, contact_id: &str, k: usize) -> Option<Vec<SimilarContact>> {
+        if !self.contact_documents.contains_key(contact_id) {
+            return None;
         }
+        Some(TermVectors::build(self).similar_to(contact_id, k))
+    }
 
-        snippet
+    /// Suggest corrected spellings of a query, best first.
+    ///
+    /// Words are corrected against the vocabulary of every indexed document,
+    /// contact names included; see [`SpellingDictionary::suggest`]. The
+    /// vocabulary is kept up to date as documents are added and removed.
+    pub fn suggest_spelling(&self, query: &str) -> Vec<SpellingSuggestion> {
+        self.spelling.suggest(query)
     }
 
     /// Clear all documents from the index.
     pub fn clear(&mut self) {
         self.documents.clear();
+        self.inverted.clear();
+        self.contact_documents.clear();
+        self.item_documents.clear();
+        self.spelling = SpellingDictionary::new();
     }
 
     /// Get the number of indexed documents.
     pub fn document_count(&self) -> usize {
-        self.documents.len()
+        self.inverted.len()
     }
 }
 
@@ -438,47 +1100,10 @@ pub fn strip_html(html: &str) -> String {
     text.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
-/// Calculate Levenshtein distance between two strings.
-fn levenshtein_distance(s1: &str, s2: &str) -> usize {
-    let len1 = s1.chars().count();
-    let len2 = s2.chars().count();
-
-    if len1 == 0 {
-        return len2;
-    }
-    if len2 == 0 {
-        return len1;
-    }
-
-    let mut matrix: Vec<Vec<usize>> = vec![vec![0; len2 + 1]; len1 + 1];
-
-    // Initialize first column
-    for (i, row) in matrix.iter_mut().enumerate() {
-        row[0] = i;
-    }
-    // Initialize first row
-    for (j, cell) in matrix[0].iter_mut().enumerate() {
-        *cell = j;
-    }
-
-    let s1_chars: Vec<char> = s1.chars().collect();
-    let s2_chars: Vec<char> = s2.chars().collect();
-
-    for (i, c1) in s1_chars.iter().enumerate() {
-        for (j, c2) in s2_chars.iter().enumerate() {
-            let cost = if c1 == c2 { 0 } else { 1 };
-            matrix[i + 1][j + 1] = (matrix[i][j + 1] + 1)
-                .min(matrix[i + 1][j] + 1)
-                .min(matrix[i][j] + cost);
-        }
-    }
-
-    matrix[len1][len2]
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::search::HighlightMarkers;
 
     fn create_test_contact(id: &str, name: &str) -> Contact {
         Contact::new(id.to_string(), name.to_string())
@@ -656,28 +1281,6 @@ mod tests {
         assert!(results.len() <= 3);
     }
 
-    #[test]
-    fn test_snippet_generation() {
-        let index = FullTextSearchIndex::new();
-        let original =
-            "This is a long text with many words to test snippet generation functionality";
-        let snippet = index.generate_snippet(original, &original.to_lowercase(), "snippet");
-
-        assert!(snippet.contains("snippet"));
-        assert!(snippet.len() <= MAX_SNIPPET_LENGTH);
-    }
-
-    #[test]
-    fn test_snippet_with_ellipsis() {
-        let index = FullTextSearchIndex::new();
-        let original = "Start of text. This is the middle section with the important keyword that we are searching for. End of text with more content.";
-        let snippet = index.generate_snippet(original, &original.to_lowercase(), "keyword");
-
-        assert!(snippet.contains("keyword"));
-        // Should have ellipsis since we're extracting from the middle
-        assert!(snippet.contains("..."));
-    }
-
     #[test]
     fn test_clear_index() {
         let mut index = FullTextSearchIndex::new();
@@ -707,11 +1310,428 @@ mod tests {
     }
 
     #[test]
-    fn test_levenshtein_distance() {
-        assert_eq!(levenshtein_distance("kitten", "sitting"), 3);
-        assert_eq!(levenshtein_distance("saturday", "sunday"), 3);
-        assert_eq!(levenshtein_distance("", "test"), 4);
-        assert_eq!(levenshtein_distance("test", ""), 4);
-        assert_eq!(levenshtein_distance("same", "same"), 0);
+    fn test_search_ranks_name_above_note() {
+        let mut index = FullTextSearchIndex::new();
+        let named = create_test_contact("1", "Morgan Lee");
+        let mentioned = create_test_contact("2", "Sam Park");
+        let notes = vec![Note {
+            id: "note1".to_string(),
+            contact_id: "2".to_string(),
+            content: "Introduced by Morgan at the conference".to_string(),
+            ..Default::default()
+        }];
+
+        let contacts = vec![named.clone(), mentioned.clone()];
+        index.index_contact(&named, &[], &[]);
+        index.index_contact(&mentioned, &notes, &[]);
+
+        let results = index.search(&contacts, "morgan", 10, 0);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].contact.id, "1");
+        assert_eq!(results[1].matches[0].field_type, SearchableField::Note);
+        assert_eq!(results[1].matches[0].item_id.as_deref(), Some("note1"));
+        assert!(results[1].matches[0].snippet.contains("Morgan"));
+    }
+
+    #[test]
+    fn test_match_context_reports_every_hit() {
+        let mut index = FullTextSearchIndex::new();
+        let contact = create_test_contact("1", "Sam Park");
+        let content = "Kayak trip planned. Bring the kayak. Later: kayaking lessons";
+        let notes = vec![Note {
+            id: "note1".to_string(),
+            contact_id: "1".to_string(),
+            content: content.to_string(),
+            ..Default::default()
+        }];
+        let contacts = vec![contact.clone()];
+        index.index_contact(&contact, &notes, &[]);
+
+        let query = SearchQuery::parse(r#"note:"kayak trip" OR note:kayak"#).unwrap();
+        let options = SnippetOptions {
+            highlight: Some(HighlightMarkers::markdown()),
+            ..Default::default()
+        };
+        let results =
+            index.search_query(&contacts, &query, &SearchFilter::default(), 10, 0, &options);
+
+        let context = &results[0].matches[0];
+        let hits: Vec<&str> = context
+            .hits
+            .iter()
+            .map(|hit| &content[hit.start..hit.end])
+            .collect();
+        // "kayaking" stems to "kayak"
+        assert_eq!(hits, vec!["Kayak trip", "kayak", "kayaking"]);
+        assert_eq!(context.snippets.len(), 1);
+        assert_eq!(
+            context.snippet,
+            "**Kayak trip** planned. Bring the **kayak**. Later: **kayaking** lessons"
+        );
+    }
+
+    #[test]
+    fn test_search_requires_half_of_query_terms() {
+        let mut index = FullTextSearchIndex::new();
+        let mut contact = create_test_contact("1", "John Doe");
+        contact.company = Some("Acme".to_string());
+
+        let contacts = vec![contact.clone()];
+        index.index_contact(&contact, &[], &[]);
+
+        assert_eq!(index.search(&contacts, "john doe", 10, 0).len(), 1);
+        assert_eq!(index.search(&contacts, "john smith", 10, 0).len(), 1);
+        assert!(index.search(&contacts, "john quux zork", 10, 0).is_empty());
+    }
+
+    /// Two contacts at Acme: Jane (investor, series A note, open reminder)
+    /// and Bob (series B note, completed reminder), plus Carol whose note
+    /// mentions Acme.
+    fn structured_fixture() -> (FullTextSearchIndex, Vec<Contact>) {
+        let mut jane = create_test_contact("1", "Jane Roe");
+        jane.company = Some("Acme".to_string());
+        jane.tags = vec!["investor".to_string()];
+        let mut bob = create_test_contact("2", "Bob Stone");
+        bob.company = Some("Acme".to_string());
+        bob.tags = vec!["investor".to_string()];
+        let carol = create_test_contact("3", "Carol King");
+
+        let note = |id: &str, contact_id: &str, content: &str| Note {
+            id: id.to_string(),
+            contact_id: contact_id.to_string(),
+            content: content.to_string(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: None,
+            tags: Vec::new(),
+            source: None,
+        };
+        let reminder = |id: &str, contact_id: &str, text: &str| Reminder {
+            id: id.to_string(),
+            contact_id: contact_id.to_string(),
+            text: text.to_string(),
+            due_date: "2024-02-01".to_string(),
+            completed: false,
+            completed_at: None,
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: None,
+            tags: Vec::new(),
+            priority: None,
+        };
+
+        let mut index = FullTextSearchIndex::new();
+        index.index_contact(
+            &jane,
+            &[note("n1", "1", "Leading their series A round")],
+            &[reminder("r1", "1", "Send the deck")],
+        );
+        index.index_contact(
+            &bob,
+            &[note("n2", "2", "A series B investor")],
+            &[reminder("r2", "2", "Intro call done")],
+        );
+        index.index_contact(&carol, &[note("n3", "3", "Used to work at Acme")], &[]);
+
+        (index, vec![jane, bob, carol])
+    }
+
+    fn query_ids(index: &FullTextSearchIndex, contacts: &[Contact], query: &str) -> Vec<String> {
+        let query = SearchQuery::parse(query).unwrap();
+        let mut ids: Vec<String> = index
+            .search_query(
+                contacts,
+                &query,
+                &SearchFilter::default(),
+                10,
+                0,
+                &SnippetOptions::default(),
+            )
+            .into_iter()
+            .map(|r| r.contact.id.clone())
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_search_query_field_scope() {
+        let (index, contacts) = structured_fixture();
+
+        assert_eq!(query_ids(&index, &contacts, "acme"), vec!["1", "2", "3"]);
+        assert_eq!(query_ids(&index, &contacts, "company:acme"), vec!["1", "2"]);
+        assert_eq!(query_ids(&index, &contacts, "note:acme"), vec!["3"]);
+    }
+
+    #[test]
+    fn test_search_query_phrase_and_negation() {
+        let (index, contacts) = structured_fixture();
+
+        assert_eq!(
+            query_ids(
+                &index,
+                &contacts,
+                r#"company:acme tag:investor note:"series A" -reminder:done"#
+            ),
+            vec!["1"]
+        );
+        // Both notes contain "series" and "a", but only one as a phrase
+        assert_eq!(
+            query_ids(&index, &contacts, r#"note:"series a""#),
+            vec!["1"]
+        );
+        assert_eq!(
+            query_ids(&index, &contacts, "tag:investor -reminder:done"),
+            vec!["1"]
+        );
+    }
+
+    #[test]
+    fn test_search_query_or_and_grouping() {
+        let (index, contacts) = structured_fixture();
+
+        assert_eq!(
+            query_ids(&index, &contacts, "name:jane OR name:carol"),
+            vec!["1", "3"]
+        );
+        assert_eq!(
+            query_ids(&index, &contacts, "acme (reminder:deck OR note:work)"),
+            vec!["1", "3"]
+        );
+    }
+
+    #[test]
+    fn test_search_query_plain_matches_keyword_search() {
+        let (index, contacts) = structured_fixture();
+
+        let query = SearchQuery::parse("jane acme").unwrap();
+        let structured = index.search_query(
+            &contacts,
+            &query,
+            &SearchFilter::default(),
+            10,
+            0,
+            &SnippetOptions::default(),
+        );
+        let keyword = index.search(&contacts, "jane acme", 10, 0);
+
+        assert_eq!(structured.len(), keyword.len());
+        for (a, b) in structured.iter().zip(&keyword) {
+            assert_eq!(a.contact.id, b.contact.id);
+            assert_eq!(a.confidence, b.confidence);
+        }
+    }
+
+    #[test]
+    fn test_search_filter_matches() {
+        let doc = |field_type, date: Option<&str>| SearchableDocument {
+            contact_id: "1".to_string(),
+            contact_name: "Jane".to_string(),
+            field_type,
+            content: "roadmap".to_string(),
+            item_id: None,
+            date: date.map(str::to_string),
+        };
+        let note = doc(SearchableField::Note, Some("2024-03-10T09:00:00Z"));
+        let name = doc(SearchableField::Name, None);
+
+        let types = SearchFilter {
+            include_types: Some(vec![SearchableField::Note]),
+            ..Default::default()
+        };
+        assert!(types.matches(&note));
+        assert!(!types.matches(&name));
+
+        let range = |from: &str, to: &str| SearchFilter {
+            date_from: Some(from.to_string()),
+            date_to: Some(to.to_string()),
+            ..Default::default()
+        };
+        assert!(range("2024-03-01", "2024-03-10").matches(&note));
+        assert!(!range("2024-03-11", "2024-04-01").matches(&note));
+        assert!(!range("2024-01-01", "2024-03-10T08:00:00Z").matches(&note));
+        // Contact fields have no date and are not affected by date bounds
+        assert!(range("2030-01-01", "2030-12-31").matches(&name));
+    }
+
+    #[test]
+    fn test_indexes_all_contact_fields() {
+        let mut contact = create_test_contact("1", "Jane Roe");
+        contact.description = Some("<p>Met at the <b>climate</b> summit</p>".to_string());
+        contact.location = Some("Berlin, Germany".to_string());
+        contact.education = Some("ETH Zurich".to_string());
+        contact.website = Some("https://janeroe.dev".to_string());
+        contact.birthday = Some("1990-05-17".to_string());
+        contact.notes = Some("Prefers Signal".to_string());
+        contact.twitter = Some("janeroe".to_string());
+        contact.social_profiles = vec![crate::models::SocialProfile {
+            profile_type: "github".to_string(),
+            username: Some("jroe".to_string()),
+            url: "https://github.com/jroe".to_string(),
+        }];
+
+        let mut index = FullTextSearchIndex::new();
+        index.index_contact(&contact, &[], &[]);
+        let contacts = vec![contact];
+
+        for (query, field) in [
+            ("climate", SearchableField::Description),
+            ("berlin", SearchableField::Location),
+            ("zurich", SearchableField::Education),
+            ("janeroe.dev", SearchableField::Website),
+            ("jroe", SearchableField::SocialProfile),
+            ("1990", SearchableField::Birthday),
+            ("signal", SearchableField::ContactNotes),
+        ] {
+            let results = index.search(&contacts, query, 10, 0);
+            assert_eq!(ids(&results), vec!["1"], "{}", query);
+            assert_eq!(results[0].matches[0].field_type, field, "{}", query);
+        }
+
+        // HTML is stripped from free-text fields
+        let description = index
+            .documents()
+            .find(|doc| doc.field_type == SearchableField::Description)
+            .unwrap();
+        assert_eq!(description.content.trim(), "Met at the climate summit");
+
+        // Field-scoped queries reach the new fields
+        assert_eq!(query_ids(&index, &contacts, "location:berlin"), vec!["1"]);
+        assert_eq!(query_ids(&index, &contacts, "social:janeroe"), vec!["1"]);
+        assert!(query_ids(&index, &contacts, "contact_notes:berlin").is_empty());
+    }
+
+    #[test]
+    fn test_field_names_round_trip() {
+        for field in SearchableField::ALL {
+            let name = field.display_name().replace(' ', "_");
+            assert_eq!(SearchableField::from_name(&name), Some(field), "{}", name);
+        }
+        assert_eq!(
+            SearchableField::from_name("notes"),
+            Some(SearchableField::Note)
+        );
+    }
+
+    fn ids(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.contact.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_upsert_contact_replaces_fields_and_keeps_notes() {
+        let (mut index, mut contacts) = structured_fixture();
+        let before = index.document_count();
+
+        contacts[0].company = Some("Globex".to_string());
+        contacts[0].name = "Jane Smith".to_string();
+        index.upsert_contact(&contacts[0]);
+
+        assert_eq!(index.document_count(), before);
+        assert_eq!(ids(&index.search(&contacts, "globex", 10, 0)), vec!["1"]);
+        assert_eq!(ids(&index.search(&contacts, "smith", 10, 0)), vec!["1"]);
+        assert!(!ids(&index.search(&contacts, "acme", 10, 0)).contains(&"1"));
+        assert_eq!(ids(&index.search(&contacts, "deck", 10, 0)), vec!["1"]);
+
+        let note = index
+            .documents
+            .iter()
+            .flatten()
+            .find(|doc| doc.item_id.as_deref() == Some("n1"))
+            .unwrap();
+        assert_eq!(note.contact_name, "Jane Smith");
+    }
+
+    #[test]
+    fn test_remove_contact_removes_notes_and_reminders() {
+        let (mut index, contacts) = structured_fixture();
+
+        index.remove_contact("2");
+
+        assert!(index.search(&contacts, "bob", 10, 0).is_empty());
+        assert!(index.search(&contacts, "intro", 10, 0).is_empty());
+        assert_eq!(ids(&index.search(&contacts, "series", 10, 0)), vec!["1"]);
+        assert!(!index.contact_documents.contains_key("2"));
+    }
+
+    #[test]
+    fn test_upsert_and_remove_notes_and_reminders() {
+        let (mut index, contacts) = structured_fixture();
+        let before = index.document_count();
+
+        let mut note = Note {
+            id: "n4".to_string(),
+            contact_id: "3".to_string(),
+            content: "Loves sailing".to_string(),
+            created_at: "2024-02-01T00:00:00Z".to_string(),
+            ..Default::default()
+        };
+        index.upsert_note(&note);
+        assert_eq!(ids(&index.search(&contacts, "sailing", 10, 0)), vec!["3"]);
+
+        // Upserting again replaces rather than duplicates
+        note.content = "Loves kayaking".to_string();
+        index.upsert_note(&note);
+        assert_eq!(index.document_count(), before + 1);
+        assert!(index.search(&contacts, "sailing", 10, 0).is_empty());
+        assert_eq!(ids(&index.search(&contacts, "kayaking", 10, 0)), vec!["3"]);
+
+        index.remove_note("n4");
+        assert!(index.search(&contacts, "kayaking", 10, 0).is_empty());
+
+        index.remove_reminder("r1");
+        assert!(index.search(&contacts, "deck", 10, 0).is_empty());
+        assert_eq!(index.document_count(), before - 1);
+
+        // Unknown IDs are ignored
+        index.remove_note("missing");
+        index.remove_contact("missing");
+        assert_eq!(index.document_count(), before - 1);
+    }
+
+    #[test]
+    fn test_spelling_suggestions_follow_updates() {
+        let (mut index, _) = structured_fixture();
+        let suggestion = |index: &FullTextSearchIndex| {
+            index
+                .suggest_spelling("kayakng")
+                .first()
+                .map(|s| s.text.clone())
+        };
+        assert_eq!(suggestion(&index), None);
+
+        let mut note = Note {
+            id: "n4".to_string(),
+            contact_id: "3".to_string(),
+            content: "Loves kayaking".to_string(),
+            created_at: "2024-02-01T00:00:00Z".to_string(),
+            ..Default::default()
+        };
+        index.upsert_note(&note);
+        assert_eq!(suggestion(&index).as_deref(), Some("kayaking"));
+
+        note.content = "Loves sailing".to_string();
+        index.upsert_note(&note);
+        assert_eq!(suggestion(&index), None);
+    }
+
+    #[test]
+    fn test_from_documents_restores_index() {
+        let (mut index, contacts) = structured_fixture();
+        index.remove_note("n1");
+
+        let mut restored =
+            FullTextSearchIndex::from_documents(Arc::default(), index.documents().cloned());
+        assert_eq!(restored.document_count(), index.document_count());
+        for query in ["acme", "company:acme -bob", "series", "reminder:intro"] {
+            assert_eq!(
+                query_ids(&restored, &contacts, query),
+                query_ids(&index, &contacts, query)
+            );
+        }
+
+        // Removed documents are not carried over, and lookups still work
+        assert!(restored
+            .documents()
+            .all(|doc| doc.item_id.as_deref() != Some("n1")));
+        restored.remove_reminder("r1");
+        assert!(restored.search(&contacts, "deck", 10, 0).is_empty());
     }
 }
@@ -0,0 +1,395 @@
+//! Match highlighting and snippet extraction.
+//!
+//! Finds every occurrence of the matched terms in a document's text, with
+//! byte and character offsets, and cuts the text into ranked snippet windows
+//! that cover as many of them as possible. Hits can be wrapped in highlight
+//! markers such as `**…**`.
+
+use super::analyzer::{find_sequences, Analyzer};
+use crate::text::fold;
+use std::collections::HashSet;
+
+/// Default maximum snippet length in characters.
+pub const DEFAULT_SNIPPET_LENGTH: usize = 150;
+
+/// Default maximum number of snippets per match.
+pub const DEFAULT_MAX_SNIPPETS: usize = 3;
+
+/// Marker added where a snippet is cut from a longer text.
+const ELLIPSIS: &str = "...";
+
+/// A range of a document's text, in both byte and character offsets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextSpan {
+    /// Byte offset of the first byte
+    pub start: usize,
+
+    /// Byte offset one past the last byte
+    pub end: usize,
+
+    /// Character offset of the first character
+    pub char_start: usize,
+
+    /// Character offset one past the last character
+    pub char_end: usize,
+}
+
+/// Markers wrapped around each hit in a snippet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighlightMarkers {
+    /// Inserted before each hit
+    pub pre: String,
+
+    /// Inserted after each hit
+    pub post: String,
+}
+
+impl HighlightMarkers {
+    /// Markdown bold markers (`**…**`).
+    pub fn markdown() -> Self {
+        Self {
+            pre: "**".to_string(),
+            post: "**".to_string(),
+        }
+    }
+}
+
+/// How snippets are cut and rendered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnippetOptions {
+    /// Maximum snippet length in characters, including ellipses but not
+    /// highlight markers
+    pub length: usize,
+
+    /// Maximum number of snippets per match
+    pub max_snippets: usize,
+
+    /// Markers to wrap hits in, if any
+    pub highlight: Option<HighlightMarkers>,
+}
+
+impl Default for SnippetOptions {
+    fn default() -> Self {
+        Self {
+            length: DEFAULT_SNIPPET_LENGTH,
+            max_snippets: DEFAULT_MAX_SNIPPETS,
+            highlight: None,
+        }
+    }
+}
+
+/// A window of a document's text around one or more hits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    /// Rendered text, with ellipses and highlight markers
+    pub text: String,
+
+    /// Part of the document text the snippet shows
+    pub span: TextSpan,
+
+    /// Number of hits inside the snippet
+    pub hit_count: usize,
+}
+
+/// Find every occurrence of the needles in `text`.
+///
+/// Each needle is a sequence of index terms that must appear consecutively
+/// when `text` is analyzed with `analyzer`, so a single term matches one
+/// word (or synonym phrase) and a phrase matches a run of them. Overlapping
+/// and touching hits are merged, and the result is sorted by position.
+pub fn find_hits(text: &str, needles: &[Vec<String>], analyzer: &Analyzer) -> Vec<TextSpan> {
+    let tokens = analyzer.analyze_spans(text);
+    let mut ranges: Vec<(usize, usize)> = needles
+        .iter()
+        .flat_map(|needle| find_sequences(&tokens, needle))
+        .map(|range| (range.start, range.end))
+        .collect();
+    ranges.sort_unstable();
+
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+
+    // Character offsets, counted in one pass over the text
+    let mut spans = Vec::with_capacity(merged.len());
+    let mut chars = 0;
+    let mut counted = 0;
+    let mut char_offset = |byte: usize| {
+        chars += text[counted..byte].chars().count();
+        counted = byte;
+        chars
+    };
+    for (start, end) in merged {
+        let char_start = char_offset(start);
+        let char_end = char_offset(end);
+        spans.push(TextSpan {
+            start,
+            end,
+            char_start,
+            char_end,
+        });
+    }
+    spans
+}
+
+/// Cut ranked snippets of `text` around `hits`.
+///
+/// Every hit is a candidate anchor for a window that extends over as many
+/// following hits as fit. Windows covering more distinct hit words rank
+/// first, then windows with more hits, then earlier ones. Up to
+/// `max_snippets` non-overlapping windows are returned, best first. Text
+/// without hits yields a single snippet from its start.
+pub fn build_snippets(text: &str, hits: &[TextSpan], options: &SnippetOptions) -> Vec<Snippet> {
+    let boundaries: Vec<usize> = text
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(text.len()))
+        .collect();
+    let total_chars = boundaries.len() - 1;
+    let window_chars = window_length(total_chars, options.length);
+
+    if hits.is_empty() {
+        return vec![render(text, &boundaries, 0, window_chars, &[], options)];
+    }
+
+    // One candidate window per anchor hit: (distinct words, hits, start)
+    let mut candidates: Vec<(usize, usize, usize)> = Vec::new();
+    for (anchor, first) in hits.iter().enumerate() {
+        let mut last = anchor;
+        while last + 1 < hits.len() && hits[last + 1].char_end - first.char_start <= window_chars {
+            last += 1;
+        }
+
+        let covered = hits[last].char_end.min(first.char_start + window_chars) - first.char_start;
+        let start = first
+            .char_start
+            .saturating_sub((window_chars - covered) / 2)
+            .min(total_chars - window_chars);
+
+        let distinct: HashSet<String> = hits[anchor..=last]
+            .iter()
+            .map(|hit| fold(&text[hit.start..hit.end]))
+            .collect();
+        candidates.push((distinct.len(), last + 1 - anchor, start));
+    }
+    candidates.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)).then(a.2.cmp(&b.2)));
+
+    let mut starts: Vec<usize> = Vec::new();
+    for (_, _, start) in candidates {
+        if starts.len() >= options.max_snippets.max(1) {
+            break;
+        }
+        if starts
+            .iter()
+            .all(|&other| start >= other + window_chars || other >= start + window_chars)
+        {
+            starts.push(start);
+        }
+    }
+
+    starts
+        .into_iter()
+        .map(|start| render(text, &boundaries, start, window_chars, hits, options))
+        .collect()
+}
+
+/// Number of text characters a snippet shows, leaving room for ellipses.
+fn window_length(total_chars: usize, length: usize) -> usize {
+    if total_chars <= length {
+        total_chars
+    } else {
+        length.saturating_sub(2 * ELLIPSIS.len()).max(1)
+    }
+}
+
+/// Render the window of `window_chars` characters starting at `char_start`,
+/// highlighting the hits inside it.
+fn render(
+    text: &str,
+    boundaries: &[usize],
+    char_start: usize,
+    window_chars: usize,
+    hits: &[TextSpan],
+    options: &SnippetOptions,
+) -> Snippet {
+    let char_end = char_start + window_chars;
+    let span = TextSpan {
+        start: boundaries[char_start],
+        end: boundaries[char_end],
+        char_start,
+        char_end,
+    };
+
+    let mut rendered = String::new();
+    if span.start > 0 {
+        rendered.push_str(ELLIPSIS);
+    }
+    let mut cursor = span.start;
+    let mut hit_count = 0;
+    for hit in hits {
+        // Hits cut by the window edge are shown and highlighted partially
+        let start = hit.start.max(span.start);
+        let end = hit.end.min(span.end);
+        if start >= end {
+            continue;
+        }
+        hit_count += 1;
+        rendered.push_str(&text[cursor..start]);
+        match &options.highlight {
+            Some(markers) => {
+                rendered.push_str(&markers.pre);
+                rendered.push_str(&text[start..end]);
+                rendered.push_str(&markers.post);
+            }
+            None => rendered.push_str(&text[start..end]),
+        }
+        cursor = end;
+    }
+    rendered.push_str(&text[cursor..span.end]);
+    if span.end < text.len() {
+        rendered.push_str(ELLIPSIS);
+    }
+
+    Snippet {
+        text: rendered,
+        span,
+        hit_count,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn needles(terms: &[&str]) -> Vec<Vec<String>> {
+        terms
+            .iter()
+            .map(|t| t.split(' ').map(fold).collect())
+            .collect()
+    }
+
+    fn snippets(text: &str, terms: &[&str], options: &SnippetOptions) -> Vec<Snippet> {
+        build_snippets(
+            text,
+            &find_hits(text, &needles(terms), &Analyzer::new()),
+            options,
+        )
+    }
+
+    #[test]
+    fn test_find_hits_reports_byte_and_char_offsets() {
+        let text = "Café with Jane, then JANE's café";
+        let hits = find_hits(text, &needles(&["jane", "cafe"]), &Analyzer::new());
+
+        let found: Vec<&str> = hits.iter().map(|h| &text[h.start..h.end]).collect();
+        assert_eq!(found, vec!["Café", "Jane", "JANE", "café"]);
+
+        let chars: Vec<char> = text.chars().collect();
+        for hit in &hits {
+            let by_chars: String = chars[hit.char_start..hit.char_end].iter().collect();
+            assert_eq!(by_chars, text[hit.start..hit.end]);
+        }
+        assert_eq!(hits[3].char_start, 28);
+        assert_eq!(hits[3].start, 29);
+    }
+
+    #[test]
+    fn test_find_hits_matches_phrases_and_merges_overlaps() {
+        let text = "series a funding, not series b";
+        let hits = find_hits(text, &needles(&["series a", "series"]), &Analyzer::new());
+
+        let found: Vec<&str> = hits.iter().map(|h| &text[h.start..h.end]).collect();
+        assert_eq!(found, vec!["series a", "series"]);
+    }
+
+    #[test]
+    fn test_short_text_is_one_snippet() {
+        let options = SnippetOptions {
+            highlight: Some(HighlightMarkers::markdown()),
+            ..Default::default()
+        };
+        let result = snippets("Met Jane at the Acme offsite", &["jane", "acme"], &options);
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].text, "Met **Jane** at the **Acme** offsite");
+        assert_eq!(result[0].hit_count, 2);
+    }
+
+    #[test]
+    fn test_snippet_generation() {
+        let text = "This is a long text with many words to test snippet generation functionality";
+        let options = SnippetOptions {
+            length: 40,
+            ..Default::default()
+        };
+        let result = snippets(text, &["snippet"], &options);
+
+        assert!(result[0].text.contains("snippet"));
+        assert!(result[0].text.chars().count() <= 40);
+    }
+
+    #[test]
+    fn test_snippet_with_ellipsis() {
+        let text = "Start of text. This is the middle section with the important keyword that we are searching for. End of text with more content.";
+        let options = SnippetOptions {
+            length: 60,
+            ..Default::default()
+        };
+        let result = snippets(text, &["keyword"], &options);
+
+        assert!(result[0].text.contains("keyword"));
+        assert!(result[0].text.starts_with("..."));
+        assert!(result[0].text.ends_with("..."));
+        assert_eq!(result[0].text.chars().count(), 60);
+    }
+
+    #[test]
+    fn test_snippet_respects_char_boundaries() {
+        let text = format!("{}café meeting{}", "é".repeat(100), "ü".repeat(100));
+        let result = snippets(&text, &["meeting"], &SnippetOptions::default());
+
+        assert!(result[0].text.contains("meeting"));
+        assert_eq!(result[0].span.char_end - result[0].span.char_start, 144);
+    }
+
+    #[test]
+    fn test_snippets_are_ranked_and_do_not_overlap() {
+        let filler = "lorem ipsum ".repeat(10);
+        let text = format!("kayak {filler}kayak trip {filler}trip");
+        let options = SnippetOptions {
+            length: 40,
+            max_snippets: 3,
+            ..Default::default()
+        };
+        let result = snippets(&text, &["kayak", "trip"], &options);
+
+        assert_eq!(result.len(), 3);
+        // The window with both words ranks first
+        assert!(result[0].text.contains("kayak trip"));
+        for pair in result.windows(2) {
+            let (a, b) = (pair[0].span, pair[1].span);
+            assert!(a.end <= b.start || b.end <= a.start);
+        }
+
+        let options = SnippetOptions {
+            max_snippets: 1,
+            ..options
+        };
+        assert_eq!(snippets(&text, &["kayak", "trip"], &options).len(), 1);
+    }
+
+    #[test]
+    fn test_text_without_hits_snippets_its_start() {
+        let text = "a".repeat(200);
+        let result = snippets(&text, &["missing"], &SnippetOptions::default());
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].span.start, 0);
+        assert_eq!(result[0].hit_count, 0);
+        assert!(result[0].text.ends_with("..."));
+    }
+}
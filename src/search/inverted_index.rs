@@ -0,0 +1,465 @@
+//! Inverted index with BM25 scoring.
+//!
+//! Maps each normalized term to a postings list of the documents containing
+//! it. Query terms are matched exactly, by prefix, or fuzzily (Levenshtein)
+//! against the index vocabulary rather than against every document, and
+//! matching documents are scored with BM25 using per-field length
+//! normalization.
+
+use super::full_text_index::SearchableField;
+use std::collections::{BTreeMap, HashMap};
+
+/// Documents and queries are split into folded terms by the shared tokenizer.
+pub use crate::text::tokenize;
+
+/// BM25 term frequency saturation.
+const BM25_K1: f64 = 1.2;
+
+/// BM25 document length normalization.
+const BM25_B: f64 = 0.75;
+
+/// Maximum number of vocabulary terms a single query term may expand to by prefix.
+const MAX_PREFIX_EXPANSIONS: usize = 64;
+
+/// Maximum normalized edit distance for a fuzzy term match.
+const MAX_FUZZY_DISTANCE: f64 = 0.4;
+
+/// Identifier of a document within the index.
+pub type DocId = usize;
+
+/// One entry of a postings list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Posting {
+    /// Document containing the term
+    pub doc: DocId,
+
+    /// Number of occurrences of the term in the document
+    pub term_frequency: u32,
+}
+
+/// How a query term matched an indexed term.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TermMatch {
+    /// The indexed term equals the query term
+    Exact,
+    /// The indexed term starts with the query term
+    Prefix,
+    /// The indexed term is within edit distance; carries the similarity (0-1)
+    Fuzzy(f64),
+}
+
+impl TermMatch {
+    /// Weight applied to the BM25 contribution of a match of this kind.
+    pub fn weight(&self) -> f64 {
+        match self {
+            Self::Exact => 1.0,
+            Self::Prefix => 0.85,
+            Self::Fuzzy(similarity) => similarity * 0.8,
+        }
+    }
+}
+
+/// Scoring details for one document matching a query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentHit {
+    /// Boosted BM25 score
+    pub score: f64,
+
+    /// Number of distinct query terms that matched
+    pub matched_terms: usize,
+
+    /// Sum over query terms of the best match weight (0-1 each)
+    pub match_quality: f64,
+
+    /// Occurrences of matched terms in the document
+    pub matched_tokens: u32,
+
+    /// Indexed terms that matched, one per matched query term, used to
+    /// highlight hits
+    pub terms: Vec<String>,
+}
+
+/// Per-field document length statistics.
+#[derive(Debug, Clone, Copy, Default)]
+struct FieldStats {
+    total_length: u64,
+    documents: u64,
+}
+
+impl FieldStats {
+    fn average_length(&self) -> f64 {
+        if self.documents == 0 {
+            1.0
+        } else {
+            (self.total_length as f64 / self.documents as f64).max(1.0)
+        }
+    }
+}
+
+/// Inverted index over tokenized documents.
+#[derive(Debug, Default)]
+pub struct InvertedIndex {
+    /// Postings lists keyed by term, ordered for prefix lookups
+    postings: BTreeMap<String, Vec<Posting>>,
+
+    /// Token count and field of each document; `None` once removed
+    documents: Vec<Option<(u32, SearchableField)>>,
+
+    /// Number of documents not removed
+    live_documents: usize,
+
+    /// Length statistics per field
+    field_stats: HashMap<SearchableField, FieldStats>,
+}
+
+impl InvertedIndex {
+    /// Create an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tokenize and index a document, returning its ID.
+    pub fn add_document(&mut self, field: SearchableField, text: &str) -> DocId {
+        self.add_terms(field, &tokenize(text))
+    }
+
+    /// Index a document given as analyzed terms, returning its ID.
+    ///
+    /// IDs are assigned sequentially starting from 0 and are never reused,
+    /// so removing a document leaves the IDs of the others unchanged.
+    pub fn add_terms(&mut self, field: SearchableField, tokens: &[String]) -> DocId {
+        let doc = self.documents.len();
+
+        let mut frequencies: HashMap<String, u32> = HashMap::new();
+        for token in tokens {
+            *frequencies.entry(token.clone()).or_default() += 1;
+        }
+        for (term, term_frequency) in frequencies {
+            self.postings.entry(term).or_default().push(Posting {
+                doc,
+                term_frequency,
+            });
+        }
+
+        let stats = self.field_stats.entry(field).or_default();
+        stats.total_length += tokens.len() as u64;
+        stats.documents += 1;
+        self.documents.push(Some((tokens.len() as u32, field)));
+        self.live_documents += 1;
+
+        doc
+    }
+
+    /// Remove a document, given the text it was indexed with.
+    ///
+    /// Returns `false` if the document does not exist or was already removed.
+    pub fn remove_document(&mut self, doc: DocId, text: &str) -> bool {
+        self.remove_terms(doc, tokenize(text))
+    }
+
+    /// Remove a document, given the terms it was indexed with.
+    ///
+    /// Returns `false` if the document does not exist or was already removed.
+    pub fn remove_terms(&mut self, doc: DocId, mut terms: Vec<String>) -> bool {
+        let Some((length, field)) = self.documents.get_mut(doc).and_then(Option::take) else {
+            return false;
+        };
+
+        terms.sort_unstable();
+        terms.dedup();
+        for term in terms {
+            if let Some(postings) = self.postings.get_mut(&term) {
+                postings.retain(|posting| posting.doc != doc);
+                if postings.is_empty() {
+                    self.postings.remove(&term);
+                }
+            }
+        }
+
+        if let Some(stats) = self.field_stats.get_mut(&field) {
+            stats.total_length -= length as u64;
+            stats.documents -= 1;
+        }
+        self.live_documents -= 1;
+
+        true
+    }
+
+    /// Number of indexed documents.
+    pub fn len(&self) -> usize {
+        self.live_documents
+    }
+
+    /// Whether the index has no documents.
+    pub fn is_empty(&self) -> bool {
+        self.live_documents == 0
+    }
+
+    /// Number of distinct terms.
+    pub fn vocabulary_size(&self) -> usize {
+        self.postings.len()
+    }
+
+    /// Token count of a document.
+    pub fn document_length(&self, doc: DocId) -> u32 {
+        self.documents
+            .get(doc)
+            .copied()
+            .flatten()
+            .map(|(len, _)| len)
+            .unwrap_or(0)
+    }
+
+    /// Remove all documents.
+    pub fn clear(&mut self) {
+        self.postings.clear();
+        self.documents.clear();
+        self.live_documents = 0;
+        self.field_stats.clear();
+    }
+
+    /// Find the indexed terms a query term matches.
+    ///
+    /// Exact matches are always included. Terms of two or more characters
+    /// also match indexed terms they prefix, and terms of three or more
+    /// characters match indexed terms within a normalized edit distance of
+    /// 0.4.
+    pub fn expand_term(&self, term: &str) -> Vec<(&str, TermMatch)> {
+        let mut expansions = Vec::new();
+
+        if let Some((indexed, _)) = self.postings.get_key_value(term) {
+            expansions.push((indexed.as_str(), TermMatch::Exact));
+        }
+
+        let term_len = term.chars().count();
+        if term_len >= 2 {
+            expansions.extend(
+                self.postings
+                    .range::<str, _>((std::ops::Bound::Excluded(term), std::ops::Bound::Unbounded))
+                    .take_while(|(indexed, _)| indexed.starts_with(term))
+                    .take(MAX_PREFIX_EXPANSIONS)
+                    .map(|(indexed, _)| (indexed.as_str(), TermMatch::Prefix)),
+            );
+        }
+
+        if term_len >= 3 {
+            for indexed in self.postings.keys() {
+                if indexed.starts_with(term) {
+                    continue;
+                }
+                let indexed_len = indexed.chars().count();
+                let max_len = term_len.max(indexed_len);
+                // Length difference is a lower bound on the edit distance
+                if term_len.abs_diff(indexed_len) as f64 / max_len as f64 > MAX_FUZZY_DISTANCE {
+                    continue;
+                }
+                let distance = levenshtein_distance(term, indexed);
+                let normalized = distance as f64 / max_len as f64;
+                if normalized <= MAX_FUZZY_DISTANCE {
+                    expansions.push((indexed.as_str(), TermMatch::Fuzzy(1.0 - normalized)));
+                }
+            }
+        }
+
+        expansions
+    }
+
+    /// Score all documents matching any of the query terms.
+    ///
+    /// For each query term only its best-scoring expansion counts towards a
+    /// document, so a short prefix matching many terms in one document does
+    /// not dominate the score.
+    pub fn score(&self, query_terms: &[String]) -> HashMap<DocId, DocumentHit> {
+        let total_docs = self.live_documents as f64;
+        let mut hits: HashMap<DocId, DocumentHit> = HashMap::new();
+
+        for query_term in query_terms {
+            // Best (score, weight, term frequency, term) per document for this query term
+            let mut best: HashMap<DocId, (f64, f64, u32, &str)> = HashMap::new();
+
+            for (indexed, term_match) in self.expand_term(query_term) {
+                let postings = &self.postings[indexed];
+                let df = postings.len() as f64;
+                let idf = (1.0 + (total_docs - df + 0.5) / (df + 0.5)).ln();
+                let weight = term_match.weight();
+
+                for posting in postings {
+                    let Some((length, field)) = self.documents[posting.doc] else {
+                        continue;
+                    };
+                    let average = self.field_stats[&field].average_length();
+                    let tf = posting.term_frequency as f64;
+                    let norm = 1.0 - BM25_B + BM25_B * length as f64 / average;
+                    let score =
+                        field.boost() * idf * weight * tf * (BM25_K1 + 1.0) / (tf + BM25_K1 * norm);
+
+                    let entry = best.entry(posting.doc).or_insert((0.0, 0.0, 0, indexed));
+                    if score > entry.0 {
+                        *entry = (score, weight, posting.term_frequency, indexed);
+                    }
+                }
+            }
+
+            for (doc, (score, weight, term_frequency, indexed)) in best {
+                let hit = hits.entry(doc).or_insert_with(|| DocumentHit {
+                    score: 0.0,
+                    matched_terms: 0,
+                    match_quality: 0.0,
+                    matched_tokens: 0,
+                    terms: Vec::new(),
+                });
+                hit.score += score;
+                hit.matched_terms += 1;
+                hit.match_quality += weight;
+                hit.matched_tokens += term_frequency;
+                hit.terms.push(indexed.to_string());
+            }
+        }
+
+        hits
+    }
+}
+
+/// Calculate Levenshtein distance between two strings.
+pub(crate) fn levenshtein_distance(s1: &str, s2: &str) -> usize {
+    let s2_chars: Vec<char> = s2.chars().collect();
+    if s1.is_empty() {
+        return s2_chars.len();
+    }
+
+    // Single-row dynamic programming
+    let mut row: Vec<usize> = (0..=s2_chars.len()).collect();
+    for (i, c1) in s1.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, c2) in s2_chars.iter().enumerate() {
+            let above = row[j + 1];
+            let cost = if c1 == *c2 { 0 } else { 1 };
+            row[j + 1] = (above + 1).min(row[j] + 1).min(diagonal + cost);
+            diagonal = above;
+        }
+    }
+
+    row[s2_chars.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms(query: &str) -> Vec<String> {
+        tokenize(query)
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(tokenize("John Doe"), vec!["john", "doe"]);
+        assert_eq!(
+            tokenize("john.doe@example.com"),
+            vec!["john", "doe", "example", "com"]
+        );
+        assert_eq!(
+            tokenize("+1 (555) 123-4567"),
+            vec!["1", "555", "123", "4567"]
+        );
+        assert!(tokenize("  --  ").is_empty());
+    }
+
+    #[test]
+    fn test_postings_and_lengths() {
+        let mut index = InvertedIndex::new();
+        let a = index.add_document(SearchableField::Note, "coffee and more coffee");
+        let b = index.add_document(SearchableField::Note, "tea");
+
+        assert_eq!((a, b), (0, 1));
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.document_length(a), 4);
+        assert_eq!(
+            index.postings["coffee"],
+            vec![Posting {
+                doc: 0,
+                term_frequency: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn test_remove_document() {
+        let mut index = InvertedIndex::new();
+        let a = index.add_document(SearchableField::Note, "coffee with jane");
+        let b = index.add_document(SearchableField::Note, "coffee");
+
+        assert!(index.remove_document(a, "coffee with jane"));
+        assert!(!index.remove_document(a, "coffee with jane"));
+
+        assert_eq!(index.len(), 1);
+        assert_eq!(index.document_length(a), 0);
+        assert!(!index.postings.contains_key("jane"));
+        assert_eq!(index.postings["coffee"].len(), 1);
+        assert_eq!(index.field_stats[&SearchableField::Note].total_length, 1);
+
+        let hits = index.score(&terms("coffee"));
+        assert_eq!(hits.keys().copied().collect::<Vec<_>>(), vec![b]);
+
+        // IDs are not reused
+        assert_eq!(index.add_document(SearchableField::Note, "tea"), 2);
+    }
+
+    #[test]
+    fn test_expand_term() {
+        let mut index = InvertedIndex::new();
+        index.add_document(SearchableField::Company, "Software Softbank Hardware");
+
+        let expansions = index.expand_term("soft");
+        assert!(expansions.contains(&("software", TermMatch::Prefix)));
+        assert!(expansions.contains(&("softbank", TermMatch::Prefix)));
+
+        let expansions = index.expand_term("sofware");
+        assert!(expansions
+            .iter()
+            .any(|(term, m)| *term == "software" && matches!(m, TermMatch::Fuzzy(_))));
+
+        assert_eq!(
+            index.expand_term("hardware"),
+            vec![("hardware", TermMatch::Exact)]
+        );
+    }
+
+    #[test]
+    fn test_score_prefers_rare_terms_and_short_fields() {
+        let mut index = InvertedIndex::new();
+        let short = index.add_document(SearchableField::Note, "acme");
+        let long = index.add_document(
+            SearchableField::Note,
+            "acme is a company we met at the fair",
+        );
+        let common = index.add_document(SearchableField::Note, "the meeting");
+
+        let hits = index.score(&terms("acme"));
+        assert!(hits[&short].score > hits[&long].score);
+        assert!(!hits.contains_key(&common));
+
+        let hits = index.score(&terms("acme the"));
+        assert_eq!(hits[&long].matched_terms, 2);
+        assert_eq!(hits[&common].matched_terms, 1);
+    }
+
+    #[test]
+    fn test_score_applies_field_boost() {
+        let mut index = InvertedIndex::new();
+        let name = index.add_document(SearchableField::Name, "Jordan");
+        let note = index.add_document(SearchableField::Note, "Jordan");
+
+        let hits = index.score(&terms("jordan"));
+        assert!(hits[&name].score > hits[&note].score);
+    }
+
+    #[test]
+    fn test_levenshtein_distance() {
+        assert_eq!(levenshtein_distance("kitten", "sitting"), 3);
+        assert_eq!(levenshtein_distance("saturday", "sunday"), 3);
+        assert_eq!(levenshtein_distance("", "test"), 4);
+        assert_eq!(levenshtein_distance("test", ""), 4);
+        assert_eq!(levenshtein_distance("same", "same"), 0);
+    }
+}
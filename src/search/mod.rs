@@ -1,10 +1,29 @@
 //! Full-text search utilities for contacts, notes, and reminders.
 //!
 //! This module provides fuzzy full-text search across all contact-related data,
-//! with snippet generation and result aggregation.
+//! with highlighted snippets and result aggregation, backed by an inverted index
+//! with BM25 ranking, language-aware text analysis, a field-scoped query
+//! syntax, facet counts, TF-IDF contact similarity, "did you mean" spelling
+//! suggestions, and on-disk snapshots for warm starts.
 
+pub mod analyzer;
+pub mod facets;
 pub mod full_text_index;
+pub mod highlight;
+pub mod inverted_index;
+pub mod query;
+pub mod similarity;
+pub mod snapshot;
+pub mod suggest;
 
+pub use analyzer::{Analyzer, Language, SynonymMap};
+pub use facets::{FacetCount, FacetFilter, SearchFacets};
 pub use full_text_index::{
-    FullTextSearchIndex, MatchContext, SearchResult, SearchableDocument, SearchableField,
+    FullTextSearchIndex, MatchContext, SearchFilter, SearchResult, SearchableDocument,
+    SearchableField,
 };
+pub use highlight::{HighlightMarkers, Snippet, SnippetOptions, TextSpan};
+pub use query::{QueryNode, SearchQuery};
+pub use similarity::{SharedTerm, SimilarContact, TermVectors};
+pub use snapshot::{IndexSnapshot, IndexSnapshotStore, SNAPSHOT_SCHEMA_VERSION};
+pub use suggest::{SpellingDictionary, SpellingSuggestion};
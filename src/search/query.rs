@@ -0,0 +1,576 @@
+//! Query syntax for full-text search.
+//!
+//! Queries are whitespace-separated terms that must all match:
+//!
+//! - `company:acme` restricts a term to one field (see [`SearchableField::from_name`])
+//! - `"series A"` matches the words as an exact phrase
+//! - `-reminder:done` or `NOT reminder:done` excludes contacts matching the term
+//! - `acme OR globex` matches either side; `AND` may be written explicitly
+//! - parentheses group terms, and `company:(acme OR globex)` scopes a group
+//!
+//! A query without any of this syntax is a plain keyword query and keeps the
+//! lenient keyword ranking of [`FullTextSearchIndex::search`]. A `word:`
+//! prefix that is not a field name only counts as syntax (and is an error)
+//! in queries that use other syntax, so text like `Re: lunch` or
+//! `mailto:ana@acme.com` is searched for as written.
+//!
+//! [`FullTextSearchIndex::search`]: super::FullTextSearchIndex::search
+
+use super::full_text_index::SearchableField;
+use super::inverted_index::tokenize;
+use crate::error::SearchError;
+
+/// A node of a parsed search query.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryNode {
+    /// A single term, optionally restricted to one field
+    Term {
+        field: Option<SearchableField>,
+        term: String,
+    },
+    /// Terms that must appear consecutively, optionally restricted to one field
+    Phrase {
+        field: Option<SearchableField>,
+        terms: Vec<String>,
+    },
+    /// Contacts that do not match the inner query
+    Not(Box<QueryNode>),
+    /// Contacts matching every child
+    And(Vec<QueryNode>),
+    /// Contacts matching any child
+    Or(Vec<QueryNode>),
+}
+
+impl QueryNode {
+    /// Whether the node can match a contact on its own, rather than only
+    /// excluding contacts.
+    fn has_positive_term(&self) -> bool {
+        match self {
+            Self::Term { .. } | Self::Phrase { .. } => true,
+            Self::Not(_) => false,
+            Self::And(children) => children.iter().any(Self::has_positive_term),
+            Self::Or(children) => children.iter().all(Self::has_positive_term),
+        }
+    }
+
+    /// Collect the terms of all leaves, in query order.
+    fn collect_terms(&self, out: &mut Vec<String>) {
+        match self {
+            Self::Term { term, .. } => out.push(term.clone()),
+            Self::Phrase { terms, .. } => out.extend(terms.iter().cloned()),
+            Self::Not(inner) => inner.collect_terms(out),
+            Self::And(children) | Self::Or(children) => {
+                for child in children {
+                    child.collect_terms(out);
+                }
+            }
+        }
+    }
+}
+
+/// A parsed full-text search query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchQuery {
+    text: String,
+    root: QueryNode,
+
+    /// Whether the query text used no syntax at all
+    plain: bool,
+}
+
+impl SearchQuery {
+    /// Parse a query string.
+    ///
+    /// # Errors
+    /// Returns [`SearchError::QueryParse`] with the 1-based column of the
+    /// problem for unknown fields in queries that use other syntax,
+    /// unterminated quotes, unbalanced
+    /// parentheses, missing operands and queries that only exclude.
+    pub fn parse(input: &str) -> Result<Self, SearchError> {
+        let tokens = lex(input)?;
+        let plain = tokens
+            .iter()
+            .all(|token| matches!(token.kind, TokenKind::Word(_)));
+
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            end_column: input.chars().count() + 1,
+        };
+        let root = parser.parse_query()?;
+
+        if !root.has_positive_term() {
+            return Err(parse_error(
+                1,
+                "query needs at least one term that is not excluded",
+            ));
+        }
+
+        Ok(Self {
+            text: input.to_string(),
+            root,
+            plain,
+        })
+    }
+
+    /// The query text as entered.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The root node of the query.
+    pub fn root(&self) -> &QueryNode {
+        &self.root
+    }
+
+    /// The query terms, if the query is a plain keyword query without
+    /// fields, phrases, operators or grouping.
+    pub fn plain_terms(&self) -> Option<Vec<String>> {
+        if !self.plain {
+            return None;
+        }
+        let mut terms = Vec::new();
+        self.root.collect_terms(&mut terms);
+        Some(terms)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Word(String),
+    Quoted(String),
+    Field(SearchableField),
+    /// A word with a prefix that looks like a field but is not one
+    UnknownField(String),
+    Minus,
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    /// 1-based character column where the token starts
+    column: usize,
+}
+
+fn parse_error(column: usize, message: impl Into<String>) -> SearchError {
+    SearchError::QueryParse {
+        column,
+        message: message.into(),
+    }
+}
+
+fn lex(input: &str) -> Result<Vec<Token>, SearchError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let column = i + 1;
+        match chars[i] {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token {
+                    kind: TokenKind::LParen,
+                    column,
+                });
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token {
+                    kind: TokenKind::RParen,
+                    column,
+                });
+                i += 1;
+            }
+            '"' => {
+                let len = chars[i + 1..]
+                    .iter()
+                    .position(|&c| c == '"')
+                    .ok_or_else(|| parse_error(column, "unterminated quote"))?;
+                let text: String = chars[i + 1..i + 1 + len].iter().collect();
+                if tokenize(&text).is_empty() {
+                    return Err(parse_error(column, "empty phrase"));
+                }
+                tokens.push(Token {
+                    kind: TokenKind::Quoted(text),
+                    column,
+                });
+                i += len + 2;
+            }
+            '-' => {
+                if chars.get(i + 1).is_none_or(|c| c.is_whitespace()) {
+                    return Err(parse_error(
+                        column,
+                        "'-' must be directly followed by the term to exclude",
+                    ));
+                }
+                tokens.push(Token {
+                    kind: TokenKind::Minus,
+                    column,
+                });
+                i += 1;
+            }
+            _ => {
+                let start = i;
+                while i < chars.len()
+                    && !chars[i].is_whitespace()
+                    && !matches!(chars[i], '(' | ')' | '"')
+                {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                lex_word(word, column, &mut tokens);
+            }
+        }
+    }
+
+    // An unknown field is most likely a typo in a query that uses syntax,
+    // but in plain text the colon is just punctuation
+    let uses_syntax = tokens
+        .iter()
+        .any(|token| !matches!(token.kind, TokenKind::Word(_) | TokenKind::UnknownField(_)));
+    for token in &mut tokens {
+        if let TokenKind::UnknownField(word) = &mut token.kind {
+            if uses_syntax {
+                let prefix = word.split_once(':').map_or(word.as_str(), |(p, _)| p);
+                return Err(parse_error(
+                    token.column,
+                    format!(
+                        "unknown field '{}' (expected one of: {})",
+                        prefix,
+                        SearchableField::name_list()
+                    ),
+                ));
+            }
+            token.kind = TokenKind::Word(std::mem::take(word));
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Classify a bare word as an operator, a field prefix (plus value) or a term.
+fn lex_word(word: String, column: usize, tokens: &mut Vec<Token>) {
+    let kind = match word.as_str() {
+        "AND" => Some(TokenKind::And),
+        "OR" => Some(TokenKind::Or),
+        "NOT" => Some(TokenKind::Not),
+        _ => None,
+    };
+    if let Some(kind) = kind {
+        tokens.push(Token { kind, column });
+        return;
+    }
+
+    if let Some((prefix, rest)) = word.split_once(':') {
+        // Only alphabetic prefixes are fields, so times ("10:30") and URLs
+        // ("https://...") stay plain terms.
+        let is_field = !prefix.is_empty()
+            && prefix.chars().all(|c| c.is_ascii_alphabetic() || c == '_')
+            && !rest.starts_with("//");
+
+        if is_field {
+            let Some(field) = SearchableField::from_name(prefix) else {
+                tokens.push(Token {
+                    kind: TokenKind::UnknownField(word),
+                    column,
+                });
+                return;
+            };
+            tokens.push(Token {
+                kind: TokenKind::Field(field),
+                column,
+            });
+            if !rest.is_empty() {
+                tokens.push(Token {
+                    kind: TokenKind::Word(rest.to_string()),
+                    column: column + prefix.chars().count() + 1,
+                });
+            }
+            return;
+        }
+    }
+
+    // Words without searchable characters ("&", "—") are noise, not errors
+    if !tokenize(&word).is_empty() {
+        tokens.push(Token {
+            kind: TokenKind::Word(word),
+            column,
+        });
+    }
+}
+
+/// Recursive descent parser. `OR` binds looser than `AND`, which binds
+/// looser than negation.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    end_column: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&TokenKind> {
+        self.tokens.get(self.pos).map(|token| &token.kind)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_query(&mut self) -> Result<QueryNode, SearchError> {
+        if self.tokens.is_empty() {
+            return Err(parse_error(1, "query has no searchable terms"));
+        }
+
+        let root = self.parse_or(None)?;
+        if let Some(token) = self.tokens.get(self.pos) {
+            return Err(parse_error(token.column, "unexpected ')'"));
+        }
+        Ok(root)
+    }
+
+    fn parse_or(&mut self, field: Option<SearchableField>) -> Result<QueryNode, SearchError> {
+        let mut children = vec![self.parse_and(field)?];
+        while self.peek() == Some(&TokenKind::Or) {
+            self.pos += 1;
+            children.push(self.parse_and(field)?);
+        }
+
+        Ok(if children.len() == 1 {
+            children.remove(0)
+        } else {
+            QueryNode::Or(children)
+        })
+    }
+
+    fn parse_and(&mut self, field: Option<SearchableField>) -> Result<QueryNode, SearchError> {
+        let mut children = vec![self.parse_unary(field)?];
+        loop {
+            match self.peek() {
+                None | Some(TokenKind::Or) | Some(TokenKind::RParen) => break,
+                Some(TokenKind::And) => {
+                    self.pos += 1;
+                    children.push(self.parse_unary(field)?);
+                }
+                Some(_) => children.push(self.parse_unary(field)?),
+            }
+        }
+
+        Ok(if children.len() == 1 {
+            children.remove(0)
+        } else {
+            QueryNode::And(children)
+        })
+    }
+
+    fn parse_unary(&mut self, field: Option<SearchableField>) -> Result<QueryNode, SearchError> {
+        if matches!(self.peek(), Some(TokenKind::Minus) | Some(TokenKind::Not)) {
+            self.pos += 1;
+            return Ok(QueryNode::Not(Box::new(self.parse_unary(field)?)));
+        }
+        self.parse_primary(field)
+    }
+
+    fn parse_primary(&mut self, field: Option<SearchableField>) -> Result<QueryNode, SearchError> {
+        let Some(token) = self.next() else {
+            return Err(parse_error(self.end_column, "expected a term"));
+        };
+
+        match token.kind {
+            TokenKind::Word(word) => {
+                let mut terms = tokenize(&word);
+                if terms.is_empty() {
+                    return Err(parse_error(
+                        token.column,
+                        format!("'{}' has no searchable text", word),
+                    ));
+                }
+                Ok(if terms.len() == 1 {
+                    QueryNode::Term {
+                        field,
+                        term: terms.remove(0),
+                    }
+                } else {
+                    QueryNode::Phrase { field, terms }
+                })
+            }
+            TokenKind::Quoted(text) => Ok(QueryNode::Phrase {
+                field,
+                terms: tokenize(&text),
+            }),
+            TokenKind::Field(inner) => {
+                if let Some(outer) = field {
+                    return Err(parse_error(
+                        token.column,
+                        format!(
+                            "field '{}' cannot be used inside '{}:(...)'",
+                            inner.display_name(),
+                            outer.display_name()
+                        ),
+                    ));
+                }
+                match self.peek() {
+                    Some(TokenKind::Word(_) | TokenKind::Quoted(_) | TokenKind::LParen) => {
+                        self.parse_primary(Some(inner))
+                    }
+                    _ => Err(parse_error(
+                        token.column,
+                        format!("field '{}' needs a value", inner.display_name()),
+                    )),
+                }
+            }
+            TokenKind::LParen => {
+                let node = self.parse_or(field)?;
+                match self.next() {
+                    Some(Token {
+                        kind: TokenKind::RParen,
+                        ..
+                    }) => Ok(node),
+                    _ => Err(parse_error(token.column, "unclosed '('")),
+                }
+            }
+            TokenKind::RParen => Err(parse_error(token.column, "unexpected ')'")),
+            TokenKind::And => Err(parse_error(token.column, "'AND' needs a term before it")),
+            TokenKind::Or => Err(parse_error(token.column, "'OR' needs a term before it")),
+            TokenKind::Minus | TokenKind::Not => {
+                unreachable!("negation is handled by parse_unary")
+            }
+            TokenKind::UnknownField(_) => unreachable!("unknown fields are resolved by lex"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(field: Option<SearchableField>, term: &str) -> QueryNode {
+        QueryNode::Term {
+            field,
+            term: term.to_string(),
+        }
+    }
+
+    fn parse_err(input: &str) -> (usize, String) {
+        match SearchQuery::parse(input) {
+            Err(SearchError::QueryParse { column, message }) => (column, message),
+            other => panic!("expected parse error for {:?}, got {:?}", input, other),
+        }
+    }
+
+    #[test]
+    fn test_plain_query() {
+        let query = SearchQuery::parse("Sarah Johnson").unwrap();
+        assert_eq!(
+            query.plain_terms(),
+            Some(vec!["sarah".to_string(), "johnson".to_string()])
+        );
+        assert_eq!(
+            query.root(),
+            &QueryNode::And(vec![term(None, "sarah"), term(None, "johnson")])
+        );
+    }
+
+    #[test]
+    fn test_field_phrase_and_negation() {
+        let query =
+            SearchQuery::parse(r#"company:acme tag:investor note:"series A" -reminder:done"#)
+                .unwrap();
+        assert_eq!(query.plain_terms(), None);
+        assert_eq!(
+            query.root(),
+            &QueryNode::And(vec![
+                term(Some(SearchableField::Company), "acme"),
+                term(Some(SearchableField::Tag), "investor"),
+                QueryNode::Phrase {
+                    field: Some(SearchableField::Note),
+                    terms: vec!["series".to_string(), "a".to_string()],
+                },
+                QueryNode::Not(Box::new(term(Some(SearchableField::Reminder), "done"))),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_or_binds_looser_than_and() {
+        let query = SearchQuery::parse("acme OR globex AND sarah").unwrap();
+        assert_eq!(
+            query.root(),
+            &QueryNode::Or(vec![
+                term(None, "acme"),
+                QueryNode::And(vec![term(None, "globex"), term(None, "sarah")]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_field_scopes_group() {
+        let query = SearchQuery::parse("company:(acme OR globex) NOT tag:former").unwrap();
+        let company = Some(SearchableField::Company);
+        assert_eq!(
+            query.root(),
+            &QueryNode::And(vec![
+                QueryNode::Or(vec![term(company, "acme"), term(company, "globex")]),
+                QueryNode::Not(Box::new(term(Some(SearchableField::Tag), "former"))),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_non_field_colons_are_terms() {
+        let query = SearchQuery::parse("10:30 https://acme.com").unwrap();
+        assert!(query.plain_terms().is_some());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse_err(r#"note:"series A"#),
+            (6, "unterminated quote".into())
+        );
+        assert_eq!(parse_err("(acme OR globex").0, 1);
+        assert_eq!(parse_err("acme)"), (5, "unexpected ')'".into()));
+        assert_eq!(parse_err("acme OR"), (8, "expected a term".into()));
+        assert_eq!(parse_err("OR acme").1, "'OR' needs a term before it");
+        assert_eq!(
+            parse_err("company: "),
+            (1, "field 'company' needs a value".into())
+        );
+        assert_eq!(parse_err("acme - globex").0, 6);
+        assert_eq!(parse_err(r#""""#).1, "empty phrase");
+        assert_eq!(parse_err("&&").1, "query has no searchable terms");
+        assert_eq!(
+            parse_err("-tag:former").1,
+            "query needs at least one term that is not excluded"
+        );
+    }
+
+    #[test]
+    fn test_unknown_prefix_in_plain_text_is_a_term() {
+        let query = SearchQuery::parse("Re: lunch").unwrap();
+        assert_eq!(
+            query.plain_terms(),
+            Some(vec!["re".to_string(), "lunch".to_string()])
+        );
+
+        for text in ["mailto:ana@acme.com", "todo: call Ana", "acme city:berlin"] {
+            let query = SearchQuery::parse(text).unwrap();
+            assert!(query.plain_terms().is_some(), "{text}");
+        }
+    }
+
+    #[test]
+    fn test_unknown_field_lists_valid_fields() {
+        let (column, message) = parse_err("company:acme city:berlin");
+        assert_eq!(column, 14);
+        assert!(message.contains("unknown field 'city'"));
+        assert!(message.contains("job_title"));
+    }
+}
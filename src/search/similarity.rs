@@ -0,0 +1,297 @@
+//! Contact similarity from TF-IDF term vectors.
+//!
+//! Each contact is represented by the analyzed terms of the fields that
+//! describe it (company, title, tags, description, location, education and
+//! its own notes field) and of its notes and reminders. Terms are weighted by
+//! TF-IDF across contacts, and contacts are compared by the cosine of their
+//! vectors. The terms contributing most to a similarity explain it.
+
+use super::full_text_index::{FullTextSearchIndex, SearchableField};
+use crate::text::fold;
+use std::collections::HashMap;
+
+/// Default number of similar contacts returned.
+pub const DEFAULT_SIMILAR_CONTACTS: usize = 10;
+
+/// Number of shared terms reported per similar contact.
+pub const MAX_SHARED_TERMS: usize = 5;
+
+/// A term two contacts have in common.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedTerm {
+    /// The term in the shortest form it was written in, folded
+    pub term: String,
+
+    /// Contribution of the term to the similarity
+    pub weight: f64,
+}
+
+/// A contact similar to another one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimilarContact {
+    /// Contact ID
+    pub contact_id: String,
+
+    /// Contact name for display
+    pub contact_name: String,
+
+    /// Cosine similarity of the two contacts' term vectors (0-1)
+    pub similarity: f64,
+
+    /// Terms contributing most to the similarity, highest first
+    pub shared_terms: Vec<SharedTerm>,
+}
+
+/// Whether a field describes what a contact does or talks about.
+///
+/// Names, contact details, websites and birthdays identify a contact
+/// rather than describe it, so they are left out of its vector.
+fn describes_contact(field: SearchableField) -> bool {
+    matches!(
+        field,
+        SearchableField::Company
+            | SearchableField::JobTitle
+            | SearchableField::Tag
+            | SearchableField::Description
+            | SearchableField::Location
+            | SearchableField::Education
+            | SearchableField::ContactNotes
+            | SearchableField::Note
+            | SearchableField::Reminder
+    )
+}
+
+/// A contact's unit-length TF-IDF vector.
+#[derive(Debug)]
+struct ContactVector {
+    name: String,
+    weights: HashMap<String, f64>,
+}
+
+/// TF-IDF term vectors of all contacts in an index.
+#[derive(Debug)]
+pub struct TermVectors {
+    vectors: HashMap<String, ContactVector>,
+
+    /// Readable form of each term: the shortest way it was written, folded
+    labels: HashMap<String, String>,
+}
+
+impl TermVectors {
+    /// Build the vectors of every contact in `index`.
+    ///
+    /// Term frequencies are damped logarithmically, so a long note does not
+    /// outweigh everything else, and inverse document frequency is counted
+    /// per contact and smoothed.
+    pub fn build(index: &FullTextSearchIndex) -> Self {
+        let analyzer = index.analyzer();
+        let mut counts: HashMap<&str, (&str, HashMap<String, u32>)> = HashMap::new();
+        let mut labels: HashMap<String, String> = HashMap::new();
+
+        for doc in index
+            .documents()
+            .filter(|d| describes_contact(d.field_type))
+        {
+            let (_, terms) = counts
+                .entry(doc.contact_id.as_str())
+                .or_insert_with(|| (doc.contact_name.as_str(), HashMap::new()));
+            for token in analyzer.analyze_spans(&doc.content) {
+                let written = fold(&doc.content[token.span]);
+                labels
+                    .entry(token.term.clone())
+                    .and_modify(|label| {
+                        if (written.len(), &written) < (label.len(), label) {
+                            *label = written.clone();
+                        }
+                    })
+                    .or_insert(written);
+                *terms.entry(token.term).or_default() += 1;
+            }
+        }
+
+        let mut contact_frequency: HashMap<&str, usize> = HashMap::new();
+        for (_, terms) in counts.values() {
+            for term in terms.keys() {
+                *contact_frequency.entry(term.as_str()).or_default() += 1;
+            }
+        }
+        let contacts = counts.len() as f64;
+        let idf = |term: &str| {
+            let frequency = contact_frequency.get(term).copied().unwrap_or(0) as f64;
+            ((1.0 + contacts) / (1.0 + frequency)).ln() + 1.0
+        };
+
+        let vectors = counts
+            .iter()
+            .map(|(&contact_id, (name, terms))| {
+                let mut weights: HashMap<String, f64> = terms
+                    .iter()
+                    .map(|(term, &count)| (term.clone(), (1.0 + f64::from(count).ln()) * idf(term)))
+                    .collect();
+                let norm = weights.values().map(|w| w * w).sum::<f64>().sqrt();
+                if norm > 0.0 {
+                    weights.values_mut().for_each(|w| *w /= norm);
+                }
+                let vector = ContactVector {
+                    name: name.to_string(),
+                    weights,
+                };
+                (contact_id.to_string(), vector)
+            })
+            .collect();
+
+        Self { vectors, labels }
+    }
+
+    /// Contacts most similar to `contact_id`, best first.
+    ///
+    /// Contacts sharing no terms are left out. Returns an empty list for a
+    /// contact with no describing text.
+    pub fn similar_to(&self, contact_id: &str, k: usize) -> Vec<SimilarContact> {
+        let Some(target) = self.vectors.get(contact_id) else {
+            return Vec::new();
+        };
+
+        let mut similar: Vec<SimilarContact> = self
+            .vectors
+            .iter()
+            .filter(|(id, _)| id.as_str() != contact_id)
+            .filter_map(|(id, other)| {
+                let mut shared: Vec<(&str, f64)> = target
+                    .weights
+                    .iter()
+                    .filter_map(|(term, weight)| {
+                        other
+                            .weights
+                            .get(term)
+                            .map(|other_weight| (term.as_str(), weight * other_weight))
+                    })
+                    .collect();
+                if shared.is_empty() {
+                    return None;
+                }
+
+                let similarity = shared.iter().map(|(_, w)| w).sum::<f64>().min(1.0);
+                shared.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+                shared.truncate(MAX_SHARED_TERMS);
+
+                Some(SimilarContact {
+                    contact_id: id.clone(),
+                    contact_name: other.name.clone(),
+                    similarity,
+                    shared_terms: shared
+                        .into_iter()
+                        .map(|(term, weight)| SharedTerm {
+                            term: self.label(term),
+                            weight,
+                        })
+                        .collect(),
+                })
+            })
+            .collect();
+
+        similar.sort_by(|a, b| {
+            b.similarity
+                .total_cmp(&a.similarity)
+                .then_with(|| a.contact_id.cmp(&b.contact_id))
+        });
+        similar.truncate(k);
+        similar
+    }
+
+    fn label(&self, term: &str) -> String {
+        self.labels
+            .get(term)
+            .cloned()
+            .unwrap_or_else(|| term.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Contact, Note};
+
+    fn contact(id: &str, name: &str, company: &str, note: &str) -> (Contact, Vec<Note>) {
+        let mut contact = Contact::new(id.to_string(), name.to_string());
+        contact.company = Some(company.to_string());
+        let notes = vec![Note {
+            id: format!("n{id}"),
+            contact_id: id.to_string(),
+            content: note.to_string(),
+            ..Default::default()
+        }];
+        (contact, notes)
+    }
+
+    fn index() -> FullTextSearchIndex {
+        let mut index = FullTextSearchIndex::new();
+        for (contact, notes) in [
+            contact(
+                "1",
+                "Ana Lopez",
+                "Northwind Ventures",
+                "Seed investor in climate startups",
+            ),
+            contact(
+                "2",
+                "Ben Ortiz",
+                "Greenleaf Capital",
+                "Seed investor in climate and energy startups",
+            ),
+            contact("3", "Cy Young", "Bakery Co", "Makes sourdough bread"),
+            contact("4", "Ana Smith", "Northwind Ventures", "Plays chess"),
+        ] {
+            index.index_contact(&contact, &notes, &[]);
+        }
+        index
+    }
+
+    #[test]
+    fn test_ranks_contacts_by_shared_topics() {
+        let vectors = TermVectors::build(&index());
+        let similar = vectors.similar_to("1", 10);
+
+        let ids: Vec<&str> = similar.iter().map(|s| s.contact_id.as_str()).collect();
+        assert_eq!(ids, vec!["2", "4"]);
+        assert!(similar[0].similarity > similar[1].similarity);
+        assert!(similar
+            .iter()
+            .all(|s| s.similarity > 0.0 && s.similarity <= 1.0));
+        assert_eq!(similar[0].contact_name, "Ben Ortiz");
+    }
+
+    #[test]
+    fn test_explains_with_shared_terms() {
+        let vectors = TermVectors::build(&index());
+        let similar = vectors.similar_to("1", 1);
+
+        let terms: Vec<&str> = similar[0]
+            .shared_terms
+            .iter()
+            .map(|t| t.term.as_str())
+            .collect();
+        // Stemmed terms are reported as written
+        assert!(terms.contains(&"climate"));
+        assert!(terms.contains(&"startups"));
+        assert!(terms.contains(&"investor"));
+        let total: f64 = similar[0].shared_terms.iter().map(|t| t.weight).sum();
+        assert!((total - similar[0].similarity).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_names_do_not_count() {
+        let vectors = TermVectors::build(&index());
+        // "Ana" is shared with contact 4, but only the company counts
+        let similar = vectors.similar_to("4", 10);
+        assert_eq!(similar.len(), 1);
+        assert_eq!(similar[0].shared_terms.len(), 2);
+    }
+
+    #[test]
+    fn test_unknown_or_empty_contact() {
+        let vectors = TermVectors::build(&index());
+        assert!(vectors.similar_to("missing", 10).is_empty());
+        assert!(vectors.similar_to("1", 0).is_empty());
+    }
+}
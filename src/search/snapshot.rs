@@ -0,0 +1,337 @@
+//! On-disk snapshots of the search index for warm starts.
+//!
+//! Building the index takes a list call per page of contacts plus a notes and
+//! a reminders call per contact. A snapshot stores the indexed documents and
+//! the contacts they cover in a versioned JSON file, so a restarted server can
+//! answer its first search without going back to the API. Postings are
+//! rebuilt from the documents on load, which needs no API calls and picks up
+//! changes to the analyzer configuration.
+
+use super::analyzer::Analyzer;
+use super::full_text_index::{FullTextSearchIndex, SearchableDocument};
+use crate::error::{SnapshotError, SnapshotResult};
+use crate::models::Contact;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Version of the snapshot file layout.
+///
+/// Bump this whenever [`SearchableDocument`], [`Contact`] or the file layout
+/// change incompatibly; snapshots with another version are ignored.
+pub const SNAPSHOT_SCHEMA_VERSION: u32 = 2;
+
+/// Fields checked before the rest of the file is deserialized.
+///
+/// The whole file is still scanned to find them, but the documents and
+/// contacts of snapshots from other versions or past their age are skipped
+/// rather than built.
+#[derive(Deserialize)]
+struct SnapshotHeader {
+    schema_version: u32,
+    /// Unix timestamp (seconds) of when the snapshot was written
+    saved_at: i64,
+}
+
+/// Snapshot file contents. Borrowed when writing, owned when reading.
+#[derive(Serialize, Deserialize)]
+struct SnapshotFile<'a> {
+    schema_version: u32,
+    saved_at: i64,
+    contacts: Vec<StoredContact<'a>>,
+    documents: Vec<Cow<'a, SearchableDocument>>,
+}
+
+/// A contact together with the computed fields that `Contact` does not
+/// serialize, so that contacts built without first/last names round-trip.
+#[derive(Serialize, Deserialize)]
+struct StoredContact<'a> {
+    contact: Cow<'a, Contact>,
+    name: Cow<'a, str>,
+    email: Option<Cow<'a, str>>,
+    phone: Option<Cow<'a, str>>,
+    title: Option<Cow<'a, str>>,
+}
+
+impl<'a> StoredContact<'a> {
+    fn new(contact: &'a Contact) -> Self {
+        Self {
+            contact: Cow::Borrowed(contact),
+            name: Cow::Borrowed(&contact.name),
+            email: contact.email.as_deref().map(Cow::Borrowed),
+            phone: contact.phone.as_deref().map(Cow::Borrowed),
+            title: contact.title.as_deref().map(Cow::Borrowed),
+        }
+    }
+
+    fn into_contact(self) -> Contact {
+        let mut contact = self.contact.into_owned();
+        contact.name = self.name.into_owned();
+        contact.email = self.email.map(Cow::into_owned);
+        contact.phone = self.phone.map(Cow::into_owned);
+        contact.title = self.title.map(Cow::into_owned);
+        contact
+    }
+}
+
+/// A search index restored from a snapshot.
+pub struct IndexSnapshot {
+    /// The restored index
+    pub index: FullTextSearchIndex,
+
+    /// Contacts covered by the index
+    pub contacts: Vec<Contact>,
+
+    /// Time since the snapshot was written
+    pub age: Duration,
+}
+
+/// Reads and writes the search index snapshot file.
+#[derive(Debug, Clone)]
+pub struct IndexSnapshotStore {
+    path: PathBuf,
+    max_age: Duration,
+}
+
+impl IndexSnapshotStore {
+    /// Create a store for the snapshot file at `path`.
+    ///
+    /// Snapshots older than `max_age` are not loaded. The file and its parent
+    /// directories are created on the first save.
+    pub fn new(path: impl Into<PathBuf>, max_age: Duration) -> Self {
+        Self {
+            path: path.into(),
+            max_age,
+        }
+    }
+
+    /// Get the path of the snapshot file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Serialize an index and its contacts into snapshot file contents.
+    ///
+    /// Kept separate from [`write`](Self::write) so that callers can encode
+    /// while holding a lock on the index and write to disk after releasing it.
+    pub fn encode(index: &FullTextSearchIndex, contacts: &[Contact]) -> SnapshotResult<Vec<u8>> {
+        let file = SnapshotFile {
+            schema_version: SNAPSHOT_SCHEMA_VERSION,
+            saved_at: chrono::Utc::now().timestamp(),
+            contacts: contacts.iter().map(StoredContact::new).collect(),
+            documents: index.documents().map(Cow::Borrowed).collect(),
+        };
+        Ok(serde_json::to_vec(&file)?)
+    }
+
+    /// Replace the snapshot file with encoded contents.
+    ///
+    /// Writes to a temporary file first and renames it into place, so a crash
+    /// mid-write never leaves a truncated snapshot behind. The snapshot holds
+    /// every contact, note and reminder, so on Unix only the owner may read it.
+    pub fn write(&self, contents: &[u8]) -> SnapshotResult<()> {
+        if let Some(parent) = self.path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+
+        // Start from a new file, left over from a crash or not, so that it
+        // gets the mode below
+        let tmp_path = self.path.with_extension("json.tmp");
+        match fs::remove_file(&tmp_path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        let mut file = options.open(&tmp_path)?;
+        file.write_all(contents)?;
+        drop(file);
+
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+
+    /// Load the snapshot, if one exists, indexing it with `analyzer`.
+    ///
+    /// # Errors
+    /// Returns [`SnapshotError::SchemaVersion`] or [`SnapshotError::Expired`]
+    /// for snapshots that must not be used, and I/O or parse errors for
+    /// unreadable files.
+    pub fn load(&self, analyzer: Arc<Analyzer>) -> SnapshotResult<Option<IndexSnapshot>> {
+        let contents = match fs::read(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let header: SnapshotHeader = serde_json::from_slice(&contents)?;
+        if header.schema_version != SNAPSHOT_SCHEMA_VERSION {
+            return Err(SnapshotError::SchemaVersion {
+                found: header.schema_version,
+                expected: SNAPSHOT_SCHEMA_VERSION,
+            });
+        }
+
+        // Timestamps from the future (clock changes) count as fresh
+        let age_secs = (chrono::Utc::now().timestamp() - header.saved_at).max(0) as u64;
+        if age_secs > self.max_age.as_secs() {
+            return Err(SnapshotError::Expired {
+                age_secs,
+                max_age_secs: self.max_age.as_secs(),
+            });
+        }
+
+        let file: SnapshotFile = serde_json::from_slice(&contents)?;
+        Ok(Some(IndexSnapshot {
+            index: FullTextSearchIndex::from_documents(
+                analyzer,
+                file.documents.into_iter().map(Cow::into_owned),
+            ),
+            contacts: file
+                .contacts
+                .into_iter()
+                .map(StoredContact::into_contact)
+                .collect(),
+            age: Duration::from_secs(age_secs),
+        }))
+    }
+
+    /// Delete the snapshot file, if any.
+    pub fn remove(&self) -> SnapshotResult<()> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Note;
+
+    fn temp_store(name: &str, max_age: Duration) -> IndexSnapshotStore {
+        let path = std::env::temp_dir().join(format!(
+            "dex-snapshot-test-{}-{}/search_index.json",
+            name,
+            std::process::id()
+        ));
+        let store = IndexSnapshotStore::new(path, max_age);
+        store.remove().unwrap();
+        store
+    }
+
+    fn fixture() -> (FullTextSearchIndex, Vec<Contact>) {
+        let mut contact = Contact::new("c1".to_string(), "Jane Roe".to_string());
+        contact.emails = vec!["jane@example.com".to_string()];
+        contact.email = Some("jane@example.com".to_string());
+        contact.company = Some("Acme".to_string());
+        let note = Note {
+            id: "n1".to_string(),
+            contact_id: "c1".to_string(),
+            content: "Talked about kayaking".to_string(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            ..Default::default()
+        };
+
+        let mut index = FullTextSearchIndex::new();
+        index.index_contact(&contact, &[note], &[]);
+        (index, vec![contact])
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let store = temp_store("round-trip", Duration::from_secs(3600));
+        let (index, contacts) = fixture();
+
+        store
+            .write(&IndexSnapshotStore::encode(&index, &contacts).unwrap())
+            .unwrap();
+        let snapshot = store
+            .load(Arc::default())
+            .unwrap()
+            .expect("snapshot was written");
+
+        // Computed fields survive even though `Contact` skips them
+        assert_eq!(snapshot.contacts, contacts);
+        assert!(snapshot.index.documents().eq(index.documents()));
+        let results = snapshot.index.search(&snapshot.contacts, "kayaking", 10, 0);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].contact.name, "Jane Roe");
+
+        store.remove().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_snapshot_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let store = temp_store("private", Duration::from_secs(3600));
+        store.write(b"{}").unwrap();
+        // Also when an earlier snapshot was readable by others
+        fs::set_permissions(store.path(), fs::Permissions::from_mode(0o644)).unwrap();
+        store.write(b"{}").unwrap();
+
+        let mode = fs::metadata(store.path()).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        store.remove().unwrap();
+    }
+
+    #[test]
+    fn test_missing_snapshot_loads_nothing() {
+        let store = temp_store("missing", Duration::from_secs(3600));
+        assert!(store.load(Arc::default()).unwrap().is_none());
+        // Removing a missing snapshot is not an error
+        store.remove().unwrap();
+    }
+
+    #[test]
+    fn test_rejects_other_schema_versions() {
+        let store = temp_store("schema", Duration::from_secs(3600));
+        let contents = serde_json::json!({
+            "schema_version": SNAPSHOT_SCHEMA_VERSION + 1,
+            "saved_at": chrono::Utc::now().timestamp(),
+            "layout": "from the future",
+        });
+        store.write(contents.to_string().as_bytes()).unwrap();
+
+        assert!(matches!(
+            store.load(Arc::default()),
+            Err(SnapshotError::SchemaVersion { found, expected })
+                if found == SNAPSHOT_SCHEMA_VERSION + 1 && expected == SNAPSHOT_SCHEMA_VERSION
+        ));
+        store.remove().unwrap();
+    }
+
+    #[test]
+    fn test_rejects_expired_snapshots() {
+        let store = temp_store("expired", Duration::from_secs(60));
+        let contents = serde_json::json!({
+            "schema_version": SNAPSHOT_SCHEMA_VERSION,
+            "saved_at": chrono::Utc::now().timestamp() - 120,
+            "contacts": [],
+            "documents": [],
+        });
+        store.write(contents.to_string().as_bytes()).unwrap();
+
+        assert!(matches!(
+            store.load(Arc::default()),
+            Err(SnapshotError::Expired {
+                max_age_secs: 60,
+                ..
+            })
+        ));
+        store.remove().unwrap();
+    }
+}
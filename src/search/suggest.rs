@@ -0,0 +1,290 @@
+//! "Did you mean" spelling suggestions.
+//!
+//! A [`SpellingDictionary`] counts the words of indexed text. When a search
+//! finds nothing, each query word missing from the dictionary is replaced
+//! by known words within a small edit distance, preferring closer and then
+//! more frequent words. Query syntax (field prefixes, operators, quotes and
+//! parentheses) is left as written.
+
+use super::inverted_index::levenshtein_distance;
+use crate::text::{fold, token_spans};
+use std::collections::HashMap;
+
+/// Maximum number of suggestions returned for a query.
+pub const MAX_SUGGESTIONS: usize = 3;
+
+/// Words shorter than this are never corrected.
+const MIN_CORRECTED_LENGTH: usize = 3;
+
+/// Alternatives considered per misspelled word.
+const MAX_CANDIDATES: usize = 3;
+
+/// A corrected version of a query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpellingSuggestion {
+    /// The query with misspelled words replaced
+    pub text: String,
+
+    /// Total edit distance of the replacements
+    pub distance: usize,
+
+    /// Occurrences of the rarest replacement word in the indexed text
+    pub frequency: usize,
+}
+
+/// A known word close to a misspelled one.
+#[derive(Debug, Clone, Copy)]
+struct Candidate<'a> {
+    word: &'a str,
+    distance: usize,
+    frequency: usize,
+}
+
+/// A query word that is not in the dictionary, with its replacements.
+struct Misspelling<'a> {
+    span: std::ops::Range<usize>,
+    capitalized: bool,
+    candidates: Vec<Candidate<'a>>,
+}
+
+/// Folded words of indexed text and how often each occurs.
+#[derive(Debug, Clone, Default)]
+pub struct SpellingDictionary {
+    words: HashMap<String, usize>,
+}
+
+impl SpellingDictionary {
+    /// Create an empty dictionary.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count the words of `text`.
+    pub fn add_text(&mut self, text: &str) {
+        for span in token_spans(text) {
+            *self.words.entry(fold(&text[span])).or_default() += 1;
+        }
+    }
+
+    /// Stop counting the words of `text`, added earlier with
+    /// [`add_text`](Self::add_text).
+    pub fn remove_text(&mut self, text: &str) {
+        for span in token_spans(text) {
+            let word = fold(&text[span]);
+            if let Some(count) = self.words.get_mut(&word) {
+                *count -= 1;
+                if *count == 0 {
+                    self.words.remove(&word);
+                }
+            }
+        }
+    }
+
+    /// Number of distinct words.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Whether no words have been added.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Whether `word` occurs in the dictionary, ignoring case and accents.
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains_key(&fold(word))
+    }
+
+    /// Suggest corrected versions of `query`, best first.
+    ///
+    /// Words of up to four characters may be one edit away from their
+    /// correction, longer words two. Unknown words with no known word that
+    /// close are kept as written; if that leaves nothing to correct, there
+    /// are no suggestions.
+    pub fn suggest(&self, query: &str) -> Vec<SpellingSuggestion> {
+        let misspellings: Vec<Misspelling<'_>> = token_spans(query)
+            .into_iter()
+            .filter(|span| is_correctable(query, span))
+            .filter_map(|span| {
+                let word = fold(&query[span.clone()]);
+                if self.words.contains_key(&word) {
+                    return None;
+                }
+                let candidates = self.candidates(&word);
+                if candidates.is_empty() {
+                    return None;
+                }
+                Some(Misspelling {
+                    capitalized: query[span.clone()]
+                        .chars()
+                        .next()
+                        .is_some_and(char::is_uppercase),
+                    span,
+                    candidates,
+                })
+            })
+            .collect();
+        if misspellings.is_empty() {
+            return Vec::new();
+        }
+
+        // The best candidate for every word, then each runner-up on its own
+        let mut choices = vec![vec![0; misspellings.len()]];
+        for (i, misspelling) in misspellings.iter().enumerate() {
+            for rank in 1..misspelling.candidates.len() {
+                let mut choice = vec![0; misspellings.len()];
+                choice[i] = rank;
+                choices.push(choice);
+            }
+        }
+
+        let mut suggestions: Vec<SpellingSuggestion> = choices
+            .into_iter()
+            .map(|choice| {
+                let picked: Vec<(&Misspelling<'_>, Candidate<'_>)> = misspellings
+                    .iter()
+                    .zip(choice)
+                    .map(|(m, rank)| (m, m.candidates[rank]))
+                    .collect();
+                SpellingSuggestion {
+                    text: replace_words(query, &picked),
+                    distance: picked.iter().map(|(_, c)| c.distance).sum(),
+                    frequency: picked.iter().map(|(_, c)| c.frequency).min().unwrap_or(0),
+                }
+            })
+            .collect();
+        suggestions.sort_by(|a, b| {
+            a.distance
+                .cmp(&b.distance)
+                .then(b.frequency.cmp(&a.frequency))
+                .then_with(|| a.text.cmp(&b.text))
+        });
+        suggestions.dedup_by(|a, b| a.text == b.text);
+        suggestions.truncate(MAX_SUGGESTIONS);
+        suggestions
+    }
+
+    /// Known words close to `word`, closest and then most frequent first.
+    fn candidates(&self, word: &str) -> Vec<Candidate<'_>> {
+        let length = word.chars().count();
+        let max_distance = if length <= 4 { 1 } else { 2 };
+
+        let mut candidates: Vec<Candidate<'_>> = self
+            .words
+            .iter()
+            .filter(|(known, _)| known.chars().count().abs_diff(length) <= max_distance)
+            .filter_map(|(known, &frequency)| {
+                let distance = levenshtein_distance(word, known);
+                (distance <= max_distance).then_some(Candidate {
+                    word: known,
+                    distance,
+                    frequency,
+                })
+            })
+            .collect();
+        candidates.sort_by(|a, b| {
+            a.distance
+                .cmp(&b.distance)
+                .then(b.frequency.cmp(&a.frequency))
+                .then_with(|| a.word.cmp(b.word))
+        });
+        candidates.truncate(MAX_CANDIDATES);
+        candidates
+    }
+}
+
+/// Whether a query token is a word that may be corrected.
+///
+/// Field names before a colon, the `AND`/`OR`/`NOT` operators, words
+/// containing digits and words too short to correct reliably are skipped.
+fn is_correctable(query: &str, span: &std::ops::Range<usize>) -> bool {
+    let word = &query[span.clone()];
+    word.chars().count() >= MIN_CORRECTED_LENGTH
+        && !word.chars().any(|c| c.is_numeric())
+        && !matches!(word, "AND" | "OR" | "NOT")
+        && !query[span.end..].starts_with(':')
+}
+
+/// `query` with the picked candidates in place of the misspelled words.
+fn replace_words(query: &str, picked: &[(&Misspelling<'_>, Candidate<'_>)]) -> String {
+    let mut text = String::with_capacity(query.len());
+    let mut end = 0;
+    for (misspelling, candidate) in picked {
+        text.push_str(&query[end..misspelling.span.start]);
+        if misspelling.capitalized {
+            let mut chars = candidate.word.chars();
+            if let Some(first) = chars.next() {
+                text.extend(first.to_uppercase());
+                text.push_str(chars.as_str());
+            }
+        } else {
+            text.push_str(candidate.word);
+        }
+        end = misspelling.span.end;
+    }
+    text.push_str(&query[end..]);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dictionary() -> SpellingDictionary {
+        let mut dictionary = SpellingDictionary::new();
+        for text in [
+            "Jonathan Smith",
+            "Jonathan Smyth",
+            "Sarah Connor",
+            "Discussed the investment round with Sarah",
+            "Investment committee meets Monday",
+        ] {
+            dictionary.add_text(text);
+        }
+        dictionary
+    }
+
+    #[test]
+    fn test_suggests_closest_then_most_frequent_word() {
+        let suggestions = dictionary().suggest("invesment");
+        assert_eq!(suggestions[0].text, "investment");
+        assert_eq!(suggestions[0].distance, 1);
+        assert_eq!(suggestions[0].frequency, 2);
+
+        // "smith" and "smyth" are both one edit from "smth"; equal frequency
+        // falls back to alphabetical order
+        let texts: Vec<String> = dictionary()
+            .suggest("smth")
+            .into_iter()
+            .map(|s| s.text)
+            .collect();
+        assert_eq!(texts, vec!["smith", "smyth"]);
+    }
+
+    #[test]
+    fn test_keeps_query_syntax_and_capitalization() {
+        let suggestions = dictionary().suggest("note:\"Jonatan Smith\" -Conor OR x");
+        assert_eq!(suggestions[0].text, "note:\"Jonathan Smith\" -Connor OR x");
+    }
+
+    #[test]
+    fn test_known_and_uncorrectable_words_are_kept() {
+        assert!(dictionary().suggest("Sarah investment").is_empty());
+        assert!(dictionary().suggest("xylophone").is_empty());
+        assert!(SpellingDictionary::new().suggest("anything").is_empty());
+
+        // Words that cannot be corrected are kept
+        let suggestions = dictionary().suggest("invesment xylophone");
+        assert_eq!(suggestions[0].text, "investment xylophone");
+    }
+
+    #[test]
+    fn test_removed_text_is_no_longer_suggested() {
+        let mut dictionary = dictionary();
+        dictionary.remove_text("Sarah Connor");
+        assert!(!dictionary.contains("connor"));
+        // Words still used elsewhere are kept
+        assert!(dictionary.contains("sarah"));
+        assert!(dictionary.suggest("Conor").is_empty());
+    }
+}
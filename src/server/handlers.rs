@@ -2,10 +2,12 @@
 //!
 //! This module implements all the MCP tools using the rmcp SDK's tool_router pattern.
 
+use crate::audit::{self, AuditLog};
 use crate::client::AsyncDexClient;
 use crate::repositories::{ContactRepository, NoteRepository, ReminderRepository};
 use crate::tools::{
-    ContactDiscoveryTools, ContactEnrichmentTools, RelationshipHistoryTools, SearchTools,
+    ContactDiscoveryTools, ContactEnrichmentTools, DiscoveryConfig, MergeContactsParams,
+    MergeStrategy, MergeTools, RelationshipHistoryTools, SearchCacheConfig, SearchTools, UndoTools,
 };
 use rmcp::handler::server::tool::ToolRouter;
 use rmcp::handler::server::wrapper::Parameters;
@@ -25,6 +27,9 @@ pub struct DexMcpServer {
     note_service: Arc<dyn crate::services::NoteService>,
     reminder_service: Arc<dyn crate::services::ReminderService>,
     history_service: Arc<dyn crate::services::HistoryService>,
+    audit_log: Arc<AuditLog>,
+    undo_tools: Arc<UndoTools>,
+    merge_tools: Arc<MergeTools>,
     #[allow(dead_code)] // Reserved for future direct API calls if needed
     client: Arc<dyn AsyncDexClient>,
     tool_router: ToolRouter<Self>,
@@ -60,9 +65,51 @@ struct SearchContactsParams {
     max_results: Option<usize>,
     #[serde(default)]
     min_confidence: Option<u8>,
+    /// Field kinds to search: name, email, phone, company, title, tag, description,
+    /// location, education, website, social_profile, birthday, contact_notes, note, reminder
     #[serde(default)]
-    #[allow(dead_code)]
     include_types: Option<Vec<String>>,
+    /// Only match notes and reminders created on or after this date (ISO 8601)
+    #[serde(default)]
+    date_from: Option<String>,
+    /// Only match notes and reminders created on or before this date (ISO 8601)
+    #[serde(default)]
+    date_to: Option<String>,
+    /// Maximum length of each snippet in characters (20-1000, default 150)
+    #[serde(default)]
+    snippet_length: Option<usize>,
+    /// Maximum number of snippets per match (1-10, default 3)
+    #[serde(default)]
+    max_snippets: Option<usize>,
+    /// Marker inserted before each hit in snippets, e.g. "**"
+    #[serde(default)]
+    highlight_pre: Option<String>,
+    /// Marker inserted after each hit in snippets; defaults to highlight_pre
+    #[serde(default)]
+    highlight_post: Option<String>,
+    /// Only return contacts with one of these tags
+    #[serde(default)]
+    facet_tags: Option<Vec<String>>,
+    /// Only return contacts at one of these companies
+    #[serde(default)]
+    facet_companies: Option<Vec<String>>,
+    /// Only return contacts with an email address at one of these domains, e.g. "acme.com"
+    #[serde(default)]
+    facet_email_domains: Option<Vec<String>>,
+    /// Only return contacts the query matched in one of these fields (same names as include_types)
+    #[serde(default)]
+    facet_fields: Option<Vec<String>>,
+    /// If nothing matches, search once more for the top spelling suggestion
+    #[serde(default)]
+    auto_correct: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct FindSimilarContactsParams {
+    contact_id: String,
+    /// Number of similar contacts to return (1-50, default 10)
+    #[serde(default)]
+    k: Option<usize>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -77,6 +124,22 @@ struct FindContactToolParams {
     social_url: Option<String>,
     #[serde(default)]
     company: Option<String>,
+    /// If no contact matches the name, try once more with the top spelling suggestion
+    #[serde(default)]
+    auto_correct: Option<bool>,
+    /// Break each match's confidence down by signal (default false)
+    #[serde(default)]
+    explain: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct FindDuplicateContactsParams {
+    /// Minimum confidence for two contacts to count as duplicates (1-100, default 80)
+    #[serde(default)]
+    min_confidence: Option<u8>,
+    /// Maximum number of clusters to return (1-500, default 50)
+    #[serde(default)]
+    max_clusters: Option<usize>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -132,6 +195,17 @@ struct EnrichContactToolParams {
     notes: Option<String>,
     #[serde(default)]
     tags: Option<Vec<String>>,
+    /// `updated_at` of the contact as last read; the update fails with a
+    /// conflict if the contact has changed since
+    #[serde(default)]
+    expected_updated_at: Option<String>,
+    /// If the contact changed mid-update, merge onto the newer version
+    /// when the changes touch different fields instead of failing
+    #[serde(default)]
+    merge_on_conflict: Option<bool>,
+    /// Preview the change without writing it
+    #[serde(default)]
+    dry_run: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -143,6 +217,46 @@ struct AddContactNoteToolParams {
     date: Option<String>,
     #[serde(default)]
     tags: Option<Vec<String>>,
+    /// Preview the change without writing it
+    #[serde(default)]
+    dry_run: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct GetAuditLogParams {
+    #[serde(default)]
+    contact_id: Option<String>,
+    #[serde(default)]
+    since: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct ChangeIdParams {
+    change_id: String,
+    /// Preview the change without writing it
+    #[serde(default)]
+    dry_run: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct UndoLastChangeParams {
+    /// Preview the change without writing it
+    #[serde(default)]
+    dry_run: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct MergeContactsToolParams {
+    /// Contact that is kept
+    primary_id: String,
+    /// Contacts merged into the primary and then deleted
+    duplicate_ids: Vec<String>,
+    /// keep_primary, fill_missing (default) or prefer_newest
+    #[serde(default)]
+    strategy: Option<String>,
+    /// Preview the change without writing it
+    #[serde(default)]
+    dry_run: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -152,6 +266,9 @@ struct CreateContactReminderToolParams {
     note: String,
     #[serde(default)]
     reminder_type: Option<String>,
+    /// Preview the change without writing it
+    #[serde(default)]
+    dry_run: Option<bool>,
 }
 
 // Helper function to convert errors to MCP errors
@@ -163,23 +280,37 @@ fn to_mcp_error(e: impl std::fmt::Display) -> McpError {
     }
 }
 
+// Helper function to render a dry-run preview as a tool result
+fn preview_result<T: serde::Serialize>(
+    preview: &crate::tools::ChangePreview<T>,
+) -> Result<CallToolResult, McpError> {
+    let json_response = serde_json::to_string_pretty(preview).map_err(to_mcp_error)?;
+    Ok(CallToolResult::success(vec![Content::text(json_response)]))
+}
+
 // Tool router implementation
 #[tool_router]
 impl DexMcpServer {
     /// Create a new Dex MCP server.
+    ///
+    /// The repositories are expected to already be wrapped in the audited
+    /// decorators that write to `audit_log`.
     pub fn new(
         contact_repo: Arc<dyn ContactRepository>,
         note_repo: Arc<dyn NoteRepository>,
         reminder_repo: Arc<dyn ReminderRepository>,
+        audit_log: Arc<AuditLog>,
         client: Arc<dyn AsyncDexClient>,
-        discovery_cache_ttl_secs: u64,
-        search_cache_ttl_secs: u64,
+        discovery: DiscoveryConfig,
+        search_cache: SearchCacheConfig,
     ) -> Self {
         // Construct all tools with repository dependencies
-        let discovery_tools = Arc::new(RwLock::new(ContactDiscoveryTools::new(
-            contact_repo.clone(),
-            discovery_cache_ttl_secs,
-        )));
+        let discovery_tools = Arc::new(RwLock::new(
+            ContactDiscoveryTools::new(contact_repo.clone(), discovery.cache_ttl_secs)
+                .with_nicknames(discovery.nicknames)
+                .with_phone_region(discovery.phone_region)
+                .with_match_weights(discovery.match_weights),
+        ));
 
         let history_tools = Arc::new(RelationshipHistoryTools::new(
             contact_repo.clone(),
@@ -187,34 +318,59 @@ impl DexMcpServer {
             reminder_repo.clone(),
         ));
 
-        let enrichment_tools = Arc::new(ContactEnrichmentTools::new(
+        let enrichment_tools = Arc::new(
+            ContactEnrichmentTools::new(
+                contact_repo.clone(),
+                note_repo.clone(),
+                reminder_repo.clone(),
+            )
+            .with_phone_region(discovery.phone_region),
+        );
+
+        let undo_tools = Arc::new(UndoTools::new(
             contact_repo.clone(),
             note_repo.clone(),
             reminder_repo.clone(),
+            audit_log.clone(),
         ));
 
-        let search_tools = SearchTools::new(
+        let merge_tools = Arc::new(
+            MergeTools::new(
+                contact_repo.clone(),
+                note_repo.clone(),
+                reminder_repo.clone(),
+            )
+            .with_phone_region(discovery.phone_region),
+        );
+
+        let mut search_tools = SearchTools::new(
             contact_repo,
             note_repo,
             reminder_repo,
-            search_cache_ttl_secs,
-        );
+            search_cache.ttl_secs,
+        )
+        .with_analyzer(search_cache.analyzer);
+        if let Some(snapshot) = search_cache.snapshot {
+            search_tools = search_tools.with_snapshot(snapshot);
+        }
 
-        // Construct services from tools
+        // Construct services from tools; they share the search cache
         let contact_service = Arc::new(crate::services::ContactServiceImpl::new(
             discovery_tools,
             enrichment_tools.clone(),
-            search_tools,
+            search_tools.clone(),
         )) as Arc<dyn crate::services::ContactService>;
 
         let note_service = Arc::new(crate::services::NoteServiceImpl::new(
             history_tools.clone(),
             enrichment_tools.clone(),
+            search_tools.clone(),
         )) as Arc<dyn crate::services::NoteService>;
 
         let reminder_service = Arc::new(crate::services::ReminderServiceImpl::new(
             history_tools.clone(),
             enrichment_tools,
+            search_tools,
         )) as Arc<dyn crate::services::ReminderService>;
 
         let history_service = Arc::new(crate::services::HistoryServiceImpl::new(history_tools))
@@ -225,14 +381,23 @@ impl DexMcpServer {
             note_service,
             reminder_service,
             history_service,
+            audit_log,
+            undo_tools,
+            merge_tools,
             client,
             tool_router: Self::tool_router(),
         }
     }
 
+    /// Persist state written in the background, such as the search index
+    /// snapshot. Call once the server has stopped serving.
+    pub async fn shutdown(&self) {
+        self.contact_service.flush_cache().await;
+    }
+
     /// Search across all contact data including names, descriptions, notes, and reminders.
     #[tool(
-        description = "Search across all contact data including names, descriptions, notes, and reminders using fuzzy matching. Returns ranked results with match context showing where the query was found."
+        description = "Search across all contact data including names, descriptions, notes, and reminders using fuzzy matching. Returns ranked results with match context showing where the query was found: ranked snippets, optionally highlighted (e.g. highlight_pre \"**\"), and the byte and character offsets of every hit in the matched field. Supports field-scoped syntax: field:value (fields: name, email, phone, company, title, tag, description, location, education, website, social_profile, birthday, contact_notes, note, reminder), \"exact phrases\", -term or NOT term to exclude, OR, and parentheses, e.g. company:acme tag:investor note:\"series A\" -reminder:done. Also returns total_matches and facet counts of all matching contacts by tag, company, email domain and matched field; narrow the results with facet_tags, facet_companies, facet_email_domains and facet_fields. When nothing matches, suggestions lists \"did you mean\" spellings of the query; set auto_correct to search once for the top suggestion (reported as corrected_query)."
     )]
     async fn search_contacts_full_text(
         &self,
@@ -243,20 +408,47 @@ impl DexMcpServer {
         // Use ContactService with validation
         let response = self
             .contact_service
-            .search_full_text(
-                params.query.clone(),
-                params.max_results,
-                params.min_confidence,
-            )
+            .search_full_text(crate::services::ContactSearchParams {
+                query: params.query.clone(),
+                max_results: params.max_results,
+                min_confidence: params.min_confidence,
+                include_types: params.include_types,
+                date_from: params.date_from,
+                date_to: params.date_to,
+                snippet_length: params.snippet_length,
+                max_snippets: params.max_snippets,
+                highlight_pre: params.highlight_pre,
+                highlight_post: params.highlight_post,
+                facet_tags: params.facet_tags,
+                facet_companies: params.facet_companies,
+                facet_email_domains: params.facet_email_domains,
+                facet_fields: params.facet_fields,
+                auto_correct: params.auto_correct.unwrap_or(false),
+            })
             .await
             .map_err(to_mcp_error)?;
 
         let results = response.results;
+        let facet_counts = |counts: &[crate::search::FacetCount]| {
+            counts
+                .iter()
+                .map(|c| serde_json::json!({ "value": c.value, "count": c.count }))
+                .collect::<Vec<_>>()
+        };
 
         // Format results as JSON
         let response = serde_json::json!({
             "query": params.query,
             "result_count": results.len(),
+            "total_matches": response.total_matches,
+            "corrected_query": response.corrected_query,
+            "suggestions": response.suggestions.iter().map(|s| s.text.as_str()).collect::<Vec<_>>(),
+            "facets": {
+                "tags": facet_counts(&response.facets.tags),
+                "companies": facet_counts(&response.facets.companies),
+                "email_domains": facet_counts(&response.facets.email_domains),
+                "fields": facet_counts(&response.facets.fields),
+            },
             "results": results.iter().map(|r| {
                 serde_json::json!({
                     "contact": {
@@ -274,6 +466,64 @@ impl DexMcpServer {
                             "found_in": mc.field_type.display_name(),
                             "field": mc.field_type.display_name(),
                             "excerpt": mc.snippet,
+                            "snippets": mc.snippets.iter().map(|snippet| {
+                                serde_json::json!({
+                                    "text": snippet.text,
+                                    "start": snippet.span.start,
+                                    "end": snippet.span.end,
+                                    "char_start": snippet.span.char_start,
+                                    "char_end": snippet.span.char_end,
+                                })
+                            }).collect::<Vec<_>>(),
+                            "hits": mc.hits.iter().map(|hit| {
+                                serde_json::json!({
+                                    "start": hit.start,
+                                    "end": hit.end,
+                                    "char_start": hit.char_start,
+                                    "char_end": hit.char_end,
+                                })
+                            }).collect::<Vec<_>>(),
+                        })
+                    }).collect::<Vec<_>>(),
+                })
+            }).collect::<Vec<_>>(),
+        });
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&response).map_err(to_mcp_error)?,
+        )]))
+    }
+
+    /// Find contacts similar to a given contact.
+    #[tool(
+        description = "Find the contacts most similar to a given contact (\"who else is like this person\"), comparing TF-IDF vectors of their company, title, tags, description, location, education, notes and reminders by cosine similarity. Returns up to k contacts (default 10) with a similarity from 0 to 1 and the shared terms that contribute most to it."
+    )]
+    async fn find_similar_contacts(
+        &self,
+        params: Parameters<FindSimilarContactsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+
+        let similar = self
+            .contact_service
+            .find_similar_contacts(&params.contact_id, params.k)
+            .await
+            .map_err(to_mcp_error)?;
+
+        let response = serde_json::json!({
+            "contact_id": params.contact_id,
+            "result_count": similar.len(),
+            "results": similar.iter().map(|s| {
+                serde_json::json!({
+                    "contact": {
+                        "id": s.contact_id,
+                        "name": s.contact_name,
+                    },
+                    "similarity": s.similarity,
+                    "shared_terms": s.shared_terms.iter().map(|t| {
+                        serde_json::json!({
+                            "term": t.term,
+                            "weight": t.weight,
                         })
                     }).collect::<Vec<_>>(),
                 })
@@ -287,7 +537,7 @@ impl DexMcpServer {
 
     /// Find contacts using smart matching with fuzzy name search or exact matches.
     #[tool(
-        description = "Find contacts using smart matching with fuzzy name search or exact matches on email/phone/social URLs. Returns top matches with confidence scores."
+        description = "Find contacts using smart matching with fuzzy name search or exact matches on email/phone/social URLs. Names also match through nicknames (Bob for Robert), initials (J. Smith), surname-first order (Smith, John), missing middle names and one part of a hyphenated surname. Returns top matches with confidence scores combining every supplied field: fields that agree with a contact raise its confidence, fields that contradict it (a different name on a matching email, say) lower it. Set explain to break each confidence down by signal (email, phone, social, name, company) with its evidence (agrees, contradicts, missing), raw score, weight and contribution, plus the query as normalized for matching. When no contact matches a name, suggestions lists \"did you mean\" spellings from contact names; set auto_correct to try the top suggestion once (reported as corrected_name)."
     )]
     async fn find_contact(
         &self,
@@ -297,19 +547,21 @@ impl DexMcpServer {
 
         let response = self
             .contact_service
-            .find_contact(
-                params.name,
-                params.email,
-                params.phone,
-                params.social_url,
-                params.company,
-            )
+            .find_contact(crate::services::ContactFindParams {
+                name: params.name,
+                email: params.email,
+                phone: params.phone,
+                social_url: params.social_url,
+                company: params.company,
+                auto_correct: params.auto_correct.unwrap_or(false),
+                explain: params.explain.unwrap_or(false),
+            })
             .await
             .map_err(to_mcp_error)?;
 
         let json_response = serde_json::to_string_pretty(&serde_json::json!({
             "matches": response.matches.iter().map(|m| {
-                serde_json::json!({
+                let mut json = serde_json::json!({
                     "contact": {
                         "id": m.contact.id,
                         "name": format!("{} {}",
@@ -322,8 +574,33 @@ impl DexMcpServer {
                     },
                     "confidence": m.confidence,
                     "match_type": format!("{:?}", m.match_type),
-                })
+                });
+                if let Some(explanation) = &m.explanation {
+                    json["explanation"] = serde_json::json!({
+                        "normalized_query": {
+                            "name": explanation.query.name,
+                            "email": explanation.query.email,
+                            "phone": explanation.query.phone,
+                            "social_url": explanation.query.social_url,
+                            "company": explanation.query.company,
+                        },
+                        "signals": explanation.signals.iter().map(|s| {
+                            serde_json::json!({
+                                "signal": s.signal.name(),
+                                "evidence": s.evidence.name(),
+                                "raw_score": s.raw_score,
+                                "weight": s.weight,
+                                "contribution": s.contribution,
+                                "method": s.method,
+                                "matched_value": s.matched_value,
+                            })
+                        }).collect::<Vec<_>>(),
+                    });
+                }
+                json
             }).collect::<Vec<_>>(),
+            "corrected_name": response.corrected_name,
+            "suggestions": response.suggestions.iter().map(|s| s.text.as_str()).collect::<Vec<_>>(),
             "from_cache": response.from_cache,
         }))
         .map_err(to_mcp_error)?;
@@ -331,6 +608,61 @@ impl DexMcpServer {
         Ok(CallToolResult::success(vec![Content::text(json_response)]))
     }
 
+    /// Find clusters of duplicate contacts across the address book.
+    #[tool(
+        description = "Find duplicate contacts across the whole address book. Contacts sharing an email address, phone number or name word are compared with the same matcher as find_contact, and matching pairs are grouped into clusters. Each cluster lists its contacts, a confidence (that of the weakest match holding it together) and every matching pair with its evidence: the emails, phones or social profiles they share, or their similar names."
+    )]
+    async fn find_duplicate_contacts(
+        &self,
+        params: Parameters<FindDuplicateContactsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+
+        let report = self
+            .contact_service
+            .find_duplicate_contacts(params.min_confidence, params.max_clusters)
+            .await
+            .map_err(to_mcp_error)?;
+
+        let response = serde_json::json!({
+            "contacts_scanned": report.contacts_scanned,
+            "pairs_compared": report.pairs_compared,
+            "total_clusters": report.total_clusters,
+            "clusters": report.clusters.iter().map(|cluster| {
+                serde_json::json!({
+                    "confidence": cluster.confidence,
+                    "contacts": cluster.contacts.iter().map(|c| {
+                        serde_json::json!({
+                            "id": c.id,
+                            "name": c.name,
+                            "email": c.email.as_deref().unwrap_or(""),
+                            "phone": c.phone.as_deref().unwrap_or(""),
+                            "company": c.company.as_deref().unwrap_or(""),
+                        })
+                    }).collect::<Vec<_>>(),
+                    "matches": cluster.matches.iter().map(|m| {
+                        serde_json::json!({
+                            "contact_id": m.contact_id,
+                            "other_contact_id": m.other_contact_id,
+                            "confidence": m.confidence,
+                            "evidence": m.evidence.iter().map(|e| {
+                                serde_json::json!({
+                                    "match_type": format!("{:?}", e.match_type),
+                                    "confidence": e.confidence,
+                                    "value": e.value,
+                                })
+                            }).collect::<Vec<_>>(),
+                        })
+                    }).collect::<Vec<_>>(),
+                })
+            }).collect::<Vec<_>>(),
+        });
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&response).map_err(to_mcp_error)?,
+        )]))
+    }
+
     /// Retrieve complete information for a specific contact by ID.
     #[tool(description = "Retrieve complete information for a specific contact by ID")]
     async fn get_contact_details(
@@ -453,7 +785,7 @@ impl DexMcpServer {
 
     /// Add or update information for an existing contact.
     #[tool(
-        description = "Add or update information for an existing contact. Intelligently merges new data without overwriting existing information."
+        description = "Add or update information for an existing contact. Intelligently merges new data without overwriting existing information. Fails with a conflict if the contact is changed by someone else mid-update; set merge_on_conflict to merge non-overlapping changes automatically. Pass the contact's updated_at as expected_updated_at to also fail if it changed since you read it. Set dry_run to preview the merged contact and a field-level diff without saving."
     )]
     async fn enrich_contact(
         &self,
@@ -470,13 +802,25 @@ impl DexMcpServer {
             notes: params.notes,
             tags: params.tags,
             social_profiles: params.social_profiles,
+            expected_updated_at: params.expected_updated_at,
+            merge_on_conflict: params.merge_on_conflict.unwrap_or(false),
         };
 
-        let updated_contact = self
-            .contact_service
-            .enrich_contact(enrich_params)
-            .await
-            .map_err(to_mcp_error)?;
+        if params.dry_run.unwrap_or(false) {
+            let preview = self
+                .contact_service
+                .preview_enrich_contact(enrich_params)
+                .await
+                .map_err(to_mcp_error)?;
+            return preview_result(&preview);
+        }
+
+        let updated_contact = audit::with_tool(
+            "enrich_contact",
+            self.contact_service.enrich_contact(enrich_params),
+        )
+        .await
+        .map_err(to_mcp_error)?;
 
         let json_response = serde_json::to_string_pretty(&updated_contact).map_err(to_mcp_error)?;
 
@@ -485,7 +829,7 @@ impl DexMcpServer {
 
     /// Create a new note for a contact.
     #[tool(
-        description = "Create a new note for a contact to track interactions and important information"
+        description = "Create a new note for a contact to track interactions and important information. Set dry_run to preview the note without creating it."
     )]
     async fn add_contact_note(
         &self,
@@ -501,18 +845,28 @@ impl DexMcpServer {
             params.tags
         );
 
-        let note = self
-            .note_service
-            .create_note(
+        if params.dry_run.unwrap_or(false) {
+            let preview = self
+                .note_service
+                .preview_note(params.contact_id, params.content, params.tags)
+                .await
+                .map_err(to_mcp_error)?;
+            return preview_result(&preview);
+        }
+
+        let note = audit::with_tool(
+            "add_contact_note",
+            self.note_service.create_note(
                 params.contact_id.clone(),
                 params.content.clone(),
                 params.tags.clone(),
-            )
-            .await
-            .map_err(|e| {
-                tracing::error!("Failed to create note: {:?}", e);
-                to_mcp_error(e)
-            })?;
+            ),
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to create note: {:?}", e);
+            to_mcp_error(e)
+        })?;
 
         tracing::info!("Note created successfully: id={}", note.id);
         let json_response = serde_json::to_string_pretty(&note).map_err(to_mcp_error)?;
@@ -521,7 +875,9 @@ impl DexMcpServer {
     }
 
     /// Set a reminder for future follow-up with a contact.
-    #[tool(description = "Set a reminder for future follow-up with a contact")]
+    #[tool(
+        description = "Set a reminder for future follow-up with a contact. Set dry_run to preview the reminder without creating it."
+    )]
     async fn create_contact_reminder(
         &self,
         params: Parameters<CreateContactReminderToolParams>,
@@ -537,23 +893,166 @@ impl DexMcpServer {
             params.reminder_type
         );
 
-        let reminder = self
-            .reminder_service
-            .create_reminder(
+        if params.dry_run.unwrap_or(false) {
+            let preview = self
+                .reminder_service
+                .preview_reminder(
+                    params.contact_id,
+                    params.note,
+                    params.reminder_date,
+                    params.reminder_type,
+                )
+                .await
+                .map_err(to_mcp_error)?;
+            return preview_result(&preview);
+        }
+
+        let reminder = audit::with_tool(
+            "create_contact_reminder",
+            self.reminder_service.create_reminder(
                 params.contact_id.clone(),
                 params.note.clone(),
                 params.reminder_date.clone(),
                 params.reminder_type.clone(),
-            )
-            .await
-            .map_err(|e| {
-                tracing::error!("Failed to create reminder: {:?}", e);
-                to_mcp_error(e)
-            })?;
+            ),
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to create reminder: {:?}", e);
+            to_mcp_error(e)
+        })?;
 
         tracing::info!("Reminder created successfully: id={}", reminder.id);
         let json_response = serde_json::to_string_pretty(&reminder).map_err(to_mcp_error)?;
 
         Ok(CallToolResult::success(vec![Content::text(json_response)]))
     }
+
+    /// Query the audit log of changes made through this server.
+    #[tool(
+        description = "Get the audit log of every create, update and delete made through this server, oldest first. Optionally filter by contact ID and by a start time (RFC 3339 timestamp or YYYY-MM-DD date)."
+    )]
+    async fn get_audit_log(
+        &self,
+        params: Parameters<GetAuditLogParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+
+        let entries = self
+            .audit_log
+            .query(params.contact_id.as_deref(), params.since.as_deref())
+            .map_err(to_mcp_error)?;
+
+        let json_response = serde_json::to_string_pretty(&serde_json::json!({
+            "entry_count": entries.len(),
+            "entries": entries,
+        }))
+        .map_err(to_mcp_error)?;
+
+        Ok(CallToolResult::success(vec![Content::text(json_response)]))
+    }
+
+    /// Revert a specific change from the audit log.
+    #[tool(
+        description = "Revert a change recorded in the audit log by its change_id. Updates are restored from the before-image, creates are deleted, and deletes are recreated (with a new ID). The revert is itself recorded in the audit log. Set dry_run to preview the result without writing."
+    )]
+    async fn revert_change(
+        &self,
+        params: Parameters<ChangeIdParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+
+        if params.dry_run.unwrap_or(false) {
+            let preview = self
+                .undo_tools
+                .preview_revert_change(&params.change_id)
+                .await
+                .map_err(to_mcp_error)?;
+            return preview_result(&preview);
+        }
+
+        let result = audit::with_tool(
+            "revert_change",
+            self.undo_tools.revert_change(&params.change_id),
+        )
+        .await
+        .map_err(to_mcp_error)?;
+        self.contact_service.invalidate_cache().await;
+
+        let json_response = serde_json::to_string_pretty(&result).map_err(to_mcp_error)?;
+
+        Ok(CallToolResult::success(vec![Content::text(json_response)]))
+    }
+
+    /// Undo the most recent change that has not been reverted yet.
+    #[tool(
+        description = "Undo the most recent change made through this server that has not already been reverted. Repeated calls step further back through the audit log. Set dry_run to preview the result without writing."
+    )]
+    async fn undo_last_change(
+        &self,
+        params: Parameters<UndoLastChangeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if params.0.dry_run.unwrap_or(false) {
+            let preview = self
+                .undo_tools
+                .preview_undo_last_change()
+                .await
+                .map_err(to_mcp_error)?;
+            return preview_result(&preview);
+        }
+
+        let result = audit::with_tool("undo_last_change", self.undo_tools.undo_last_change())
+            .await
+            .map_err(to_mcp_error)?;
+        self.contact_service.invalidate_cache().await;
+
+        let json_response = serde_json::to_string_pretty(&result).map_err(to_mcp_error)?;
+
+        Ok(CallToolResult::success(vec![Content::text(json_response)]))
+    }
+
+    /// Merge duplicate contacts into a primary contact.
+    #[tool(
+        description = "Merge duplicate contacts into a primary contact. Emails, phones, tags and social profiles of the duplicates are added to the primary, every note and reminder of the duplicates is moved to the primary, and the duplicates are deleted. strategy decides the other fields: keep_primary leaves them as they are, fill_missing (default) fills the primary's empty fields, prefer_newest takes them from the most recently updated contact. If any step fails, the steps already done are rolled back. Every write is recorded in the audit log. Set dry_run to preview the merge without writing."
+    )]
+    async fn merge_contacts(
+        &self,
+        params: Parameters<MergeContactsToolParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+
+        let strategy = match params.strategy.as_deref() {
+            Some(strategy) => strategy.parse().map_err(to_mcp_error)?,
+            None => MergeStrategy::default(),
+        };
+        let merge_params = MergeContactsParams {
+            primary_id: params.primary_id,
+            duplicate_ids: params.duplicate_ids,
+            strategy,
+        };
+
+        if params.dry_run.unwrap_or(false) {
+            let preview = self
+                .merge_tools
+                .preview_merge_contacts(merge_params)
+                .await
+                .map_err(to_mcp_error)?;
+            let json_response = serde_json::to_string_pretty(&preview).map_err(to_mcp_error)?;
+            return Ok(CallToolResult::success(vec![Content::text(json_response)]));
+        }
+
+        let result = audit::with_tool(
+            "merge_contacts",
+            self.merge_tools.merge_contacts(merge_params),
+        )
+        .await;
+        // A failed merge has still written, and its rollback recreated
+        // deleted records under new IDs, so the caches are stale either way
+        self.contact_service.invalidate_cache().await;
+        let result = result.map_err(to_mcp_error)?;
+
+        let json_response = serde_json::to_string_pretty(&result).map_err(to_mcp_error)?;
+
+        Ok(CallToolResult::success(vec![Content::text(json_response)]))
+    }
 }
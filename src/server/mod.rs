@@ -23,10 +23,12 @@ use rmcp::ServiceExt;
 /// An error if the server fails to start or encounters a fatal error
 pub async fn run_server(server: DexMcpServer) -> Result<()> {
     // Serve the server with stdio transport
-    let service = server.serve(stdio()).await?;
+    let service = server.clone().serve(stdio()).await?;
 
     // Wait for completion
     service.waiting().await?;
 
+    server.shutdown().await;
+
     Ok(())
 }
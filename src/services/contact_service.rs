@@ -3,11 +3,17 @@
 //! Business logic for contact discovery, search, and enrichment.
 
 use crate::error::DexApiResult;
+use crate::matching::duplicates::{DEFAULT_DUPLICATE_CONFIDENCE, DEFAULT_MAX_CLUSTERS};
+use crate::matching::DuplicateReport;
 use crate::models::{Contact, SocialProfile};
+use crate::search::similarity::DEFAULT_SIMILAR_CONTACTS;
+use crate::search::{
+    FacetFilter, HighlightMarkers, SearchFilter, SearchableField, SimilarContact, SnippetOptions,
+};
 use crate::tools::search::{SearchParams, SearchResponse};
 use crate::tools::{
-    ContactDiscoveryTools, ContactEnrichmentTools, EnrichContactParams, FindContactParams,
-    FindContactResponse, SearchTools,
+    ChangePreview, ContactDiscoveryTools, ContactEnrichmentTools, EnrichContactParams,
+    FindContactParams, FindContactResponse, SearchTools,
 };
 use async_trait::async_trait;
 use std::sync::Arc;
@@ -24,41 +30,112 @@ pub struct ContactEnrichParams {
     pub notes: Option<String>,
     pub tags: Option<Vec<String>>,
     pub social_profiles: Option<Vec<String>>,
+    pub expected_updated_at: Option<String>,
+    pub merge_on_conflict: bool,
+}
+
+/// Parameters for finding a contact by name, email, phone or social URL.
+#[derive(Debug, Clone, Default)]
+pub struct ContactFindParams {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub social_url: Option<String>,
+    /// Company, boosting name matches at that company
+    pub company: Option<String>,
+    /// Retry once with the top spelling suggestion if nothing matches the name
+    pub auto_correct: bool,
+    /// Explain the confidence of each match
+    pub explain: bool,
+}
+
+/// Parameters for full-text search.
+#[derive(Debug, Clone, Default)]
+pub struct ContactSearchParams {
+    pub query: String,
+    pub max_results: Option<usize>,
+    pub min_confidence: Option<u8>,
+    /// Field kinds to search, by name (e.g. "note", "reminder")
+    pub include_types: Option<Vec<String>>,
+    /// Earliest note/reminder creation date (ISO 8601)
+    pub date_from: Option<String>,
+    /// Latest note/reminder creation date (ISO 8601)
+    pub date_to: Option<String>,
+    /// Maximum snippet length in characters (default: 150)
+    pub snippet_length: Option<usize>,
+    /// Maximum number of snippets per match (default: 3)
+    pub max_snippets: Option<usize>,
+    /// Marker inserted before each hit in snippets (e.g. "**")
+    pub highlight_pre: Option<String>,
+    /// Marker inserted after each hit; defaults to `highlight_pre`
+    pub highlight_post: Option<String>,
+    /// Only keep contacts with one of these tags
+    pub facet_tags: Option<Vec<String>>,
+    /// Only keep contacts at one of these companies
+    pub facet_companies: Option<Vec<String>>,
+    /// Only keep contacts with an email address at one of these domains
+    pub facet_email_domains: Option<Vec<String>>,
+    /// Only keep contacts matched in one of these fields, by name
+    pub facet_fields: Option<Vec<String>>,
+    /// Retry once with the top spelling suggestion if nothing matches
+    pub auto_correct: bool,
 }
 
 /// Contact service trait for business operations.
 #[async_trait]
 pub trait ContactService: Send + Sync {
     /// Search contacts using full-text search with ranking.
-    async fn search_full_text(
+    async fn search_full_text(&self, params: ContactSearchParams) -> DexApiResult<SearchResponse>;
+
+    /// Find the contacts most similar to a contact, by the topics of their
+    /// fields, notes and reminders.
+    ///
+    /// `k` is the number of contacts to return (1-50, default 10).
+    async fn find_similar_contacts(
         &self,
-        query: String,
-        max_results: Option<usize>,
-        min_confidence: Option<u8>,
-    ) -> DexApiResult<SearchResponse>;
+        contact_id: &str,
+        k: Option<usize>,
+    ) -> DexApiResult<Vec<SimilarContact>>;
 
     /// Find contacts using intelligent matching (fuzzy name, exact email/phone, etc.).
-    async fn find_contact(
+    ///
+    /// If nothing matches a name, spellings from contact names are suggested;
+    /// with `auto_correct` the top suggestion is tried once instead. With
+    /// `explain`, each match's confidence is broken down by signal.
+    async fn find_contact(&self, params: ContactFindParams) -> DexApiResult<FindContactResponse>;
+
+    /// Find clusters of contacts that are likely the same person.
+    async fn find_duplicate_contacts(
         &self,
-        name: Option<String>,
-        email: Option<String>,
-        phone: Option<String>,
-        social_url: Option<String>,
-        company: Option<String>,
-    ) -> DexApiResult<FindContactResponse>;
+        min_confidence: Option<u8>,
+        max_clusters: Option<usize>,
+    ) -> DexApiResult<DuplicateReport>;
 
     /// Get complete details for a specific contact.
     async fn get_contact_details(&self, contact_id: &str) -> DexApiResult<Contact>;
 
     /// Enrich a contact with new information.
     ///
-    /// This performs intelligent merging of data and invalidates caches.
+    /// This performs intelligent merging of data, invalidates the discovery
+    /// cache and updates the search index in place.
     async fn enrich_contact(&self, params: ContactEnrichParams) -> DexApiResult<Contact>;
 
+    /// Preview enriching a contact without writing anything.
+    async fn preview_enrich_contact(
+        &self,
+        params: ContactEnrichParams,
+    ) -> DexApiResult<ChangePreview<Contact>>;
+
     /// Invalidate the discovery cache.
     ///
     /// Should be called after any contact modification.
     async fn invalidate_cache(&self);
+
+    /// Persist cached data that is written in the background, such as the
+    /// search index snapshot.
+    ///
+    /// Should be called before shutting down.
+    async fn flush_cache(&self);
 }
 
 /// Default implementation of ContactService.
@@ -81,6 +158,147 @@ impl ContactServiceImpl {
         Ok(())
     }
 
+    /// Validate snippet options and convert them for the search index.
+    fn to_snippet_options(params: &ContactSearchParams) -> Result<SnippetOptions, String> {
+        let mut options = SnippetOptions::default();
+        if let Some(length) = params.snippet_length {
+            if !(20..=1000).contains(&length) {
+                return Err("snippet_length must be between 20 and 1000".to_string());
+            }
+            options.length = length;
+        }
+        if let Some(max_snippets) = params.max_snippets {
+            if !(1..=10).contains(&max_snippets) {
+                return Err("max_snippets must be between 1 and 10".to_string());
+            }
+            options.max_snippets = max_snippets;
+        }
+        let markers = [&params.highlight_pre, &params.highlight_post];
+        if markers.into_iter().flatten().any(|m| m.len() > 20) {
+            return Err("Highlight markers too long (max 20 characters)".to_string());
+        }
+        options.highlight = match (&params.highlight_pre, &params.highlight_post) {
+            (None, None) => None,
+            (pre, post) => {
+                let pre = pre.clone().or_else(|| post.clone()).unwrap_or_default();
+                let post = post.clone().unwrap_or_else(|| pre.clone());
+                Some(HighlightMarkers { pre, post })
+            }
+        };
+        Ok(options)
+    }
+
+    /// Validate ISO 8601 date format (basic check).
+    fn validate_date_format(date: &str) -> Result<(), String> {
+        let valid = date
+            .get(..10)
+            .is_some_and(|day| chrono::NaiveDate::parse_from_str(day, "%Y-%m-%d").is_ok());
+        if !valid {
+            return Err(format!("Invalid date '{}' (expected ISO 8601)", date));
+        }
+        Ok(())
+    }
+
+    /// Resolve field names given in the `param` search parameter.
+    fn resolve_fields(param: &str, names: &[String]) -> DexApiResult<Vec<SearchableField>> {
+        names
+            .iter()
+            .map(|name| {
+                SearchableField::from_name(name).ok_or_else(|| {
+                    crate::error::DexApiError::InvalidRequest(format!(
+                        "Unknown {} value '{}' (expected one of: {})",
+                        param,
+                        name,
+                        SearchableField::name_list()
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    /// Validate facet filter values and convert them for the search index.
+    fn to_facet_filter(
+        tags: Option<Vec<String>>,
+        companies: Option<Vec<String>>,
+        email_domains: Option<Vec<String>>,
+        fields: Option<Vec<String>>,
+    ) -> DexApiResult<FacetFilter> {
+        let clean = |values: Option<Vec<String>>| -> Vec<String> {
+            values
+                .unwrap_or_default()
+                .into_iter()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+                .collect()
+        };
+        let filter = FacetFilter {
+            tags: clean(tags),
+            companies: clean(companies),
+            email_domains: clean(email_domains),
+            fields: Self::resolve_fields("facet_fields", &clean(fields))?,
+        };
+
+        let values = [&filter.tags, &filter.companies, &filter.email_domains];
+        if values.iter().any(|v| v.len() > 50) || filter.fields.len() > 50 {
+            return Err(crate::error::DexApiError::InvalidRequest(
+                "Too many facet filter values (max 50 per facet)".to_string(),
+            ));
+        }
+        if values.iter().flat_map(|v| v.iter()).any(|v| v.len() > 200) {
+            return Err(crate::error::DexApiError::InvalidRequest(
+                "Facet filter value too long (max 200 characters)".to_string(),
+            ));
+        }
+        Ok(filter)
+    }
+
+    /// Validate search parameters and convert them for the search tools.
+    fn to_search_params(params: ContactSearchParams) -> DexApiResult<SearchParams> {
+        // Validate query
+        Self::validate_search_query(&params.query)
+            .map_err(crate::error::DexApiError::InvalidRequest)?;
+        let snippets =
+            Self::to_snippet_options(&params).map_err(crate::error::DexApiError::InvalidRequest)?;
+
+        // Resolve field names
+        let include_types = params
+            .include_types
+            .map(|names| Self::resolve_fields("include_types", &names))
+            .transpose()?;
+        let facets = Self::to_facet_filter(
+            params.facet_tags,
+            params.facet_companies,
+            params.facet_email_domains,
+            params.facet_fields,
+        )?;
+
+        // Validate date range
+        for date in [&params.date_from, &params.date_to].into_iter().flatten() {
+            Self::validate_date_format(date).map_err(crate::error::DexApiError::InvalidRequest)?;
+        }
+        if let (Some(from), Some(to)) = (&params.date_from, &params.date_to) {
+            if from > to {
+                return Err(crate::error::DexApiError::InvalidRequest(
+                    "date_from must not be after date_to".to_string(),
+                ));
+            }
+        }
+
+        Ok(SearchParams {
+            query: params.query,
+            max_results: params.max_results,
+            min_confidence: params.min_confidence,
+            filter: SearchFilter {
+                include_types,
+                date_from: params.date_from,
+                date_to: params.date_to,
+                facets,
+            },
+            snippets,
+            auto_correct: params.auto_correct,
+        })
+    }
+
     /// Validate email format.
     fn validate_email(email: &str) -> Result<(), String> {
         if !email.contains('@') || email.len() < 3 {
@@ -99,6 +317,50 @@ impl ContactServiceImpl {
         }
         Ok(())
     }
+
+    /// Validate enrichment parameters and convert them for the enrichment tools.
+    fn to_enrich_params(params: ContactEnrichParams) -> DexApiResult<EnrichContactParams> {
+        // Validate contact ID
+        Self::validate_contact_id(&params.contact_id)
+            .map_err(crate::error::DexApiError::InvalidRequest)?;
+
+        // Validate email if provided
+        if let Some(ref email_val) = params.email {
+            Self::validate_email(email_val).map_err(crate::error::DexApiError::InvalidRequest)?;
+        }
+
+        // Convert social_profiles from strings to SocialProfile objects
+        let social_profiles = params.social_profiles.map(|profiles| {
+            profiles
+                .into_iter()
+                .map(|url| SocialProfile {
+                    profile_type: "unknown".to_string(), // Will be inferred from URL
+                    url,
+                    username: None,
+                })
+                .collect()
+        });
+
+        Ok(EnrichContactParams {
+            contact_id: params.contact_id,
+            first_name: None,
+            last_name: None,
+            email: params.email,
+            phone: params.phone,
+            company: params.company,
+            title: params.title,
+            website: None,
+            location: None,
+            birthday: None,
+            notes: params.notes,
+            additional_emails: None,
+            additional_phones: None,
+            tags: params.tags,
+            social_profiles,
+            expected_updated_at: params.expected_updated_at,
+            merge_on_conflict: params.merge_on_conflict,
+        })
+    }
 }
 
 impl ContactServiceImpl {
@@ -118,51 +380,73 @@ impl ContactServiceImpl {
 
 #[async_trait]
 impl ContactService for ContactServiceImpl {
-    async fn search_full_text(
-        &self,
-        query: String,
-        max_results: Option<usize>,
-        min_confidence: Option<u8>,
-    ) -> DexApiResult<SearchResponse> {
-        // Validate query
-        Self::validate_search_query(&query).map_err(crate::error::DexApiError::InvalidRequest)?;
-
-        let search_params = SearchParams {
-            query,
-            max_results,
-            min_confidence,
-        };
-
+    async fn search_full_text(&self, params: ContactSearchParams) -> DexApiResult<SearchResponse> {
+        let search_params = Self::to_search_params(params)?;
         self.search_tools.search_full_text(search_params).await
     }
 
-    async fn find_contact(
+    async fn find_similar_contacts(
         &self,
-        name: Option<String>,
-        email: Option<String>,
-        phone: Option<String>,
-        social_url: Option<String>,
-        company: Option<String>,
-    ) -> DexApiResult<FindContactResponse> {
+        contact_id: &str,
+        k: Option<usize>,
+    ) -> DexApiResult<Vec<SimilarContact>> {
+        Self::validate_contact_id(contact_id).map_err(crate::error::DexApiError::InvalidRequest)?;
+        let k = k.unwrap_or(DEFAULT_SIMILAR_CONTACTS);
+        if !(1..=50).contains(&k) {
+            return Err(crate::error::DexApiError::InvalidRequest(
+                "k must be between 1 and 50".to_string(),
+            ));
+        }
+
+        self.search_tools.find_similar_contacts(contact_id, k).await
+    }
+
+    async fn find_contact(&self, params: ContactFindParams) -> DexApiResult<FindContactResponse> {
         // Validate email if provided
-        if let Some(ref email_val) = email {
+        if let Some(ref email_val) = params.email {
             Self::validate_email(email_val).map_err(crate::error::DexApiError::InvalidRequest)?;
         }
 
         let find_params = FindContactParams {
-            name,
-            email,
-            phone,
-            social_url,
-            company,
+            name: params.name,
+            email: params.email,
+            phone: params.phone,
+            social_url: params.social_url,
+            company: params.company,
             max_results: Some(5),
             min_confidence: Some(30),
+            auto_correct: params.auto_correct,
+            explain: params.explain,
         };
 
-        let mut discovery = self.discovery_tools.write().await;
+        let discovery = self.discovery_tools.read().await;
         discovery.find_contact(find_params).await
     }
 
+    async fn find_duplicate_contacts(
+        &self,
+        min_confidence: Option<u8>,
+        max_clusters: Option<usize>,
+    ) -> DexApiResult<DuplicateReport> {
+        let min_confidence = min_confidence.unwrap_or(DEFAULT_DUPLICATE_CONFIDENCE);
+        if !(1..=100).contains(&min_confidence) {
+            return Err(crate::error::DexApiError::InvalidRequest(
+                "min_confidence must be between 1 and 100".to_string(),
+            ));
+        }
+        let max_clusters = max_clusters.unwrap_or(DEFAULT_MAX_CLUSTERS);
+        if !(1..=500).contains(&max_clusters) {
+            return Err(crate::error::DexApiError::InvalidRequest(
+                "max_clusters must be between 1 and 500".to_string(),
+            ));
+        }
+
+        let discovery = self.discovery_tools.read().await;
+        discovery
+            .find_duplicates(min_confidence, max_clusters)
+            .await
+    }
+
     async fn get_contact_details(&self, contact_id: &str) -> DexApiResult<Contact> {
         // Validate contact ID
         Self::validate_contact_id(contact_id).map_err(crate::error::DexApiError::InvalidRequest)?;
@@ -172,53 +456,27 @@ impl ContactService for ContactServiceImpl {
     }
 
     async fn enrich_contact(&self, params: ContactEnrichParams) -> DexApiResult<Contact> {
-        // Validate contact ID
-        Self::validate_contact_id(&params.contact_id)
-            .map_err(crate::error::DexApiError::InvalidRequest)?;
-
-        // Validate email if provided
-        if let Some(ref email_val) = params.email {
-            Self::validate_email(email_val).map_err(crate::error::DexApiError::InvalidRequest)?;
-        }
-
-        // Convert social_profiles from strings to SocialProfile objects
-        let social_profiles = params.social_profiles.map(|profiles| {
-            profiles
-                .into_iter()
-                .map(|url| SocialProfile {
-                    profile_type: "unknown".to_string(), // Will be inferred from URL
-                    url,
-                    username: None,
-                })
-                .collect()
-        });
-
-        let enrich_params = EnrichContactParams {
-            contact_id: params.contact_id.clone(),
-            first_name: None,
-            last_name: None,
-            email: params.email,
-            phone: params.phone,
-            company: params.company,
-            title: params.title,
-            website: None,
-            location: None,
-            birthday: None,
-            notes: params.notes,
-            additional_emails: None,
-            additional_phones: None,
-            tags: params.tags,
-            social_profiles,
-        };
+        let enrich_params = Self::to_enrich_params(params)?;
 
         let updated_contact = self.enrichment_tools.enrich_contact(enrich_params).await?;
 
-        // Invalidate discovery cache after enrichment
-        self.invalidate_cache().await;
+        // Invalidate discovery cache after enrichment, and update the search index in place
+        self.discovery_tools.write().await.invalidate_cache();
+        self.search_tools.upsert_contact(&updated_contact).await;
 
         Ok(updated_contact)
     }
 
+    async fn preview_enrich_contact(
+        &self,
+        params: ContactEnrichParams,
+    ) -> DexApiResult<ChangePreview<Contact>> {
+        let enrich_params = Self::to_enrich_params(params)?;
+        self.enrichment_tools
+            .preview_enrich_contact(enrich_params)
+            .await
+    }
+
     async fn invalidate_cache(&self) {
         let discovery = self.discovery_tools.write().await;
         discovery.invalidate_cache();
@@ -226,6 +484,10 @@ impl ContactService for ContactServiceImpl {
         // Also invalidate search cache
         self.search_tools.invalidate_cache().await;
     }
+
+    async fn flush_cache(&self) {
+        self.search_tools.flush_snapshot().await;
+    }
 }
 
 #[cfg(test)]
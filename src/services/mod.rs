@@ -9,7 +9,9 @@ mod history_service;
 mod note_service;
 mod reminder_service;
 
-pub use contact_service::{ContactEnrichParams, ContactService, ContactServiceImpl};
+pub use contact_service::{
+    ContactEnrichParams, ContactFindParams, ContactSearchParams, ContactService, ContactServiceImpl,
+};
 pub use history_service::{HistoryService, HistoryServiceImpl};
 pub use note_service::{NoteService, NoteServiceImpl};
 pub use reminder_service::{ReminderService, ReminderServiceImpl, ReminderStatus};
@@ -5,7 +5,8 @@
 use crate::error::DexApiResult;
 use crate::models::Note;
 use crate::tools::{
-    ContactEnrichmentTools, CreateNoteParams, HistoryFilterParams, RelationshipHistoryTools,
+    ChangePreview, ContactEnrichmentTools, CreateNoteParams, HistoryFilterParams,
+    RelationshipHistoryTools, SearchTools,
 };
 use async_trait::async_trait;
 use std::sync::Arc;
@@ -28,12 +29,21 @@ pub trait NoteService: Send + Sync {
         content: String,
         tags: Option<Vec<String>>,
     ) -> DexApiResult<Note>;
+
+    /// Preview the note `create_note` would create without writing it.
+    async fn preview_note(
+        &self,
+        contact_id: String,
+        content: String,
+        tags: Option<Vec<String>>,
+    ) -> DexApiResult<ChangePreview<Note>>;
 }
 
 /// Default implementation of NoteService.
 pub struct NoteServiceImpl {
     history_tools: Arc<RelationshipHistoryTools>,
     enrichment_tools: Arc<ContactEnrichmentTools>,
+    search_tools: SearchTools,
 }
 
 /// Validation helper functions.
@@ -59,17 +69,43 @@ impl NoteServiceImpl {
         }
         Ok(())
     }
+
+    /// Validate note input and build the creation parameters.
+    fn to_note_params(
+        contact_id: String,
+        content: String,
+        tags: Option<Vec<String>>,
+    ) -> DexApiResult<CreateNoteParams> {
+        // Validate contact ID
+        Self::validate_contact_id(&contact_id)
+            .map_err(crate::error::DexApiError::InvalidRequest)?;
+
+        // Validate note content
+        Self::validate_note_content(&content).map_err(crate::error::DexApiError::InvalidRequest)?;
+
+        Ok(CreateNoteParams {
+            contact_id,
+            content,
+            tags,
+            source: Some("mcp".to_string()),
+        })
+    }
 }
 
 impl NoteServiceImpl {
     /// Create a new note service.
+    ///
+    /// `search_tools` should share its cache with the contact service, so
+    /// that new notes are searchable immediately.
     pub fn new(
         history_tools: Arc<RelationshipHistoryTools>,
         enrichment_tools: Arc<ContactEnrichmentTools>,
+        search_tools: SearchTools,
     ) -> Self {
         Self {
             history_tools,
             enrichment_tools,
+            search_tools,
         }
     }
 }
@@ -103,21 +139,22 @@ impl NoteService for NoteServiceImpl {
         content: String,
         tags: Option<Vec<String>>,
     ) -> DexApiResult<Note> {
-        // Validate contact ID
-        Self::validate_contact_id(&contact_id)
-            .map_err(crate::error::DexApiError::InvalidRequest)?;
+        let note_params = Self::to_note_params(contact_id, content, tags)?;
+        let note = self.enrichment_tools.add_contact_note(note_params).await?;
 
-        // Validate note content
-        Self::validate_note_content(&content).map_err(crate::error::DexApiError::InvalidRequest)?;
+        self.search_tools.upsert_note(&note).await;
 
-        let note_params = CreateNoteParams {
-            contact_id,
-            content,
-            tags,
-            source: Some("mcp".to_string()),
-        };
+        Ok(note)
+    }
 
-        self.enrichment_tools.add_contact_note(note_params).await
+    async fn preview_note(
+        &self,
+        contact_id: String,
+        content: String,
+        tags: Option<Vec<String>>,
+    ) -> DexApiResult<ChangePreview<Note>> {
+        let note_params = Self::to_note_params(contact_id, content, tags)?;
+        Ok(self.enrichment_tools.preview_contact_note(note_params))
     }
 }
 
@@ -149,12 +186,13 @@ mod tests {
             reminder_repo.clone(),
         ));
         let enrichment_tools = Arc::new(ContactEnrichmentTools::new(
-            contact_repo,
-            note_repo,
-            reminder_repo,
+            contact_repo.clone(),
+            note_repo.clone(),
+            reminder_repo.clone(),
         ));
+        let search_tools = SearchTools::new(contact_repo, note_repo, reminder_repo, 300);
 
-        let _service = NoteServiceImpl::new(history_tools, enrichment_tools);
+        let _service = NoteServiceImpl::new(history_tools, enrichment_tools, search_tools);
         // Just verify it constructs without panic
     }
 }
@@ -5,7 +5,8 @@
 use crate::error::DexApiResult;
 use crate::models::Reminder;
 use crate::tools::{
-    ContactEnrichmentTools, CreateReminderParams, HistoryFilterParams, RelationshipHistoryTools,
+    ChangePreview, ContactEnrichmentTools, CreateReminderParams, HistoryFilterParams,
+    RelationshipHistoryTools, SearchTools,
 };
 use async_trait::async_trait;
 use std::str::FromStr;
@@ -54,12 +55,22 @@ pub trait ReminderService: Send + Sync {
         due_date: String,
         priority: Option<String>,
     ) -> DexApiResult<Reminder>;
+
+    /// Preview the reminder `create_reminder` would create without writing it.
+    async fn preview_reminder(
+        &self,
+        contact_id: String,
+        text: String,
+        due_date: String,
+        priority: Option<String>,
+    ) -> DexApiResult<ChangePreview<Reminder>>;
 }
 
 /// Default implementation of ReminderService.
 pub struct ReminderServiceImpl {
     history_tools: Arc<RelationshipHistoryTools>,
     enrichment_tools: Arc<ContactEnrichmentTools>,
+    search_tools: SearchTools,
 }
 
 /// Validation helper functions.
@@ -94,17 +105,48 @@ impl ReminderServiceImpl {
         }
         Ok(())
     }
+
+    /// Validate reminder input and build the creation parameters.
+    fn to_reminder_params(
+        contact_id: String,
+        text: String,
+        due_date: String,
+        priority: Option<String>,
+    ) -> DexApiResult<CreateReminderParams> {
+        // Validate contact ID
+        Self::validate_contact_id(&contact_id)
+            .map_err(crate::error::DexApiError::InvalidRequest)?;
+
+        // Validate reminder text
+        Self::validate_reminder_text(&text).map_err(crate::error::DexApiError::InvalidRequest)?;
+
+        // Validate due date format
+        Self::validate_date_format(&due_date).map_err(crate::error::DexApiError::InvalidRequest)?;
+
+        Ok(CreateReminderParams {
+            contact_id,
+            text,
+            due_date,
+            tags: None,
+            priority,
+        })
+    }
 }
 
 impl ReminderServiceImpl {
     /// Create a new reminder service.
+    ///
+    /// `search_tools` should share its cache with the contact service, so
+    /// that new reminders are searchable immediately.
     pub fn new(
         history_tools: Arc<RelationshipHistoryTools>,
         enrichment_tools: Arc<ContactEnrichmentTools>,
+        search_tools: SearchTools,
     ) -> Self {
         Self {
             history_tools,
             enrichment_tools,
+            search_tools,
         }
     }
 }
@@ -159,27 +201,28 @@ impl ReminderService for ReminderServiceImpl {
         due_date: String,
         priority: Option<String>,
     ) -> DexApiResult<Reminder> {
-        // Validate contact ID
-        Self::validate_contact_id(&contact_id)
-            .map_err(crate::error::DexApiError::InvalidRequest)?;
-
-        // Validate reminder text
-        Self::validate_reminder_text(&text).map_err(crate::error::DexApiError::InvalidRequest)?;
+        let reminder_params = Self::to_reminder_params(contact_id, text, due_date, priority)?;
+        let reminder = self
+            .enrichment_tools
+            .create_contact_reminder(reminder_params)
+            .await?;
 
-        // Validate due date format
-        Self::validate_date_format(&due_date).map_err(crate::error::DexApiError::InvalidRequest)?;
+        self.search_tools.upsert_reminder(&reminder).await;
 
-        let reminder_params = CreateReminderParams {
-            contact_id,
-            text,
-            due_date,
-            tags: None,
-            priority,
-        };
+        Ok(reminder)
+    }
 
-        self.enrichment_tools
-            .create_contact_reminder(reminder_params)
-            .await
+    async fn preview_reminder(
+        &self,
+        contact_id: String,
+        text: String,
+        due_date: String,
+        priority: Option<String>,
+    ) -> DexApiResult<ChangePreview<Reminder>> {
+        let reminder_params = Self::to_reminder_params(contact_id, text, due_date, priority)?;
+        Ok(self
+            .enrichment_tools
+            .preview_contact_reminder(reminder_params))
     }
 }
 
@@ -211,12 +254,13 @@ mod tests {
             reminder_repo.clone(),
         ));
         let enrichment_tools = Arc::new(ContactEnrichmentTools::new(
-            contact_repo,
-            note_repo,
-            reminder_repo,
+            contact_repo.clone(),
+            note_repo.clone(),
+            reminder_repo.clone(),
         ));
+        let search_tools = SearchTools::new(contact_repo, note_repo, reminder_repo, 300);
 
-        let _service = ReminderServiceImpl::new(history_tools, enrichment_tools);
+        let _service = ReminderServiceImpl::new(history_tools, enrichment_tools, search_tools);
         // Just verify it constructs without panic
     }
 
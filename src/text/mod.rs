@@ -0,0 +1,8 @@
+//! Text normalization shared by contact matching and full-text search.
+//!
+//! Both sides fold text the same way, so a name matches regardless of
+//! accents, case or Unicode normalization form.
+
+pub mod normalize;
+
+pub use normalize::{fold, normalize, token_spans, tokenize};
@@ -0,0 +1,213 @@
+//! Unicode normalization and tokenization.
+//!
+//! Text is compared in a folded form: compatibility-decomposed (NFKD), with
+//! combining marks removed and case folded, so that "José Müller" and
+//! "jose muller" compare equal. Tokenization walks grapheme clusters, so a
+//! base letter is never split from its accents, and treats each CJK
+//! ideograph or kana and each emoji as a token of its own.
+
+use std::ops::Range;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Fold text for comparison: NFKD, strip diacritics, fold case.
+///
+/// Latin letters that have no decomposition (such as "ß", "ø" or "ł") are
+/// mapped to their closest ASCII spelling. The result is recomposed (NFC),
+/// so scripts whose letters decompose without marks, like Hangul, keep
+/// their usual form.
+pub fn fold(text: &str) -> String {
+    let mut folded = String::with_capacity(text.len());
+    for c in text.nfkd().filter(|c| !is_combining_mark(*c)) {
+        match fold_letter(c) {
+            Some(replacement) => folded.push_str(replacement),
+            None => folded.extend(c.to_lowercase()),
+        }
+    }
+    if folded.is_ascii() {
+        folded
+    } else {
+        folded.nfc().collect()
+    }
+}
+
+/// Fold text and collapse whitespace runs to single spaces.
+pub fn normalize(text: &str) -> String {
+    fold(text).split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Split text into folded tokens.
+///
+/// See [`token_spans`] for where tokens start and end.
+pub fn tokenize(text: &str) -> Vec<String> {
+    token_spans(text)
+        .into_iter()
+        .map(|span| fold(&text[span]))
+        .filter(|token| !token.is_empty())
+        .collect()
+}
+
+/// Byte ranges of the tokens of `text`.
+///
+/// A token is a run of grapheme clusters starting with a letter or digit,
+/// or a single ideograph, kana or emoji cluster. Everything else separates
+/// tokens. Ranges always fall on grapheme boundaries.
+pub fn token_spans(text: &str) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut run: Option<usize> = None;
+
+    for (start, grapheme) in text.grapheme_indices(true) {
+        let end = start + grapheme.len();
+        match classify(grapheme) {
+            Grapheme::Word => {
+                run.get_or_insert(start);
+            }
+            Grapheme::Single => {
+                if let Some(run_start) = run.take() {
+                    spans.push(run_start..start);
+                }
+                spans.push(start..end);
+            }
+            Grapheme::Separator => {
+                if let Some(run_start) = run.take() {
+                    spans.push(run_start..start);
+                }
+            }
+        }
+    }
+    if let Some(run_start) = run {
+        spans.push(run_start..text.len());
+    }
+    spans
+}
+
+/// How a grapheme cluster takes part in tokenization.
+enum Grapheme {
+    /// Part of a word (letters and digits)
+    Word,
+    /// A token by itself (ideographs, kana, emoji)
+    Single,
+    /// Between tokens (whitespace, punctuation, symbols)
+    Separator,
+}
+
+fn classify(grapheme: &str) -> Grapheme {
+    let Some(base) = grapheme.chars().next() else {
+        return Grapheme::Separator;
+    };
+    if is_ideographic(base) || is_pictographic(base) {
+        Grapheme::Single
+    } else if base.is_alphanumeric() {
+        Grapheme::Word
+    } else {
+        Grapheme::Separator
+    }
+}
+
+/// CJK ideographs and Japanese kana, which are written without spaces.
+fn is_ideographic(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'     // Hiragana, Katakana
+        | '\u{3400}'..='\u{4DBF}'   // CJK Extension A
+        | '\u{4E00}'..='\u{9FFF}'   // CJK Unified Ideographs
+        | '\u{F900}'..='\u{FAFF}'   // CJK Compatibility Ideographs
+        | '\u{FF66}'..='\u{FF9D}'   // Halfwidth Katakana
+        | '\u{20000}'..='\u{2FA1F}' // CJK Extensions B-F, Compatibility Supplement
+    )
+}
+
+/// Emoji and other pictographs.
+fn is_pictographic(c: char) -> bool {
+    matches!(c,
+        '\u{1F000}'..='\u{1FAFF}'   // Mahjong tiles to Symbols and Pictographs Extended-A
+        | '\u{2600}'..='\u{27BF}'   // Miscellaneous Symbols, Dingbats
+        | '\u{2B00}'..='\u{2BFF}'   // Miscellaneous Symbols and Arrows
+    )
+}
+
+/// ASCII spellings of letters that NFKD leaves intact.
+fn fold_letter(c: char) -> Option<&'static str> {
+    Some(match c {
+        'ß' | 'ẞ' => "ss",
+        'Æ' | 'æ' => "ae",
+        'Œ' | 'œ' => "oe",
+        'Ø' | 'ø' => "o",
+        'Đ' | 'đ' | 'Ð' | 'ð' => "d",
+        'Ł' | 'ł' => "l",
+        'Þ' | 'þ' => "th",
+        'ı' => "i",
+        'ς' => "σ",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold_strips_diacritics_and_case() {
+        assert_eq!(fold("José Müller"), "jose muller");
+        assert_eq!(fold("FRANÇOIS"), "francois");
+        assert_eq!(fold("Straße"), "strasse");
+        assert_eq!(fold("Łukasz Øster"), "lukasz oster");
+        // Precomposed and decomposed forms fold alike
+        assert_eq!(fold("Zoe\u{0308}"), fold("Zoë"));
+        // Compatibility forms
+        assert_eq!(fold("ﬁle №１"), "file no1");
+    }
+
+    #[test]
+    fn test_fold_non_latin_scripts() {
+        assert_eq!(fold("Дмитрий Иванов"), "дмитрии иванов");
+        assert_eq!(fold("ΣΟΦΊΑ"), "σοφια");
+        assert_eq!(fold("山田太郎"), "山田太郎");
+        assert_eq!(fold("김민수"), "김민수");
+    }
+
+    #[test]
+    fn test_normalize_collapses_whitespace() {
+        assert_eq!(normalize("  José \t  Müller "), "jose muller");
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(tokenize("John Doe"), vec!["john", "doe"]);
+        assert_eq!(
+            tokenize("john.doe@example.com"),
+            vec!["john", "doe", "example", "com"]
+        );
+        assert_eq!(
+            tokenize("+1 (555) 123-4567"),
+            vec!["1", "555", "123", "4567"]
+        );
+        assert!(tokenize("  --  ").is_empty());
+    }
+
+    #[test]
+    fn test_tokenize_keeps_graphemes_together() {
+        // A decomposed accent stays with its letter and is then folded away
+        assert_eq!(
+            tokenize("Jose\u{0301} Mu\u{0308}ller"),
+            vec!["jose", "muller"]
+        );
+        assert_eq!(tokenize("Олег Петров"), vec!["олег", "петров"]);
+    }
+
+    #[test]
+    fn test_tokenize_cjk_and_emoji() {
+        assert_eq!(tokenize("山田太郎"), vec!["山", "田", "太", "郎"]);
+        assert_eq!(tokenize("Kim 김민수"), vec!["kim", "김민수"]);
+        assert_eq!(tokenize("Alex🚀Rivera"), vec!["alex", "🚀", "rivera"]);
+        // A ZWJ sequence is a single grapheme and a single token
+        assert_eq!(tokenize("👩‍💻 Dana"), vec!["👩\u{200d}💻", "dana"]);
+    }
+
+    #[test]
+    fn test_token_spans_are_byte_ranges_of_the_original() {
+        let text = "Zoë, 東京";
+        let tokens: Vec<&str> = token_spans(text).into_iter().map(|s| &text[s]).collect();
+        assert_eq!(tokens, vec!["Zoë", "東", "京"]);
+    }
+}
@@ -1,23 +1,57 @@
 //! Contact discovery tools.
 //!
 //! Provides intelligent contact search with fuzzy matching, exact matching,
-//! and result ranking. Includes caching for performance.
-
-use crate::cache::TimedCache;
-use crate::error::DexApiResult;
-use crate::matching::{ContactMatcher, ContactQuery, MatchResult};
+//! and result ranking. Includes caching for performance; the contact cache is
+//! refreshed in the background.
+
+use crate::cache::RefreshingCache;
+use crate::domain::PhoneRegion;
+use crate::error::{DexApiError, DexApiResult};
+use crate::matching::{
+    find_duplicates, ContactMatcher, ContactQuery, DuplicateReport, MatchResult, MatchWeights,
+    NameIndex, NicknameMap, PARALLEL_MATCH_THRESHOLD,
+};
 use crate::models::Contact;
 use crate::repositories::ContactRepository;
-use std::sync::Arc;
+use crate::search::{SpellingDictionary, SpellingSuggestion};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
 
 /// Contact discovery tools for finding and retrieving contacts.
 pub struct ContactDiscoveryTools {
     contact_repo: Arc<dyn ContactRepository>,
-    contact_cache: Arc<TimedCache<String, Vec<Contact>>>,
-    matcher: ContactMatcher,
+    contact_cache: RefreshingCache<Arc<Vec<Contact>>>,
+    /// Matcher state, locked by the blocking task matching runs on
+    matching: Arc<Mutex<Matching>>,
     cache_ttl_secs: u64,
 }
 
+/// The contact matcher with its warm name index.
+#[derive(Default)]
+struct Matching {
+    matcher: ContactMatcher,
+    /// Warm nucleo index over the cached contacts, once there are enough
+    name_index: Option<NameIndex>,
+}
+
+/// Caching and matching configuration for [`ContactDiscoveryTools`].
+#[derive(Debug, Clone)]
+pub struct DiscoveryConfig {
+    /// Contact cache time-to-live in seconds
+    pub cache_ttl_secs: u64,
+
+    /// Nicknames used to match given names
+    pub nicknames: Arc<NicknameMap>,
+
+    /// Region assumed for phone numbers without a country code, when
+    /// matching them and when merging them into contacts
+    pub phone_region: PhoneRegion,
+
+    /// How much agreeing and contradicting signals move a match's
+    /// confidence
+    pub match_weights: MatchWeights,
+}
+
 /// Parameters for finding a contact.
 #[derive(Debug, Clone)]
 pub struct FindContactParams {
@@ -41,6 +75,12 @@ pub struct FindContactParams {
 
     /// Minimum confidence threshold (0-100, default: 30)
     pub min_confidence: Option<u8>,
+
+    /// Retry once with the top spelling suggestion if nothing matches the name
+    pub auto_correct: bool,
+
+    /// Explain the confidence of each match
+    pub explain: bool,
 }
 
 impl Default for FindContactParams {
@@ -53,6 +93,8 @@ impl Default for FindContactParams {
             company: None,
             max_results: Some(5),
             min_confidence: Some(30),
+            auto_correct: false,
+            explain: false,
         }
     }
 }
@@ -65,6 +107,13 @@ pub struct FindContactResponse {
 
     /// Whether the results came from cache
     pub from_cache: bool,
+
+    /// Corrected spellings of the name, best first, if nothing matched it
+    pub suggestions: Vec<SpellingSuggestion>,
+
+    /// The suggestion matched instead of the name, if auto-correct was
+    /// requested and used
+    pub corrected_name: Option<String>,
 }
 
 impl ContactDiscoveryTools {
@@ -76,12 +125,37 @@ impl ContactDiscoveryTools {
     pub fn new(contact_repo: Arc<dyn ContactRepository>, cache_ttl_secs: u64) -> Self {
         Self {
             contact_repo,
-            contact_cache: Arc::new(TimedCache::new(cache_ttl_secs)),
-            matcher: ContactMatcher::new(),
+            contact_cache: RefreshingCache::new(Duration::from_secs(cache_ttl_secs)),
+            matching: Arc::default(),
             cache_ttl_secs,
         }
     }
 
+    /// Match names with `nicknames` instead of the built-in nickname table.
+    pub fn with_nicknames(self, nicknames: Arc<NicknameMap>) -> Self {
+        self.configure_matcher(|matcher| matcher.with_nicknames(nicknames))
+    }
+
+    /// Read phone numbers without a country code in `region` instead of the
+    /// US.
+    pub fn with_phone_region(self, region: PhoneRegion) -> Self {
+        self.configure_matcher(|matcher| matcher.with_phone_region(region))
+    }
+
+    /// Combine match signals with `weights` instead of the default weights.
+    pub fn with_match_weights(self, weights: MatchWeights) -> Self {
+        self.configure_matcher(|matcher| matcher.with_weights(weights))
+    }
+
+    fn configure_matcher(self, configure: impl FnOnce(ContactMatcher) -> ContactMatcher) -> Self {
+        {
+            let mut matching = self.matching.lock().unwrap_or_else(PoisonError::into_inner);
+            let matcher = std::mem::take(&mut matching.matcher);
+            matching.matcher = configure(matcher);
+        }
+        self
+    }
+
     /// Find contacts using intelligent matching.
     ///
     /// This method searches for contacts using:
@@ -92,6 +166,11 @@ impl ContactDiscoveryTools {
     /// - Company-based confidence boosting
     ///
     /// Results are ranked by confidence score and cached for performance.
+    /// If no contact matches a name, the response suggests spellings taken
+    /// from contact names, and with `params.auto_correct` the top suggestion
+    /// is matched instead.
+    ///
+    /// Matching runs on a blocking thread, as it scores every contact.
     ///
     /// # Arguments
     /// * `params` - Search parameters
@@ -99,56 +178,125 @@ impl ContactDiscoveryTools {
     /// # Returns
     /// A list of matching contacts with confidence scores
     pub async fn find_contact(
-        &mut self,
+        &self,
         params: FindContactParams,
     ) -> DexApiResult<FindContactResponse> {
         // Get all contacts (with caching)
         let contacts = self.get_cached_contacts().await?;
 
+        // Build query for the matcher
+        let mut query = ContactQuery {
+            name: params.name,
+            email: params.email,
+            phone: params.phone,
+            company: params.company,
+            social_url: params.social_url,
+            explain: params.explain,
+        };
+
+        let max_results = params.max_results.unwrap_or(5);
+        let min_confidence = params.min_confidence.unwrap_or(30);
+
         // If email is provided and we have no cache, try direct email search first
-        let from_cache = self.contact_cache.contains_key(&"all_contacts".to_string());
-        if !from_cache && params.email.is_some() {
-            let results = self
-                .contact_repo
-                .search_by_email(params.email.as_ref().unwrap(), 10, 0)
+        let from_cache = self.contact_cache.get().is_some();
+        if let Some(email) = query.email.as_ref().filter(|_| !from_cache) {
+            let results = self.contact_repo.search_by_email(email, 10, 0).await?;
+            // Score the contacts found by email against the rest of the query too
+            let matches = self
+                .match_contacts(&query, &Arc::new(results), max_results, min_confidence)
                 .await?;
-            if !results.is_empty() {
-                // Found by email, return as high-confidence matches
+            if !matches.is_empty() {
                 return Ok(FindContactResponse {
-                    matches: results
-                        .into_iter()
-                        .map(|contact| MatchResult {
-                            contact,
-                            confidence: 100,
-                            match_type: crate::matching::MatchType::ExactEmail,
-                        })
-                        .collect(),
+                    matches,
                     from_cache: false,
+                    suggestions: Vec::new(),
+                    corrected_name: None,
                 });
             }
         }
 
         // Perform fuzzy matching
-        let max_results = params.max_results.unwrap_or(5);
-        let min_confidence = params.min_confidence.unwrap_or(30);
-
-        // Build query for the matcher
-        let query = ContactQuery {
-            name: params.name,
-            email: params.email,
-            phone: params.phone,
-            company: params.company,
-            social_url: params.social_url,
-        };
-
-        let matches = self
-            .matcher
-            .find_matches(&query, &contacts, max_results, min_confidence);
+        let mut matches = self
+            .match_contacts(&query, &contacts, max_results, min_confidence)
+            .await?;
+
+        // Suggest names when nothing matched, and retry once if asked to
+        let mut suggestions = Vec::new();
+        let mut corrected_name = None;
+        if let Some(name) = query.name.as_deref().filter(|_| matches.is_empty()) {
+            suggestions = suggest_names(name, &contacts);
+            if let Some(suggestion) = suggestions.first().filter(|_| params.auto_correct) {
+                query.name = Some(suggestion.text.clone());
+                matches = self
+                    .match_contacts(&query, &contacts, max_results, min_confidence)
+                    .await?;
+                corrected_name = query.name;
+            }
+        }
 
         Ok(FindContactResponse {
             matches,
             from_cache,
+            suggestions,
+            corrected_name,
+        })
+    }
+
+    /// Find clusters of contacts that are likely the same person.
+    ///
+    /// Only contacts sharing an email address, phone number or name word are
+    /// compared, so this scales to large address books.
+    ///
+    /// # Arguments
+    /// * `min_confidence` - Minimum confidence for two contacts to be duplicates
+    /// * `max_clusters` - Maximum number of clusters to return
+    pub async fn find_duplicates(
+        &self,
+        min_confidence: u8,
+        max_clusters: usize,
+    ) -> DexApiResult<DuplicateReport> {
+        let contacts = self.get_cached_contacts().await?;
+        self.with_matching(move |matching| {
+            find_duplicates(
+                &mut matching.matcher,
+                &contacts,
+                min_confidence,
+                max_clusters,
+            )
+        })
+        .await
+    }
+
+    /// Match `contacts` against a query on a blocking thread.
+    async fn match_contacts(
+        &self,
+        query: &ContactQuery,
+        contacts: &Arc<Vec<Contact>>,
+        max_results: usize,
+        min_confidence: u8,
+    ) -> DexApiResult<Vec<MatchResult>> {
+        let query = query.clone();
+        let contacts = contacts.clone();
+        self.with_matching(move |matching| {
+            matching.match_contacts(&query, &contacts, max_results, min_confidence)
         })
+        .await
+    }
+
+    /// Run `f` with the matcher on a blocking thread.
+    ///
+    /// Matching is CPU-bound and, for large contact lists, waits for nucleo's
+    /// workers, so it must not run on the async runtime's threads.
+    async fn with_matching<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&mut Matching) -> T + Send + 'static,
+    ) -> DexApiResult<T> {
+        let matching = self.matching.clone();
+        tokio::task::spawn_blocking(move || {
+            f(&mut matching.lock().unwrap_or_else(PoisonError::into_inner))
+        })
+        .await
+        .map_err(|e| DexApiError::Other(format!("Matching task failed: {}", e)))
     }
 
     /// Get detailed information about a specific contact.
@@ -165,38 +313,18 @@ impl ContactDiscoveryTools {
     /// Get all contacts from cache or API.
     ///
     /// This method maintains a cache of all contacts to improve performance
-    /// of repeated searches.
-    async fn get_cached_contacts(&self) -> DexApiResult<Vec<Contact>> {
-        let cache_key = "all_contacts".to_string();
-
-        // Check cache first
-        if let Some(contacts) = self.contact_cache.get(&cache_key) {
-            return Ok(contacts);
-        }
-
-        // Cache miss - fetch from repository
-        // Fetch in pages of 100 until we have all contacts
-        let mut all_contacts = Vec::new();
-        let mut offset = 0;
-        const PAGE_SIZE: usize = 100;
-
-        loop {
-            let contacts = self.contact_repo.list(PAGE_SIZE, offset).await?;
-            let count = contacts.len();
-            all_contacts.extend(contacts);
-
-            if count < PAGE_SIZE {
-                // Last page
-                break;
-            }
-
-            offset += PAGE_SIZE;
-        }
-
-        // Store in cache
-        self.contact_cache.insert(cache_key, all_contacts.clone());
-
-        Ok(all_contacts)
+    /// of repeated searches. An expired cache keeps being used while it is
+    /// refreshed in the background.
+    async fn get_cached_contacts(&self) -> DexApiResult<Arc<Vec<Contact>>> {
+        let contact_repo = self.contact_repo.clone();
+        let (contacts, _) = self
+            .contact_cache
+            .get_or_build(move || {
+                let contact_repo = contact_repo.clone();
+                async move { fetch_all_contacts(contact_repo).await.map(Arc::new) }
+            })
+            .await?;
+        Ok(contacts)
     }
 
     /// Invalidate the contact cache.
@@ -204,7 +332,7 @@ impl ContactDiscoveryTools {
     /// This should be called after any contact modifications to ensure
     /// fresh data is fetched on the next search.
     pub fn invalidate_cache(&self) {
-        self.contact_cache.remove(&"all_contacts".to_string());
+        self.contact_cache.invalidate();
     }
 
     /// Get the current cache TTL in seconds.
@@ -213,6 +341,68 @@ impl ContactDiscoveryTools {
     }
 }
 
+impl Matching {
+    /// Match `contacts` against a query.
+    ///
+    /// Lists of at least [`PARALLEL_MATCH_THRESHOLD`] contacts are matched
+    /// through a warm [`NameIndex`], which is rebuilt whenever the contact
+    /// cache is.
+    fn match_contacts(
+        &mut self,
+        query: &ContactQuery,
+        contacts: &Arc<Vec<Contact>>,
+        max_results: usize,
+        min_confidence: u8,
+    ) -> Vec<MatchResult> {
+        if contacts.len() < PARALLEL_MATCH_THRESHOLD {
+            self.name_index = None;
+            return self
+                .matcher
+                .find_matches(query, contacts, max_results, min_confidence);
+        }
+
+        let index = match &mut self.name_index {
+            Some(index) if index.is_for(contacts) => index,
+            slot => slot.insert(NameIndex::new(contacts.clone(), None)),
+        };
+        self.matcher
+            .find_matches_indexed(query, index, max_results, min_confidence)
+    }
+}
+
+/// Spelling suggestions for a name, from the names of `contacts`.
+fn suggest_names(name: &str, contacts: &[Contact]) -> Vec<SpellingSuggestion> {
+    let mut dictionary = SpellingDictionary::new();
+    for contact in contacts {
+        dictionary.add_text(&contact.name);
+    }
+    dictionary.suggest(name)
+}
+
+/// Fetch all contacts in pages of 100.
+async fn fetch_all_contacts(
+    contact_repo: Arc<dyn ContactRepository>,
+) -> DexApiResult<Vec<Contact>> {
+    const PAGE_SIZE: usize = 100;
+    let mut all_contacts = Vec::new();
+    let mut offset = 0;
+
+    loop {
+        let contacts = contact_repo.list(PAGE_SIZE, offset).await?;
+        let count = contacts.len();
+        all_contacts.extend(contacts);
+
+        if count < PAGE_SIZE {
+            // Last page
+            break;
+        }
+
+        offset += PAGE_SIZE;
+    }
+
+    Ok(all_contacts)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -261,18 +451,12 @@ mod tests {
         let tools = ContactDiscoveryTools::new(contact_repo, cache_ttl_secs);
 
         // Insert something into cache
-        tools
-            .contact_cache
-            .insert("all_contacts".to_string(), vec![]);
-        assert!(tools
-            .contact_cache
-            .contains_key(&"all_contacts".to_string()));
+        tools.contact_cache.insert(Arc::new(vec![]), Duration::ZERO);
+        assert!(tools.contact_cache.get().is_some());
 
         // Invalidate
         tools.invalidate_cache();
-        assert!(!tools
-            .contact_cache
-            .contains_key(&"all_contacts".to_string()));
+        assert!(tools.contact_cache.get().is_none());
     }
 
     // Note: More comprehensive tests would require mocking the DexClient
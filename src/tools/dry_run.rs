@@ -0,0 +1,150 @@
+//! Dry-run previews for mutating tools.
+//!
+//! A preview holds the record as it is now, the record as it would be after
+//! the change, and a field-level diff between the two. Nothing is written.
+
+use crate::audit::AuditOperation;
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// A single field that would change.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    /// Serialized field name
+    pub field: String,
+
+    /// Current value (absent if the field is unset or the record is new)
+    pub before: Option<serde_json::Value>,
+
+    /// Value after the change (absent if the field is cleared or the record deleted)
+    pub after: Option<serde_json::Value>,
+}
+
+/// The outcome a mutating tool would have, without performing it.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangePreview<T> {
+    /// Always true; lets callers tell previews apart from real results
+    pub dry_run: bool,
+
+    /// Kind of write that would be performed
+    pub operation: AuditOperation,
+
+    /// The record as it is now (absent for creates)
+    pub current: Option<T>,
+
+    /// The record as it would be (absent for deletes)
+    pub proposed: Option<T>,
+
+    /// Fields that differ between `current` and `proposed`
+    pub changes: Vec<FieldChange>,
+}
+
+impl<T: Serialize> ChangePreview<T> {
+    /// Preview creating `proposed`.
+    pub fn create(proposed: T) -> Self {
+        Self::new(AuditOperation::Create, None, Some(proposed))
+    }
+
+    /// Preview replacing `current` with `proposed`.
+    pub fn update(current: T, proposed: T) -> Self {
+        Self::new(AuditOperation::Update, Some(current), Some(proposed))
+    }
+
+    /// Preview deleting `current`.
+    pub fn delete(current: T) -> Self {
+        Self::new(AuditOperation::Delete, Some(current), None)
+    }
+
+    fn new(operation: AuditOperation, current: Option<T>, proposed: Option<T>) -> Self {
+        let changes = diff_fields(current.as_ref(), proposed.as_ref());
+        Self {
+            dry_run: true,
+            operation,
+            current,
+            proposed,
+            changes,
+        }
+    }
+}
+
+/// Compare the serialized top-level fields of two records.
+///
+/// Fields are compared as they would be sent to Dex, so computed fields that
+/// are never serialized do not show up in the diff.
+pub fn diff_fields<T: Serialize>(current: Option<&T>, proposed: Option<&T>) -> Vec<FieldChange> {
+    let before = to_object(current);
+    let after = to_object(proposed);
+
+    let fields: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    fields
+        .into_iter()
+        .filter_map(|field| {
+            let old = before.get(field);
+            let new = after.get(field);
+            (old != new).then(|| FieldChange {
+                field: field.clone(),
+                before: old.cloned(),
+                after: new.cloned(),
+            })
+        })
+        .collect()
+}
+
+fn to_object<T: Serialize>(record: Option<&T>) -> serde_json::Map<String, serde_json::Value> {
+    match record.and_then(|r| serde_json::to_value(r).ok()) {
+        Some(serde_json::Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Contact;
+
+    #[test]
+    fn test_update_diff_lists_only_changed_fields() {
+        let current = Contact {
+            id: "c1".to_string(),
+            company: Some("Acme".to_string()),
+            tags: vec!["friend".to_string()],
+            ..Default::default()
+        };
+        let mut proposed = current.clone();
+        proposed.company = Some("Globex".to_string());
+        proposed.website = Some("https://globex.com".to_string());
+
+        let preview = ChangePreview::update(current, proposed);
+        assert!(preview.dry_run);
+        assert_eq!(preview.operation, AuditOperation::Update);
+
+        let fields: Vec<&str> = preview.changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, vec!["company", "website"]);
+        assert_eq!(preview.changes[0].before, Some("Acme".into()));
+        assert_eq!(preview.changes[1].before, None);
+        assert_eq!(preview.changes[1].after, Some("https://globex.com".into()));
+    }
+
+    #[test]
+    fn test_create_and_delete_diffs() {
+        let contact = Contact {
+            id: "c1".to_string(),
+            company: Some("Acme".to_string()),
+            ..Default::default()
+        };
+
+        let created = ChangePreview::create(contact.clone());
+        assert!(created.current.is_none());
+        assert!(created
+            .changes
+            .iter()
+            .all(|c| c.before.is_none() && c.after.is_some()));
+
+        let deleted = ChangePreview::delete(contact);
+        assert!(deleted.proposed.is_none());
+        assert!(deleted
+            .changes
+            .iter()
+            .all(|c| c.before.is_some() && c.after.is_none()));
+    }
+}
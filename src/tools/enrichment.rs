@@ -3,16 +3,24 @@
 //! Provides tools for updating contacts, adding notes, and creating reminders
 //! with smart data merging.
 
-use crate::error::DexApiResult;
+use crate::domain::{PhoneNumber, PhoneRegion};
+use crate::error::{DexApiError, DexApiResult};
 use crate::models::{Contact, Note, Reminder, SocialProfile};
 use crate::repositories::{ContactRepository, NoteRepository, ReminderRepository};
+use crate::tools::dry_run::{diff_fields, ChangePreview};
+use std::collections::{BTreeSet, HashSet};
 use std::sync::Arc;
 
+/// How many times `enrich_contact` re-merges onto a newer version of the
+/// contact before giving up with a conflict.
+const MAX_MERGE_RETRIES: usize = 3;
+
 /// Contact enrichment tools for updating contact data.
 pub struct ContactEnrichmentTools {
     contact_repo: Arc<dyn ContactRepository>,
     note_repo: Arc<dyn NoteRepository>,
     reminder_repo: Arc<dyn ReminderRepository>,
+    phone_region: PhoneRegion,
 }
 
 /// Parameters for enriching a contact.
@@ -44,6 +52,16 @@ pub struct EnrichContactParams {
 
     /// Social profiles (will be merged with existing)
     pub social_profiles: Option<Vec<SocialProfile>>,
+
+    /// Version (`updated_at`) of the contact as the caller last read it; if
+    /// set, the enrichment fails with a conflict when the contact has changed
+    /// since
+    pub expected_updated_at: Option<String>,
+
+    /// If the contact changed while enriching, merge this enrichment onto
+    /// the newer version instead of failing, as long as the two changes touch
+    /// different fields
+    pub merge_on_conflict: bool,
 }
 
 /// Parameters for creating a note.
@@ -97,9 +115,17 @@ impl ContactEnrichmentTools {
             contact_repo,
             note_repo,
             reminder_repo,
+            phone_region: PhoneRegion::default(),
         }
     }
 
+    /// Read phone numbers without a country code in `region` instead of the
+    /// US when telling added phones apart from existing ones.
+    pub fn with_phone_region(mut self, region: PhoneRegion) -> Self {
+        self.phone_region = region;
+        self
+    }
+
     /// Enrich a contact with new data.
     ///
     /// This method performs smart merging:
@@ -107,81 +133,81 @@ impl ContactEnrichmentTools {
     /// - Array fields (emails, phones, tags, social profiles): Merges with existing,
     ///   removing duplicates
     ///
+    /// The contact's `updated_at` is used as a version: it is re-checked just
+    /// before writing, and if another session changed the contact in between
+    /// the update fails with `DexApiError::Conflict`. With `merge_on_conflict`
+    /// set, the enrichment is instead re-applied to the newer version when
+    /// the two changes touch different fields.
+    ///
+    /// Callers that read the contact earlier can also pass that version as
+    /// `expected_updated_at`; a contact changed since then is a conflict too,
+    /// whether or not `merge_on_conflict` is set.
+    ///
     /// # Arguments
     /// * `params` - Enrichment parameters
     ///
     /// # Returns
     /// The updated contact
     pub async fn enrich_contact(&self, params: EnrichContactParams) -> DexApiResult<Contact> {
-        // Fetch existing contact
-        let mut contact = self.contact_repo.get(&params.contact_id).await?;
+        let contact_id = params.contact_id.clone();
 
-        // Update simple fields
-        if let Some(first_name) = params.first_name {
-            contact.first_name = Some(first_name);
-        }
-        if let Some(last_name) = params.last_name {
-            contact.last_name = Some(last_name);
-        }
-        if let Some(email) = params.email {
-            contact.email = Some(email);
-        }
-        if let Some(phone) = params.phone {
-            contact.phone = Some(phone);
-        }
-        if let Some(company) = params.company {
-            contact.company = Some(company);
-        }
-        if let Some(title) = params.title {
-            contact.title = Some(title);
-        }
-        if let Some(website) = params.website {
-            contact.website = Some(website);
-        }
-        if let Some(location) = params.location {
-            contact.location = Some(location);
-        }
-        if let Some(birthday) = params.birthday {
-            contact.birthday = Some(birthday);
-        }
-        if let Some(notes) = params.notes {
-            contact.notes = Some(notes);
-        }
+        // Fetch existing contact
+        let mut base = self.contact_repo.get(&contact_id).await?;
+        check_expected_version(&params, &base)?;
+        let mut contact = base.clone();
+        merge_enrichment(&mut contact, params.clone(), self.phone_region);
+
+        let mut retries = 0;
+        loop {
+            // Re-check the version right before writing
+            let latest = self.contact_repo.get(&contact_id).await?;
+            if latest.updated_at == base.updated_at {
+                return self.contact_repo.update(&contact_id, &contact).await;
+            }
 
-        // Merge array fields (remove duplicates)
-        if let Some(mut additional_emails) = params.additional_emails {
-            contact.emails.append(&mut additional_emails);
-            contact.emails.sort();
-            contact.emails.dedup();
-        }
+            let fields = conflicting_fields(&base, &latest, &contact);
+            if !params.merge_on_conflict || !fields.is_empty() || retries == MAX_MERGE_RETRIES {
+                return Err(DexApiError::Conflict {
+                    id: contact_id,
+                    expected: base.updated_at,
+                    actual: latest.updated_at,
+                    fields,
+                });
+            }
 
-        if let Some(mut additional_phones) = params.additional_phones {
-            contact.phones.append(&mut additional_phones);
-            contact.phones.sort();
-            contact.phones.dedup();
+            tracing::info!(
+                "Contact {} changed concurrently, merging enrichment onto version {:?}",
+                contact_id,
+                latest.updated_at
+            );
+            contact = latest.clone();
+            merge_enrichment(&mut contact, params.clone(), self.phone_region);
+            base = latest;
+            retries += 1;
         }
+    }
 
-        if let Some(mut tags) = params.tags {
-            contact.tags.append(&mut tags);
-            contact.tags.sort();
-            contact.tags.dedup();
-        }
+    /// Preview what `enrich_contact` would do without updating the contact.
+    ///
+    /// Fails with the same conflict as `enrich_contact` if the contact no
+    /// longer has `expected_updated_at`.
+    ///
+    /// # Arguments
+    /// * `params` - Enrichment parameters
+    ///
+    /// # Returns
+    /// The current contact, the merged result and the fields that would change
+    pub async fn preview_enrich_contact(
+        &self,
+        params: EnrichContactParams,
+    ) -> DexApiResult<ChangePreview<Contact>> {
+        let current = self.contact_repo.get(&params.contact_id).await?;
+        check_expected_version(&params, &current)?;
+        let mut proposed = current.clone();
 
-        if let Some(mut social_profiles) = params.social_profiles {
-            // Merge social profiles by URL (avoid duplicates)
-            for new_profile in social_profiles.drain(..) {
-                if !contact
-                    .social_profiles
-                    .iter()
-                    .any(|p| p.url == new_profile.url)
-                {
-                    contact.social_profiles.push(new_profile);
-                }
-            }
-        }
+        merge_enrichment(&mut proposed, params, self.phone_region);
 
-        // Update contact via repository
-        self.contact_repo.update(&params.contact_id, &contact).await
+        Ok(ChangePreview::update(current, proposed))
     }
 
     /// Add a note to a contact.
@@ -192,18 +218,12 @@ impl ContactEnrichmentTools {
     /// # Returns
     /// The created note
     pub async fn add_contact_note(&self, params: CreateNoteParams) -> DexApiResult<Note> {
-        // Build note object
-        let note = Note {
-            id: String::new(), // Will be assigned by the API
-            contact_id: params.contact_id,
-            content: params.content,
-            created_at: String::new(), // Will be assigned by the API
-            updated_at: None,
-            tags: params.tags.unwrap_or_default(),
-            source: params.source,
-        };
+        self.note_repo.create(&build_note(params)).await
+    }
 
-        self.note_repo.create(&note).await
+    /// Preview the note `add_contact_note` would create.
+    pub fn preview_contact_note(&self, params: CreateNoteParams) -> ChangePreview<Note> {
+        ChangePreview::create(build_note(params))
     }
 
     /// Create a reminder for a contact.
@@ -217,21 +237,166 @@ impl ContactEnrichmentTools {
         &self,
         params: CreateReminderParams,
     ) -> DexApiResult<Reminder> {
-        // Build reminder object
-        let reminder = Reminder {
-            id: String::new(), // Will be assigned by the API
-            contact_id: params.contact_id,
-            text: params.text,
-            due_date: params.due_date,
-            completed: false,
-            completed_at: None,
-            created_at: String::new(), // Will be assigned by the API
-            updated_at: None,
-            tags: params.tags.unwrap_or_default(),
-            priority: params.priority,
-        };
+        self.reminder_repo.create(&build_reminder(params)).await
+    }
 
-        self.reminder_repo.create(&reminder).await
+    /// Preview the reminder `create_contact_reminder` would create.
+    pub fn preview_contact_reminder(
+        &self,
+        params: CreateReminderParams,
+    ) -> ChangePreview<Reminder> {
+        ChangePreview::create(build_reminder(params))
+    }
+}
+
+/// Merge enrichment parameters into a contact.
+///
+/// Shared by `enrich_contact` and its dry-run preview so both produce the
+/// same record. Phones are told apart by their E.164 form and extension,
+/// reading them in `region` if they have no country code.
+pub(crate) fn merge_enrichment(
+    contact: &mut Contact,
+    params: EnrichContactParams,
+    region: PhoneRegion,
+) {
+    // Update simple fields
+    if let Some(first_name) = params.first_name {
+        contact.first_name = Some(first_name);
+    }
+    if let Some(last_name) = params.last_name {
+        contact.last_name = Some(last_name);
+    }
+    if let Some(email) = params.email {
+        contact.email = Some(email);
+    }
+    if let Some(phone) = params.phone {
+        contact.phone = Some(phone);
+    }
+    if let Some(company) = params.company {
+        contact.company = Some(company);
+    }
+    if let Some(title) = params.title {
+        contact.title = Some(title);
+    }
+    if let Some(website) = params.website {
+        contact.website = Some(website);
+    }
+    if let Some(location) = params.location {
+        contact.location = Some(location);
+    }
+    if let Some(birthday) = params.birthday {
+        contact.birthday = Some(birthday);
+    }
+    if let Some(notes) = params.notes {
+        contact.notes = Some(notes);
+    }
+
+    // Merge array fields (remove duplicates)
+    if let Some(mut additional_emails) = params.additional_emails {
+        contact.emails.append(&mut additional_emails);
+        contact.emails.sort();
+        contact.emails.dedup();
+    }
+
+    if let Some(mut additional_phones) = params.additional_phones {
+        contact.phones.append(&mut additional_phones);
+        contact.phones.sort();
+        // Keep the first spelling of each number
+        let mut seen = HashSet::new();
+        contact
+            .phones
+            .retain(|phone| seen.insert(phone_identity(phone, region)));
+    }
+
+    if let Some(mut tags) = params.tags {
+        contact.tags.append(&mut tags);
+        contact.tags.sort();
+        contact.tags.dedup();
+    }
+
+    if let Some(mut social_profiles) = params.social_profiles {
+        // Merge social profiles by URL (avoid duplicates)
+        for new_profile in social_profiles.drain(..) {
+            if !contact
+                .social_profiles
+                .iter()
+                .any(|p| p.url == new_profile.url)
+            {
+                contact.social_profiles.push(new_profile);
+            }
+        }
+    }
+}
+
+/// What makes a phone number distinct: its E.164 form and extension, or
+/// the text itself if it cannot be parsed.
+fn phone_identity(phone: &str, region: PhoneRegion) -> String {
+    match PhoneNumber::parse(phone, region) {
+        Ok(phone) => match phone.extension() {
+            Some(extension) => format!("{};ext={}", phone.comparable(), extension),
+            None => phone.comparable(),
+        },
+        Err(_) => phone.trim().to_string(),
+    }
+}
+
+/// Fail with a conflict if `contact` is not at the caller's
+/// `expected_updated_at`, when one is given.
+fn check_expected_version(params: &EnrichContactParams, contact: &Contact) -> DexApiResult<()> {
+    match params.expected_updated_at {
+        Some(ref expected) if contact.updated_at.as_ref() != Some(expected) => {
+            Err(DexApiError::Conflict {
+                id: params.contact_id.clone(),
+                expected: Some(expected.clone()),
+                actual: contact.updated_at.clone(),
+                fields: Vec::new(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Fields changed both by another session (`base` to `latest`) and by this
+/// enrichment (`base` to `proposed`).
+fn conflicting_fields(base: &Contact, latest: &Contact, proposed: &Contact) -> Vec<String> {
+    let theirs: BTreeSet<String> = diff_fields(Some(base), Some(latest))
+        .into_iter()
+        .map(|change| change.field)
+        .collect();
+
+    diff_fields(Some(base), Some(proposed))
+        .into_iter()
+        .map(|change| change.field)
+        .filter(|field| theirs.contains(field))
+        .collect()
+}
+
+/// Build the note to be created for the given parameters.
+fn build_note(params: CreateNoteParams) -> Note {
+    Note {
+        id: String::new(), // Will be assigned by the API
+        contact_id: params.contact_id,
+        content: params.content,
+        created_at: String::new(), // Will be assigned by the API
+        updated_at: None,
+        tags: params.tags.unwrap_or_default(),
+        source: params.source,
+    }
+}
+
+/// Build the reminder to be created for the given parameters.
+fn build_reminder(params: CreateReminderParams) -> Reminder {
+    Reminder {
+        id: String::new(), // Will be assigned by the API
+        contact_id: params.contact_id,
+        text: params.text,
+        due_date: params.due_date,
+        completed: false,
+        completed_at: None,
+        created_at: String::new(), // Will be assigned by the API
+        updated_at: None,
+        tags: params.tags.unwrap_or_default(),
+        priority: params.priority,
     }
 }
 
@@ -294,6 +459,31 @@ mod tests {
         assert_eq!(params.priority.unwrap(), "high");
     }
 
+    #[test]
+    fn test_merge_enrichment_dedups_arrays() {
+        let mut contact = Contact {
+            id: "c1".to_string(),
+            tags: vec!["friend".to_string()],
+            phones: vec!["+1 (555) 123-4567".to_string()],
+            ..Default::default()
+        };
+        let params = EnrichContactParams {
+            contact_id: "c1".to_string(),
+            company: Some("Acme".to_string()),
+            tags: Some(vec!["work".to_string(), "friend".to_string()]),
+            additional_phones: Some(vec![
+                "555-123-4567".to_string(),
+                "555-123-4567 x2".to_string(),
+            ]),
+            ..Default::default()
+        };
+
+        merge_enrichment(&mut contact, params, PhoneRegion::default());
+        assert_eq!(contact.company.as_deref(), Some("Acme"));
+        assert_eq!(contact.tags, vec!["friend", "work"]);
+        assert_eq!(contact.phones, vec!["+1 (555) 123-4567", "555-123-4567 x2"]);
+    }
+
     // Note: More comprehensive tests for enrich_contact, add_contact_note,
     // and create_contact_reminder would require mocking the DexClient
     // These integration tests should be in tests/ directory with mockito
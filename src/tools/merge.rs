@@ -0,0 +1,628 @@
+//! Contact merge tools.
+//!
+//! Merge duplicate contacts into a primary contact: their emails, phones,
+//! tags and social profiles are added to the primary the way
+//! `enrich_contact` merges them, their notes and reminders are moved to the
+//! primary, and the duplicates are deleted.
+//!
+//! Dex cannot change which contact an existing note or reminder belongs to,
+//! so moving one means recreating it on the primary and deleting the
+//! original; the moved record gets a new ID.
+//!
+//! Each write is journaled as it is made; if a later write fails, the journal
+//! is replayed backwards so the merge is rolled back instead of being left
+//! half done.
+
+use crate::domain::PhoneRegion;
+use crate::error::{DexApiError, DexApiResult};
+use crate::models::{Contact, Note, Reminder};
+use crate::repositories::{ContactRepository, NoteRepository, ReminderRepository};
+use crate::tools::dry_run::ChangePreview;
+use crate::tools::enrichment::{merge_enrichment, EnrichContactParams};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Maximum number of duplicates merged at once.
+pub const MAX_MERGE_DUPLICATES: usize = 20;
+
+/// Page size used when fetching a duplicate's notes and reminders.
+const PAGE_SIZE: usize = 100;
+
+/// How conflicting single-valued fields (name, company, title, website,
+/// location, birthday, notes) are resolved.
+///
+/// Emails, phones, tags and social profiles are always combined.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// Keep the primary's fields as they are
+    KeepPrimary,
+    /// Keep the primary's fields, filling empty ones from the duplicates
+    #[default]
+    FillMissing,
+    /// Take each field from the most recently updated contact that has it
+    PreferNewest,
+}
+
+impl FromStr for MergeStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "keep_primary" => Ok(MergeStrategy::KeepPrimary),
+            "fill_missing" => Ok(MergeStrategy::FillMissing),
+            "prefer_newest" => Ok(MergeStrategy::PreferNewest),
+            _ => Err(format!(
+                "Unknown merge strategy '{}' (expected keep_primary, fill_missing or prefer_newest)",
+                s
+            )),
+        }
+    }
+}
+
+/// Parameters for merging contacts.
+#[derive(Debug, Clone, Default)]
+pub struct MergeContactsParams {
+    /// Contact that is kept
+    pub primary_id: String,
+
+    /// Contacts merged into the primary and then deleted
+    pub duplicate_ids: Vec<String>,
+
+    /// How conflicting single-valued fields are resolved
+    pub strategy: MergeStrategy,
+}
+
+/// What `merge_contacts` would do, without doing it.
+#[derive(Debug, Clone, Serialize)]
+pub struct MergePreview {
+    /// Always true; lets callers tell previews apart from real results
+    pub dry_run: bool,
+
+    /// The primary contact as it is now and as it would be
+    pub primary: ChangePreview<Contact>,
+
+    /// IDs of the notes that would move to the primary
+    pub moved_note_ids: Vec<String>,
+
+    /// IDs of the reminders that would move to the primary
+    pub moved_reminder_ids: Vec<String>,
+
+    /// Contacts that would be deleted
+    pub deleted_contacts: Vec<Contact>,
+}
+
+/// Result of merging contacts.
+#[derive(Debug, Clone, Serialize)]
+pub struct MergeResult {
+    /// The primary contact after the merge
+    pub contact: Contact,
+
+    /// Notes moved to the primary
+    pub moved_notes: Vec<MovedRecord>,
+
+    /// Reminders moved to the primary
+    pub moved_reminders: Vec<MovedRecord>,
+
+    /// IDs of the deleted duplicates
+    pub deleted_contact_ids: Vec<String>,
+}
+
+/// A note or reminder moved to another contact.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MovedRecord {
+    /// ID of the original, now deleted
+    pub from_id: String,
+
+    /// ID of the copy on the primary
+    pub to_id: String,
+}
+
+/// Everything a merge will write, computed before writing anything.
+struct MergePlan {
+    primary: Contact,
+    merged: Contact,
+    duplicates: Vec<Contact>,
+    notes: Vec<Note>,
+    reminders: Vec<Reminder>,
+}
+
+/// A write made by a merge, with what is needed to undo it.
+enum JournalEntry {
+    /// The primary, as it was before the update
+    UpdatedContact(Contact),
+    /// ID of a note copied to the primary
+    CreatedNote(String),
+    /// A duplicate's note, as it was before the delete
+    DeletedNote(Note),
+    /// ID of a reminder copied to the primary
+    CreatedReminder(String),
+    /// A duplicate's reminder, as it was before the delete
+    DeletedReminder(Reminder),
+    /// A duplicate, as it was before the delete
+    DeletedContact(Contact),
+}
+
+/// Merge tools for combining duplicate contacts.
+pub struct MergeTools {
+    contact_repo: Arc<dyn ContactRepository>,
+    note_repo: Arc<dyn NoteRepository>,
+    reminder_repo: Arc<dyn ReminderRepository>,
+    phone_region: PhoneRegion,
+}
+
+impl MergeTools {
+    /// Create new merge tools.
+    ///
+    /// With the audited repository decorators, every write of a merge (and
+    /// of its rollback) is also recorded in the audit log.
+    pub fn new(
+        contact_repo: Arc<dyn ContactRepository>,
+        note_repo: Arc<dyn NoteRepository>,
+        reminder_repo: Arc<dyn ReminderRepository>,
+    ) -> Self {
+        Self {
+            contact_repo,
+            note_repo,
+            reminder_repo,
+            phone_region: PhoneRegion::default(),
+        }
+    }
+
+    /// Read phone numbers without a country code in `region` instead of the
+    /// US when combining the contacts' phones.
+    pub fn with_phone_region(mut self, region: PhoneRegion) -> Self {
+        self.phone_region = region;
+        self
+    }
+
+    /// Merge duplicates into a primary contact.
+    ///
+    /// The primary is updated first, then every note and reminder of the
+    /// duplicates is copied to it and the original deleted, and finally the
+    /// duplicates are deleted. If any write fails, the writes already made
+    /// are undone in reverse order and the error says whether that rollback
+    /// succeeded. Deleted records are recreated by a rollback, which gives
+    /// them new IDs in Dex.
+    pub async fn merge_contacts(&self, params: MergeContactsParams) -> DexApiResult<MergeResult> {
+        let plan = self.plan(&params).await?;
+        let mut journal = Vec::new();
+
+        match self.apply(plan, &mut journal).await {
+            Ok(result) => Ok(result),
+            Err(err) => {
+                tracing::warn!(
+                    "Merge into {} failed after {} writes, rolling back: {}",
+                    params.primary_id,
+                    journal.len(),
+                    err
+                );
+                let steps = journal.len();
+                let failures = self.roll_back(journal).await;
+                Err(if failures.is_empty() {
+                    DexApiError::Other(format!(
+                        "Merge into {} failed and its {} completed writes were rolled back: {}",
+                        params.primary_id, steps, err
+                    ))
+                } else {
+                    DexApiError::Other(format!(
+                        "Merge into {} failed ({}) and could not be fully rolled back: {}",
+                        params.primary_id,
+                        err,
+                        failures.join("; ")
+                    ))
+                })
+            }
+        }
+    }
+
+    /// Preview `merge_contacts` without writing anything.
+    pub async fn preview_merge_contacts(
+        &self,
+        params: MergeContactsParams,
+    ) -> DexApiResult<MergePreview> {
+        let plan = self.plan(&params).await?;
+
+        Ok(MergePreview {
+            dry_run: true,
+            moved_note_ids: plan.notes.into_iter().map(|note| note.id).collect(),
+            moved_reminder_ids: plan
+                .reminders
+                .into_iter()
+                .map(|reminder| reminder.id)
+                .collect(),
+            primary: ChangePreview::update(plan.primary, plan.merged),
+            deleted_contacts: plan.duplicates,
+        })
+    }
+
+    /// Validate the parameters and read everything the merge will change.
+    async fn plan(&self, params: &MergeContactsParams) -> DexApiResult<MergePlan> {
+        validate(params).map_err(DexApiError::InvalidRequest)?;
+
+        let primary = self.contact_repo.get(&params.primary_id).await?;
+        let mut duplicates = Vec::with_capacity(params.duplicate_ids.len());
+        let mut notes = Vec::new();
+        let mut reminders = Vec::new();
+        for id in &params.duplicate_ids {
+            duplicates.push(self.contact_repo.get(id).await?);
+            notes.extend(self.fetch_all_notes(id).await?);
+            reminders.extend(self.fetch_all_reminders(id).await?);
+        }
+
+        let merged = merge_duplicates(&primary, &duplicates, params.strategy, self.phone_region);
+        Ok(MergePlan {
+            primary,
+            merged,
+            duplicates,
+            notes,
+            reminders,
+        })
+    }
+
+    /// Perform the writes of a plan, journaling each one once it succeeded.
+    async fn apply(
+        &self,
+        plan: MergePlan,
+        journal: &mut Vec<JournalEntry>,
+    ) -> DexApiResult<MergeResult> {
+        let primary_id = plan.primary.id.clone();
+
+        let contact = self.contact_repo.update(&primary_id, &plan.merged).await?;
+        journal.push(JournalEntry::UpdatedContact(plan.primary));
+
+        let mut moved_notes = Vec::with_capacity(plan.notes.len());
+        for note in plan.notes {
+            let copy = Note {
+                id: String::new(),
+                contact_id: primary_id.clone(),
+                ..note.clone()
+            };
+            let created = self.note_repo.create(&copy).await?;
+            journal.push(JournalEntry::CreatedNote(created.id.clone()));
+            self.note_repo.delete(&note.id).await?;
+            moved_notes.push(MovedRecord {
+                from_id: note.id.clone(),
+                to_id: created.id,
+            });
+            journal.push(JournalEntry::DeletedNote(note));
+        }
+
+        let mut moved_reminders = Vec::with_capacity(plan.reminders.len());
+        for reminder in plan.reminders {
+            let copy = Reminder {
+                id: String::new(),
+                contact_id: primary_id.clone(),
+                ..reminder.clone()
+            };
+            let created = self.reminder_repo.create(&copy).await?;
+            journal.push(JournalEntry::CreatedReminder(created.id.clone()));
+            self.reminder_repo.delete(&reminder.id).await?;
+            moved_reminders.push(MovedRecord {
+                from_id: reminder.id.clone(),
+                to_id: created.id,
+            });
+            journal.push(JournalEntry::DeletedReminder(reminder));
+        }
+
+        let mut deleted_contact_ids = Vec::with_capacity(plan.duplicates.len());
+        for duplicate in plan.duplicates {
+            self.contact_repo.delete(&duplicate.id).await?;
+            deleted_contact_ids.push(duplicate.id.clone());
+            journal.push(JournalEntry::DeletedContact(duplicate));
+        }
+
+        Ok(MergeResult {
+            contact,
+            moved_notes,
+            moved_reminders,
+            deleted_contact_ids,
+        })
+    }
+
+    /// Undo journaled writes, newest first.
+    ///
+    /// Deleted duplicates are recreated before their notes and reminders, so
+    /// those are recreated on the recreated contacts' new IDs. Returns a
+    /// description of every write that could not be undone.
+    async fn roll_back(&self, journal: Vec<JournalEntry>) -> Vec<String> {
+        let mut recreated_ids: HashMap<String, String> = HashMap::new();
+        let mut failures = Vec::new();
+
+        for entry in journal.into_iter().rev() {
+            let outcome = match entry {
+                JournalEntry::DeletedContact(contact) => {
+                    match self.contact_repo.create(&contact).await {
+                        Ok(created) => {
+                            recreated_ids.insert(contact.id.clone(), created.id);
+                            Ok(())
+                        }
+                        Err(err) => Err(format!("recreate contact {}: {}", contact.id, err)),
+                    }
+                }
+                JournalEntry::DeletedReminder(reminder) => {
+                    let restored = Reminder {
+                        id: String::new(),
+                        contact_id: recreated_ids
+                            .get(&reminder.contact_id)
+                            .unwrap_or(&reminder.contact_id)
+                            .clone(),
+                        ..reminder.clone()
+                    };
+                    self.reminder_repo
+                        .create(&restored)
+                        .await
+                        .map(|_| ())
+                        .map_err(|err| format!("recreate reminder {}: {}", reminder.id, err))
+                }
+                JournalEntry::CreatedReminder(id) => self
+                    .reminder_repo
+                    .delete(&id)
+                    .await
+                    .map_err(|err| format!("delete copied reminder {}: {}", id, err)),
+                JournalEntry::DeletedNote(note) => {
+                    let restored = Note {
+                        id: String::new(),
+                        contact_id: recreated_ids
+                            .get(&note.contact_id)
+                            .unwrap_or(&note.contact_id)
+                            .clone(),
+                        ..note.clone()
+                    };
+                    self.note_repo
+                        .create(&restored)
+                        .await
+                        .map(|_| ())
+                        .map_err(|err| format!("recreate note {}: {}", note.id, err))
+                }
+                JournalEntry::CreatedNote(id) => self
+                    .note_repo
+                    .delete(&id)
+                    .await
+                    .map_err(|err| format!("delete copied note {}: {}", id, err)),
+                JournalEntry::UpdatedContact(contact) => self
+                    .contact_repo
+                    .update(&contact.id, &contact)
+                    .await
+                    .map(|_| ())
+                    .map_err(|err| format!("restore contact {}: {}", contact.id, err)),
+            };
+            if let Err(failure) = outcome {
+                tracing::error!("Merge rollback failed to {}", failure);
+                failures.push(failure);
+            }
+        }
+
+        failures
+    }
+
+    /// Fetch all notes for a contact, handling pagination.
+    async fn fetch_all_notes(&self, contact_id: &str) -> DexApiResult<Vec<Note>> {
+        let mut all_notes = Vec::new();
+        let mut offset = 0;
+
+        loop {
+            let notes = self
+                .note_repo
+                .get_for_contact(contact_id, PAGE_SIZE, offset)
+                .await?;
+            let count = notes.len();
+            all_notes.extend(notes);
+
+            if count < PAGE_SIZE {
+                break;
+            }
+
+            offset += PAGE_SIZE;
+        }
+
+        Ok(all_notes)
+    }
+
+    /// Fetch all reminders for a contact, handling pagination.
+    async fn fetch_all_reminders(&self, contact_id: &str) -> DexApiResult<Vec<Reminder>> {
+        let mut all_reminders = Vec::new();
+        let mut offset = 0;
+
+        loop {
+            let reminders = self
+                .reminder_repo
+                .get_for_contact(contact_id, PAGE_SIZE, offset)
+                .await?;
+            let count = reminders.len();
+            all_reminders.extend(reminders);
+
+            if count < PAGE_SIZE {
+                break;
+            }
+
+            offset += PAGE_SIZE;
+        }
+
+        Ok(all_reminders)
+    }
+}
+
+/// Check the IDs of a merge.
+fn validate(params: &MergeContactsParams) -> Result<(), String> {
+    if params.primary_id.trim().is_empty() {
+        return Err("primary_id cannot be empty".to_string());
+    }
+    if params.duplicate_ids.is_empty() {
+        return Err("duplicate_ids must list at least one contact".to_string());
+    }
+    if params.duplicate_ids.len() > MAX_MERGE_DUPLICATES {
+        return Err(format!(
+            "Too many duplicate_ids (max {})",
+            MAX_MERGE_DUPLICATES
+        ));
+    }
+
+    let mut seen = HashSet::new();
+    for id in &params.duplicate_ids {
+        if id.trim().is_empty() {
+            return Err("duplicate_ids cannot contain empty IDs".to_string());
+        }
+        if *id == params.primary_id {
+            return Err(format!("Contact {} cannot be merged into itself", id));
+        }
+        if !seen.insert(id) {
+            return Err(format!("Contact {} is listed twice in duplicate_ids", id));
+        }
+    }
+    Ok(())
+}
+
+/// The primary contact with the duplicates merged into it.
+///
+/// Multi-valued fields go through the same merge as `enrich_contact`;
+/// single-valued fields follow `strategy`.
+fn merge_duplicates(
+    primary: &Contact,
+    duplicates: &[Contact],
+    strategy: MergeStrategy,
+    region: PhoneRegion,
+) -> Contact {
+    let mut ordered: Vec<&Contact> = duplicates.iter().collect();
+    if strategy == MergeStrategy::PreferNewest {
+        // Apply newer duplicates last, so their values win
+        ordered.sort_by(|a, b| a.updated_at.cmp(&b.updated_at));
+    }
+
+    let mut merged = primary.clone();
+    for duplicate in ordered {
+        let overwrite =
+            strategy == MergeStrategy::PreferNewest && duplicate.updated_at > primary.updated_at;
+        let fill = strategy != MergeStrategy::KeepPrimary;
+        let pick = |current: &Option<String>, theirs: &Option<String>| {
+            let theirs = theirs.as_ref().filter(|value| !value.trim().is_empty())?;
+            let empty = current.as_ref().is_none_or(|value| value.trim().is_empty());
+            (overwrite || (fill && empty)).then(|| theirs.clone())
+        };
+
+        let params = EnrichContactParams {
+            contact_id: merged.id.clone(),
+            first_name: pick(&merged.first_name, &duplicate.first_name),
+            last_name: pick(&merged.last_name, &duplicate.last_name),
+            company: pick(&merged.company, &duplicate.company),
+            title: pick(&merged.title, &duplicate.title),
+            website: pick(&merged.website, &duplicate.website),
+            location: pick(&merged.location, &duplicate.location),
+            birthday: pick(&merged.birthday, &duplicate.birthday),
+            notes: pick(&merged.notes, &duplicate.notes),
+            additional_emails: Some(
+                duplicate
+                    .email
+                    .iter()
+                    .chain(&duplicate.emails)
+                    .cloned()
+                    .collect(),
+            ),
+            additional_phones: Some(
+                duplicate
+                    .phone
+                    .iter()
+                    .chain(&duplicate.phones)
+                    .cloned()
+                    .collect(),
+            ),
+            tags: Some(duplicate.tags.clone()),
+            social_profiles: Some(duplicate.social_profiles.clone()),
+            ..Default::default()
+        };
+        merge_enrichment(&mut merged, params, region);
+    }
+
+    if merged.first_name != primary.first_name || merged.last_name != primary.last_name {
+        merged.name = match (&merged.first_name, &merged.last_name) {
+            (Some(first), Some(last)) => format!("{} {}", first, last),
+            (Some(first), None) => first.clone(),
+            (None, Some(last)) => last.clone(),
+            (None, None) => String::new(),
+        };
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contact(id: &str, first: &str, updated_at: &str) -> Contact {
+        Contact {
+            id: id.to_string(),
+            first_name: Some(first.to_string()),
+            updated_at: Some(updated_at.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_merge_strategy_from_str() {
+        assert_eq!(
+            "Prefer_Newest".parse::<MergeStrategy>(),
+            Ok(MergeStrategy::PreferNewest)
+        );
+        assert!("newest".parse::<MergeStrategy>().is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_ids() {
+        let params = |primary: &str, duplicates: &[&str]| MergeContactsParams {
+            primary_id: primary.to_string(),
+            duplicate_ids: duplicates.iter().map(|id| id.to_string()).collect(),
+            strategy: MergeStrategy::default(),
+        };
+
+        assert!(validate(&params("c1", &["c2", "c3"])).is_ok());
+        assert!(validate(&params("c1", &[])).is_err());
+        assert!(validate(&params("c1", &["c1"])).is_err());
+        assert!(validate(&params("c1", &["c2", "c2"])).is_err());
+        assert!(validate(&params("", &["c2"])).is_err());
+    }
+
+    #[test]
+    fn test_merge_unions_lists_and_follows_strategy() {
+        let mut primary = contact("c1", "Ada", "2024-01-01T00:00:00Z");
+        primary.emails = vec!["ada@engine.org".to_string()];
+        primary.tags = vec!["math".to_string()];
+        let mut older = contact("c2", "Augusta", "2023-01-01T00:00:00Z");
+        older.company = Some("Analytical Engines".to_string());
+        older.emails = vec!["ada@engine.org".to_string(), "aak@lovelace.uk".to_string()];
+        older.tags = vec!["math".to_string(), "poetry".to_string()];
+        let mut newer = contact("c3", "Countess", "2025-01-01T00:00:00Z");
+        newer.company = Some("Lovelace Estates".to_string());
+        let duplicates = vec![older, newer];
+
+        let kept = merge_duplicates(
+            &primary,
+            &duplicates,
+            MergeStrategy::KeepPrimary,
+            PhoneRegion::default(),
+        );
+        assert_eq!(kept.emails, vec!["aak@lovelace.uk", "ada@engine.org"]);
+        assert_eq!(kept.tags, vec!["math", "poetry"]);
+        assert_eq!(kept.company, None);
+
+        let filled = merge_duplicates(
+            &primary,
+            &duplicates,
+            MergeStrategy::FillMissing,
+            PhoneRegion::default(),
+        );
+        assert_eq!(filled.first_name.as_deref(), Some("Ada"));
+        assert_eq!(filled.company.as_deref(), Some("Analytical Engines"));
+
+        let newest = merge_duplicates(
+            &primary,
+            &duplicates,
+            MergeStrategy::PreferNewest,
+            PhoneRegion::default(),
+        );
+        assert_eq!(newest.first_name.as_deref(), Some("Countess"));
+        assert_eq!(newest.company.as_deref(), Some("Lovelace Estates"));
+        assert_eq!(newest.name, "Countess");
+    }
+}
@@ -1,21 +1,33 @@
 //! MCP tools for interacting with Dex Personal CRM.
 //!
-//! This module provides four categories of tools:
+//! This module provides six categories of tools:
 //! - **Discovery**: Find and retrieve contacts
 //! - **History**: Access relationship history (notes, reminders, timeline)
 //! - **Enrichment**: Update contacts and add notes/reminders
+//! - **Merge**: Combine duplicate contacts with their notes and reminders
 //! - **Search**: Full-text search with caching
+//! - **Undo**: Revert changes recorded in the audit log
 
 pub mod discovery;
+pub mod dry_run;
 pub mod enrichment;
 pub mod history;
+pub mod merge;
 pub mod search;
+pub mod undo;
 
-pub use discovery::{ContactDiscoveryTools, FindContactParams, FindContactResponse};
+pub use discovery::{
+    ContactDiscoveryTools, DiscoveryConfig, FindContactParams, FindContactResponse,
+};
+pub use dry_run::{ChangePreview, FieldChange};
 pub use enrichment::{
     ContactEnrichmentTools, CreateNoteParams, CreateReminderParams, EnrichContactParams,
 };
 pub use history::{
     ContactHistoryResponse, HistoryFilterParams, RelationshipHistoryTools, TimelineEntry,
 };
-pub use search::{SearchParams, SearchResponse, SearchTools};
+pub use merge::{
+    MergeContactsParams, MergePreview, MergeResult, MergeStrategy, MergeTools, MovedRecord,
+};
+pub use search::{SearchCacheConfig, SearchParams, SearchResponse, SearchTools};
+pub use undo::{RevertAction, RevertResult, UndoTools};
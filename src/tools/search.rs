@@ -1,22 +1,47 @@
 //! Search tools for full-text search with caching.
 //!
 //! Provides efficient full-text search over contacts, notes, and reminders
-//! using a cached search index.
-
-use crate::cache::TimedCache;
-use crate::error::DexApiResult;
-use crate::models::Contact;
+//! using a cached search index. Writes update the cached index in place, so
+//! changes are searchable without waiting for a rebuild. With a snapshot store
+//! configured, the index is also kept on disk and loaded on the first search
+//! after a restart.
+
+use crate::cache::RefreshingCache;
+use crate::error::{DexApiError, DexApiResult};
+use crate::models::{Contact, Note, Reminder};
 use crate::repositories::{ContactRepository, NoteRepository, ReminderRepository};
-use crate::search::{FullTextSearchIndex, SearchResult};
+use crate::search::{
+    Analyzer, FullTextSearchIndex, IndexSnapshotStore, SearchFacets, SearchFilter, SearchQuery,
+    SearchResult, SimilarContact, SnippetOptions, SpellingSuggestion,
+};
 use futures::stream::{self, StreamExt};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock};
+
+/// How long after an in-place update the snapshot is written, so a burst of
+/// writes is persisted once
+const SNAPSHOT_DEBOUNCE: Duration = Duration::from_secs(5);
+
+/// Search index and the contacts it covers.
+struct SearchData {
+    index: FullTextSearchIndex,
+    contacts: Vec<Contact>,
+}
 
-/// Cached search data including index and contacts.
+/// Cached search data, shared so that writes can update it in place.
 #[derive(Clone)]
 struct SearchCache {
-    index: Arc<FullTextSearchIndex>,
-    contacts: Arc<Vec<Contact>>,
+    data: Arc<RwLock<SearchData>>,
+}
+
+impl SearchCache {
+    fn new(index: FullTextSearchIndex, contacts: Vec<Contact>) -> Self {
+        Self {
+            data: Arc::new(RwLock::new(SearchData { index, contacts })),
+        }
+    }
 }
 
 /// Search tools for performing full-text searches.
@@ -25,15 +50,41 @@ pub struct SearchTools {
     contact_repo: Arc<dyn ContactRepository>,
     note_repo: Arc<dyn NoteRepository>,
     reminder_repo: Arc<dyn ReminderRepository>,
-    /// Cached search index and contacts
-    cache: Arc<RwLock<TimedCache<String, SearchCache>>>,
+    /// Cached search index and contacts, refreshed in the background
+    cache: RefreshingCache<SearchCache>,
     cache_ttl_secs: u64,
+    /// Text analysis applied to indexed documents and queries
+    analyzer: Arc<Analyzer>,
+    /// Incremented on every write, so an index built from data fetched
+    /// before a write is not persisted
+    generation: Arc<AtomicU64>,
+    /// On-disk copy of the cached index, if enabled
+    snapshot: Option<IndexSnapshotStore>,
+    /// Set once loading the snapshot has been attempted
+    snapshot_loaded: Arc<AtomicBool>,
+    /// Serializes snapshot writes so they land in the order they were encoded
+    snapshot_write_lock: Arc<Mutex<()>>,
+    /// Set when the cached index has in-place updates the snapshot lacks
+    snapshot_dirty: Arc<AtomicBool>,
+}
+
+/// Caching and indexing configuration for [`SearchTools`].
+#[derive(Debug, Clone)]
+pub struct SearchCacheConfig {
+    /// In-memory cache time-to-live in seconds
+    pub ttl_secs: u64,
+
+    /// On-disk snapshot of the index for warm starts, if enabled
+    pub snapshot: Option<IndexSnapshotStore>,
+
+    /// Text analysis for indexed documents and queries
+    pub analyzer: Arc<Analyzer>,
 }
 
 /// Parameters for full-text search.
 #[derive(Debug, Clone)]
 pub struct SearchParams {
-    /// Search query string
+    /// Search query string, in the syntax of [`SearchQuery`]
     pub query: String,
 
     /// Maximum number of results to return (default: 10)
@@ -41,6 +92,15 @@ pub struct SearchParams {
 
     /// Minimum confidence threshold (0-100, default: 50)
     pub min_confidence: Option<u8>,
+
+    /// Field kinds, note/reminder dates and facet values to restrict matches to
+    pub filter: SearchFilter,
+
+    /// Snippet length, count and highlighting of match contexts
+    pub snippets: SnippetOptions,
+
+    /// Retry once with the top spelling suggestion if nothing matches
+    pub auto_correct: bool,
 }
 
 impl Default for SearchParams {
@@ -49,6 +109,9 @@ impl Default for SearchParams {
             query: String::new(),
             max_results: Some(10),
             min_confidence: Some(50),
+            filter: SearchFilter::default(),
+            snippets: SnippetOptions::default(),
+            auto_correct: false,
         }
     }
 }
@@ -59,6 +122,20 @@ pub struct SearchResponse {
     /// Search results
     pub results: Vec<SearchResult>,
 
+    /// Number of matching contacts, including those beyond `max_results`
+    pub total_matches: usize,
+
+    /// Counts by tag, company, email domain and matched field over all
+    /// matching contacts
+    pub facets: SearchFacets,
+
+    /// Corrected spellings of the query, best first, if nothing matched it
+    pub suggestions: Vec<SpellingSuggestion>,
+
+    /// The suggestion searched for instead of the query, if auto-correct
+    /// was requested and used
+    pub corrected_query: Option<String>,
+
     /// Whether the results came from cache
     pub from_cache: bool,
 
@@ -84,15 +161,52 @@ impl SearchTools {
             contact_repo,
             note_repo,
             reminder_repo,
-            cache: Arc::new(RwLock::new(TimedCache::new(cache_ttl_secs))),
+            cache: RefreshingCache::new(Duration::from_secs(cache_ttl_secs)),
             cache_ttl_secs,
+            analyzer: Arc::new(Analyzer::default()),
+            generation: Arc::new(AtomicU64::new(0)),
+            snapshot: None,
+            snapshot_loaded: Arc::new(AtomicBool::new(false)),
+            snapshot_write_lock: Arc::new(Mutex::new(())),
+            snapshot_dirty: Arc::new(AtomicBool::new(false)),
         }
     }
 
+    /// Keep the cached index in an on-disk snapshot.
+    ///
+    /// The snapshot is written after every build, and in the background a few
+    /// seconds after in-place updates (or on [`flush_snapshot`](Self::flush_snapshot)).
+    /// It is loaded instead of building when the first search after startup finds
+    /// no cached index. It is skipped if data was written before that search,
+    /// since those writes would be missing from it.
+    pub fn with_snapshot(mut self, store: IndexSnapshotStore) -> Self {
+        self.snapshot = Some(store);
+        self
+    }
+
+    /// Analyze documents and queries with `analyzer`.
+    ///
+    /// Defaults to English stemming and stopwords without synonyms.
+    pub fn with_analyzer(mut self, analyzer: Arc<Analyzer>) -> Self {
+        self.analyzer = analyzer;
+        self
+    }
+
     /// Perform a full-text search.
     ///
+    /// Besides the best `max_results` contacts, the response counts all
+    /// matching contacts by tag, company, email domain and matched field.
+    /// Facet filters in `params.filter` narrow both. If nothing matches,
+    /// spelling suggestions from the index vocabulary are returned, and with
+    /// `params.auto_correct` the top one is searched for instead.
+    ///
     /// This method uses a cached search index for performance. The index is
-    /// built on first search and cached for `cache_ttl_secs`.
+    /// loaded from the snapshot or built on first search, and cached for
+    /// `cache_ttl_secs`. It is rebuilt in the background shortly before it
+    /// expires, and an expired index is served until its replacement is ready.
+    ///
+    /// The query is parsed before the index is touched, so malformed queries
+    /// fail fast with [`DexApiError::InvalidRequest`](crate::error::DexApiError::InvalidRequest).
     ///
     /// # Arguments
     /// * `params` - Search parameters
@@ -102,41 +216,94 @@ impl SearchTools {
     pub async fn search_full_text(&self, params: SearchParams) -> DexApiResult<SearchResponse> {
         let max_results = params.max_results.unwrap_or(10);
         let min_confidence = params.min_confidence.unwrap_or(50);
+        let query = SearchQuery::parse(&params.query)?;
 
         let (search_cache, from_cache) = self.get_or_build_cache().await?;
 
-        // Perform search on the index
-        let results = search_cache.index.search(
-            &search_cache.contacts,
-            &params.query,
-            max_results,
-            min_confidence,
-        );
-        let index_size = search_cache.index.document_count();
+        // Perform search on the index, counting facets over the cached contacts
+        let data = search_cache.data.read().await;
+        let search = |query: &SearchQuery| {
+            data.index.search_faceted(
+                &data.contacts,
+                query,
+                &params.filter,
+                max_results,
+                min_confidence,
+                &params.snippets,
+            )
+        };
+        let mut faceted = search(&query);
+
+        // Suggest spellings when nothing matched, and retry once if asked to
+        let mut suggestions = Vec::new();
+        let mut corrected_query = None;
+        if faceted.total_matches == 0 {
+            suggestions = data.index.suggest_spelling(&params.query);
+            let retry = suggestions
+                .first()
+                .filter(|_| params.auto_correct)
+                .and_then(|s| Some((s.text.clone(), SearchQuery::parse(&s.text).ok()?)));
+            if let Some((text, corrected)) = retry {
+                faceted = search(&corrected);
+                corrected_query = Some(text);
+            }
+        }
+        let index_size = data.index.document_count();
 
         Ok(SearchResponse {
-            results,
+            results: faceted.results,
+            total_matches: faceted.total_matches,
+            facets: faceted.facets,
+            suggestions,
+            corrected_query,
             from_cache,
             index_size,
         })
     }
 
+    /// Find the `k` contacts most similar to a contact.
+    ///
+    /// Contacts are compared by the TF-IDF vectors of their indexed fields,
+    /// notes and reminders, entirely within the cached index.
+    ///
+    /// # Errors
+    /// [`DexApiError::NotFound`] if the contact is not in the index.
+    pub async fn find_similar_contacts(
+        &self,
+        contact_id: &str,
+        k: usize,
+    ) -> DexApiResult<Vec<SimilarContact>> {
+        let (search_cache, _) = self.get_or_build_cache().await?;
+
+        let data = search_cache.data.read().await;
+        data.index.similar_contacts(contact_id, k).ok_or_else(|| {
+            DexApiError::NotFound(format!("Contact {} in the search index", contact_id))
+        })
+    }
+
     /// Get the cached search data or build new.
+    ///
+    /// An expired index keeps being served while a new one is built in the
+    /// background; only a search with no index at all waits for a build.
     async fn get_or_build_cache(&self) -> DexApiResult<(SearchCache, bool)> {
-        let cache_key = "search_data".to_string();
-
-        // Try to get from cache
-        {
-            let cache = self.cache.read().await;
-            if let Some(cached_data) = cache.get(&cache_key) {
-                tracing::debug!("Using cached search index");
-                return Ok((cached_data, true));
-            }
+        if self.cache.get().is_none() {
+            self.load_snapshot().await;
         }
 
-        // Build new index and fetch contacts
+        let tools = self.clone();
+        self.cache
+            .get_or_build(move || {
+                let tools = tools.clone();
+                async move { tools.build_cache().await }
+            })
+            .await
+    }
+
+    /// Fetch all contacts with their notes and reminders, and index them.
+    async fn build_cache(&self) -> DexApiResult<SearchCache> {
         tracing::info!("Building search index");
         let start = std::time::Instant::now();
+        let generation = self.generation.load(Ordering::SeqCst);
 
         let contacts = self.fetch_all_contacts().await?;
 
@@ -184,7 +351,7 @@ impl SearchTools {
             .await;
 
         // Build index from results
-        let mut index = FullTextSearchIndex::new();
+        let mut index = FullTextSearchIndex::with_analyzer(self.analyzer.clone());
         for (contact, notes, reminders) in results {
             index.index_contact(&contact, &notes, &reminders);
         }
@@ -196,18 +363,128 @@ impl SearchTools {
             contacts.len()
         );
 
-        let search_cache = SearchCache {
-            index: Arc::new(index),
-            contacts: Arc::new(contacts),
+        let search_cache = SearchCache::new(index, contacts);
+
+        // Only persist the index if no write raced with the fetch; the cache
+        // discards it in that case too
+        self.save_snapshot(&search_cache, generation).await;
+
+        Ok(search_cache)
+    }
+
+    /// Load the snapshot into the cache, once per process.
+    ///
+    /// Nothing is loaded if there is no usable snapshot, or if data was
+    /// written since startup. A snapshot older than the cache TTL is served
+    /// while a fresh index is built in the background.
+    async fn load_snapshot(&self) {
+        let Some(store) = self.snapshot.clone() else {
+            return;
         };
+        if self.snapshot_loaded.swap(true, Ordering::SeqCst) {
+            return;
+        }
 
-        // Cache the search data
-        {
-            let cache = self.cache.write().await;
-            cache.insert(cache_key, search_cache.clone());
+        let generation = self.generation.load(Ordering::SeqCst);
+        if generation != 0 {
+            tracing::debug!("Data changed since startup; not loading search index snapshot");
+            return;
         }
 
-        Ok((search_cache, false))
+        let path = store.path().display().to_string();
+        let analyzer = self.analyzer.clone();
+        let snapshot = match tokio::task::spawn_blocking(move || store.load(analyzer)).await {
+            Ok(Ok(Some(snapshot))) => snapshot,
+            Ok(Ok(None)) => {
+                tracing::debug!("No search index snapshot at {}", path);
+                return;
+            }
+            Ok(Err(e)) => {
+                tracing::info!("Not using search index snapshot {}: {}", path, e);
+                return;
+            }
+            Err(e) => {
+                tracing::warn!("Loading search index snapshot {} failed: {}", path, e);
+                return;
+            }
+        };
+
+        tracing::info!(
+            "Loaded search index snapshot from {} ({} contacts, {}s old)",
+            path,
+            snapshot.contacts.len(),
+            snapshot.age.as_secs()
+        );
+        self.cache.insert(
+            SearchCache::new(snapshot.index, snapshot.contacts),
+            snapshot.age,
+        );
+
+        // A write that ran while loading may have missed the snapshot
+        if self.generation.load(Ordering::SeqCst) != generation {
+            tracing::debug!("Data changed while loading search index snapshot; discarding it");
+            self.cache.invalidate();
+        }
+    }
+
+    /// Write the cached index to the snapshot now if it has in-place updates
+    /// the snapshot lacks.
+    ///
+    /// Updates are otherwise written a few seconds after they are made; call
+    /// this before shutting down so the last ones are not lost.
+    pub async fn flush_snapshot(&self) {
+        if !self.snapshot_dirty.swap(false, Ordering::SeqCst) {
+            return;
+        }
+        let generation = self.generation.load(Ordering::SeqCst);
+        if let Some(search_cache) = self.cache.get() {
+            self.save_snapshot(&search_cache, generation).await;
+        }
+    }
+
+    /// Mark the snapshot as lacking in-place updates, and write it after
+    /// [`SNAPSHOT_DEBOUNCE`] unless a write is already scheduled.
+    fn schedule_snapshot(&self) {
+        if self.snapshot.is_none() || self.snapshot_dirty.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let tools = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(SNAPSHOT_DEBOUNCE).await;
+            tools.flush_snapshot().await;
+        });
+    }
+
+    /// Write the cached search data to the snapshot, if enabled.
+    ///
+    /// The data is encoded and written on a blocking thread. Nothing is
+    /// written if a write or invalidation happened since `generation` was
+    /// read, as the data may no longer match what was cached then.
+    ///
+    /// Failures are logged rather than returned: the snapshot only speeds up
+    /// the next start, so a broken one must not fail searches or writes.
+    async fn save_snapshot(&self, search_cache: &SearchCache, generation: u64) {
+        let Some(store) = self.snapshot.clone() else {
+            return;
+        };
+
+        let _guard = self.snapshot_write_lock.lock().await;
+        let data = search_cache.data.clone().read_owned().await;
+        let current = self.generation.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let contents = IndexSnapshotStore::encode(&data.index, &data.contacts)?;
+            drop(data);
+            if current.load(Ordering::SeqCst) != generation {
+                tracing::debug!("Data changed while encoding; not writing search index snapshot");
+                return Ok(());
+            }
+            store.write(&contents)
+        })
+        .await
+        .unwrap_or_else(|e| Err(std::io::Error::other(e).into()));
+        if let Err(e) = result {
+            tracing::warn!("Failed to write search index snapshot: {}", e);
+        }
     }
 
     /// Fetch all contacts with pagination.
@@ -234,13 +511,83 @@ impl SearchTools {
     /// Invalidate the search index cache.
     ///
     /// This should be called after any contact modifications to ensure
-    /// fresh data is indexed on the next search.
+    /// fresh data is indexed on the next search. The snapshot is deleted too,
+    /// so a restart before the next build does not bring back stale data.
     pub async fn invalidate_cache(&self) {
-        let cache = self.cache.write().await;
-        cache.remove(&"search_data".to_string());
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.cache.invalidate();
+        self.snapshot_dirty.store(false, Ordering::SeqCst);
+        if let Some(ref store) = self.snapshot {
+            let _guard = self.snapshot_write_lock.lock().await;
+            if let Err(e) = store.remove() {
+                tracing::warn!("Failed to delete search index snapshot: {}", e);
+            }
+        }
         tracing::debug!("Search index cache invalidated");
     }
 
+    /// Add or replace a contact's fields in the cached index.
+    pub async fn upsert_contact(&self, contact: &Contact) {
+        self.update_cached(|data| {
+            data.index.upsert_contact(contact);
+            match data.contacts.iter_mut().find(|c| c.id == contact.id) {
+                Some(existing) => *existing = contact.clone(),
+                None => data.contacts.push(contact.clone()),
+            }
+        })
+        .await;
+    }
+
+    /// Remove a contact with its notes and reminders from the cached index.
+    pub async fn remove_contact(&self, contact_id: &str) {
+        self.update_cached(|data| {
+            data.index.remove_contact(contact_id);
+            data.contacts.retain(|c| c.id != contact_id);
+        })
+        .await;
+    }
+
+    /// Add or replace a note in the cached index.
+    pub async fn upsert_note(&self, note: &Note) {
+        self.update_cached(|data| data.index.upsert_note(note))
+            .await;
+    }
+
+    /// Remove a note from the cached index.
+    pub async fn remove_note(&self, note_id: &str) {
+        self.update_cached(|data| data.index.remove_note(note_id))
+            .await;
+    }
+
+    /// Add or replace a reminder in the cached index.
+    pub async fn upsert_reminder(&self, reminder: &Reminder) {
+        self.update_cached(|data| data.index.upsert_reminder(reminder))
+            .await;
+    }
+
+    /// Remove a reminder from the cached index.
+    pub async fn remove_reminder(&self, reminder_id: &str) {
+        self.update_cached(|data| data.index.remove_reminder(reminder_id))
+            .await;
+    }
+
+    /// Apply a write to the cached search data, if any.
+    ///
+    /// Without a cached index there is nothing to update: the next search
+    /// builds a fresh one that already includes the write. The snapshot is
+    /// written later in the background, so writes do not wait for it.
+    async fn update_cached(&self, update: impl FnOnce(&mut SearchData)) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        // A build running now fetched its data before this write
+        self.cache.discard_pending();
+
+        if let Some(search_cache) = self.cache.get() {
+            update(&mut *search_cache.data.write().await);
+            tracing::debug!("Search index updated in place");
+            self.schedule_snapshot();
+        }
+    }
+
     /// Get the current cache TTL in seconds.
     pub fn cache_ttl_secs(&self) -> u64 {
         self.cache_ttl_secs
@@ -291,31 +638,42 @@ mod tests {
         let tools = SearchTools::new(contact_repo, note_repo, reminder_repo, cache_ttl_secs);
 
         // Insert something into cache
-        {
-            let cache = tools.cache.write().await;
-            cache.insert(
-                "search_data".to_string(),
-                SearchCache {
-                    index: Arc::new(FullTextSearchIndex::new()),
-                    contacts: Arc::new(vec![]),
-                },
-            );
-        }
+        tools.cache.insert(
+            SearchCache::new(FullTextSearchIndex::new(), vec![]),
+            Duration::ZERO,
+        );
 
         // Verify it exists
-        {
-            let cache = tools.cache.read().await;
-            assert!(cache.contains_key(&"search_data".to_string()));
-        }
+        assert!(tools.cache.get().is_some());
 
         // Invalidate
         tools.invalidate_cache().await;
 
         // Verify it's gone
-        {
-            let cache = tools.cache.read().await;
-            assert!(!cache.contains_key(&"search_data".to_string()));
-        }
+        assert!(tools.cache.get().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_malformed_query_fails_before_indexing() {
+        let config = Config::default();
+        let sync_client = DexClient::new(&config);
+        let client = Arc::new(AsyncDexClientImpl::new(sync_client)) as Arc<dyn AsyncDexClient>;
+
+        let contact_repo = Arc::new(DexContactRepository::new(client.clone()));
+        let note_repo = Arc::new(DexNoteRepository::new(client.clone()));
+        let reminder_repo = Arc::new(DexReminderRepository::new(client));
+        let tools = SearchTools::new(contact_repo, note_repo, reminder_repo, 300);
+
+        let params = SearchParams {
+            query: "city:berlin -tag:former".to_string(),
+            ..Default::default()
+        };
+        let err = tools.search_full_text(params).await.unwrap_err();
+        assert!(matches!(
+            err,
+            crate::error::DexApiError::InvalidRequest(ref message)
+                if message.contains("unknown field 'city'")
+        ));
     }
 
     // Note: More comprehensive tests would require mocking the AsyncDexClient
@@ -0,0 +1,254 @@
+//! Undo tools.
+//!
+//! Reverse changes recorded in the audit log by replaying their before-images
+//! through the (audited) repositories.
+
+use crate::audit::{self, AuditEntry, AuditLog, AuditOperation, AuditRecordType};
+use crate::error::{DexApiError, DexApiResult};
+use crate::models::{Contact, Note, Reminder};
+use crate::repositories::{ContactRepository, NoteRepository, ReminderRepository};
+use crate::tools::dry_run::ChangePreview;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// Undo tools for reverting audited changes.
+pub struct UndoTools {
+    contact_repo: Arc<dyn ContactRepository>,
+    note_repo: Arc<dyn NoteRepository>,
+    reminder_repo: Arc<dyn ReminderRepository>,
+    audit_log: Arc<AuditLog>,
+}
+
+/// What was done to reverse a change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RevertAction {
+    /// An update was reverted by writing the before-image back
+    Restored,
+    /// A create was reverted by deleting the record
+    Deleted,
+    /// A delete was reverted by creating the record again
+    Recreated,
+}
+
+/// Result of reverting a change.
+#[derive(Debug, Clone, Serialize)]
+pub struct RevertResult {
+    /// The audit entry that was reverted
+    pub reverted: AuditEntry,
+
+    /// How the change was reversed
+    pub action: RevertAction,
+
+    /// ID of the affected record. Recreated records get a new ID from Dex.
+    pub record_id: String,
+}
+
+impl UndoTools {
+    /// Create new undo tools.
+    ///
+    /// The repositories should be the audited decorators writing to
+    /// `audit_log`, so that each revert is itself recorded.
+    pub fn new(
+        contact_repo: Arc<dyn ContactRepository>,
+        note_repo: Arc<dyn NoteRepository>,
+        reminder_repo: Arc<dyn ReminderRepository>,
+        audit_log: Arc<AuditLog>,
+    ) -> Self {
+        Self {
+            contact_repo,
+            note_repo,
+            reminder_repo,
+            audit_log,
+        }
+    }
+
+    /// Revert the change with the given ID.
+    ///
+    /// Fails if the change does not exist, was already reverted, or lacks the
+    /// before-image needed to reverse it.
+    pub async fn revert_change(&self, change_id: &str) -> DexApiResult<RevertResult> {
+        let entry = self.revertible(change_id)?;
+        audit::with_revert(change_id, self.apply_revert(entry)).await
+    }
+
+    /// Preview reverting the change with the given ID without writing anything.
+    pub async fn preview_revert_change(
+        &self,
+        change_id: &str,
+    ) -> DexApiResult<ChangePreview<serde_json::Value>> {
+        let entry = self.revertible(change_id)?;
+        self.preview_revert(&entry).await
+    }
+
+    /// Revert the most recent change that has not already been reverted.
+    ///
+    /// Reverts are skipped, so calling this repeatedly walks further back
+    /// through the history instead of undoing the previous undo.
+    pub async fn undo_last_change(&self) -> DexApiResult<RevertResult> {
+        let entry = self
+            .audit_log
+            .last_undoable()?
+            .ok_or_else(|| DexApiError::NotFound("No changes to undo".to_string()))?;
+
+        let change_id = entry.change_id.clone();
+        audit::with_revert(&change_id, self.apply_revert(entry)).await
+    }
+
+    /// Preview `undo_last_change` without writing anything.
+    pub async fn preview_undo_last_change(&self) -> DexApiResult<ChangePreview<serde_json::Value>> {
+        let entry = self
+            .audit_log
+            .last_undoable()?
+            .ok_or_else(|| DexApiError::NotFound("No changes to undo".to_string()))?;
+
+        self.preview_revert(&entry).await
+    }
+
+    /// Look up a change and check that it can still be reverted.
+    fn revertible(&self, change_id: &str) -> DexApiResult<AuditEntry> {
+        let entry = self.audit_log.find(change_id)?.ok_or_else(|| {
+            DexApiError::NotFound(format!("No change with ID {} in the audit log", change_id))
+        })?;
+
+        if let Some(revert) = self.audit_log.find_revert_of(change_id)? {
+            return Err(DexApiError::InvalidRequest(format!(
+                "Change {} was already reverted by {}",
+                change_id, revert.change_id
+            )));
+        }
+
+        Ok(entry)
+    }
+
+    async fn preview_revert(
+        &self,
+        entry: &AuditEntry,
+    ) -> DexApiResult<ChangePreview<serde_json::Value>> {
+        Ok(match entry.operation {
+            AuditOperation::Create => ChangePreview::delete(self.current_value(entry).await?),
+            AuditOperation::Update => {
+                ChangePreview::update(self.current_value(entry).await?, before_image_value(entry)?)
+            }
+            AuditOperation::Delete => ChangePreview::create(before_image_value(entry)?),
+        })
+    }
+
+    /// Fetch the live record an audit entry refers to.
+    async fn current_value(&self, entry: &AuditEntry) -> DexApiResult<serde_json::Value> {
+        let value = match entry.record_type {
+            AuditRecordType::Contact => {
+                serde_json::to_value(self.contact_repo.get(&entry.record_id).await?)?
+            }
+            AuditRecordType::Note => {
+                serde_json::to_value(self.note_repo.get(&entry.record_id).await?)?
+            }
+            AuditRecordType::Reminder => {
+                serde_json::to_value(self.reminder_repo.get(&entry.record_id).await?)?
+            }
+        };
+        Ok(value)
+    }
+
+    async fn apply_revert(&self, entry: AuditEntry) -> DexApiResult<RevertResult> {
+        let record_id = match (entry.operation, entry.record_type) {
+            (AuditOperation::Create, AuditRecordType::Contact) => {
+                self.contact_repo.delete(&entry.record_id).await?;
+                entry.record_id.clone()
+            }
+            (AuditOperation::Create, AuditRecordType::Note) => {
+                self.note_repo.delete(&entry.record_id).await?;
+                entry.record_id.clone()
+            }
+            (AuditOperation::Create, AuditRecordType::Reminder) => {
+                self.reminder_repo.delete(&entry.record_id).await?;
+                entry.record_id.clone()
+            }
+            (AuditOperation::Update, AuditRecordType::Contact) => {
+                let contact = contact_before_image(&entry)?;
+                self.contact_repo
+                    .update(&entry.record_id, &contact)
+                    .await?
+                    .id
+            }
+            (AuditOperation::Update, AuditRecordType::Note) => {
+                let note = note_before_image(&entry)?;
+                self.note_repo.update(&entry.record_id, &note).await?.id
+            }
+            (AuditOperation::Update, AuditRecordType::Reminder) => {
+                let reminder = reminder_before_image(&entry)?;
+                self.reminder_repo
+                    .update(&entry.record_id, &reminder)
+                    .await?
+                    .id
+            }
+            (AuditOperation::Delete, AuditRecordType::Contact) => {
+                let contact = contact_before_image(&entry)?;
+                self.contact_repo.create(&contact).await?.id
+            }
+            (AuditOperation::Delete, AuditRecordType::Note) => {
+                let note = note_before_image(&entry)?;
+                self.note_repo.create(&note).await?.id
+            }
+            (AuditOperation::Delete, AuditRecordType::Reminder) => {
+                let reminder = reminder_before_image(&entry)?;
+                self.reminder_repo.create(&reminder).await?.id
+            }
+        };
+
+        let action = match entry.operation {
+            AuditOperation::Create => RevertAction::Deleted,
+            AuditOperation::Update => RevertAction::Restored,
+            AuditOperation::Delete => RevertAction::Recreated,
+        };
+
+        Ok(RevertResult {
+            reverted: entry,
+            action,
+            record_id,
+        })
+    }
+}
+
+/// Deserialize the before-image of an audit entry.
+fn before_image<T: DeserializeOwned>(entry: &AuditEntry) -> DexApiResult<T> {
+    let before = entry.before.clone().ok_or_else(|| {
+        DexApiError::InvalidRequest(format!(
+            "Change {} has no before-image and cannot be reverted",
+            entry.change_id
+        ))
+    })?;
+    Ok(serde_json::from_value(before)?)
+}
+
+/// The before-image as it would be written back, serialized like a live record.
+fn before_image_value(entry: &AuditEntry) -> DexApiResult<serde_json::Value> {
+    let value = match entry.record_type {
+        AuditRecordType::Contact => serde_json::to_value(contact_before_image(entry)?)?,
+        AuditRecordType::Note => serde_json::to_value(note_before_image(entry)?)?,
+        AuditRecordType::Reminder => serde_json::to_value(reminder_before_image(entry)?)?,
+    };
+    Ok(value)
+}
+
+fn contact_before_image(entry: &AuditEntry) -> DexApiResult<Contact> {
+    let mut contact: Contact = before_image(entry)?;
+    contact.id = entry.record_id.clone();
+    contact.populate_computed_fields();
+    Ok(contact)
+}
+
+fn note_before_image(entry: &AuditEntry) -> DexApiResult<Note> {
+    let mut note: Note = before_image(entry)?;
+    note.id = entry.record_id.clone();
+    note.contact_id = entry.contact_id.clone();
+    Ok(note)
+}
+
+fn reminder_before_image(entry: &AuditEntry) -> DexApiResult<Reminder> {
+    let mut reminder: Reminder = before_image(entry)?;
+    reminder.id = entry.record_id.clone();
+    reminder.contact_id = entry.contact_id.clone();
+    Ok(reminder)
+}
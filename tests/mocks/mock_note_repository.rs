@@ -82,6 +82,16 @@ impl NoteRepository for MockNoteRepository {
         Ok(result)
     }
 
+    async fn get(&self, id: &str) -> DexApiResult<Note> {
+        self.track_call("get");
+
+        let notes = self.notes.lock().unwrap();
+        notes
+            .get(id)
+            .cloned()
+            .ok_or_else(|| DexApiError::NotFound(format!("Note {} not found", id)))
+    }
+
     async fn create(&self, note: &Note) -> DexApiResult<Note> {
         self.track_call("create");
 
@@ -94,8 +104,18 @@ impl NoteRepository for MockNoteRepository {
             )));
         }
 
-        notes.insert(note.id.clone(), note.clone());
-        Ok(note.clone())
+        // Assign an ID the way the API does when none was provided
+        let mut created = note.clone();
+        if created.id.is_empty() {
+            let mut n = notes.len() + 1;
+            while notes.contains_key(&format!("note-{}", n)) {
+                n += 1;
+            }
+            created.id = format!("note-{}", n);
+        }
+
+        notes.insert(created.id.clone(), created.clone());
+        Ok(created)
     }
 
     async fn update(&self, id: &str, note: &Note) -> DexApiResult<Note> {
@@ -82,6 +82,16 @@ impl ReminderRepository for MockReminderRepository {
         Ok(result)
     }
 
+    async fn get(&self, id: &str) -> DexApiResult<Reminder> {
+        self.track_call("get");
+
+        let reminders = self.reminders.lock().unwrap();
+        reminders
+            .get(id)
+            .cloned()
+            .ok_or_else(|| DexApiError::NotFound(format!("Reminder {} not found", id)))
+    }
+
     async fn create(&self, reminder: &Reminder) -> DexApiResult<Reminder> {
         self.track_call("create");
 
@@ -94,8 +104,18 @@ impl ReminderRepository for MockReminderRepository {
             )));
         }
 
-        reminders.insert(reminder.id.clone(), reminder.clone());
-        Ok(reminder.clone())
+        // Assign an ID the way the API does when none was provided
+        let mut created = reminder.clone();
+        if created.id.is_empty() {
+            let mut n = reminders.len() + 1;
+            while reminders.contains_key(&format!("reminder-{}", n)) {
+                n += 1;
+            }
+            created.id = format!("reminder-{}", n);
+        }
+
+        reminders.insert(created.id.clone(), created.clone());
+        Ok(created)
     }
 
     async fn update(&self, id: &str, reminder: &Reminder) -> DexApiResult<Reminder> {
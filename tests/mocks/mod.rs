@@ -3,3 +3,165 @@ pub mod mock_note_repository;
 pub mod mock_reminder_repository;
 
 pub use mock_contact_repository::MockContactRepository;
+#[allow(unused_imports)]
+pub use mock_note_repository::MockNoteRepository;
+#[allow(unused_imports)]
+pub use mock_reminder_repository::MockReminderRepository;
+
+use dex_mcp_server::audit::AuditLog;
+use dex_mcp_server::models::Note;
+use dex_mcp_server::repositories::{
+    AuditedContactRepository, AuditedNoteRepository, AuditedReminderRepository, ContactRepository,
+    NoteRepository, ReminderRepository,
+};
+use dex_mcp_server::services::{
+    ContactSearchParams, ContactServiceImpl, NoteServiceImpl, ReminderServiceImpl,
+};
+use dex_mcp_server::tools::{
+    ContactDiscoveryTools, ContactEnrichmentTools, RelationshipHistoryTools, SearchTools,
+};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Mock repositories behind the audited decorators, writing to a temporary
+/// audit log that is removed when the environment is dropped.
+#[allow(dead_code)]
+pub struct AuditedEnv {
+    pub audit_log: Arc<AuditLog>,
+    pub contacts: MockContactRepository,
+    pub notes: MockNoteRepository,
+    pub reminders: MockReminderRepository,
+    pub contact_repo: Arc<dyn ContactRepository>,
+    pub note_repo: Arc<dyn NoteRepository>,
+    pub reminder_repo: Arc<dyn ReminderRepository>,
+}
+
+impl Drop for AuditedEnv {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(self.audit_log.path());
+    }
+}
+
+/// Audited mock repositories with an audit log named after `name`.
+#[allow(dead_code)]
+pub fn audited_env(name: &str) -> AuditedEnv {
+    audited_env_with_contacts(name, |contacts| Arc::new(contacts.clone()))
+}
+
+/// Like [`audited_env`], auditing the contact repository `contact_repo`
+/// builds over the mock contacts (to inject failures, say).
+#[allow(dead_code)]
+pub fn audited_env_with_contacts(
+    name: &str,
+    contact_repo: impl FnOnce(&MockContactRepository) -> Arc<dyn ContactRepository>,
+) -> AuditedEnv {
+    let path = std::env::temp_dir().join(format!("dex-test-{}-{}.jsonl", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let audit_log = Arc::new(AuditLog::new(path));
+
+    let contacts = MockContactRepository::new();
+    let notes = MockNoteRepository::new();
+    let reminders = MockReminderRepository::new();
+
+    let contact_repo = Arc::new(AuditedContactRepository::new(
+        contact_repo(&contacts),
+        audit_log.clone(),
+    )) as Arc<dyn ContactRepository>;
+    let note_repo = Arc::new(AuditedNoteRepository::new(
+        Arc::new(notes.clone()),
+        audit_log.clone(),
+    )) as Arc<dyn NoteRepository>;
+    let reminder_repo = Arc::new(AuditedReminderRepository::new(
+        Arc::new(reminders.clone()),
+        audit_log.clone(),
+    )) as Arc<dyn ReminderRepository>;
+
+    AuditedEnv {
+        audit_log,
+        contacts,
+        notes,
+        reminders,
+        contact_repo,
+        note_repo,
+        reminder_repo,
+    }
+}
+
+/// Contact, note and reminder services over the same mock repositories,
+/// sharing one search cache.
+#[allow(dead_code)]
+pub struct MockServices {
+    pub contact_service: ContactServiceImpl,
+    pub note_service: NoteServiceImpl,
+    pub reminder_service: ReminderServiceImpl,
+}
+
+/// Services over the mock repositories, with 300 second caches.
+#[allow(dead_code)]
+pub fn services_with(
+    contacts: MockContactRepository,
+    notes: MockNoteRepository,
+    reminders: MockReminderRepository,
+) -> MockServices {
+    let contact_repo = Arc::new(contacts) as Arc<dyn ContactRepository>;
+    let note_repo = Arc::new(notes) as Arc<dyn NoteRepository>;
+    let reminder_repo = Arc::new(reminders) as Arc<dyn ReminderRepository>;
+
+    let history_tools = Arc::new(RelationshipHistoryTools::new(
+        contact_repo.clone(),
+        note_repo.clone(),
+        reminder_repo.clone(),
+    ));
+    let enrichment_tools = Arc::new(ContactEnrichmentTools::new(
+        contact_repo.clone(),
+        note_repo.clone(),
+        reminder_repo.clone(),
+    ));
+    let search_tools = SearchTools::new(contact_repo.clone(), note_repo, reminder_repo, 300);
+
+    MockServices {
+        contact_service: ContactServiceImpl::new(
+            Arc::new(RwLock::new(ContactDiscoveryTools::new(contact_repo, 300))),
+            enrichment_tools.clone(),
+            search_tools.clone(),
+        ),
+        note_service: NoteServiceImpl::new(
+            history_tools.clone(),
+            enrichment_tools.clone(),
+            search_tools.clone(),
+        ),
+        reminder_service: ReminderServiceImpl::new(history_tools, enrichment_tools, search_tools),
+    }
+}
+
+/// A contact service over the mock repositories, with 300 second caches.
+#[allow(dead_code)]
+pub fn service_with(
+    contacts: MockContactRepository,
+    notes: MockNoteRepository,
+    reminders: MockReminderRepository,
+) -> ContactServiceImpl {
+    services_with(contacts, notes, reminders).contact_service
+}
+
+/// A note created on 2024-01-01.
+#[allow(dead_code)]
+pub fn note(id: &str, contact_id: &str, content: &str) -> Note {
+    Note {
+        id: id.to_string(),
+        contact_id: contact_id.to_string(),
+        content: content.to_string(),
+        created_at: "2024-01-01T00:00:00Z".to_string(),
+        ..Default::default()
+    }
+}
+
+/// Full-text search parameters returning matches of any confidence.
+#[allow(dead_code)]
+pub fn search_params(query: &str) -> ContactSearchParams {
+    ContactSearchParams {
+        query: query.to_string(),
+        min_confidence: Some(0),
+        ..Default::default()
+    }
+}
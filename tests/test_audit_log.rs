@@ -0,0 +1,107 @@
+//! Tests for the audited repository decorators and the JSONL audit log.
+
+mod mocks;
+
+use dex_mcp_server::audit::{self, AuditOperation, AuditRecordType};
+use dex_mcp_server::models::{Contact, Note};
+use dex_mcp_server::tools::{ContactEnrichmentTools, CreateNoteParams, EnrichContactParams};
+use mocks::audited_env;
+
+fn sample_contact(id: &str, company: &str) -> Contact {
+    Contact {
+        id: id.to_string(),
+        first_name: Some("Jane".to_string()),
+        last_name: Some("Doe".to_string()),
+        company: Some(company.to_string()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_update_records_before_and_after() {
+    let env = audited_env("update");
+    env.contacts.add_contact(sample_contact("c1", "Acme"));
+
+    let tools = ContactEnrichmentTools::new(
+        env.contact_repo.clone(),
+        env.note_repo.clone(),
+        env.reminder_repo.clone(),
+    );
+    let params = EnrichContactParams {
+        contact_id: "c1".to_string(),
+        company: Some("Globex".to_string()),
+        ..Default::default()
+    };
+    audit::with_tool("enrich_contact", tools.enrich_contact(params))
+        .await
+        .unwrap();
+
+    let entries = env.audit_log.query(None, None).unwrap();
+    assert_eq!(entries.len(), 1);
+
+    let entry = &entries[0];
+    assert_eq!(entry.tool, "enrich_contact");
+    assert_eq!(entry.operation, AuditOperation::Update);
+    assert_eq!(entry.record_type, AuditRecordType::Contact);
+    assert_eq!(entry.contact_id, "c1");
+    assert_eq!(entry.before.as_ref().unwrap()["company"], "Acme");
+    assert_eq!(entry.after.as_ref().unwrap()["company"], "Globex");
+}
+
+#[tokio::test]
+async fn test_create_and_delete_note_are_logged() {
+    let env = audited_env("note");
+
+    let tools = ContactEnrichmentTools::new(
+        env.contact_repo.clone(),
+        env.note_repo.clone(),
+        env.reminder_repo.clone(),
+    );
+    let params = CreateNoteParams {
+        contact_id: "c1".to_string(),
+        content: "Met for coffee".to_string(),
+        tags: None,
+        source: None,
+    };
+    let note = audit::with_tool("add_contact_note", tools.add_contact_note(params))
+        .await
+        .unwrap();
+    env.note_repo.delete(&note.id).await.unwrap();
+
+    let entries = env.audit_log.query(Some("c1"), None).unwrap();
+    assert_eq!(entries.len(), 2);
+
+    assert_eq!(entries[0].operation, AuditOperation::Create);
+    assert_eq!(entries[0].tool, "add_contact_note");
+    assert!(entries[0].before.is_none());
+    assert_eq!(entries[0].after.as_ref().unwrap()["note"], "Met for coffee");
+
+    assert_eq!(entries[1].operation, AuditOperation::Delete);
+    assert_eq!(entries[1].tool, "unknown");
+    assert_eq!(entries[1].record_id, note.id);
+    assert_eq!(entries[1].before.as_ref().unwrap()["id"], note.id.as_str());
+    assert!(entries[1].after.is_none());
+}
+
+#[tokio::test]
+async fn test_failed_mutation_is_not_logged() {
+    let env = audited_env("failed");
+
+    let result = env.note_repo.update("missing", &Note::default()).await;
+    assert!(result.is_err());
+    assert_eq!(env.notes.get_call_count("update"), 1);
+
+    assert!(env.audit_log.query(None, None).unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_reads_are_not_logged() {
+    let env = audited_env("reads");
+    env.contacts.add_contact(sample_contact("c1", "Acme"));
+
+    env.contact_repo.get("c1").await.unwrap();
+    env.contact_repo.list(10, 0).await.unwrap();
+    env.note_repo.get_for_contact("c1", 10, 0).await.unwrap();
+
+    assert!(env.audit_log.query(None, None).unwrap().is_empty());
+}
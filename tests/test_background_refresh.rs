@@ -0,0 +1,99 @@
+//! Tests for stale-while-revalidate refreshing of the search and discovery caches.
+
+mod mocks;
+
+use dex_mcp_server::models::Contact;
+use dex_mcp_server::tools::{
+    ContactDiscoveryTools, FindContactParams, SearchParams, SearchResponse, SearchTools,
+};
+use mocks::{MockContactRepository, MockNoteRepository, MockReminderRepository};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn contacts() -> MockContactRepository {
+    let contacts = MockContactRepository::new();
+    contacts.add_contact(Contact::new("c1".to_string(), "Jane Roe".to_string()));
+    contacts
+}
+
+fn search_tools(contacts: &MockContactRepository, ttl_secs: u64) -> SearchTools {
+    SearchTools::new(
+        Arc::new(contacts.clone()),
+        Arc::new(MockNoteRepository::new()),
+        Arc::new(MockReminderRepository::new()),
+        ttl_secs,
+    )
+}
+
+async fn search(tools: &SearchTools, query: &str) -> SearchResponse {
+    tools
+        .search_full_text(SearchParams {
+            query: query.to_string(),
+            min_confidence: Some(0),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_concurrent_searches_share_one_build() {
+    let contacts = contacts();
+    let tools = search_tools(&contacts, 300);
+
+    let (a, b) = tokio::join!(search(&tools, "jane"), search(&tools, "roe"));
+    assert_eq!(a.results.len(), 1);
+    assert_eq!(b.results.len(), 1);
+    assert_eq!(contacts.get_call_count("list"), 1);
+}
+
+#[tokio::test]
+async fn test_expired_index_is_served_while_rebuilding() {
+    let contacts = contacts();
+    let tools = search_tools(&contacts, 1);
+
+    assert!(!search(&tools, "jane").await.from_cache);
+    contacts.add_contact(Contact::new("c2".to_string(), "John Doe".to_string()));
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+
+    // The expired index answers immediately and a rebuild starts
+    let stale = search(&tools, "john").await;
+    assert!(stale.from_cache);
+    assert!(stale.results.is_empty());
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let fresh = search(&tools, "john").await;
+    assert!(fresh.from_cache);
+    assert_eq!(fresh.results[0].contact.id, "c2");
+    assert_eq!(contacts.get_call_count("list"), 2);
+}
+
+#[tokio::test]
+async fn test_expired_contacts_are_served_while_refreshing() {
+    let contacts = contacts();
+    let tools = ContactDiscoveryTools::new(Arc::new(contacts.clone()), 1);
+    let find = |name: &str| FindContactParams {
+        name: Some(name.to_string()),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        tools
+            .find_contact(find("Jane Roe"))
+            .await
+            .unwrap()
+            .matches
+            .len(),
+        1
+    );
+    contacts.add_contact(Contact::new("c2".to_string(), "John Doe".to_string()));
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+
+    let stale = tools.find_contact(find("John Doe")).await.unwrap();
+    assert!(stale.matches.iter().all(|m| m.contact.id != "c2"));
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let fresh = tools.find_contact(find("John Doe")).await.unwrap();
+    assert_eq!(fresh.matches[0].contact.id, "c2");
+    assert_eq!(contacts.get_call_count("list"), 2);
+}
@@ -0,0 +1,112 @@
+//! Tests for dry-run previews of mutating tools.
+
+mod mocks;
+
+use dex_mcp_server::audit::AuditOperation;
+use dex_mcp_server::models::Contact;
+use dex_mcp_server::tools::{
+    ContactEnrichmentTools, CreateNoteParams, CreateReminderParams, EnrichContactParams,
+};
+use mocks::{MockContactRepository, MockNoteRepository, MockReminderRepository};
+use std::sync::Arc;
+
+struct TestEnv {
+    contacts: MockContactRepository,
+    notes: MockNoteRepository,
+    reminders: MockReminderRepository,
+    tools: ContactEnrichmentTools,
+}
+
+fn setup() -> TestEnv {
+    let contacts = MockContactRepository::new();
+    let notes = MockNoteRepository::new();
+    let reminders = MockReminderRepository::new();
+
+    contacts.add_contact(Contact {
+        id: "c1".to_string(),
+        first_name: Some("Jane".to_string()),
+        company: Some("Acme".to_string()),
+        tags: vec!["friend".to_string()],
+        ..Default::default()
+    });
+
+    let tools = ContactEnrichmentTools::new(
+        Arc::new(contacts.clone()),
+        Arc::new(notes.clone()),
+        Arc::new(reminders.clone()),
+    );
+
+    TestEnv {
+        contacts,
+        notes,
+        reminders,
+        tools,
+    }
+}
+
+fn enrich_params() -> EnrichContactParams {
+    EnrichContactParams {
+        contact_id: "c1".to_string(),
+        company: Some("Globex".to_string()),
+        tags: Some(vec!["work".to_string(), "friend".to_string()]),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_enrich_preview_matches_real_update() {
+    let env = setup();
+
+    let preview = env
+        .tools
+        .preview_enrich_contact(enrich_params())
+        .await
+        .unwrap();
+    assert_eq!(env.contacts.get_call_count("update"), 0);
+
+    assert_eq!(preview.operation, AuditOperation::Update);
+    let fields: Vec<&str> = preview.changes.iter().map(|c| c.field.as_str()).collect();
+    assert_eq!(fields, vec!["company", "tags"]);
+
+    let updated = env.tools.enrich_contact(enrich_params()).await.unwrap();
+    assert_eq!(preview.proposed, Some(updated));
+}
+
+#[tokio::test]
+async fn test_enrich_preview_of_missing_contact_fails() {
+    let env = setup();
+
+    let params = EnrichContactParams {
+        contact_id: "missing".to_string(),
+        ..Default::default()
+    };
+    assert!(env.tools.preview_enrich_contact(params).await.is_err());
+}
+
+#[tokio::test]
+async fn test_note_and_reminder_previews_do_not_create() {
+    let env = setup();
+
+    let note = env.tools.preview_contact_note(CreateNoteParams {
+        contact_id: "c1".to_string(),
+        content: "Met for coffee".to_string(),
+        tags: None,
+        source: Some("mcp".to_string()),
+    });
+    assert_eq!(note.operation, AuditOperation::Create);
+    assert!(note.current.is_none());
+    assert_eq!(note.proposed.as_ref().unwrap().content, "Met for coffee");
+    assert!(note.changes.iter().any(|c| c.field == "note"));
+
+    let reminder = env.tools.preview_contact_reminder(CreateReminderParams {
+        contact_id: "c1".to_string(),
+        text: "Follow up".to_string(),
+        due_date: "2024-02-01".to_string(),
+        tags: None,
+        priority: None,
+    });
+    assert_eq!(reminder.proposed.as_ref().unwrap().due_date, "2024-02-01");
+
+    assert_eq!(env.notes.get_call_count("create"), 0);
+    assert_eq!(env.reminders.get_call_count("create"), 0);
+}
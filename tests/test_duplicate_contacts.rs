@@ -0,0 +1,115 @@
+//! Tests for address-book-wide duplicate detection.
+
+mod mocks;
+
+use dex_mcp_server::error::DexApiError;
+use dex_mcp_server::matching::{MatchType, NameVariantKind};
+use dex_mcp_server::models::Contact;
+use dex_mcp_server::services::{ContactService, ContactServiceImpl};
+use mocks::{service_with, MockContactRepository, MockNoteRepository, MockReminderRepository};
+
+fn setup(contacts: Vec<Contact>) -> ContactServiceImpl {
+    let contact_repo = MockContactRepository::new();
+    contact_repo.add_contacts(contacts);
+    service_with(
+        contact_repo,
+        MockNoteRepository::new(),
+        MockReminderRepository::new(),
+    )
+}
+
+fn contact(id: &str, name: &str, email: Option<&str>, phone: Option<&str>) -> Contact {
+    let mut contact = Contact::new(id.to_string(), name.to_string());
+    contact.email = email.map(str::to_string);
+    contact.phone = phone.map(str::to_string);
+    contact
+}
+
+/// 10,000 distinct contacts, half of them named John, plus planted duplicates.
+fn address_book() -> Vec<Contact> {
+    let mut contacts: Vec<Contact> = (0..10_000)
+        .map(|i| {
+            let first = if i % 2 == 0 { "John" } else { "Person" };
+            let name = format!("{} Family{}x{}", first, i, i % 7);
+            contact(&format!("c{}", i), &name, None, None)
+        })
+        .collect();
+    contacts.extend([
+        contact("ada1", "Ada Lovelace", Some("ada@engine.org"), None),
+        contact("ada2", "Augusta Ada King", Some(" ADA@engine.org"), None),
+        contact("grace1", "Grace Hopper", None, Some("+1 (555) 010-1906")),
+        contact("grace2", "Grace M. Hopper", None, Some("555.010.1906")),
+        contact("alan1", "Alan Turing", None, None),
+        contact("alan2", "Alan Turin", None, None),
+    ]);
+    contacts
+}
+
+#[tokio::test]
+async fn test_finds_planted_duplicates_in_10k_contacts() {
+    let service = setup(address_book());
+
+    let report = service.find_duplicate_contacts(None, None).await.unwrap();
+
+    assert_eq!(report.contacts_scanned, 10_006);
+    // Far fewer than the ~50 million possible pairs
+    assert!(report.pairs_compared < 1_000, "{}", report.pairs_compared);
+
+    let clusters: Vec<Vec<&str>> = report
+        .clusters
+        .iter()
+        .map(|cluster| cluster.contacts.iter().map(|c| c.id.as_str()).collect())
+        .collect();
+    assert_eq!(
+        clusters,
+        vec![
+            vec!["ada1", "ada2"],
+            vec!["grace1", "grace2"],
+            // Contacts are listed by name
+            vec!["alan2", "alan1"]
+        ]
+    );
+    assert_eq!(report.total_clusters, 3);
+
+    let ada = &report.clusters[0];
+    assert_eq!(ada.confidence, 100);
+    assert_eq!(ada.matches[0].evidence[0].match_type, MatchType::ExactEmail);
+
+    let grace = &report.clusters[1].matches[0];
+    assert_eq!(grace.evidence[0].match_type, MatchType::ExactPhone);
+    assert_eq!(
+        grace.evidence[1].match_type,
+        MatchType::NameVariant(NameVariantKind::MiddleName)
+    );
+
+    let alan = &report.clusters[2].matches[0];
+    assert_eq!(alan.evidence.len(), 1);
+    assert_eq!(alan.evidence[0].match_type, MatchType::FuzzyName);
+    assert_eq!(alan.evidence[0].value, "Alan Turing / Alan Turin");
+}
+
+#[tokio::test]
+async fn test_min_confidence_and_max_clusters() {
+    let service = setup(address_book());
+
+    let report = service
+        .find_duplicate_contacts(Some(100), Some(1))
+        .await
+        .unwrap();
+    assert_eq!(report.total_clusters, 2);
+    assert_eq!(report.clusters.len(), 1);
+    assert_eq!(report.clusters[0].contacts[0].id, "ada1");
+}
+
+#[tokio::test]
+async fn test_invalid_parameters_are_rejected() {
+    let service = setup(Vec::new());
+
+    for (min_confidence, max_clusters) in [(Some(0), None), (Some(101), None), (None, Some(0))] {
+        let err = service
+            .find_duplicate_contacts(min_confidence, max_clusters)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DexApiError::InvalidRequest(_)), "{}", err);
+    }
+}
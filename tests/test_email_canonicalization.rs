@@ -0,0 +1,99 @@
+//! Tests for provider-aware email matching.
+
+mod mocks;
+
+use dex_mcp_server::domain::EmailAddress;
+use dex_mcp_server::matching::{find_duplicates, ContactMatcher, MatchType};
+use dex_mcp_server::models::Contact;
+use dex_mcp_server::tools::{ContactDiscoveryTools, FindContactParams};
+use mocks::MockContactRepository;
+use std::sync::Arc;
+
+fn contact(id: &str, name: &str, email: &str) -> Contact {
+    let mut contact = Contact::new(id.to_string(), name.to_string());
+    contact.email = Some(email.to_string());
+    contact
+}
+
+async fn find_by_email(contacts: Vec<Contact>, email: &str) -> Vec<String> {
+    let repo = Arc::new(MockContactRepository::new());
+    repo.add_contacts(contacts);
+    let tools = ContactDiscoveryTools::new(repo, 300);
+
+    let response = tools
+        .find_contact(FindContactParams {
+            email: Some(email.to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    response
+        .matches
+        .into_iter()
+        .inspect(|m| assert_eq!(m.match_type, MatchType::ExactEmail))
+        .map(|m| m.contact.id)
+        .collect()
+}
+
+#[tokio::test]
+async fn test_find_contact_matches_mailbox_spellings() {
+    let contacts = vec![
+        contact("gmail", "John Doe", "johndoe@googlemail.com"),
+        contact("outlook", "Jane Roe", "Jane.Roe@Outlook.com"),
+        contact("idn", "Max Müller", "max@bücher.de"),
+        contact("work", "Sam Poe", "sam@example.com"),
+    ];
+
+    for (query, id) in [
+        ("john.doe+crm@gmail.com", "gmail"),
+        ("J.O.H.N.D.O.E@GMAIL.COM", "gmail"),
+        ("jane.roe+newsletters@outlook.com", "outlook"),
+        ("max@xn--bcher-kva.de", "idn"),
+        ("MAX@BÜCHER.DE", "idn"),
+    ] {
+        assert_eq!(
+            find_by_email(contacts.clone(), query).await,
+            [id],
+            "{}",
+            query
+        );
+    }
+
+    // Dots matter at Outlook, and tags at unknown domains
+    assert!(find_by_email(contacts.clone(), "janeroe@outlook.com")
+        .await
+        .is_empty());
+    assert!(find_by_email(contacts, "sam+crm@example.com")
+        .await
+        .is_empty());
+}
+
+#[test]
+fn test_duplicates_share_a_canonical_email() {
+    let contacts = vec![
+        contact("a", "Jonathan Doe", "john.doe+crm@gmail.com"),
+        contact("b", "J Doe", "JohnDoe@googlemail.com"),
+        contact("c", "John Smith", "john.smith@example.com"),
+    ];
+    let report = find_duplicates(&mut ContactMatcher::new(), &contacts, 80, 10);
+
+    assert_eq!(report.clusters.len(), 1);
+    let ids: Vec<&str> = report.clusters[0]
+        .contacts
+        .iter()
+        .map(|c| c.id.as_str())
+        .collect();
+    assert_eq!(ids.len(), 2);
+    assert!(ids.contains(&"a") && ids.contains(&"b"));
+
+    // The contacts keep their addresses as written
+    assert_eq!(contacts[1].email.as_deref(), Some("JohnDoe@googlemail.com"));
+}
+
+#[test]
+fn test_canonical_form_leaves_original_untouched() {
+    let email = EmailAddress::new("John.Doe+crm@GoogleMail.com").unwrap();
+    assert_eq!(email.canonical(), "johndoe@gmail.com");
+    assert_eq!(email.as_str(), "John.Doe+crm@GoogleMail.com");
+    assert_eq!(email.to_string(), "John.Doe+crm@GoogleMail.com");
+}
@@ -0,0 +1,260 @@
+//! Tests for optimistic concurrency checks in enrich_contact.
+
+mod mocks;
+
+use async_trait::async_trait;
+use dex_mcp_server::error::{DexApiError, DexApiResult};
+use dex_mcp_server::models::Contact;
+use dex_mcp_server::repositories::ContactRepository;
+use dex_mcp_server::tools::{ContactEnrichmentTools, EnrichContactParams};
+use mocks::{MockContactRepository, MockNoteRepository, MockReminderRepository};
+use std::sync::{Arc, Mutex};
+
+type Edit = Box<dyn Fn(&mut Contact) + Send + Sync>;
+
+/// Wraps the mock repository and applies queued edits after reads, simulating
+/// another session changing the contact between enrich_contact's read and
+/// its write.
+struct ConcurrentEditRepository {
+    inner: MockContactRepository,
+    edits: Mutex<Vec<Edit>>,
+}
+
+impl ConcurrentEditRepository {
+    fn new(inner: MockContactRepository, edits: Vec<Edit>) -> Self {
+        Self {
+            inner,
+            edits: Mutex::new(edits),
+        }
+    }
+}
+
+#[async_trait]
+impl ContactRepository for ConcurrentEditRepository {
+    async fn get(&self, id: &str) -> DexApiResult<Contact> {
+        let contact = self.inner.get(id).await?;
+
+        let mut edits = self.edits.lock().unwrap();
+        if !edits.is_empty() {
+            let mut edited = contact.clone();
+            edits.remove(0)(&mut edited);
+            self.inner.add_contact(edited);
+        }
+
+        Ok(contact)
+    }
+
+    async fn list(&self, limit: usize, offset: usize) -> DexApiResult<Vec<Contact>> {
+        self.inner.list(limit, offset).await
+    }
+
+    async fn search_by_email(
+        &self,
+        email: &str,
+        limit: usize,
+        offset: usize,
+    ) -> DexApiResult<Vec<Contact>> {
+        self.inner.search_by_email(email, limit, offset).await
+    }
+
+    async fn search_by_name(
+        &self,
+        name: &str,
+        limit: usize,
+        offset: usize,
+    ) -> DexApiResult<Vec<Contact>> {
+        self.inner.search_by_name(name, limit, offset).await
+    }
+
+    async fn create(&self, contact: &Contact) -> DexApiResult<Contact> {
+        self.inner.create(contact).await
+    }
+
+    async fn update(&self, id: &str, contact: &Contact) -> DexApiResult<Contact> {
+        self.inner.update(id, contact).await
+    }
+
+    async fn delete(&self, id: &str) -> DexApiResult<()> {
+        self.inner.delete(id).await
+    }
+}
+
+fn setup(edits: Vec<Edit>) -> (MockContactRepository, ContactEnrichmentTools) {
+    let contacts = MockContactRepository::new();
+    contacts.add_contact(Contact {
+        id: "c1".to_string(),
+        first_name: Some("Jane".to_string()),
+        company: Some("Acme".to_string()),
+        updated_at: Some("2024-01-01T00:00:00Z".to_string()),
+        ..Default::default()
+    });
+
+    let repo = Arc::new(ConcurrentEditRepository::new(contacts.clone(), edits));
+    let tools = ContactEnrichmentTools::new(
+        repo,
+        Arc::new(MockNoteRepository::new()),
+        Arc::new(MockReminderRepository::new()),
+    );
+    (contacts, tools)
+}
+
+/// An edit made by another session at the given version.
+fn edit(version: &'static str, apply: fn(&mut Contact)) -> Edit {
+    Box::new(move |contact: &mut Contact| {
+        apply(contact);
+        contact.updated_at = Some(version.to_string());
+    })
+}
+
+fn company_params(merge_on_conflict: bool) -> EnrichContactParams {
+    EnrichContactParams {
+        contact_id: "c1".to_string(),
+        company: Some("Globex".to_string()),
+        merge_on_conflict,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_unchanged_contact_updates_normally() {
+    let (contacts, tools) = setup(vec![]);
+
+    let updated = tools.enrich_contact(company_params(false)).await.unwrap();
+    assert_eq!(updated.company.as_deref(), Some("Globex"));
+    assert_eq!(contacts.get_call_count("update"), 1);
+}
+
+#[tokio::test]
+async fn test_concurrent_change_returns_conflict() {
+    let (contacts, tools) = setup(vec![edit("2024-01-02T00:00:00Z", |c| {
+        c.location = Some("Berlin".to_string())
+    })]);
+
+    let err = tools
+        .enrich_contact(company_params(false))
+        .await
+        .unwrap_err();
+    match err {
+        DexApiError::Conflict {
+            id,
+            expected,
+            actual,
+            ..
+        } => {
+            assert_eq!(id, "c1");
+            assert_eq!(expected.as_deref(), Some("2024-01-01T00:00:00Z"));
+            assert_eq!(actual.as_deref(), Some("2024-01-02T00:00:00Z"));
+        }
+        other => panic!("expected conflict, got {:?}", other),
+    }
+
+    assert_eq!(contacts.get_call_count("update"), 0);
+    let stored = contacts.get("c1").await.unwrap();
+    assert_eq!(stored.company.as_deref(), Some("Acme"));
+    assert_eq!(stored.location.as_deref(), Some("Berlin"));
+}
+
+#[tokio::test]
+async fn test_merge_on_conflict_keeps_both_changes() {
+    let (contacts, tools) = setup(vec![edit("2024-01-02T00:00:00Z", |c| {
+        c.location = Some("Berlin".to_string())
+    })]);
+
+    let updated = tools.enrich_contact(company_params(true)).await.unwrap();
+    assert_eq!(updated.company.as_deref(), Some("Globex"));
+    assert_eq!(updated.location.as_deref(), Some("Berlin"));
+
+    let stored = contacts.get("c1").await.unwrap();
+    assert_eq!(stored.updated_at.as_deref(), Some("2024-01-02T00:00:00Z"));
+}
+
+#[tokio::test]
+async fn test_merge_on_conflict_rejects_overlapping_fields() {
+    let (contacts, tools) = setup(vec![edit("2024-01-02T00:00:00Z", |c| {
+        c.company = Some("Initech".to_string())
+    })]);
+
+    let err = tools
+        .enrich_contact(company_params(true))
+        .await
+        .unwrap_err();
+    match err {
+        DexApiError::Conflict { fields, .. } => assert_eq!(fields, vec!["company"]),
+        other => panic!("expected conflict, got {:?}", other),
+    }
+
+    let stored = contacts.get("c1").await.unwrap();
+    assert_eq!(stored.company.as_deref(), Some("Initech"));
+}
+
+#[tokio::test]
+async fn test_merge_gives_up_after_repeated_changes() {
+    let (contacts, tools) = setup(vec![
+        edit("v2", |c| c.location = Some("Berlin".to_string())),
+        edit("v3", |c| c.location = Some("Paris".to_string())),
+        edit("v4", |c| c.location = Some("Rome".to_string())),
+        edit("v5", |c| c.location = Some("Oslo".to_string())),
+        edit("v6", |c| c.location = Some("Lima".to_string())),
+    ]);
+
+    let err = tools
+        .enrich_contact(company_params(true))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DexApiError::Conflict { .. }));
+    assert_eq!(contacts.get_call_count("update"), 0);
+}
+
+#[tokio::test]
+async fn test_expected_version_matches() {
+    let (contacts, tools) = setup(vec![]);
+
+    let params = EnrichContactParams {
+        expected_updated_at: Some("2024-01-01T00:00:00Z".to_string()),
+        ..company_params(false)
+    };
+    let updated = tools.enrich_contact(params).await.unwrap();
+    assert_eq!(updated.company.as_deref(), Some("Globex"));
+    assert_eq!(contacts.get_call_count("update"), 1);
+}
+
+#[tokio::test]
+async fn test_stale_expected_version_returns_conflict() {
+    let (contacts, tools) = setup(vec![]);
+
+    // Merging cannot help: the caller's version is not known to this call
+    let params = EnrichContactParams {
+        expected_updated_at: Some("2023-12-31T00:00:00Z".to_string()),
+        ..company_params(true)
+    };
+    let err = tools.enrich_contact(params).await.unwrap_err();
+    match err {
+        DexApiError::Conflict {
+            expected, actual, ..
+        } => {
+            assert_eq!(expected.as_deref(), Some("2023-12-31T00:00:00Z"));
+            assert_eq!(actual.as_deref(), Some("2024-01-01T00:00:00Z"));
+        }
+        other => panic!("expected conflict, got {:?}", other),
+    }
+    assert_eq!(contacts.get_call_count("update"), 0);
+}
+
+#[tokio::test]
+async fn test_preview_checks_expected_version() {
+    let (_contacts, tools) = setup(vec![]);
+
+    let stale = EnrichContactParams {
+        expected_updated_at: Some("2023-12-31T00:00:00Z".to_string()),
+        ..company_params(false)
+    };
+    let err = tools.preview_enrich_contact(stale).await.unwrap_err();
+    assert!(matches!(err, DexApiError::Conflict { .. }));
+
+    let current = EnrichContactParams {
+        expected_updated_at: Some("2024-01-01T00:00:00Z".to_string()),
+        ..company_params(false)
+    };
+    let preview = tools.preview_enrich_contact(current).await.unwrap();
+    assert_eq!(preview.proposed.unwrap().company.as_deref(), Some("Globex"));
+}
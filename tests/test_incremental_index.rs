@@ -0,0 +1,130 @@
+//! Tests for in-place search index updates on writes.
+
+mod mocks;
+
+use dex_mcp_server::models::Contact;
+use dex_mcp_server::services::{
+    ContactEnrichParams, ContactService, ContactServiceImpl, NoteService, NoteServiceImpl,
+    ReminderService, ReminderServiceImpl,
+};
+use mocks::{
+    search_params, services_with, MockContactRepository, MockNoteRepository,
+    MockReminderRepository, MockServices,
+};
+
+struct TestEnv {
+    contacts: MockContactRepository,
+    contact_service: ContactServiceImpl,
+    note_service: NoteServiceImpl,
+    reminder_service: ReminderServiceImpl,
+}
+
+fn setup() -> TestEnv {
+    let contacts = MockContactRepository::new();
+    let mut jane = Contact::new("c1".to_string(), "Jane Roe".to_string());
+    jane.company = Some("Acme".to_string());
+    contacts.add_contact(jane);
+
+    let MockServices {
+        contact_service,
+        note_service,
+        reminder_service,
+    } = services_with(
+        contacts.clone(),
+        MockNoteRepository::new(),
+        MockReminderRepository::new(),
+    );
+
+    TestEnv {
+        contacts,
+        contact_service,
+        note_service,
+        reminder_service,
+    }
+}
+
+/// Search and return (contact IDs, whether the cached index was used).
+async fn search(env: &TestEnv, query: &str) -> (Vec<String>, bool) {
+    let response = env
+        .contact_service
+        .search_full_text(search_params(query))
+        .await
+        .unwrap();
+    let ids = response
+        .results
+        .iter()
+        .map(|r| r.contact.id.clone())
+        .collect();
+    (ids, response.from_cache)
+}
+
+#[tokio::test]
+async fn test_note_is_searchable_immediately() {
+    let env = setup();
+    assert_eq!(search(&env, "jane").await, (vec!["c1".to_string()], false));
+
+    env.note_service
+        .create_note("c1".to_string(), "Talked about kayaking".to_string(), None)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        search(&env, "kayaking").await,
+        (vec!["c1".to_string()], true)
+    );
+    assert_eq!(env.contacts.get_call_count("list"), 1);
+}
+
+#[tokio::test]
+async fn test_reminder_is_searchable_immediately() {
+    let env = setup();
+    search(&env, "jane").await;
+
+    env.reminder_service
+        .create_reminder(
+            "c1".to_string(),
+            "Send the pitch deck".to_string(),
+            "2024-02-01".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(search(&env, "pitch").await, (vec!["c1".to_string()], true));
+}
+
+#[tokio::test]
+async fn test_enrichment_updates_index_in_place() {
+    let env = setup();
+    search(&env, "jane").await;
+
+    let updated = env
+        .contact_service
+        .enrich_contact(ContactEnrichParams {
+            contact_id: "c1".to_string(),
+            company: Some("Globex".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(updated.company.as_deref(), Some("Globex"));
+
+    assert_eq!(search(&env, "globex").await, (vec!["c1".to_string()], true));
+    assert_eq!(search(&env, "acme").await, (vec![], true));
+    assert_eq!(env.contacts.get_call_count("list"), 1);
+}
+
+#[tokio::test]
+async fn test_write_before_first_search_is_included_in_build() {
+    let env = setup();
+
+    env.note_service
+        .create_note("c1".to_string(), "Talked about kayaking".to_string(), None)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        search(&env, "kayaking").await,
+        (vec!["c1".to_string()], false)
+    );
+}
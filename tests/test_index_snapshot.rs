@@ -0,0 +1,205 @@
+//! Tests for warm starts from the on-disk search index snapshot.
+
+mod mocks;
+
+use dex_mcp_server::models::{Contact, Note};
+use dex_mcp_server::search::IndexSnapshotStore;
+use dex_mcp_server::tools::{SearchParams, SearchTools};
+use mocks::{MockContactRepository, MockNoteRepository, MockReminderRepository};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A snapshot file that is deleted when the test ends.
+struct SnapshotFile(PathBuf);
+
+impl SnapshotFile {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "dex-index-snapshot-test-{}-{}/search_index.json",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        Self(path)
+    }
+
+    fn store(&self) -> IndexSnapshotStore {
+        IndexSnapshotStore::new(self.0.clone(), Duration::from_secs(3600))
+    }
+}
+
+impl Drop for SnapshotFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// A server process: search tools over its own mock repositories.
+struct Server {
+    contacts: MockContactRepository,
+    tools: SearchTools,
+}
+
+fn start(snapshot: &SnapshotFile) -> Server {
+    let contacts = MockContactRepository::new();
+    let mut jane = Contact::new("c1".to_string(), "Jane Roe".to_string());
+    jane.company = Some("Acme".to_string());
+    contacts.add_contact(jane);
+
+    let tools = SearchTools::new(
+        Arc::new(contacts.clone()),
+        Arc::new(MockNoteRepository::new()),
+        Arc::new(MockReminderRepository::new()),
+        300,
+    )
+    .with_snapshot(snapshot.store());
+
+    Server { contacts, tools }
+}
+
+/// Search and return (contact IDs, whether the cached index was used).
+async fn search(server: &Server, query: &str) -> (Vec<String>, bool) {
+    let response = server
+        .tools
+        .search_full_text(SearchParams {
+            query: query.to_string(),
+            min_confidence: Some(0),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let ids = response
+        .results
+        .iter()
+        .map(|r| r.contact.id.clone())
+        .collect();
+    (ids, response.from_cache)
+}
+
+fn note(content: &str) -> Note {
+    Note {
+        id: "n1".to_string(),
+        contact_id: "c1".to_string(),
+        content: content.to_string(),
+        created_at: "2024-01-01T00:00:00Z".to_string(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_first_search_after_restart_is_served_from_snapshot() {
+    let snapshot = SnapshotFile::new("warm-start");
+
+    let first = start(&snapshot);
+    assert_eq!(
+        search(&first, "acme").await,
+        (vec!["c1".to_string()], false)
+    );
+    assert!(snapshot.0.exists());
+
+    let second = start(&snapshot);
+    assert_eq!(
+        search(&second, "acme").await,
+        (vec!["c1".to_string()], true)
+    );
+    assert_eq!(
+        search(&second, "jane").await,
+        (vec!["c1".to_string()], true)
+    );
+    assert_eq!(second.contacts.get_call_count("list"), 0);
+}
+
+#[tokio::test]
+async fn test_in_place_updates_are_persisted() {
+    let snapshot = SnapshotFile::new("updates");
+
+    let first = start(&snapshot);
+    search(&first, "acme").await;
+    first
+        .tools
+        .upsert_note(&note("Talked about kayaking"))
+        .await;
+
+    // The write did not wait for the snapshot
+    let second = start(&snapshot);
+    assert_eq!(search(&second, "kayaking").await, (vec![], true));
+
+    first.tools.flush_snapshot().await;
+    let third = start(&snapshot);
+    assert_eq!(
+        search(&third, "kayaking").await,
+        (vec!["c1".to_string()], true)
+    );
+}
+
+#[tokio::test]
+async fn test_pending_update_is_dropped_on_invalidation() {
+    let snapshot = SnapshotFile::new("pending-invalidate");
+
+    let first = start(&snapshot);
+    search(&first, "acme").await;
+    first
+        .tools
+        .upsert_note(&note("Talked about kayaking"))
+        .await;
+    first.tools.invalidate_cache().await;
+    first.tools.flush_snapshot().await;
+    assert!(!snapshot.0.exists());
+}
+
+#[tokio::test]
+async fn test_snapshot_is_skipped_after_write_since_startup() {
+    let snapshot = SnapshotFile::new("write-first");
+
+    let first = start(&snapshot);
+    search(&first, "acme").await;
+
+    // The write is not in the snapshot, so the index is rebuilt
+    let second = start(&snapshot);
+    second
+        .tools
+        .upsert_note(&note("Talked about kayaking"))
+        .await;
+    assert_eq!(
+        search(&second, "acme").await,
+        (vec!["c1".to_string()], false)
+    );
+    assert_eq!(second.contacts.get_call_count("list"), 1);
+}
+
+#[tokio::test]
+async fn test_invalidation_deletes_snapshot() {
+    let snapshot = SnapshotFile::new("invalidate");
+
+    let first = start(&snapshot);
+    search(&first, "acme").await;
+    first.tools.invalidate_cache().await;
+    assert!(!snapshot.0.exists());
+
+    let second = start(&snapshot);
+    assert_eq!(
+        search(&second, "acme").await,
+        (vec!["c1".to_string()], false)
+    );
+}
+
+#[tokio::test]
+async fn test_unusable_snapshot_falls_back_to_building() {
+    let snapshot = SnapshotFile::new("corrupt");
+    std::fs::create_dir_all(snapshot.0.parent().unwrap()).unwrap();
+    std::fs::write(&snapshot.0, b"not json").unwrap();
+
+    let server = start(&snapshot);
+    assert_eq!(
+        search(&server, "acme").await,
+        (vec!["c1".to_string()], false)
+    );
+
+    // The rebuilt index replaced the unusable snapshot
+    let restarted = start(&snapshot);
+    assert_eq!(
+        search(&restarted, "acme").await,
+        (vec!["c1".to_string()], true)
+    );
+}
@@ -0,0 +1,196 @@
+//! Tests for per-signal explanations of find_contact confidences.
+
+mod mocks;
+
+use dex_mcp_server::matching::{
+    ContactMatcher, ContactQuery, Evidence, MatchSignal, NameIndex, PARALLEL_MATCH_THRESHOLD,
+};
+use dex_mcp_server::models::Contact;
+use dex_mcp_server::tools::{ContactDiscoveryTools, FindContactParams};
+use mocks::MockContactRepository;
+use std::sync::Arc;
+
+fn contacts() -> Vec<Contact> {
+    let mut john = Contact::new("c1".to_string(), "John Smith".to_string());
+    john.email = Some("john.smith@gmail.com".to_string());
+    john.company = Some("Acme Corp".to_string());
+    let mut jane = Contact::new("c2".to_string(), "Jane Smithers".to_string());
+    jane.company = Some("Globex".to_string());
+    let robert = Contact::new("c3".to_string(), "Robert Jones".to_string());
+    vec![john, jane, robert]
+}
+
+#[test]
+fn test_explanation_adds_up_to_the_confidence() {
+    let contacts = contacts();
+    let mut matcher = ContactMatcher::new();
+
+    for query in [
+        ContactQuery {
+            name: Some("Jon Smyth".to_string()),
+            ..Default::default()
+        },
+        ContactQuery {
+            name: Some("Jon Smyth".to_string()),
+            company: Some("acme".to_string()),
+            ..Default::default()
+        },
+        ContactQuery {
+            name: Some("Bob Jones".to_string()),
+            company: Some("Initech".to_string()),
+            ..Default::default()
+        },
+        ContactQuery {
+            name: Some("Jane".to_string()),
+            email: Some("JohnSmith+crm@googlemail.com".to_string()),
+            ..Default::default()
+        },
+    ] {
+        let query = ContactQuery {
+            explain: true,
+            ..query
+        };
+        let results = matcher.find_matches(&query, &contacts, 5, 1);
+        assert!(!results.is_empty(), "{:?}", query);
+        for result in results {
+            let explanation = result.explanation.unwrap();
+            assert_eq!(
+                explanation.confidence(),
+                result.confidence,
+                "{:?} {:?}",
+                query,
+                explanation
+            );
+        }
+    }
+}
+
+#[test]
+fn test_name_and_company_signals() {
+    let contacts = contacts();
+    let mut matcher = ContactMatcher::new();
+    let query = ContactQuery {
+        name: Some("Jon Smyth".to_string()),
+        company: Some("ACME".to_string()),
+        explain: true,
+        ..Default::default()
+    };
+
+    let result = matcher.find_matches(&query, &contacts, 1, 30).remove(0);
+    let explanation = result.explanation.unwrap();
+    assert_eq!(explanation.query.name.as_deref(), Some("jon smyth"));
+    assert_eq!(explanation.query.company.as_deref(), Some("acme"));
+    assert_eq!(explanation.query.email, None);
+
+    let [name, company] = &explanation.signals[..] else {
+        panic!("{:?}", explanation.signals);
+    };
+    assert_eq!(name.signal, MatchSignal::Name);
+    assert_eq!(name.method, Some("edit_distance"));
+    assert_eq!(name.evidence, Evidence::Agrees);
+    assert_eq!(name.weight, 1.0);
+    assert_eq!(name.contribution as u8, name.raw_score);
+    assert_eq!(name.matched_value.as_deref(), Some("John Smith"));
+
+    // An agreeing company closes half its score's share of the gap to 95
+    assert_eq!(company.signal, MatchSignal::Company);
+    assert_eq!(company.evidence, Evidence::Agrees);
+    assert!(company.raw_score > 50);
+    assert_eq!(company.weight, 0.5);
+    let name_score = f64::from(name.raw_score);
+    let expected = name_score + 0.5 * f64::from(company.raw_score) / 100.0 * (95.0 - name_score);
+    assert_eq!(result.confidence, expected.round() as u8);
+    assert_eq!(
+        company.contribution as u8,
+        result.confidence - name.raw_score
+    );
+    assert_eq!(company.matched_value.as_deref(), Some("Acme Corp"));
+}
+
+#[test]
+fn test_contradicting_name_lowers_an_exact_match() {
+    let contacts = contacts();
+    let mut matcher = ContactMatcher::new();
+    let query = ContactQuery {
+        name: Some("Someone Else".to_string()),
+        email: Some("johnsmith+crm@googlemail.com".to_string()),
+        phone: Some("555-123-4567".to_string()),
+        explain: true,
+        ..Default::default()
+    };
+
+    let result = matcher.find_matches(&query, &contacts, 1, 30).remove(0);
+    assert_eq!(result.confidence, 50);
+    let explanation = result.explanation.unwrap();
+    assert_eq!(
+        explanation.query.email.as_deref(),
+        Some("johnsmith@gmail.com")
+    );
+    assert_eq!(explanation.query.phone.as_deref(), Some("+15551234567"));
+
+    let signals: Vec<_> = explanation
+        .signals
+        .iter()
+        .map(|s| (s.signal, s.evidence, s.raw_score, s.weight, s.contribution))
+        .collect();
+    assert_eq!(
+        signals,
+        vec![
+            (MatchSignal::Email, Evidence::Agrees, 100, 1.0, 100),
+            // John has no phone, so it neither helps nor hurts
+            (MatchSignal::Phone, Evidence::Missing, 0, 0.0, 0),
+            (MatchSignal::Name, Evidence::Contradicts, 0, 0.5, -50),
+        ]
+    );
+    assert_eq!(
+        explanation.signals[0].matched_value.as_deref(),
+        Some("john.smith@gmail.com")
+    );
+}
+
+#[test]
+fn test_indexed_matches_are_explained_alike() {
+    let mut contacts: Vec<Contact> = (0..PARALLEL_MATCH_THRESHOLD)
+        .map(|i| Contact::new(format!("filler{}", i), format!("Person Number{}", i)))
+        .collect();
+    contacts.extend(self::contacts());
+    let query = ContactQuery {
+        name: Some("Bob Jones".to_string()),
+        explain: true,
+        ..Default::default()
+    };
+
+    let mut matcher = ContactMatcher::new();
+    let sequential = matcher.find_matches(&query, &contacts, 3, 50);
+    let mut index = NameIndex::new(Arc::new(contacts), Some(2));
+    let indexed = matcher.find_matches_indexed(&query, &mut index, 3, 50);
+
+    assert_eq!(indexed[0].contact.name, "Robert Jones");
+    assert_eq!(indexed[0].explanation, sequential[0].explanation);
+    let name = &indexed[0].explanation.as_ref().unwrap().signals[0];
+    assert_eq!(name.method, Some("nickname"));
+}
+
+#[tokio::test]
+async fn test_find_contact_explains_only_on_request() {
+    let repo = Arc::new(MockContactRepository::new());
+    repo.add_contacts(contacts());
+    let tools = ContactDiscoveryTools::new(repo, 300);
+    let params = FindContactParams {
+        name: Some("Jon Smyth".to_string()),
+        ..Default::default()
+    };
+
+    let response = tools.find_contact(params.clone()).await.unwrap();
+    assert!(response.matches[0].explanation.is_none());
+
+    let response = tools
+        .find_contact(FindContactParams {
+            explain: true,
+            ..params
+        })
+        .await
+        .unwrap();
+    let explanation = response.matches[0].explanation.as_ref().unwrap();
+    assert_eq!(explanation.confidence(), response.matches[0].confidence);
+}
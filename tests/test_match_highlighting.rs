@@ -0,0 +1,123 @@
+//! Tests for highlighted snippets and hit offsets in full-text search results.
+
+mod mocks;
+
+use dex_mcp_server::error::DexApiError;
+use dex_mcp_server::models::Contact;
+use dex_mcp_server::search::MatchContext;
+use dex_mcp_server::services::{ContactSearchParams, ContactService, ContactServiceImpl};
+use mocks::{
+    note, search_params, service_with, MockContactRepository, MockNoteRepository,
+    MockReminderRepository,
+};
+
+/// A long note mentioning kayaking at its start and end.
+const NOTE: &str = "<p>Kayak trip to the fjords is booked for June.</p>\
+    <p>Spent most of the call on the quarterly board deck, hiring plans, the office move \
+    and a long tangent about espresso machines and coffee beans.</p>\
+    <p>Asked me to bring the spare kayak.</p>";
+
+fn setup() -> ContactServiceImpl {
+    let contacts = MockContactRepository::new();
+    let notes = MockNoteRepository::new();
+    contacts.add_contact(Contact::new("c1".to_string(), "Sam Park".to_string()));
+    notes.add_note(note("n1", "c1", NOTE));
+
+    service_with(contacts, notes, MockReminderRepository::new())
+}
+
+async fn note_match(service: &ContactServiceImpl, params: ContactSearchParams) -> MatchContext {
+    let response = service.search_full_text(params).await.unwrap();
+    response.results[0].matches[0].clone()
+}
+
+#[tokio::test]
+async fn test_every_hit_gets_a_highlighted_snippet() {
+    let service = setup();
+
+    let context = note_match(
+        &service,
+        ContactSearchParams {
+            snippet_length: Some(60),
+            highlight_pre: Some("<em>".to_string()),
+            highlight_post: Some("</em>".to_string()),
+            ..search_params("kayak")
+        },
+    )
+    .await;
+
+    assert_eq!(context.hits.len(), 2);
+    assert_eq!(context.snippets.len(), 2);
+    assert!(context.snippets[0].text.starts_with("<em>Kayak</em> trip"));
+    assert!(context.snippets[1].text.contains("spare <em>kayak</em>"));
+    assert_eq!(context.snippet, context.snippets[0].text);
+    for snippet in &context.snippets {
+        let plain = snippet.text.replace("<em>", "").replace("</em>", "");
+        assert!(plain.chars().count() <= 60);
+    }
+}
+
+#[tokio::test]
+async fn test_hit_offsets_point_into_the_plain_text() {
+    let service = setup();
+
+    let context = note_match(&service, search_params("espresso")).await;
+    let plain = dex_mcp_server::search::full_text_index::strip_html(NOTE);
+
+    assert_eq!(context.hits.len(), 1);
+    let hit = context.hits[0];
+    assert_eq!(&plain[hit.start..hit.end], "espresso");
+    let by_chars: String = plain
+        .chars()
+        .skip(hit.char_start)
+        .take(hit.char_end - hit.char_start)
+        .collect();
+    assert_eq!(by_chars, "espresso");
+    // Without markers the snippet is plain text
+    assert!(context.snippet.contains("espresso machines"));
+}
+
+#[tokio::test]
+async fn test_max_snippets_limits_snippets_but_not_hits() {
+    let service = setup();
+
+    let context = note_match(
+        &service,
+        ContactSearchParams {
+            snippet_length: Some(40),
+            max_snippets: Some(1),
+            highlight_pre: Some("**".to_string()),
+            ..search_params("kayak")
+        },
+    )
+    .await;
+
+    assert_eq!(context.hits.len(), 2);
+    assert_eq!(context.snippets.len(), 1);
+    assert!(context.snippet.starts_with("**Kayak** trip"));
+}
+
+#[tokio::test]
+async fn test_rejects_invalid_snippet_options() {
+    let service = setup();
+
+    for params in [
+        ContactSearchParams {
+            snippet_length: Some(5),
+            ..search_params("kayak")
+        },
+        ContactSearchParams {
+            max_snippets: Some(0),
+            ..search_params("kayak")
+        },
+        ContactSearchParams {
+            highlight_pre: Some("*".repeat(50)),
+            ..search_params("kayak")
+        },
+    ] {
+        assert!(matches!(
+            service.search_full_text(params).await,
+            Err(DexApiError::InvalidRequest(_))
+        ));
+    }
+}
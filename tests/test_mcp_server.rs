@@ -85,6 +85,7 @@ async fn test_tool_find_contact_by_name() {
         social_url: None,
         max_results: Some(5),
         min_confidence: Some(30),
+        auto_correct: false,
     };
 
     let result = discovery_tools.find_contact(params).await;
@@ -0,0 +1,332 @@
+//! Tests for merging duplicate contacts with MergeTools.
+
+mod mocks;
+
+use async_trait::async_trait;
+use dex_mcp_server::audit::{self, AuditOperation};
+use dex_mcp_server::error::{DexApiError, DexApiResult};
+use dex_mcp_server::models::{Contact, Note, Reminder};
+use dex_mcp_server::repositories::{ContactRepository, NoteRepository, ReminderRepository};
+use dex_mcp_server::tools::{MergeContactsParams, MergeStrategy, MergeTools};
+use mocks::{audited_env_with_contacts, AuditedEnv, MockContactRepository};
+use std::sync::Arc;
+
+/// Contact repository that fails to delete one contact.
+struct FailingDeleteRepository {
+    inner: MockContactRepository,
+    fail_id: String,
+}
+
+#[async_trait]
+impl ContactRepository for FailingDeleteRepository {
+    async fn get(&self, id: &str) -> DexApiResult<Contact> {
+        self.inner.get(id).await
+    }
+
+    async fn list(&self, limit: usize, offset: usize) -> DexApiResult<Vec<Contact>> {
+        self.inner.list(limit, offset).await
+    }
+
+    async fn search_by_email(
+        &self,
+        email: &str,
+        limit: usize,
+        offset: usize,
+    ) -> DexApiResult<Vec<Contact>> {
+        self.inner.search_by_email(email, limit, offset).await
+    }
+
+    async fn search_by_name(
+        &self,
+        query: &str,
+        limit: usize,
+        offset: usize,
+    ) -> DexApiResult<Vec<Contact>> {
+        self.inner.search_by_name(query, limit, offset).await
+    }
+
+    async fn create(&self, contact: &Contact) -> DexApiResult<Contact> {
+        self.inner.create(contact).await
+    }
+
+    async fn update(&self, id: &str, contact: &Contact) -> DexApiResult<Contact> {
+        self.inner.update(id, contact).await
+    }
+
+    async fn delete(&self, id: &str) -> DexApiResult<()> {
+        if id == self.fail_id {
+            return Err(DexApiError::HttpError(
+                "503 Service Unavailable".to_string(),
+            ));
+        }
+        self.inner.delete(id).await
+    }
+}
+
+/// Set up merge tools over audited mocks, optionally failing to delete `fail_id`.
+fn setup(name: &str, fail_id: Option<&str>) -> (AuditedEnv, MergeTools) {
+    let env = audited_env_with_contacts(name, |contacts| {
+        Arc::new(FailingDeleteRepository {
+            inner: contacts.clone(),
+            fail_id: fail_id.unwrap_or_default().to_string(),
+        })
+    });
+    let merge = MergeTools::new(
+        env.contact_repo.clone(),
+        env.note_repo.clone(),
+        env.reminder_repo.clone(),
+    );
+    seed(&env);
+    (env, merge)
+}
+
+fn contact(id: &str, first: &str, last: &str, email: &str, updated_at: &str) -> Contact {
+    let mut contact = Contact {
+        id: id.to_string(),
+        first_name: Some(first.to_string()),
+        last_name: Some(last.to_string()),
+        emails: vec![email.to_string()],
+        updated_at: Some(updated_at.to_string()),
+        ..Default::default()
+    };
+    contact.populate_computed_fields();
+    contact
+}
+
+/// A primary contact and two duplicates with notes and a reminder.
+fn seed(env: &AuditedEnv) {
+    let mut primary = contact(
+        "c1",
+        "Grace",
+        "Hopper",
+        "grace@navy.mil",
+        "2024-01-01T00:00:00Z",
+    );
+    primary.tags = vec!["navy".to_string()];
+
+    let mut dup1 = contact(
+        "c2",
+        "Grace",
+        "Hopper",
+        "ghopper@yale.edu",
+        "2023-01-01T00:00:00Z",
+    );
+    dup1.company = Some("Yale".to_string());
+    dup1.phones = vec!["555-0100".to_string()];
+    dup1.tags = vec!["navy".to_string(), "cobol".to_string()];
+    dup1.populate_computed_fields();
+
+    let mut dup2 = contact(
+        "c3",
+        "Amazing",
+        "Grace",
+        "grace@navy.mil",
+        "2025-01-01T00:00:00Z",
+    );
+    dup2.company = Some("US Navy".to_string());
+    dup2.title = Some("Rear Admiral".to_string());
+    dup2.populate_computed_fields();
+
+    env.contacts.add_contacts(vec![primary, dup1, dup2]);
+    env.notes.add_notes(vec![
+        Note::new(
+            "n1".to_string(),
+            "c2".to_string(),
+            "Found the first bug".to_string(),
+            "1947-09-09T00:00:00Z".to_string(),
+        ),
+        Note::new(
+            "n2".to_string(),
+            "c3".to_string(),
+            "Retired again".to_string(),
+            "1986-08-14T00:00:00Z".to_string(),
+        ),
+    ]);
+    env.reminders.add_reminder(Reminder::new(
+        "r1".to_string(),
+        "c3".to_string(),
+        "Send birthday card".to_string(),
+        "2025-12-09".to_string(),
+        "2024-01-01T00:00:00Z".to_string(),
+    ));
+}
+
+fn params(strategy: MergeStrategy) -> MergeContactsParams {
+    MergeContactsParams {
+        primary_id: "c1".to_string(),
+        duplicate_ids: vec!["c2".to_string(), "c3".to_string()],
+        strategy,
+    }
+}
+
+#[tokio::test]
+async fn test_merge_combines_contacts_and_moves_history() {
+    let (env, merge) = setup("merge", None);
+
+    let result = audit::with_tool(
+        "merge_contacts",
+        merge.merge_contacts(params(MergeStrategy::FillMissing)),
+    )
+    .await
+    .unwrap();
+
+    let merged = env.contacts.get("c1").await.unwrap();
+    assert_eq!(merged.name, "Grace Hopper");
+    assert_eq!(merged.company.as_deref(), Some("Yale"));
+    assert_eq!(merged.title.as_deref(), Some("Rear Admiral"));
+    assert_eq!(merged.emails, vec!["ghopper@yale.edu", "grace@navy.mil"]);
+    assert_eq!(merged.phones, vec!["555-0100"]);
+    assert_eq!(merged.tags, vec!["cobol", "navy"]);
+    assert_eq!(result.contact.emails, merged.emails);
+
+    assert_eq!(result.deleted_contact_ids, vec!["c2", "c3"]);
+    assert!(env.contacts.get("c2").await.is_err());
+    assert!(env.contacts.get("c3").await.is_err());
+
+    let notes = env.notes.get_for_contact("c1", 10, 0).await.unwrap();
+    let mut contents: Vec<&str> = notes.iter().map(|n| n.content.as_str()).collect();
+    contents.sort();
+    assert_eq!(contents, vec!["Found the first bug", "Retired again"]);
+    assert!(env.notes.get("n1").await.is_err());
+    let moved = result
+        .moved_notes
+        .iter()
+        .find(|m| m.from_id == "n1")
+        .unwrap();
+    let note = env.notes.get(&moved.to_id).await.unwrap();
+    assert_eq!(note.created_at, "1947-09-09T00:00:00Z");
+
+    let reminders = env.reminders.get_for_contact("c1", 10, 0).await.unwrap();
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].due_date, "2025-12-09");
+    assert_eq!(result.moved_reminders[0].from_id, "r1");
+
+    // Primary update, a create and delete per note and reminder, two deletes
+    let entries = env.audit_log.query(None, None).unwrap();
+    assert_eq!(entries.len(), 9);
+    assert!(entries.iter().all(|e| e.tool == "merge_contacts"));
+    assert_eq!(entries[0].operation, AuditOperation::Update);
+}
+
+#[tokio::test]
+async fn test_merge_strategies() {
+    let (_env, merge) = setup("keep", None);
+    let result = merge
+        .merge_contacts(params(MergeStrategy::KeepPrimary))
+        .await
+        .unwrap();
+    assert_eq!(result.contact.company, None);
+    assert_eq!(result.contact.tags, vec!["cobol", "navy"]);
+
+    let (_env, merge) = setup("newest", None);
+    let result = merge
+        .merge_contacts(params(MergeStrategy::PreferNewest))
+        .await
+        .unwrap();
+    assert_eq!(result.contact.name, "Amazing Grace");
+    assert_eq!(result.contact.company.as_deref(), Some("US Navy"));
+}
+
+#[tokio::test]
+async fn test_preview_writes_nothing() {
+    let (env, merge) = setup("preview", None);
+
+    let preview = merge
+        .preview_merge_contacts(params(MergeStrategy::FillMissing))
+        .await
+        .unwrap();
+
+    assert!(preview.dry_run);
+    assert_eq!(preview.primary.operation, AuditOperation::Update);
+    let fields: Vec<&str> = preview
+        .primary
+        .changes
+        .iter()
+        .map(|c| c.field.as_str())
+        .collect();
+    assert!(fields.contains(&"company"), "{:?}", fields);
+    assert_eq!(preview.moved_note_ids.len(), 2);
+    assert_eq!(preview.moved_reminder_ids, vec!["r1"]);
+    assert_eq!(preview.deleted_contacts.len(), 2);
+
+    assert!(env.contacts.get("c2").await.is_ok());
+    assert_eq!(env.contacts.get("c1").await.unwrap().company, None);
+    assert!(env.audit_log.query(None, None).unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_partial_failure_is_rolled_back() {
+    let (env, merge) = setup("rollback", Some("c3"));
+
+    let err = merge
+        .merge_contacts(params(MergeStrategy::FillMissing))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("rolled back"), "{}", err);
+    assert!(!err.to_string().contains("fully"), "{}", err);
+
+    let primary = env.contacts.get("c1").await.unwrap();
+    assert_eq!(primary.company, None);
+    assert_eq!(primary.emails, vec!["grace@navy.mil"]);
+
+    // c2 was deleted before the failure and has been recreated
+    let dup1 = env.contacts.get("c2").await.unwrap();
+    assert_eq!(dup1.company.as_deref(), Some("Yale"));
+    assert!(env.contacts.get("c3").await.is_ok());
+
+    assert!(env
+        .notes
+        .get_for_contact("c1", 10, 0)
+        .await
+        .unwrap()
+        .is_empty());
+    let notes = env.notes.get_for_contact("c2", 10, 0).await.unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].content, "Found the first bug");
+    assert_eq!(
+        env.notes.get_for_contact("c3", 10, 0).await.unwrap().len(),
+        1
+    );
+    assert!(env
+        .reminders
+        .get_for_contact("c1", 10, 0)
+        .await
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        env.reminders
+            .get_for_contact("c3", 10, 0)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_merges_are_rejected() {
+    let (env, merge) = setup("invalid", None);
+
+    for duplicate_ids in [vec![], vec!["c1"], vec!["c2", "c2"]] {
+        let err = merge
+            .merge_contacts(MergeContactsParams {
+                primary_id: "c1".to_string(),
+                duplicate_ids: duplicate_ids.iter().map(|id| id.to_string()).collect(),
+                strategy: MergeStrategy::default(),
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DexApiError::InvalidRequest(_)), "{}", err);
+    }
+
+    let err = merge
+        .merge_contacts(MergeContactsParams {
+            primary_id: "c1".to_string(),
+            duplicate_ids: vec!["missing".to_string()],
+            strategy: MergeStrategy::default(),
+        })
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DexApiError::NotFound(_)), "{}", err);
+    assert!(env.audit_log.query(None, None).unwrap().is_empty());
+}
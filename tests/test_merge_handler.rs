@@ -0,0 +1,209 @@
+//! Tests for the merge_contacts tool handler, called through an MCP client.
+
+mod mocks;
+
+use async_trait::async_trait;
+use dex_mcp_server::client::{AsyncDexClient, AsyncDexClientImpl};
+use dex_mcp_server::error::{DexApiError, DexApiResult};
+use dex_mcp_server::models::Contact;
+use dex_mcp_server::repositories::ContactRepository;
+use dex_mcp_server::search::Analyzer;
+use dex_mcp_server::tools::{DiscoveryConfig, SearchCacheConfig};
+use dex_mcp_server::{Config, DexClient, DexMcpServer};
+use mocks::{audited_env_with_contacts, AuditedEnv, MockContactRepository};
+use rmcp::model::CallToolRequestParam;
+use rmcp::service::RunningService;
+use rmcp::{RoleClient, ServiceExt};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Contact repository that behaves like Dex: created contacts get new IDs,
+/// and deleting `fail_id` fails.
+struct DexLikeRepository {
+    inner: MockContactRepository,
+    fail_id: String,
+    created: AtomicUsize,
+}
+
+#[async_trait]
+impl ContactRepository for DexLikeRepository {
+    async fn get(&self, id: &str) -> DexApiResult<Contact> {
+        self.inner.get(id).await
+    }
+
+    async fn list(&self, limit: usize, offset: usize) -> DexApiResult<Vec<Contact>> {
+        self.inner.list(limit, offset).await
+    }
+
+    async fn search_by_email(
+        &self,
+        email: &str,
+        limit: usize,
+        offset: usize,
+    ) -> DexApiResult<Vec<Contact>> {
+        self.inner.search_by_email(email, limit, offset).await
+    }
+
+    async fn search_by_name(
+        &self,
+        query: &str,
+        limit: usize,
+        offset: usize,
+    ) -> DexApiResult<Vec<Contact>> {
+        self.inner.search_by_name(query, limit, offset).await
+    }
+
+    async fn create(&self, contact: &Contact) -> DexApiResult<Contact> {
+        let n = self.created.fetch_add(1, Ordering::SeqCst);
+        self.inner
+            .create(&Contact {
+                id: format!("new{}", n),
+                ..contact.clone()
+            })
+            .await
+    }
+
+    async fn update(&self, id: &str, contact: &Contact) -> DexApiResult<Contact> {
+        self.inner.update(id, contact).await
+    }
+
+    async fn delete(&self, id: &str) -> DexApiResult<()> {
+        if id == self.fail_id {
+            return Err(DexApiError::HttpError(
+                "503 Service Unavailable".to_string(),
+            ));
+        }
+        self.inner.delete(id).await
+    }
+}
+
+fn contact(id: &str, first: &str, last: &str) -> Contact {
+    let mut contact = Contact {
+        id: id.to_string(),
+        first_name: Some(first.to_string()),
+        last_name: Some(last.to_string()),
+        ..Default::default()
+    };
+    contact.populate_computed_fields();
+    contact
+}
+
+/// Serve a DexMcpServer over audited mocks that fail to delete `fail_id`,
+/// and connect an MCP client to it.
+async fn serve(name: &str, fail_id: &str) -> (AuditedEnv, RunningService<RoleClient, ()>) {
+    let env = audited_env_with_contacts(name, |contacts| {
+        Arc::new(DexLikeRepository {
+            inner: contacts.clone(),
+            fail_id: fail_id.to_string(),
+            created: AtomicUsize::new(0),
+        })
+    });
+    env.contacts.add_contacts(vec![
+        contact("c1", "Grace", "Hopper"),
+        contact("c2", "Grace", "Hopper"),
+        contact("c3", "Grace", "Hopper"),
+    ]);
+
+    let client = Arc::new(AsyncDexClientImpl::new(DexClient::new(&Config::default())))
+        as Arc<dyn AsyncDexClient>;
+    let server = DexMcpServer::new(
+        env.contact_repo.clone(),
+        env.note_repo.clone(),
+        env.reminder_repo.clone(),
+        env.audit_log.clone(),
+        client,
+        DiscoveryConfig {
+            cache_ttl_secs: 300,
+            nicknames: Default::default(),
+            phone_region: Default::default(),
+            match_weights: Default::default(),
+        },
+        SearchCacheConfig {
+            ttl_secs: 300,
+            snapshot: None,
+            analyzer: Arc::new(Analyzer::new()),
+        },
+    );
+
+    let (server_transport, client_transport) = tokio::io::duplex(64 * 1024);
+    tokio::spawn(async move {
+        if let Ok(service) = server.serve(server_transport).await {
+            let _ = service.waiting().await;
+        }
+    });
+    let client = ().serve(client_transport).await.unwrap();
+    (env, client)
+}
+
+async fn call(
+    client: &RunningService<RoleClient, ()>,
+    tool: &'static str,
+    arguments: serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let result = client
+        .call_tool(CallToolRequestParam {
+            name: tool.into(),
+            arguments: arguments.as_object().cloned(),
+        })
+        .await
+        .map_err(|err| err.to_string())?;
+    let text = result.content[0].as_text().unwrap().text.clone();
+    Ok(serde_json::from_str(&text).unwrap())
+}
+
+/// IDs of the contacts find_contact returns for Grace Hopper.
+async fn found_ids(client: &RunningService<RoleClient, ()>) -> Vec<String> {
+    let response = call(
+        client,
+        "find_contact",
+        serde_json::json!({"name": "Grace Hopper", "max_results": 10}),
+    )
+    .await
+    .unwrap();
+    let mut ids: Vec<String> = response["matches"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["contact"]["id"].as_str().unwrap().to_string())
+        .collect();
+    ids.sort();
+    ids
+}
+
+#[tokio::test]
+async fn test_failed_merge_invalidates_the_contact_cache() {
+    let (_env, client) = serve("merge-handler-failed", "c3").await;
+
+    // Warm the discovery cache
+    assert_eq!(found_ids(&client).await, ["c1", "c2", "c3"]);
+
+    // c2 is deleted, deleting c3 fails, and the rollback recreates c2 as new0
+    let err = call(
+        &client,
+        "merge_contacts",
+        serde_json::json!({"primary_id": "c1", "duplicate_ids": ["c2", "c3"]}),
+    )
+    .await
+    .unwrap_err();
+    assert!(err.contains("rolled back"), "{}", err);
+
+    assert_eq!(found_ids(&client).await, ["c1", "c3", "new0"]);
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_merge_invalidates_the_contact_cache() {
+    let (_env, client) = serve("merge-handler-ok", "").await;
+    assert_eq!(found_ids(&client).await, ["c1", "c2", "c3"]);
+
+    call(
+        &client,
+        "merge_contacts",
+        serde_json::json!({"primary_id": "c1", "duplicate_ids": ["c2", "c3"]}),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(found_ids(&client).await, ["c1"]);
+    client.cancel().await.unwrap();
+}
@@ -0,0 +1,156 @@
+//! Tests for nickname, initial and other name variant matching.
+
+mod mocks;
+
+use dex_mcp_server::matching::{
+    ContactMatcher, ContactQuery, MatchType, NameIndex, NameVariantKind, NicknameMap,
+    PARALLEL_MATCH_THRESHOLD,
+};
+use dex_mcp_server::models::Contact;
+use dex_mcp_server::tools::{ContactDiscoveryTools, FindContactParams};
+use mocks::MockContactRepository;
+use std::sync::Arc;
+
+fn contacts() -> Vec<Contact> {
+    [
+        "Robert Smith",
+        "Elizabeth Taylor",
+        "John Paul Jones",
+        "Mary Smith-Jones",
+        "Iñigo Montoya",
+        "Roberta Flack",
+    ]
+    .iter()
+    .enumerate()
+    .map(|(i, name)| Contact::new(format!("c{}", i), name.to_string()))
+    .collect()
+}
+
+fn top_match(
+    matcher: &mut ContactMatcher,
+    contacts: &[Contact],
+    name: &str,
+) -> Option<(String, MatchType, u8)> {
+    let query = ContactQuery {
+        name: Some(name.to_string()),
+        ..Default::default()
+    };
+    matcher
+        .find_matches(&query, contacts, 1, 50)
+        .pop()
+        .map(|m| (m.contact.name, m.match_type, m.confidence))
+}
+
+#[test]
+fn test_variants_find_the_right_contact() {
+    let contacts = contacts();
+    let mut matcher = ContactMatcher::new();
+
+    for (query, name, kind) in [
+        ("Bob Smith", "Robert Smith", NameVariantKind::Nickname),
+        ("Liz", "Elizabeth Taylor", NameVariantKind::Nickname),
+        ("R. Smith", "Robert Smith", NameVariantKind::Initial),
+        (
+            "Taylor, Elizabeth",
+            "Elizabeth Taylor",
+            NameVariantKind::Reordered,
+        ),
+        ("John Jones", "John Paul Jones", NameVariantKind::MiddleName),
+        (
+            "Mary Jones",
+            "Mary Smith-Jones",
+            NameVariantKind::HyphenatedSurname,
+        ),
+    ] {
+        let (found, match_type, confidence) =
+            top_match(&mut matcher, &contacts, query).unwrap_or_else(|| panic!("{}", query));
+        assert_eq!(found, name, "{}", query);
+        assert_eq!(match_type, MatchType::NameVariant(kind), "{}", query);
+        assert_eq!(confidence, kind.max_confidence(), "{}", query);
+    }
+}
+
+#[test]
+fn test_confidence_reflects_variant_kind() {
+    let contacts = contacts();
+    let mut matcher = ContactMatcher::new();
+
+    let confidence = |matcher: &mut ContactMatcher, query| {
+        top_match(matcher, &contacts, query).map_or(0, |(_, _, confidence)| confidence)
+    };
+    let exact = confidence(&mut matcher, "Robert Smith");
+    let nickname = confidence(&mut matcher, "Bob Smith");
+    let initial = confidence(&mut matcher, "R. Smith");
+    assert!(exact > nickname, "{} > {}", exact, nickname);
+    assert!(nickname > initial, "{} > {}", nickname, initial);
+}
+
+#[test]
+fn test_user_nicknames_extend_the_matcher() {
+    let contacts = contacts();
+
+    let mut matcher = ContactMatcher::new();
+    assert_ne!(
+        top_match(&mut matcher, &contacts, "Iñaki Montoya").map(|m| m.1),
+        Some(MatchType::NameVariant(NameVariantKind::Nickname))
+    );
+
+    let path = std::env::temp_dir().join(format!("dex-nicknames-{}.txt", std::process::id()));
+    std::fs::write(&path, "# Basque names\niñigo, iñaki\n").unwrap();
+    let nicknames = NicknameMap::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(
+        nicknames.are_variants("bob", "robert"),
+        "built-ins are kept"
+    );
+
+    let mut matcher = ContactMatcher::new().with_nicknames(Arc::new(nicknames));
+    let (name, match_type, _) = top_match(&mut matcher, &contacts, "Iñaki Montoya").unwrap();
+    assert_eq!(name, "Iñigo Montoya");
+    assert_eq!(
+        match_type,
+        MatchType::NameVariant(NameVariantKind::Nickname)
+    );
+}
+
+#[test]
+fn test_variants_match_through_the_name_index() {
+    let mut contacts: Vec<Contact> = (0..PARALLEL_MATCH_THRESHOLD)
+        .map(|i| Contact::new(format!("filler{}", i), format!("Person Number{}", i)))
+        .collect();
+    contacts.extend(self::contacts());
+
+    let query = ContactQuery {
+        name: Some("Bob Smith".to_string()),
+        ..Default::default()
+    };
+    let mut matcher = ContactMatcher::new();
+    let sequential = matcher.find_matches(&query, &contacts, 3, 50);
+    let mut index = NameIndex::new(Arc::new(contacts), Some(2));
+    let indexed = matcher.find_matches_indexed(&query, &mut index, 3, 50);
+
+    assert_eq!(indexed[0].contact.name, "Robert Smith");
+    assert_eq!(indexed.len(), sequential.len());
+    assert_eq!(indexed[0].confidence, sequential[0].confidence);
+}
+
+#[tokio::test]
+async fn test_find_contact_uses_configured_nicknames() {
+    let repo = Arc::new(MockContactRepository::new());
+    repo.add_contacts(contacts());
+    let nicknames = NicknameMap::parse("roberta, bobbie");
+    let tools = ContactDiscoveryTools::new(repo, 300).with_nicknames(Arc::new(nicknames));
+
+    let response = tools
+        .find_contact(FindContactParams {
+            name: Some("Bobbie Flack".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(response.matches[0].contact.name, "Roberta Flack");
+    assert_eq!(
+        response.matches[0].match_type,
+        MatchType::NameVariant(NameVariantKind::Nickname)
+    );
+}
@@ -0,0 +1,94 @@
+//! Tests for multithreaded name matching on large contact lists.
+
+mod mocks;
+
+use dex_mcp_server::matching::{
+    ContactMatcher, ContactQuery, MatchResult, NameIndex, PARALLEL_MATCH_THRESHOLD,
+};
+use dex_mcp_server::models::Contact;
+use dex_mcp_server::tools::{ContactDiscoveryTools, FindContactParams};
+use mocks::MockContactRepository;
+use std::sync::Arc;
+
+const FIRST_NAMES: [&str; 12] = [
+    "John", "Jane", "Johnny", "Joan", "Mary", "Marie", "Ana", "Anna", "José", "Zoë", "Li", "Sam",
+];
+
+const LAST_NAMES: [&str; 10] = [
+    "Doe", "Smith", "Smyth", "Müller", "Miller", "García", "Chen", "Kim", "Rivera", "Okafor",
+];
+
+/// More contacts than the parallel threshold, all with distinct names.
+fn contacts() -> Vec<Contact> {
+    let mut contacts = Vec::new();
+    for i in 0.. {
+        let first = FIRST_NAMES[i % FIRST_NAMES.len()];
+        let last = LAST_NAMES[(i / FIRST_NAMES.len()) % LAST_NAMES.len()];
+        let suffix = i / (FIRST_NAMES.len() * LAST_NAMES.len());
+        let mut contact = Contact::new(i.to_string(), format!("{} {} {}", first, last, suffix));
+        contact.company = Some(if i % 3 == 0 { "Acme" } else { "Globex" }.to_string());
+        contacts.push(contact);
+        if contacts.len() > PARALLEL_MATCH_THRESHOLD {
+            break;
+        }
+    }
+    contacts
+}
+
+fn query(name: &str, company: Option<&str>) -> ContactQuery {
+    ContactQuery {
+        name: Some(name.to_string()),
+        company: company.map(str::to_string),
+        ..Default::default()
+    }
+}
+
+fn summary(matches: &[MatchResult]) -> Vec<(String, u8)> {
+    matches
+        .iter()
+        .map(|m| (m.contact.id.clone(), m.confidence))
+        .collect()
+}
+
+#[test]
+fn test_indexed_matching_equals_sequential_matching() {
+    let contacts = contacts();
+    let mut index = NameIndex::new(Arc::new(contacts.clone()), Some(4));
+    let mut matcher = ContactMatcher::new();
+
+    for query in [
+        query("john doe", None),
+        query("jon smyth 1", Some("acme")),
+        query("johnn doe 0", None),
+        query("zoe muller", None),
+        query("anna", Some("Globex")),
+    ] {
+        let sequential = matcher.find_matches(&query, &contacts, 20, 30);
+        let indexed = matcher.find_matches_indexed(&query, &mut index, 20, 30);
+        assert!(!sequential.is_empty(), "{:?}", query.name);
+        assert_eq!(summary(&indexed), summary(&sequential), "{:?}", query.name);
+    }
+}
+
+#[tokio::test]
+async fn test_find_contact_uses_warm_index_for_large_lists() {
+    let contacts = contacts();
+    let repo = MockContactRepository::new();
+    repo.add_contacts(contacts.clone());
+    let tools = ContactDiscoveryTools::new(Arc::new(repo), 300);
+
+    for name in ["Marie Chen 3", "marie chen 3", "Mari Chen 3"] {
+        let response = tools
+            .find_contact(FindContactParams {
+                name: Some(name.to_string()),
+                max_results: Some(5),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let expected = ContactMatcher::new().find_matches(&query(name, None), &contacts, 5, 30);
+        assert_eq!(summary(&response.matches), summary(&expected));
+        assert_eq!(response.matches[0].contact.name, "Marie Chen 3");
+    }
+}
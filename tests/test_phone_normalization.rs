@@ -0,0 +1,149 @@
+//! Tests for phone number parsing in matching, duplicate detection and
+//! enrichment.
+
+mod mocks;
+
+use dex_mcp_server::domain::{PhoneNumber, PhoneRegion};
+use dex_mcp_server::matching::{find_duplicates, ContactMatcher, ContactQuery, MatchType};
+use dex_mcp_server::models::Contact;
+use dex_mcp_server::tools::{
+    ContactDiscoveryTools, ContactEnrichmentTools, EnrichContactParams, FindContactParams,
+};
+use mocks::{MockContactRepository, MockNoteRepository, MockReminderRepository};
+use std::sync::Arc;
+
+fn contact(id: &str, name: &str, phone: &str) -> Contact {
+    let mut contact = Contact::new(id.to_string(), name.to_string());
+    contact.phone = Some(phone.to_string());
+    contact
+}
+
+fn phone_matches(matcher: &mut ContactMatcher, contacts: &[Contact], phone: &str) -> Vec<String> {
+    let query = ContactQuery {
+        phone: Some(phone.to_string()),
+        ..Default::default()
+    };
+    matcher
+        .find_matches(&query, contacts, 10, 50)
+        .into_iter()
+        .inspect(|m| assert_eq!(m.match_type, MatchType::ExactPhone))
+        .map(|m| m.contact.id)
+        .collect()
+}
+
+#[test]
+fn test_formats_of_the_same_number_match() {
+    let contacts = vec![
+        contact("us", "Ada Lovelace", "+1 (555) 123-4567"),
+        contact("uk", "Charles Babbage", "+44 20 7123 4567"),
+        contact("ext", "Grace Hopper", "(555) 987-6543 ext. 12"),
+    ];
+    let mut matcher = ContactMatcher::new();
+
+    for query in [
+        "555-123-4567",
+        "5551234567",
+        "011 1 555 123 4567",
+        "+15551234567",
+    ] {
+        assert_eq!(
+            phone_matches(&mut matcher, &contacts, query),
+            ["us"],
+            "{}",
+            query
+        );
+    }
+    for query in ["+44 (0)20 7123 4567", "011 44 20 7123 4567"] {
+        assert_eq!(
+            phone_matches(&mut matcher, &contacts, query),
+            ["uk"],
+            "{}",
+            query
+        );
+    }
+
+    // The last ten digits alone no longer make a match across countries
+    assert!(phone_matches(&mut matcher, &contacts, "+61 555 123 4567").is_empty());
+
+    assert_eq!(
+        phone_matches(&mut matcher, &contacts, "555-987-6543"),
+        ["ext"]
+    );
+    assert_eq!(
+        phone_matches(&mut matcher, &contacts, "555-987-6543 x12"),
+        ["ext"]
+    );
+    assert!(phone_matches(&mut matcher, &contacts, "555-987-6543 x13").is_empty());
+}
+
+#[test]
+fn test_default_region_reads_national_numbers() {
+    let contacts = vec![contact("uk", "Charles Babbage", "+44 20 7123 4567")];
+
+    let mut us = ContactMatcher::new();
+    assert!(phone_matches(&mut us, &contacts, "020 7123 4567").is_empty());
+
+    let gb: PhoneRegion = "GB".parse().unwrap();
+    let mut uk = ContactMatcher::new().with_phone_region(gb);
+    assert_eq!(phone_matches(&mut uk, &contacts, "020 7123 4567"), ["uk"]);
+
+    let phone = PhoneNumber::parse("020 7123 4567", gb).unwrap();
+    assert_eq!(phone.e164(), Some("+442071234567"));
+    assert_eq!(phone.national(), Some("020 7123 4567"));
+    assert_eq!(phone.international(), Some("+44 20 7123 4567"));
+}
+
+#[test]
+fn test_duplicates_share_a_number_in_different_formats() {
+    let contacts = vec![
+        contact("a", "Ada Lovelace", "+1 (555) 123-4567"),
+        contact("b", "A. King", "555.123.4567"),
+    ];
+    let report = find_duplicates(&mut ContactMatcher::new(), &contacts, 80, 10);
+
+    assert_eq!(report.clusters.len(), 1);
+    assert_eq!(report.clusters[0].matches[0].confidence, 100);
+}
+
+#[tokio::test]
+async fn test_find_contact_uses_configured_region() {
+    let repo = Arc::new(MockContactRepository::new());
+    repo.add_contacts(vec![contact("uk", "Charles Babbage", "+44 20 7123 4567")]);
+    let tools = ContactDiscoveryTools::new(repo, 300).with_phone_region("gb".parse().unwrap());
+
+    let response = tools
+        .find_contact(FindContactParams {
+            phone: Some("020 7123 4567".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(response.matches[0].contact.id, "uk");
+}
+
+#[tokio::test]
+async fn test_enrichment_skips_phones_already_present() {
+    let repo = Arc::new(MockContactRepository::new());
+    let mut existing = Contact::new("c1".to_string(), "Ada Lovelace".to_string());
+    existing.phones = vec!["+1 (555) 123-4567".to_string()];
+    repo.add_contacts(vec![existing]);
+    let tools = ContactEnrichmentTools::new(
+        repo,
+        Arc::new(MockNoteRepository::new()),
+        Arc::new(MockReminderRepository::new()),
+    );
+
+    let contact = tools
+        .enrich_contact(EnrichContactParams {
+            contact_id: "c1".to_string(),
+            additional_phones: Some(vec![
+                "555-123-4567".to_string(),
+                "1 555 123 4567".to_string(),
+                "555-123-4567 x2".to_string(),
+            ]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(contact.phones, ["+1 (555) 123-4567", "555-123-4567 x2"]);
+}
@@ -0,0 +1,133 @@
+//! Tests for facet counts and facet filters on full-text search.
+
+mod mocks;
+
+use dex_mcp_server::error::DexApiError;
+use dex_mcp_server::models::Contact;
+use dex_mcp_server::search::FacetCount;
+use dex_mcp_server::services::{ContactSearchParams, ContactService, ContactServiceImpl};
+use mocks::{
+    note, search_params, service_with, MockContactRepository, MockNoteRepository,
+    MockReminderRepository,
+};
+
+fn contact(id: &str, name: &str, company: &str, tags: &[&str], email: &str) -> Contact {
+    let mut contact = Contact::new(id.to_string(), name.to_string());
+    contact.company = Some(company.to_string());
+    contact.tags = tags.iter().map(|t| t.to_string()).collect();
+    contact.emails = vec![email.to_string()];
+    contact
+}
+
+/// Four contacts interested in "climate": three in notes, one in their
+/// job title.
+fn setup() -> ContactServiceImpl {
+    let contacts = MockContactRepository::new();
+    let mut dana = contact("c4", "Dana Kim", "Globex", &["Founder"], "dana@globex.io");
+    dana.title = Some("Climate Analyst".to_string());
+    contacts.add_contacts(vec![
+        contact(
+            "c1",
+            "Ana Lopez",
+            "Acme",
+            &["Investor", "climate"],
+            "ana@acme.com",
+        ),
+        contact("c2", "Ben Ortiz", "acme", &["investor"], "ben@ACME.com"),
+        contact("c3", "Cy Young", "Initech", &["Founder"], "cy@gmail.com"),
+        dana,
+    ]);
+
+    let notes = MockNoteRepository::new();
+    for (id, contact_id) in [("n1", "c1"), ("n2", "c2"), ("n3", "c3")] {
+        notes.add_note(note(id, contact_id, "Talked about climate tech"));
+    }
+
+    service_with(contacts, notes, MockReminderRepository::new())
+}
+
+fn params() -> ContactSearchParams {
+    search_params("climate")
+}
+
+fn counts(facet: &[FacetCount]) -> Vec<(&str, usize)> {
+    facet.iter().map(|c| (c.value.as_str(), c.count)).collect()
+}
+
+fn strings(values: &[&str]) -> Option<Vec<String>> {
+    Some(values.iter().map(|v| v.to_string()).collect())
+}
+
+#[tokio::test]
+async fn test_counts_facets_of_all_matches() {
+    let service = setup();
+
+    let mut limited = params();
+    limited.max_results = Some(1);
+    let response = service.search_full_text(limited).await.unwrap();
+
+    assert_eq!(response.results.len(), 1);
+    assert_eq!(response.total_matches, 4);
+    let facets = &response.facets;
+    assert_eq!(
+        counts(&facets.tags),
+        vec![("Founder", 2), ("Investor", 2), ("climate", 1)]
+    );
+    assert_eq!(
+        counts(&facets.companies),
+        vec![("Acme", 2), ("Globex", 1), ("Initech", 1)]
+    );
+    assert_eq!(
+        counts(&facets.email_domains),
+        vec![("acme.com", 2), ("globex.io", 1), ("gmail.com", 1)]
+    );
+    assert_eq!(
+        counts(&facets.fields),
+        vec![("note", 3), ("job_title", 1), ("tag", 1)]
+    );
+}
+
+#[tokio::test]
+async fn test_facet_filters_narrow_results_and_counts() {
+    let service = setup();
+
+    let mut investors = params();
+    investors.facet_tags = strings(&["INVESTOR"]);
+    let response = service.search_full_text(investors).await.unwrap();
+    let mut ids: Vec<&str> = response
+        .results
+        .iter()
+        .map(|r| r.contact.id.as_str())
+        .collect();
+    ids.sort();
+    assert_eq!(ids, vec!["c1", "c2"]);
+    assert_eq!(response.total_matches, 2);
+    assert_eq!(counts(&response.facets.companies), vec![("Acme", 2)]);
+
+    // Facets are combined with AND, values of one facet with OR
+    let mut founders = params();
+    founders.facet_tags = strings(&["founder", "investor"]);
+    founders.facet_email_domains = strings(&["@gmail.com", "globex.io"]);
+    founders.facet_fields = strings(&["note"]);
+    let response = service.search_full_text(founders).await.unwrap();
+    let ids: Vec<&str> = response
+        .results
+        .iter()
+        .map(|r| r.contact.id.as_str())
+        .collect();
+    assert_eq!(ids, vec!["c3"]);
+}
+
+#[tokio::test]
+async fn test_invalid_facet_fields_are_rejected() {
+    let service = setup();
+
+    let mut bad_field = params();
+    bad_field.facet_fields = strings(&["city"]);
+    let err = service.search_full_text(bad_field).await.unwrap_err();
+    assert!(
+        matches!(err, DexApiError::InvalidRequest(ref m) if m.contains("facet_fields")),
+        "{}",
+        err
+    );
+}
@@ -0,0 +1,196 @@
+//! Tests for include_types and date-range filters on full-text search.
+
+mod mocks;
+
+use dex_mcp_server::error::DexApiError;
+use dex_mcp_server::models::{Contact, Note, Reminder};
+use dex_mcp_server::search::SearchableField;
+use dex_mcp_server::services::{ContactSearchParams, ContactService, ContactServiceImpl};
+use mocks::{
+    search_params, service_with, MockContactRepository, MockNoteRepository, MockReminderRepository,
+};
+
+fn note(id: &str, contact_id: &str, content: &str, created_at: &str) -> Note {
+    Note {
+        created_at: created_at.to_string(),
+        ..mocks::note(id, contact_id, content)
+    }
+}
+
+fn reminder(id: &str, contact_id: &str, text: &str, created_at: &str) -> Reminder {
+    Reminder {
+        id: id.to_string(),
+        contact_id: contact_id.to_string(),
+        text: text.to_string(),
+        due_date: "2024-06-01".to_string(),
+        completed: false,
+        completed_at: None,
+        created_at: created_at.to_string(),
+        updated_at: None,
+        tags: Vec::new(),
+        priority: None,
+    }
+}
+
+/// Three contacts mentioning "roadmap": in a company name, in notes from
+/// January and March, and in a reminder. Two of them mention Berlin: one in
+/// their location, one in their description.
+fn setup() -> ContactServiceImpl {
+    let contacts = MockContactRepository::new();
+    let notes = MockNoteRepository::new();
+    let reminders = MockReminderRepository::new();
+
+    let mut ada = Contact::new("c1".to_string(), "Ada Lovelace".to_string());
+    ada.company = Some("Roadmap Labs".to_string());
+    let mut grace = Contact::new("c2".to_string(), "Grace Hopper".to_string());
+    grace.location = Some("Berlin".to_string());
+    let mut alan = Contact::new("c3".to_string(), "Alan Turing".to_string());
+    alan.description = Some("Moved away from Berlin".to_string());
+    contacts.add_contacts(vec![ada, grace, alan]);
+
+    notes.add_notes(vec![
+        note("n1", "c2", "Reviewed the roadmap", "2024-01-15T09:00:00Z"),
+        note("n2", "c3", "Shared roadmap draft", "2024-03-10T09:00:00Z"),
+    ]);
+    reminders.add_reminder(reminder(
+        "r1",
+        "c3",
+        "Send roadmap feedback",
+        "2024-03-20T09:00:00Z",
+    ));
+
+    service_with(contacts, notes, reminders)
+}
+
+fn params(include_types: Option<&[&str]>) -> ContactSearchParams {
+    ContactSearchParams {
+        include_types: include_types.map(|types| types.iter().map(|t| t.to_string()).collect()),
+        ..search_params("roadmap")
+    }
+}
+
+/// (contact ID, matched fields) of each result, sorted by contact ID.
+async fn search(
+    service: &ContactServiceImpl,
+    params: ContactSearchParams,
+) -> Vec<(String, Vec<SearchableField>)> {
+    let response = service.search_full_text(params).await.unwrap();
+    let mut results: Vec<_> = response
+        .results
+        .iter()
+        .map(|r| {
+            let fields = r.matches.iter().map(|m| m.field_type).collect();
+            (r.contact.id.clone(), fields)
+        })
+        .collect();
+    results.sort_by(|a, b| a.0.cmp(&b.0));
+    results
+}
+
+#[tokio::test]
+async fn test_without_filters_matches_every_field() {
+    let service = setup();
+
+    let results = search(&service, params(None)).await;
+    let ids: Vec<&str> = results.iter().map(|(id, _)| id.as_str()).collect();
+    assert_eq!(ids, vec!["c1", "c2", "c3"]);
+}
+
+#[tokio::test]
+async fn test_include_types_restricts_matched_fields() {
+    let service = setup();
+
+    let results = search(&service, params(Some(&["note"]))).await;
+    assert_eq!(
+        results,
+        vec![
+            ("c2".to_string(), vec![SearchableField::Note]),
+            ("c3".to_string(), vec![SearchableField::Note]),
+        ]
+    );
+
+    let results = search(&service, params(Some(&["reminders", "company"]))).await;
+    assert_eq!(
+        results,
+        vec![
+            ("c1".to_string(), vec![SearchableField::Company]),
+            ("c3".to_string(), vec![SearchableField::Reminder]),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_include_types_targets_contact_fields() {
+    let service = setup();
+
+    let mut berlin = params(None);
+    berlin.query = "berlin".to_string();
+    let results = search(&service, berlin.clone()).await;
+    assert_eq!(
+        results,
+        vec![
+            ("c2".to_string(), vec![SearchableField::Location]),
+            ("c3".to_string(), vec![SearchableField::Description]),
+        ]
+    );
+
+    berlin.include_types = Some(vec!["location".to_string()]);
+    let results = search(&service, berlin).await;
+    assert_eq!(
+        results,
+        vec![("c2".to_string(), vec![SearchableField::Location])]
+    );
+}
+
+#[tokio::test]
+async fn test_date_range_filters_notes_and_reminders() {
+    let service = setup();
+
+    let mut filtered = params(Some(&["note", "reminder"]));
+    filtered.date_from = Some("2024-03-01".to_string());
+    let results = search(&service, filtered).await;
+    assert_eq!(
+        results,
+        vec![(
+            "c3".to_string(),
+            vec![SearchableField::Note, SearchableField::Reminder]
+        )]
+    );
+
+    // A date-only upper bound includes the whole day
+    let mut filtered = params(None);
+    filtered.date_to = Some("2024-01-15".to_string());
+    let results = search(&service, filtered).await;
+    let ids: Vec<&str> = results.iter().map(|(id, _)| id.as_str()).collect();
+    assert_eq!(ids, vec!["c1", "c2"]);
+}
+
+#[tokio::test]
+async fn test_invalid_filters_are_rejected() {
+    let service = setup();
+
+    let err = service
+        .search_full_text(params(Some(&["city"])))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, DexApiError::InvalidRequest(ref m) if m.contains("'city'")),
+        "{}",
+        err
+    );
+
+    let mut bad_date = params(None);
+    bad_date.date_from = Some("last week".to_string());
+    assert!(matches!(
+        service.search_full_text(bad_date).await,
+        Err(DexApiError::InvalidRequest(_))
+    ));
+
+    let mut reversed = params(None);
+    reversed.date_from = Some("2024-03-01".to_string());
+    reversed.date_to = Some("2024-01-01".to_string());
+    assert!(matches!(
+        service.search_full_text(reversed).await,
+        Err(DexApiError::InvalidRequest(_))
+    ));
+}
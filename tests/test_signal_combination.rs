@@ -0,0 +1,183 @@
+//! Tests for combining agreeing and contradicting signals into one
+//! find_contact confidence.
+
+mod mocks;
+
+use dex_mcp_server::matching::{ContactMatcher, ContactQuery, MatchType, MatchWeights};
+use dex_mcp_server::models::Contact;
+use dex_mcp_server::tools::{ContactDiscoveryTools, FindContactParams};
+use mocks::MockContactRepository;
+use std::sync::Arc;
+
+fn contact(id: &str, name: &str, email: Option<&str>, company: Option<&str>) -> Contact {
+    let mut contact = Contact::new(id.to_string(), name.to_string());
+    contact.email = email.map(str::to_string);
+    contact.company = company.map(str::to_string);
+    contact
+}
+
+fn contacts() -> Vec<Contact> {
+    vec![
+        contact("c1", "John Smith", Some("john@acme.com"), Some("Acme Corp")),
+        contact("c2", "Jane Doe", Some("jane@globex.com"), Some("Globex")),
+        contact("c3", "John Smithson", None, None),
+    ]
+}
+
+fn confidence_of(matcher: &mut ContactMatcher, query: &ContactQuery, id: &str) -> Option<u8> {
+    matcher
+        .find_matches(query, &contacts(), 10, 0)
+        .into_iter()
+        .find(|m| m.contact.id == id)
+        .map(|m| m.confidence)
+}
+
+#[test]
+fn test_contradicting_name_lowers_an_email_match() {
+    let mut matcher = ContactMatcher::new();
+    let email_only = ContactQuery {
+        email: Some("john@acme.com".to_string()),
+        ..Default::default()
+    };
+    let agreeing = ContactQuery {
+        name: Some("John Smith".to_string()),
+        ..email_only.clone()
+    };
+    let contradicting = ContactQuery {
+        name: Some("Maria Garcia".to_string()),
+        ..email_only.clone()
+    };
+
+    assert_eq!(confidence_of(&mut matcher, &email_only, "c1"), Some(100));
+    assert_eq!(confidence_of(&mut matcher, &agreeing, "c1"), Some(100));
+    assert_eq!(confidence_of(&mut matcher, &contradicting, "c1"), Some(50));
+
+    let results = matcher.find_matches(&contradicting, &contacts(), 10, 0);
+    assert_eq!(results[0].match_type, MatchType::ExactEmail);
+}
+
+#[test]
+fn test_agreeing_company_raises_a_name_match() {
+    let mut matcher = ContactMatcher::new();
+    let name_only = ContactQuery {
+        name: Some("Jon Smith".to_string()),
+        ..Default::default()
+    };
+    let with_company = ContactQuery {
+        company: Some("Acme".to_string()),
+        ..name_only.clone()
+    };
+    let other_company = ContactQuery {
+        company: Some("Initech".to_string()),
+        ..name_only.clone()
+    };
+
+    let base = confidence_of(&mut matcher, &name_only, "c1").unwrap();
+    let raised = confidence_of(&mut matcher, &with_company, "c1").unwrap();
+    let lowered = confidence_of(&mut matcher, &other_company, "c1").unwrap();
+    assert!(raised > base, "{} > {}", raised, base);
+    assert!(raised <= 95, "name matches stay below exact matches");
+    assert!(lowered < base, "{} < {}", lowered, base);
+
+    // Contacts without a company are neither raised nor lowered
+    assert_eq!(
+        confidence_of(&mut matcher, &other_company, "c3"),
+        confidence_of(&mut matcher, &name_only, "c3")
+    );
+}
+
+#[test]
+fn test_contradicting_email_lowers_a_name_match() {
+    let mut matcher = ContactMatcher::new();
+    let name_only = ContactQuery {
+        name: Some("John Smith".to_string()),
+        ..Default::default()
+    };
+    let other_email = ContactQuery {
+        email: Some("someone@else.com".to_string()),
+        ..name_only.clone()
+    };
+
+    let base = confidence_of(&mut matcher, &name_only, "c1").unwrap();
+    assert_eq!(base, 95);
+    assert_eq!(confidence_of(&mut matcher, &other_email, "c1"), Some(81));
+    // John Smithson has no email to contradict
+    assert_eq!(
+        confidence_of(&mut matcher, &other_email, "c3"),
+        confidence_of(&mut matcher, &name_only, "c3")
+    );
+}
+
+#[test]
+fn test_company_alone_never_matches() {
+    let mut matcher = ContactMatcher::new();
+    let query = ContactQuery {
+        name: Some("Maria Garcia".to_string()),
+        company: Some("Acme Corp".to_string()),
+        ..Default::default()
+    };
+
+    assert!(matcher.find_matches(&query, &contacts(), 10, 0).is_empty());
+}
+
+#[test]
+fn test_custom_weights() {
+    let contradicting = ContactQuery {
+        email: Some("john@acme.com".to_string()),
+        name: Some("Maria Garcia".to_string()),
+        ..Default::default()
+    };
+
+    let weights: MatchWeights = "name.contradict=0.2".parse().unwrap();
+    let mut matcher = ContactMatcher::new().with_weights(weights);
+    assert_eq!(confidence_of(&mut matcher, &contradicting, "c1"), Some(80));
+
+    let weights: MatchWeights = "name.contradict=0".parse().unwrap();
+    let mut matcher = ContactMatcher::new().with_weights(weights);
+    assert_eq!(confidence_of(&mut matcher, &contradicting, "c1"), Some(100));
+}
+
+#[test]
+fn test_ranking_is_deterministic() {
+    // Same name, same confidence: ordered by id whatever the input order
+    let twins = vec![
+        contact("b", "Alex Kim", None, None),
+        contact("c", "Alex Kim", None, None),
+        contact("a", "Alex Kim", None, None),
+    ];
+    let query = ContactQuery {
+        name: Some("Alex Kim".to_string()),
+        ..Default::default()
+    };
+    let mut matcher = ContactMatcher::new();
+
+    let ids = |contacts: &[Contact], matcher: &mut ContactMatcher| {
+        matcher
+            .find_matches(&query, contacts, 10, 0)
+            .into_iter()
+            .map(|m| m.contact.id)
+            .collect::<Vec<_>>()
+    };
+    let reversed: Vec<_> = twins.iter().rev().cloned().collect();
+    assert_eq!(ids(&twins, &mut matcher), ["a", "b", "c"]);
+    assert_eq!(ids(&reversed, &mut matcher), ["a", "b", "c"]);
+}
+
+#[tokio::test]
+async fn test_find_contact_uses_configured_weights() {
+    let repo = Arc::new(MockContactRepository::new());
+    repo.add_contacts(contacts());
+    let tools = ContactDiscoveryTools::new(repo, 300)
+        .with_match_weights("name.contradict=0.2".parse().unwrap());
+
+    let response = tools
+        .find_contact(FindContactParams {
+            email: Some("john@acme.com".to_string()),
+            name: Some("Maria Garcia".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(response.matches[0].contact.id, "c1");
+    assert_eq!(response.matches[0].confidence, 80);
+}
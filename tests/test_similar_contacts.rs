@@ -0,0 +1,114 @@
+//! Tests for finding similar contacts by TF-IDF similarity.
+
+mod mocks;
+
+use dex_mcp_server::error::DexApiError;
+use dex_mcp_server::models::{Contact, Reminder};
+use dex_mcp_server::services::{ContactService, ContactServiceImpl};
+use mocks::{
+    note, service_with, MockContactRepository, MockNoteRepository, MockReminderRepository,
+};
+
+fn contact(id: &str, name: &str, company: &str, tags: &[&str]) -> Contact {
+    let mut contact = Contact::new(id.to_string(), name.to_string());
+    contact.company = Some(company.to_string());
+    contact.tags = tags.iter().map(|t| t.to_string()).collect();
+    contact
+}
+
+fn setup() -> ContactServiceImpl {
+    let contacts = MockContactRepository::new();
+    contacts.add_contacts(vec![
+        contact("c1", "Maya Chen", "Solaris Bio", &["biotech", "founder"]),
+        contact("c2", "Raj Patel", "Helix Therapeutics", &["biotech"]),
+        contact("c3", "Olivia Brown", "Brown & Sons Bakery", &["local"]),
+        contact("c4", "Sam Ito", "Cloudpath", &["saas", "founder"]),
+    ]);
+
+    let notes = MockNoteRepository::new();
+    notes.add_note(note(
+        "n1",
+        "c1",
+        "Raising a seed round for gene therapy research",
+    ));
+    notes.add_note(note(
+        "n2",
+        "c2",
+        "Runs clinical trials for gene therapies, interested in research partnerships",
+    ));
+    notes.add_note(note("n3", "c3", "Best sourdough in town"));
+    notes.add_note(note(
+        "n4",
+        "c4",
+        "Building developer tooling for data teams",
+    ));
+
+    let reminders = MockReminderRepository::new();
+    reminders.add_reminder(Reminder {
+        id: "r1".to_string(),
+        contact_id: "c2".to_string(),
+        text: "Send the biotech investor list".to_string(),
+        created_at: "2024-01-02T00:00:00Z".to_string(),
+        ..Default::default()
+    });
+
+    service_with(contacts, notes, reminders)
+}
+
+#[tokio::test]
+async fn test_finds_contacts_with_overlapping_topics() {
+    let service = setup();
+
+    let similar = service.find_similar_contacts("c1", None).await.unwrap();
+    let ids: Vec<&str> = similar.iter().map(|s| s.contact_id.as_str()).collect();
+
+    assert_eq!(ids, vec!["c2", "c4"]);
+    assert!(similar[0].similarity > similar[1].similarity);
+    assert!(similar[0].similarity <= 1.0);
+    assert_eq!(similar[0].contact_name, "Raj Patel");
+}
+
+#[tokio::test]
+async fn test_explains_similarity_with_shared_terms() {
+    let service = setup();
+
+    let similar = service.find_similar_contacts("c1", Some(1)).await.unwrap();
+    assert_eq!(similar.len(), 1);
+
+    let terms: Vec<&str> = similar[0]
+        .shared_terms
+        .iter()
+        .map(|t| t.term.as_str())
+        .collect();
+    for term in ["biotech", "gene", "therapy", "research"] {
+        assert!(terms.contains(&term), "{term} missing from {terms:?}");
+    }
+    let weights: Vec<f64> = similar[0].shared_terms.iter().map(|t| t.weight).collect();
+    assert!(weights.windows(2).all(|w| w[0] >= w[1]));
+}
+
+#[tokio::test]
+async fn test_unrelated_contacts_are_left_out() {
+    let service = setup();
+
+    let similar = service.find_similar_contacts("c3", None).await.unwrap();
+    assert!(similar.is_empty());
+}
+
+#[tokio::test]
+async fn test_rejects_unknown_contact_and_invalid_k() {
+    let service = setup();
+
+    assert!(matches!(
+        service.find_similar_contacts("missing", None).await,
+        Err(DexApiError::NotFound(_))
+    ));
+    assert!(matches!(
+        service.find_similar_contacts("c1", Some(0)).await,
+        Err(DexApiError::InvalidRequest(_))
+    ));
+    assert!(matches!(
+        service.find_similar_contacts(" ", None).await,
+        Err(DexApiError::InvalidRequest(_))
+    ));
+}
@@ -0,0 +1,100 @@
+//! Tests for "did you mean" spelling suggestions on empty searches.
+
+mod mocks;
+
+use dex_mcp_server::models::Contact;
+use dex_mcp_server::services::{
+    ContactFindParams, ContactSearchParams, ContactService, ContactServiceImpl,
+};
+use mocks::{
+    note, service_with, MockContactRepository, MockNoteRepository, MockReminderRepository,
+};
+
+fn setup() -> ContactServiceImpl {
+    let contacts = MockContactRepository::new();
+    let mut katherine = Contact::new("c1".to_string(), "Katherine Johnson".to_string());
+    katherine.company = Some("Langley Research".to_string());
+    contacts.add_contacts(vec![
+        katherine,
+        Contact::new("c2".to_string(), "Dorothy Vaughan".to_string()),
+    ]);
+
+    let notes = MockNoteRepository::new();
+    notes.add_note(note("n1", "c2", "Talked about orbital mechanics"));
+
+    service_with(contacts, notes, MockReminderRepository::new())
+}
+
+fn params(query: &str, auto_correct: bool) -> ContactSearchParams {
+    ContactSearchParams {
+        query: query.to_string(),
+        auto_correct,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_empty_search_suggests_spellings() {
+    let service = setup();
+
+    let response = service
+        .search_full_text(params("note:orbtial mechancs", false))
+        .await
+        .unwrap();
+
+    assert!(response.results.is_empty());
+    assert_eq!(response.suggestions[0].text, "note:orbital mechanics");
+    assert_eq!(response.corrected_query, None);
+}
+
+#[tokio::test]
+async fn test_search_auto_correct_retries_with_top_suggestion() {
+    let service = setup();
+
+    let response = service
+        .search_full_text(params("note:orbtial mechancs", true))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        response.corrected_query.as_deref(),
+        Some("note:orbital mechanics")
+    );
+    assert_eq!(response.results.len(), 1);
+    assert_eq!(response.results[0].contact.id, "c2");
+}
+
+#[tokio::test]
+async fn test_matching_search_has_no_suggestions() {
+    let service = setup();
+
+    let response = service
+        .search_full_text(params("langley", true))
+        .await
+        .unwrap();
+
+    assert_eq!(response.results.len(), 1);
+    assert!(response.suggestions.is_empty());
+    assert_eq!(response.corrected_query, None);
+}
+
+#[tokio::test]
+async fn test_find_contact_suggests_and_auto_corrects_names() {
+    let service = setup();
+    let find = |auto_correct| {
+        service.find_contact(ContactFindParams {
+            name: Some("Vuaghan".to_string()),
+            auto_correct,
+            ..Default::default()
+        })
+    };
+
+    let response = find(false).await.unwrap();
+    assert!(response.matches.is_empty());
+    assert_eq!(response.suggestions[0].text, "Vaughan");
+    assert_eq!(response.corrected_name, None);
+
+    let response = find(true).await.unwrap();
+    assert_eq!(response.corrected_name.as_deref(), Some("Vaughan"));
+    assert_eq!(response.matches[0].contact.id, "c2");
+}
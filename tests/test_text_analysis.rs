@@ -0,0 +1,119 @@
+//! Tests for stemming, synonyms and stopwords in full-text search.
+
+mod mocks;
+
+use dex_mcp_server::models::Contact;
+use dex_mcp_server::search::{Analyzer, Language, SynonymMap};
+use dex_mcp_server::tools::{SearchParams, SearchTools};
+use mocks::{note, MockContactRepository, MockNoteRepository, MockReminderRepository};
+use std::sync::Arc;
+
+fn search_tools(analyzer: Analyzer) -> SearchTools {
+    let contacts = MockContactRepository::new();
+    contacts.add_contacts(vec![
+        Contact::new("c1".to_string(), "Priya Shah".to_string()),
+        Contact::new("c2".to_string(), "Tom Becker".to_string()),
+        Contact::new("c3".to_string(), "Lena Vogel".to_string()),
+        Contact::new("c4".to_string(), "Marco Bianchi".to_string()),
+    ]);
+    let notes = MockNoteRepository::new();
+    notes.add_note(note("n1", "c1", "She invested early in two fintechs"));
+    notes.add_note(note("n2", "c2", "Runs an investment club on weekends"));
+    notes.add_note(note(
+        "n3",
+        "c3",
+        "Works at a venture capital firm in Berlin",
+    ));
+    notes.add_note(note(
+        "n4",
+        "c4",
+        "Hat über Gründungen und Kooperationen gesprochen",
+    ));
+
+    SearchTools::new(
+        Arc::new(contacts),
+        Arc::new(notes),
+        Arc::new(MockReminderRepository::new()),
+        300,
+    )
+    .with_analyzer(Arc::new(analyzer))
+}
+
+/// Sorted IDs of the contacts a full-text search returns.
+async fn search(tools: &SearchTools, query: &str) -> Vec<String> {
+    let mut ids: Vec<String> = tools
+        .search_full_text(SearchParams {
+            query: query.to_string(),
+            min_confidence: Some(0),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .results
+        .iter()
+        .map(|r| r.contact.id.clone())
+        .collect();
+    ids.sort();
+    ids
+}
+
+#[tokio::test]
+async fn test_stemming_matches_word_forms() {
+    let tools = search_tools(Analyzer::default());
+
+    assert_eq!(search(&tools, "investing").await, vec!["c1", "c2"]);
+    assert_eq!(search(&tools, "note:investments").await, vec!["c1", "c2"]);
+    // Phrases match stemmed words in their original order
+    assert_eq!(search(&tools, "\"invests early\"").await, vec!["c1"]);
+    assert!(search(&tools, "\"early invests\"").await.is_empty());
+}
+
+#[tokio::test]
+async fn test_synonyms_from_file() {
+    let path = std::env::temp_dir().join(format!("dex-synonyms-test-{}.txt", std::process::id()));
+    std::fs::write(&path, "# funding\nvc, venture capital\n").unwrap();
+    let synonyms = SynonymMap::load(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(synonyms.len(), 2);
+
+    let tools = search_tools(Analyzer::default().with_synonyms(synonyms));
+
+    assert_eq!(search(&tools, "VC").await, vec!["c3"]);
+    assert_eq!(search(&tools, "venture capital").await, vec!["c3"]);
+    // The words of a synonym phrase are still searchable on their own
+    assert_eq!(search(&tools, "capital").await, vec!["c3"]);
+    assert_eq!(search(&tools, "venture").await, vec!["c3"]);
+    assert_eq!(search(&tools, "\"capital firm\"").await, vec!["c3"]);
+    assert_eq!(search(&tools, "\"vc firm\"").await, vec!["c3"]);
+}
+
+#[test]
+fn test_missing_synonyms_file_is_empty() {
+    let path = std::env::temp_dir().join("dex-synonyms-test-missing.txt");
+    let synonyms = SynonymMap::load(&path).unwrap();
+    assert!(synonyms.is_empty());
+}
+
+#[tokio::test]
+async fn test_stopwords_do_not_constrain_queries() {
+    let tools = search_tools(Analyzer::default());
+
+    // "of" is dropped, so only "fintechs" has to match
+    assert_eq!(search(&tools, "note:fintechs note:of").await, vec!["c1"]);
+    assert!(search(&tools, "of").await.is_empty());
+
+    // Without stopword removal every term must match
+    let tools = search_tools(Analyzer::default().without_stopwords());
+    assert!(search(&tools, "note:fintechs note:of").await.is_empty());
+}
+
+#[tokio::test]
+async fn test_language_selects_stemmer() {
+    // "und" is only a stopword in German
+    let english = search_tools(Analyzer::default());
+    assert_eq!(search(&english, "und").await, vec!["c4"]);
+
+    let german = search_tools(Analyzer::for_language(Language::German));
+    assert!(search(&german, "und").await.is_empty());
+    assert_eq!(search(&german, "Kooperation gründung").await, vec!["c4"]);
+}
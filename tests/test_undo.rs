@@ -0,0 +1,185 @@
+//! Tests for reverting audited changes with UndoTools.
+
+mod mocks;
+
+use dex_mcp_server::audit::{self, AuditOperation};
+use dex_mcp_server::models::{Contact, Note};
+use dex_mcp_server::repositories::{ContactRepository, NoteRepository};
+use dex_mcp_server::tools::{
+    ContactEnrichmentTools, CreateNoteParams, EnrichContactParams, RevertAction, UndoTools,
+};
+use mocks::{audited_env, AuditedEnv};
+
+/// Enrichment and undo tools over audited mocks.
+fn setup(name: &str) -> (AuditedEnv, ContactEnrichmentTools, UndoTools) {
+    let env = audited_env(name);
+    let enrichment = ContactEnrichmentTools::new(
+        env.contact_repo.clone(),
+        env.note_repo.clone(),
+        env.reminder_repo.clone(),
+    );
+    let undo = UndoTools::new(
+        env.contact_repo.clone(),
+        env.note_repo.clone(),
+        env.reminder_repo.clone(),
+        env.audit_log.clone(),
+    );
+    (env, enrichment, undo)
+}
+
+fn sample_contact() -> Contact {
+    let mut contact = Contact {
+        id: "c1".to_string(),
+        first_name: Some("Jane".to_string()),
+        last_name: Some("Doe".to_string()),
+        company: Some("Acme".to_string()),
+        emails: vec!["jane@acme.com".to_string()],
+        ..Default::default()
+    };
+    contact.populate_computed_fields();
+    contact
+}
+
+fn note_params(content: &str) -> CreateNoteParams {
+    CreateNoteParams {
+        contact_id: "c1".to_string(),
+        content: content.to_string(),
+        tags: None,
+        source: None,
+    }
+}
+
+#[tokio::test]
+async fn test_revert_update_restores_before_image() {
+    let (env, enrichment, undo) = setup("update");
+    env.contacts.add_contact(sample_contact());
+
+    let params = EnrichContactParams {
+        contact_id: "c1".to_string(),
+        company: Some("Globex".to_string()),
+        ..Default::default()
+    };
+    audit::with_tool("enrich_contact", enrichment.enrich_contact(params))
+        .await
+        .unwrap();
+    let change_id = env.audit_log.query(None, None).unwrap()[0]
+        .change_id
+        .clone();
+
+    let result = audit::with_tool("revert_change", undo.revert_change(&change_id))
+        .await
+        .unwrap();
+    assert_eq!(result.action, RevertAction::Restored);
+    assert_eq!(result.record_id, "c1");
+
+    let contact = env.contacts.get("c1").await.unwrap();
+    assert_eq!(contact.company.as_deref(), Some("Acme"));
+    assert_eq!(contact.email.as_deref(), Some("jane@acme.com"));
+
+    let entries = env.audit_log.query(None, None).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1].tool, "revert_change");
+    assert_eq!(entries[1].reverts.as_deref(), Some(change_id.as_str()));
+}
+
+#[tokio::test]
+async fn test_revert_create_deletes_record() {
+    let (env, enrichment, undo) = setup("create");
+
+    let note = enrichment
+        .add_contact_note(note_params("Met for coffee"))
+        .await
+        .unwrap();
+    let result = undo.undo_last_change().await.unwrap();
+
+    assert_eq!(result.action, RevertAction::Deleted);
+    assert_eq!(result.reverted.operation, AuditOperation::Create);
+    assert!(env.notes.get(&note.id).await.is_err());
+}
+
+#[tokio::test]
+async fn test_revert_delete_recreates_record() {
+    let (env, _, undo) = setup("delete");
+    env.notes.add_note(Note {
+        id: "n1".to_string(),
+        contact_id: "c1".to_string(),
+        content: "Birthday in May".to_string(),
+        created_at: "2024-05-01T00:00:00Z".to_string(),
+        ..Default::default()
+    });
+
+    env.note_repo.delete("n1").await.unwrap();
+    let result = undo.undo_last_change().await.unwrap();
+
+    assert_eq!(result.action, RevertAction::Recreated);
+    let note = env.notes.get(&result.record_id).await.unwrap();
+    assert_eq!(note.content, "Birthday in May");
+    assert_eq!(note.contact_id, "c1");
+    assert_eq!(note.created_at, "2024-05-01T00:00:00Z");
+}
+
+#[tokio::test]
+async fn test_undo_last_change_walks_back_through_history() {
+    let (_env, enrichment, undo) = setup("walk");
+
+    let first = enrichment
+        .add_contact_note(note_params("First"))
+        .await
+        .unwrap();
+    let second = enrichment
+        .add_contact_note(note_params("Second"))
+        .await
+        .unwrap();
+
+    let undone = undo.undo_last_change().await.unwrap();
+    assert_eq!(undone.record_id, second.id);
+    let undone = undo.undo_last_change().await.unwrap();
+    assert_eq!(undone.record_id, first.id);
+
+    assert!(undo.undo_last_change().await.is_err());
+}
+
+#[tokio::test]
+async fn test_revert_twice_is_rejected() {
+    let (env, enrichment, undo) = setup("twice");
+
+    enrichment
+        .add_contact_note(note_params("Once"))
+        .await
+        .unwrap();
+    let change_id = env.audit_log.query(None, None).unwrap()[0]
+        .change_id
+        .clone();
+
+    undo.revert_change(&change_id).await.unwrap();
+    let err = undo.revert_change(&change_id).await.unwrap_err();
+    assert!(err.to_string().contains("already reverted"));
+
+    assert!(undo.revert_change("chg_missing").await.is_err());
+}
+
+#[tokio::test]
+async fn test_preview_revert_does_not_write() {
+    let (env, enrichment, undo) = setup("preview");
+    env.contacts.add_contact(sample_contact());
+
+    let params = EnrichContactParams {
+        contact_id: "c1".to_string(),
+        company: Some("Globex".to_string()),
+        ..Default::default()
+    };
+    enrichment.enrich_contact(params).await.unwrap();
+    let change_id = env.audit_log.query(None, None).unwrap()[0]
+        .change_id
+        .clone();
+
+    let preview = undo.preview_revert_change(&change_id).await.unwrap();
+    assert_eq!(preview.operation, AuditOperation::Update);
+    assert_eq!(preview.changes.len(), 1);
+    assert_eq!(preview.changes[0].field, "company");
+    assert_eq!(preview.changes[0].after, Some("Acme".into()));
+
+    assert_eq!(env.audit_log.query(None, None).unwrap().len(), 1);
+    let contact = env.contacts.get("c1").await.unwrap();
+    assert_eq!(contact.company.as_deref(), Some("Globex"));
+}
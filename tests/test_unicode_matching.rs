@@ -0,0 +1,110 @@
+//! Tests for Unicode normalization in contact matching and full-text search.
+
+mod mocks;
+
+use dex_mcp_server::matching::{ContactMatcher, ContactQuery};
+use dex_mcp_server::models::{Contact, Note};
+use dex_mcp_server::tools::{SearchParams, SearchTools};
+use mocks::{MockContactRepository, MockNoteRepository, MockReminderRepository};
+use std::sync::Arc;
+
+fn contacts() -> Vec<Contact> {
+    [
+        ("c1", "José Müller"),
+        ("c2", "Дмитрий Иванов"),
+        ("c3", "山田太郎"),
+        ("c4", "Alex 🚀 Rivera"),
+        ("c5", "Zoë Łukasiewicz"),
+    ]
+    .into_iter()
+    .map(|(id, name)| Contact::new(id.to_string(), name.to_string()))
+    .collect()
+}
+
+/// Top match of `ContactMatcher` for a name query.
+fn best_match(name: &str) -> Option<(String, u8)> {
+    let query = ContactQuery {
+        name: Some(name.to_string()),
+        ..Default::default()
+    };
+    ContactMatcher::new()
+        .find_matches(&query, &contacts(), 5, 50)
+        .first()
+        .map(|m| (m.contact.id.clone(), m.confidence))
+}
+
+fn search_tools() -> SearchTools {
+    let contact_repo = MockContactRepository::new();
+    contact_repo.add_contacts(contacts());
+    let notes = MockNoteRepository::new();
+    notes.add_note(Note {
+        id: "n1".to_string(),
+        contact_id: "c2".to_string(),
+        content: "Встреча в Санкт-Петербурге, обсудили café menu".to_string(),
+        created_at: "2024-01-01T00:00:00Z".to_string(),
+        ..Default::default()
+    });
+
+    SearchTools::new(
+        Arc::new(contact_repo),
+        Arc::new(notes),
+        Arc::new(MockReminderRepository::new()),
+        300,
+    )
+}
+
+/// IDs of the contacts a full-text search returns.
+async fn search(tools: &SearchTools, query: &str) -> Vec<String> {
+    tools
+        .search_full_text(SearchParams {
+            query: query.to_string(),
+            min_confidence: Some(0),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .results
+        .iter()
+        .map(|r| r.contact.id.clone())
+        .collect()
+}
+
+#[test]
+fn test_matcher_folds_accented_latin() {
+    assert_eq!(best_match("jose muller"), Some(("c1".to_string(), 95)));
+    assert_eq!(best_match("JOSÉ MÜLLER"), Some(("c1".to_string(), 95)));
+    assert_eq!(best_match("zoe lukasiewicz"), Some(("c5".to_string(), 95)));
+}
+
+#[test]
+fn test_matcher_handles_other_scripts() {
+    assert_eq!(best_match("дмитрий иванов"), Some(("c2".to_string(), 95)));
+    // Typos are measured in characters, not bytes
+    assert_eq!(
+        best_match("Дмитрий Иваноф").map(|m| m.0),
+        Some("c2".to_string())
+    );
+    assert_eq!(best_match("山田太郎"), Some(("c3".to_string(), 95)));
+    assert_eq!(best_match("alex 🚀 rivera"), Some(("c4".to_string(), 95)));
+}
+
+#[tokio::test]
+async fn test_search_folds_accented_latin() {
+    let tools = search_tools();
+
+    assert_eq!(search(&tools, "jose muller").await, vec!["c1"]);
+    assert_eq!(search(&tools, "MÜLLER").await, vec!["c1"]);
+    assert_eq!(search(&tools, "lukasiewicz").await, vec!["c5"]);
+    assert_eq!(search(&tools, "cafe").await, vec!["c2"]);
+}
+
+#[tokio::test]
+async fn test_search_handles_cyrillic_cjk_and_emoji() {
+    let tools = search_tools();
+
+    assert_eq!(search(&tools, "иванов").await, vec!["c2"]);
+    assert_eq!(search(&tools, "петербурге").await, vec!["c2"]);
+    // Ideographs are indexed one by one, so part of a name matches
+    assert_eq!(search(&tools, "太郎").await, vec!["c3"]);
+    assert_eq!(search(&tools, "🚀").await, vec!["c4"]);
+}